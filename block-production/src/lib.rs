@@ -13,6 +13,7 @@ use nimiq_primitives::policy::Policy;
 use nimiq_transaction::{
     extended_transaction::ExtendedTransaction, inherent::Inherent, Transaction,
 };
+use nimiq_vrf::VrfSeed;
 use rand::{CryptoRng, Rng, RngCore};
 
 /// Struct that contains all necessary information to actually produce blocks.
@@ -78,6 +79,55 @@ impl BlockProducer {
         skip_block_proof: Option<SkipBlockProof>,
         // The rng seed. We need this parameterized in order to have determinism when running unit tests.
         rng: &mut R,
+    ) -> MicroBlock {
+        // Calculate the seed for this block by signing the previous block seed with the validator
+        // key. The VRF seed of a skip block is carried over since a new VRF seed would require a
+        // new leader.
+        let prev_seed = blockchain.head().seed().clone();
+        let seed = if skip_block_proof.is_some() {
+            prev_seed
+        } else {
+            prev_seed.sign_next_with_rng(&self.signing_key, rng)
+        };
+
+        let mut block = Self::next_unsigned_micro_block(
+            blockchain,
+            timestamp,
+            seed,
+            fork_proofs,
+            transactions,
+            extra_data,
+            skip_block_proof,
+        );
+
+        if block.justification.is_none() {
+            // Signs the block header using the signing key.
+            let hash = block.header.hash::<Blake2bHash>();
+            let signature = self.signing_key.sign(hash.as_slice());
+            block.justification = Some(MicroJustification::Micro(signature));
+        }
+
+        block
+    }
+
+    /// Creates the next micro block with the given VRF seed. Only skip blocks are justified, the
+    /// header of any other block still needs to be signed by the block producer.
+    pub fn next_unsigned_micro_block(
+        // The (upgradable) read locked guard to the blockchain.
+        blockchain: &Blockchain,
+        // The timestamp for the block.
+        timestamp: u64,
+        // The VRF seed of the block. For skip blocks, this must be the seed of the previous block.
+        seed: VrfSeed,
+        // Proofs of any forks created by malicious validators. A fork proof may be submitted during
+        // the batch when it happened or in the next one, but not after that.
+        fork_proofs: Vec<ForkProof>,
+        // The transactions to be included in the block body.
+        transactions: Vec<Transaction>,
+        // Extra data for this block.
+        extra_data: Vec<u8>,
+        // Skip block proof.
+        skip_block_proof: Option<SkipBlockProof>,
     ) -> MicroBlock {
         // Calculate the block number. It is simply the previous block number incremented by one.
         let block_number = blockchain.block_number() + 1;
@@ -92,27 +142,15 @@ impl BlockProducer {
         // Get the hash of the latest block. It can be any block type.
         let parent_hash = blockchain.head_hash();
 
-        // Calculate the seed for this block by signing the previous block seed with the validator
-        // key.
-        let prev_seed = blockchain.head().seed().clone();
-
         let skip_block_info = if skip_block_proof.is_some() {
             Some(SkipBlockInfo {
                 block_number,
-                vrf_entropy: prev_seed.entropy(),
+                vrf_entropy: blockchain.head().seed().entropy(),
             })
         } else {
             None
         };
 
-        let seed = if skip_block_proof.is_some() {
-            // VRF seed of a skip block is carried over since a new VRF seed would require a new
-            // leader.
-            prev_seed
-        } else {
-            prev_seed.sign_next_with_rng(&self.signing_key, rng)
-        };
-
         // Create the inherents from the fork proofs or skip block info.
         let inherents = blockchain.create_slash_inherents(&fork_proofs, skip_block_info, None);
 
@@ -166,20 +204,11 @@ impl BlockProducer {
             history_root,
         };
 
-        let justification = if let Some(skip_block_proof) = skip_block_proof {
-            MicroJustification::Skip(skip_block_proof)
-        } else {
-            // Signs the block header using the signing key.
-            let hash = header.hash::<Blake2bHash>();
-            let signature = self.signing_key.sign(hash.as_slice());
-            MicroJustification::Micro(signature)
-        };
-
         // Returns the micro block.
         MicroBlock {
             header,
             body: Some(body),
-            justification: Some(justification),
+            justification: skip_block_proof.map(MicroJustification::Skip),
         }
     }
 
@@ -223,6 +252,32 @@ impl BlockProducer {
         extra_data: Vec<u8>,
         // The rng seed. We need this parameterized in order to have determinism when running unit tests.
         rng: &mut R,
+    ) -> MacroBlock {
+        // Calculate the seed for this block by signing the previous block seed with the validator
+        // key.
+        let seed = blockchain
+            .head()
+            .seed()
+            .sign_next_with_rng(&self.signing_key, rng);
+
+        Self::next_macro_block_proposal_with_seed(blockchain, timestamp, round, seed, extra_data)
+    }
+
+    /// Creates a proposal for the next macro block (checkpoint or election) with the given VRF
+    /// seed. It is just a proposal, NOT a complete block. It still needs to go through the
+    /// Tendermint protocol in order to be finalized.
+    // Note: Needs to be called with the Blockchain lock held.
+    pub fn next_macro_block_proposal_with_seed(
+        // The (upgradable) read locked guard to the blockchain.
+        blockchain: &Blockchain,
+        // The timestamp for the block proposal.
+        timestamp: u64,
+        // The round for the block proposal.
+        round: u32,
+        // The VRF seed of the block proposal.
+        seed: VrfSeed,
+        // Extra data for this block.
+        extra_data: Vec<u8>,
     ) -> MacroBlock {
         // Calculate the block number. It is simply the previous block number incremented by one.
         let block_number = blockchain.block_number() + 1;
@@ -246,13 +301,6 @@ impl BlockProducer {
             None
         };
 
-        // Calculate the seed for this block by signing the previous block seed with the validator
-        // key.
-        let seed = blockchain
-            .head()
-            .seed()
-            .sign_next_with_rng(&self.signing_key, rng);
-
         // Create the header for the macro block without the state root and the transactions root.
         // We need several fields of this header in order to calculate the transactions and the
         // state.
@@ -59,6 +59,10 @@ pub enum BlockchainError {
     InvalidEpoch,
     #[error("Accounts diff not found")]
     AccountsDiffNotFound,
+    #[error("Accounts tree is incomplete")]
+    AccountsIncomplete,
+    #[error("Block {0} is outside of the stored history")]
+    BlockOutsideStoredHistory(u32),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1,11 +1,16 @@
+use std::cmp;
+
 use nimiq_account::{
-    Account, Accounts, BlockLogger, BlockState, RevertInfo, TransactionOperationReceipt,
+    Account, Accounts, BlockLogger, BlockState, HashedTimeLockedContract, Log, LogType, RevertInfo,
+    TransactionOperationReceipt, VestingContract,
 };
 use nimiq_block::{Block, BlockError, SkipBlockInfo};
-use nimiq_blockchain_interface::PushError;
+use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainError, Direction, PushError};
 use nimiq_database::{traits::Database, TransactionProxy};
+use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
 use nimiq_primitives::{
+    coin::Coin,
     key_nibbles::KeyNibbles,
     trie::{trie_diff::TrieDiff, trie_proof::TrieProof},
 };
@@ -15,6 +20,28 @@ use nimiq_trie::WriteTransactionProxy;
 
 use crate::{blockchain_state::BlockchainState, Blockchain};
 
+/// The maximum number of blocks whose logs are reverted at once when reconstructing an account at
+/// a past block.
+const ACCOUNT_AT_CHUNK_SIZE: u32 = 1_000;
+
+/// An account that is being reconstructed as it was right after a past block, see
+/// `Blockchain::start_account_at`.
+pub struct AccountAt {
+    address: Address,
+    /// The block at which the account is reconstructed.
+    block_number: u32,
+    /// The head at which the reconstruction was started.
+    head_hash: Blake2bHash,
+    /// The account as it was right after `to_block`, except for its balance.
+    account: Account,
+    /// The balance of the account right after `to_block`.
+    balance: Coin,
+    /// The last block whose changes have not been reverted yet.
+    to_block: u32,
+    /// The contract created at the address, once it has been looked up.
+    created_contract: Option<Option<Account>>,
+}
+
 /// Subset of the accounts in the accounts tree
 pub struct AccountsChunk {
     /// The end of the chunk. The end key is exclusive.
@@ -73,7 +100,16 @@ impl Blockchain {
                         .history_store
                         .add_to_history(txn.raw(), macro_block.epoch_number(), &ext_txs)
                         .expect("Failed to store history")
-                        .1
+                        .1;
+
+                    // Index the logs produced by the block.
+                    let block_log = block_logger.block_log();
+                    self.history_store.add_logs(
+                        txn.raw(),
+                        macro_block.header.block_number,
+                        block_log.transaction_logs(),
+                        block_log.inherent_logs(),
+                    );
                 };
 
                 Ok(total_tx_size)
@@ -149,7 +185,16 @@ impl Blockchain {
                         .history_store
                         .add_to_history(txn.raw(), micro_block.epoch_number(), &ext_txs)
                         .expect("Failed to store history")
-                        .1
+                        .1;
+
+                    // Index the logs produced by the block.
+                    let block_log = block_logger.block_log();
+                    self.history_store.add_logs(
+                        txn.raw(),
+                        micro_block.header.block_number,
+                        block_log.transaction_logs(),
+                        block_log.inherent_logs(),
+                    );
                 };
 
                 Ok(total_tx_size)
@@ -221,6 +266,8 @@ impl Blockchain {
             .history_store
             .remove_partial_history(txn.raw(), block.epoch_number(), num_txs)
             .expect("Failed to remove partial history");
+        self.history_store
+            .remove_logs(txn.raw(), block.block_number(), block.block_number());
 
         Ok(total_size)
     }
@@ -233,6 +280,309 @@ impl Blockchain {
         self.state().accounts.get_proof(Some(&txn), keys).ok()
     }
 
+    /// Starts reconstructing the account at the given address as it was right after the given
+    /// block. The account is taken from the current state, and the changes recorded in the log
+    /// index of the history store since the given block are then reverted chunk by chunk with
+    /// `revert_account_at_chunk`, so that the blockchain doesn't need to be locked during the
+    /// whole reconstruction.
+    /// Fails if the accounts tree is incomplete or if the given block is after the head.
+    pub fn start_account_at(
+        &self,
+        address: &Address,
+        block_number: u32,
+    ) -> Result<AccountAt, BlockchainError> {
+        let txn = self.read_transaction();
+
+        let account = self
+            .state()
+            .accounts
+            .get(address, Some(&txn))
+            .map_err(|_| BlockchainError::AccountsIncomplete)?;
+
+        let head = self.block_number();
+        if block_number > head {
+            return Err(BlockchainError::BlockOutsideStoredHistory(block_number));
+        }
+
+        Ok(AccountAt {
+            address: address.clone(),
+            block_number,
+            head_hash: self.head_hash(),
+            balance: account.balance(),
+            account,
+            to_block: head,
+            created_contract: None,
+        })
+    }
+
+    /// Reverts the changes of the next at most `ACCOUNT_AT_CHUNK_SIZE` blocks to the account that
+    /// is being reconstructed. Returns the account once all changes since the block it is
+    /// reconstructed at have been reverted.
+    /// Fails if the logs of any of the blocks are not in the log index, or if the head at which
+    /// the reconstruction was started is no longer on the main chain.
+    pub fn revert_account_at_chunk(
+        &self,
+        account_at: &mut AccountAt,
+    ) -> Result<Option<Account>, BlockchainError> {
+        let txn = self.read_transaction();
+
+        // The blocks up to the head at which we started must not have changed in between.
+        if !self
+            .chain_store
+            .get_chain_info(&account_at.head_hash, false, Some(&txn))?
+            .on_main_chain
+        {
+            return Err(BlockchainError::InconsistentState);
+        }
+
+        if account_at.to_block > account_at.block_number {
+            let from_block = cmp::max(
+                account_at.block_number + 1,
+                account_at
+                    .to_block
+                    .saturating_sub(ACCOUNT_AT_CHUNK_SIZE - 1),
+            );
+            if !self
+                .history_store
+                .has_logs_of_range(from_block, account_at.to_block, Some(&txn))
+            {
+                return Err(BlockchainError::BlockOutsideStoredHistory(
+                    account_at.block_number,
+                ));
+            }
+
+            let logs = self.history_store.get_logs(
+                from_block,
+                account_at.to_block,
+                &[account_at.address.clone()],
+                &[
+                    LogType::PayFee,
+                    LogType::Transfer,
+                    LogType::PayoutReward,
+                    LogType::HTLCCreate,
+                    LogType::VestingCreate,
+                ],
+                None,
+                usize::MAX,
+                Some(&txn),
+            );
+
+            // Revert the changes in reverse order.
+            for indexed_log in logs.iter().rev() {
+                self.revert_account_at_log(account_at, &indexed_log.log, &txn)?;
+            }
+            account_at.to_block = from_block - 1;
+        }
+
+        if account_at.to_block > account_at.block_number {
+            return Ok(None);
+        }
+
+        let mut account = account_at.account.clone();
+        match account {
+            Account::Basic(ref mut account) => account.balance = account_at.balance,
+            Account::Vesting(ref mut account) => account.balance = account_at.balance,
+            Account::HTLC(ref mut account) => account.balance = account_at.balance,
+            Account::Staking(ref mut account) => account.balance = account_at.balance,
+        }
+        Ok(Some(account))
+    }
+
+    /// Reconstructs the account at the given address as it was right after the given block, see
+    /// `start_account_at`. This keeps the blockchain locked during the whole reconstruction.
+    pub fn get_account_at(
+        &self,
+        address: &Address,
+        block_number: u32,
+    ) -> Result<Account, BlockchainError> {
+        let mut account_at = self.start_account_at(address, block_number)?;
+        loop {
+            if let Some(account) = self.revert_account_at_chunk(&mut account_at)? {
+                return Ok(account);
+            }
+        }
+    }
+
+    /// Reverts the change of a single log to the account that is being reconstructed.
+    fn revert_account_at_log(
+        &self,
+        account_at: &mut AccountAt,
+        log: &Log,
+        txn: &TransactionProxy,
+    ) -> Result<(), BlockchainError> {
+        let address = &account_at.address;
+
+        // Contracts are pruned once they are empty, so the account found in their place is a basic
+        // one. Since nobody can send from a contract address otherwise, an outgoing change means
+        // that the account still was the contract created at the address.
+        let is_outgoing = match log {
+            Log::PayFee { from, .. } | Log::Transfer { from, .. } => from == address,
+            _ => false,
+        };
+        if is_outgoing && matches!(account_at.account, Account::Basic(_)) {
+            if account_at.created_contract.is_none() {
+                account_at.created_contract = Some(self.get_created_contract(address, txn));
+            }
+            if let Some(Some(contract)) = &account_at.created_contract {
+                account_at.account = contract.clone();
+            }
+        }
+
+        let balance = account_at.balance;
+        account_at.balance = match log {
+            Log::PayFee { from, fee } if from == address => balance.checked_add(*fee),
+            Log::Transfer {
+                from, to, amount, ..
+            } => {
+                let mut balance = Some(balance);
+                if to == address {
+                    balance = balance.and_then(|balance| balance.checked_sub(*amount));
+                }
+                if from == address {
+                    balance = balance.and_then(|balance| balance.checked_add(*amount));
+                }
+                balance
+            }
+            Log::PayoutReward { to, value } if to == address => balance.checked_sub(*value),
+            // Before its creation, there was no contract at the address.
+            Log::HTLCCreate {
+                contract_address, ..
+            }
+            | Log::VestingCreate {
+                contract_address, ..
+            } if contract_address == address => {
+                account_at.account = Account::default();
+                Some(balance)
+            }
+            _ => Some(balance),
+        }
+        .ok_or(BlockchainError::InconsistentState)?;
+
+        Ok(())
+    }
+
+    /// Returns the contract that was created at the given address, with a balance of zero, or
+    /// None if there was never a contract at the address. The address of a contract is derived
+    /// from the transaction creating it, which is thus the first transaction of the address.
+    fn get_created_contract(&self, address: &Address, txn: &TransactionProxy) -> Option<Account> {
+        let tx_hash = self
+            .history_store
+            .get_tx_hashes_by_address(address, 1, None, Direction::Forward, Some(txn))
+            .pop()?;
+        let block_number = self
+            .history_store
+            .get_ext_tx_by_hash(&tx_hash, Some(txn))
+            .first()?
+            .block_number;
+
+        self.history_store
+            .get_logs(
+                block_number,
+                block_number,
+                &[address.clone()],
+                &[LogType::HTLCCreate, LogType::VestingCreate],
+                None,
+                usize::MAX,
+                Some(txn),
+            )
+            .into_iter()
+            .find_map(|indexed_log| match indexed_log.log {
+                Log::HTLCCreate {
+                    contract_address,
+                    sender,
+                    recipient,
+                    hash_root,
+                    hash_count,
+                    timeout,
+                    total_amount,
+                } if contract_address == *address => {
+                    Some(Account::HTLC(HashedTimeLockedContract {
+                        balance: Coin::ZERO,
+                        sender,
+                        recipient,
+                        hash_root,
+                        hash_count,
+                        timeout,
+                        total_amount,
+                    }))
+                }
+                Log::VestingCreate {
+                    contract_address,
+                    owner,
+                    start_time,
+                    time_step,
+                    step_amount,
+                    total_amount,
+                } if contract_address == *address => Some(Account::Vesting(VestingContract {
+                    balance: Coin::ZERO,
+                    owner,
+                    start_time,
+                    time_step,
+                    step_amount,
+                    total_amount,
+                })),
+                _ => None,
+            })
+    }
+
+    /// Reverts the given copy of the accounts tree to its state right after the given block of the
+    /// main chain. The copy must hold the state of the head block as seen by the given read
+    /// transaction, from which the blocks and the information needed to revert them are read.
+    /// The blockchain itself is not modified. Only the micro blocks after the last macro block can
+    /// be reverted, since the information needed to revert a block is discarded once its batch is
+    /// finalized.
+    pub fn revert_accounts_copy_to(
+        &self,
+        txn: &TransactionProxy,
+        accounts: &Accounts,
+        accounts_txn: &mut WriteTransactionProxy,
+        block_number: u32,
+    ) -> Result<(), BlockchainError> {
+        let head_hash = self
+            .chain_store
+            .get_head(Some(txn))
+            .ok_or(BlockchainError::InconsistentState)?;
+        let head = self.chain_store.get_block(&head_hash, false, Some(txn))?;
+        if block_number > head.block_number() {
+            return Err(BlockchainError::BlockOutsideStoredHistory(block_number));
+        }
+
+        for current in (block_number + 1..=head.block_number()).rev() {
+            let block = match self.chain_store.get_block_at(current, true, Some(txn))? {
+                Block::Micro(block) => block,
+                Block::Macro(_) => {
+                    return Err(BlockchainError::BlockOutsideStoredHistory(block_number))
+                }
+            };
+            let body = block
+                .body
+                .as_ref()
+                .ok_or(BlockchainError::InconsistentState)?;
+            let revert_info = self
+                .chain_store
+                .get_revert_info(current, Some(txn))
+                .ok_or(BlockchainError::BlockOutsideStoredHistory(block_number))?;
+
+            let skip_block_info = SkipBlockInfo::from_micro_block(&block);
+            let inherents =
+                self.create_slash_inherents(&body.fork_proofs, skip_block_info, Some(txn));
+
+            let block_state = BlockState::new(current, block.header.timestamp);
+            accounts
+                .revert(
+                    accounts_txn,
+                    &body.get_raw_transactions(),
+                    &inherents,
+                    &block_state,
+                    revert_info,
+                    &mut BlockLogger::empty(),
+                )
+                .map_err(|_| BlockchainError::InconsistentState)?;
+        }
+
+        Ok(())
+    }
+
     /// Gets an accounts chunk given a start key and a limit
     pub fn get_accounts_chunk(
         &self,
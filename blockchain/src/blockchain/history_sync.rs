@@ -203,12 +203,13 @@ impl Blockchain {
 
             // Commit block to AccountsTree and create the receipts.
             let block_state = BlockState::new(block_numbers[i], block_timestamps[i]);
+            let mut block_logger = BlockLogger::empty();
             let receipts = this.state.accounts.commit_batch(
                 &mut (&mut txn).into(),
                 &txns,
                 &block_inherents[i],
                 &block_state,
-                &mut BlockLogger::empty(),
+                &mut block_logger,
             );
 
             // Check if the receipts contain an error.
@@ -228,6 +229,15 @@ impl Blockchain {
                 this.metrics.note_invalid_block();
                 return Err(PushError::AccountsError(e));
             }
+
+            // Index the logs produced by the block.
+            let block_log = block_logger.block_log();
+            this.history_store.add_logs(
+                &mut txn,
+                block_numbers[i],
+                block_log.transaction_logs(),
+                block_log.inherent_logs(),
+            );
         }
         this.state.accounts.finalize_batch(&mut (&mut txn).into());
 
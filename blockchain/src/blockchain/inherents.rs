@@ -6,7 +6,7 @@ use nimiq_primitives::{account::AccountType, coin::Coin, policy::Policy, slots::
 use nimiq_transaction::{inherent::Inherent, reward::RewardTransaction};
 use nimiq_vrf::{AliasMethod, VrfUseCase};
 
-use crate::{blockchain_state::BlockchainState, reward::block_reward_for_batch, Blockchain};
+use crate::{blockchain_state::BlockchainState, reward::reward_pot_for_batch, Blockchain};
 
 /// Implements methods that create inherents.
 impl Blockchain {
@@ -179,18 +179,14 @@ impl Blockchain {
         };
         let slashed_set = lost_rewards_set | disabled_set;
 
-        // Total reward for the previous batch
-        let block_reward = block_reward_for_batch(
+        // Total reward for the previous batch, including the transaction fees
+        let reward_pot = reward_pot_for_batch(
             macro_header,
-            &prev_macro_info.head.unwrap_macro_ref().header,
+            prev_macro_info,
             self.genesis_supply,
             self.genesis_timestamp,
         );
 
-        let tx_fees = prev_macro_info.cum_tx_fees;
-
-        let reward_pot = block_reward + tx_fees;
-
         // Distribute reward between all slots and calculate the remainder
         let slot_reward = reward_pot / Policy::SLOTS as u64;
         let remainder = reward_pot % Policy::SLOTS as u64;
@@ -204,6 +200,9 @@ impl Blockchain {
         // All accepted inherents.
         let mut transactions = Vec::new();
 
+        // The inherents distributing the rewards of validators to their stakers.
+        let mut staker_transactions = Vec::new();
+
         // Remember the number of eligible slots that a validator had (that was able to accept the inherent)
         let mut num_eligible_slots_for_accepted_tx = Vec::new();
 
@@ -254,11 +253,25 @@ impl Blockchain {
                 .get_validator(&data_store.read(&txn), &validator_slot.address)
                 .expect("Couldn't find validator in the accounts trie when paying rewards!");
 
-            let tx = RewardTransaction {
-                recipient: validator.reward_address.clone(),
-                value: reward,
+            // If the validator distributes its rewards, the stakers' share of the reward is paid to
+            // the staking contract. The stakers claim it from the reward pool of the validator.
+            let stakers_reward = staking_contract
+                .get_reward_pool(&data_store.read(&txn), &validator_slot.address)
+                .map(|pool| pool.stakers_share(reward, validator.deposit, macro_header.block_number))
+                .unwrap_or(Coin::ZERO);
+
+            let reward = if stakers_reward.is_zero() {
+                reward
+            } else {
+                staker_transactions.push(RewardTransaction::new_staker_rewards(
+                    validator_slot.address.clone(),
+                    stakers_reward,
+                ));
+                reward - stakers_reward
             };
 
+            let tx = RewardTransaction::new(validator.reward_address.clone(), reward);
+
             // Test whether account will accept inherent. If it can't then the reward will be
             // burned.
             // TODO Improve this check: it assumes that only BasicAccounts can receive transactions.
@@ -293,12 +306,12 @@ impl Blockchain {
         let index = lookup.sample(&mut rng);
         transactions[index].value += remainder;
 
+        // The rewards of the stakers are paid after the rewards of the validators.
+        transactions.append(&mut staker_transactions);
+
         // Create the inherent for the burned reward.
         if burned_reward > Coin::ZERO {
-            let tx = RewardTransaction {
-                recipient: Address::burn_address(),
-                value: burned_reward,
-            };
+            let tx = RewardTransaction::new(Address::burn_address(), burned_reward);
 
             transactions.push(tx);
         }
@@ -74,7 +74,7 @@ impl Blockchain {
         let disabled_slots = macro_block.unwrap_macro().body.unwrap().disabled_set;
 
         // Compute the slot number of the next proposer.
-        let slot_number = Self::compute_slot_number(offset, vrf_entropy, disabled_slots);
+        let slot_number = Self::compute_slot_number(offset, vrf_entropy, &disabled_slots);
 
         // Fetch the validators that are active in given block's epoch.
         let epoch_number = Policy::epoch_at(block_number);
@@ -93,7 +93,14 @@ impl Blockchain {
         })
     }
 
-    fn compute_slot_number(offset: u32, vrf_entropy: VrfEntropy, disabled_slots: BitSet) -> u16 {
+    /// Computes the number of the proposer slot for the given offset and VRF entropy, skipping
+    /// the disabled slots. This allows to compute the proposers of many blocks of a batch without
+    /// fetching its preceding macro block again for every block, see `get_proposer_at`.
+    pub fn compute_slot_number(
+        offset: u32,
+        vrf_entropy: VrfEntropy,
+        disabled_slots: &BitSet,
+    ) -> u16 {
         // RNG for slot selection
         let mut rng = vrf_entropy.rng(VrfUseCase::ViewSlotSelection);
 
@@ -3,9 +3,11 @@ use std::{
     collections::{HashSet, VecDeque},
 };
 
+use nimiq_account::{Log, LogType, TransactionLog};
+use nimiq_blockchain_interface::Direction;
 use nimiq_database::{
     traits::{Database, ReadCursor, ReadTransaction, WriteCursor, WriteTransaction},
-    DatabaseProxy, TableFlags, TableProxy, TransactionProxy, WriteTransactionProxy,
+    CursorProxy, DatabaseProxy, TableFlags, TableProxy, TransactionProxy, WriteTransactionProxy,
 };
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
@@ -28,7 +30,12 @@ use nimiq_transaction::{
     inherent::Inherent,
 };
 
-use crate::history::{mmr_store::MMRStore, ordered_hash::OrderedHash, HistoryTreeChunk};
+use crate::history::{
+    indexed_log::{IndexedLog, LogIndexKey, LogPosition},
+    mmr_store::MMRStore,
+    ordered_hash::OrderedHash,
+    HistoryTreeChunk,
+};
 
 /// A struct that contains databases to store history trees (which are Merkle Mountain Ranges
 /// constructed from the list of extended transactions in an epoch) and extended transactions (which
@@ -52,6 +59,10 @@ pub struct HistoryStore {
     /// A database of all transaction (and reward inherent) hashes indexed by their sender and
     /// recipient addresses.
     address_table: TableProxy,
+    /// A database of all logs indexed by their log type, block number and position within the block.
+    log_table: TableProxy,
+    /// A database of the number of logs of each block whose logs have been added to the log index.
+    num_logs_table: TableProxy,
 }
 
 impl HistoryStore {
@@ -60,6 +71,8 @@ impl HistoryStore {
     const TX_HASH_DB_NAME: &'static str = "LeafHashesByTxHash";
     const LAST_LEAF_DB_NAME: &'static str = "LastLeafIndexesByBlock";
     const ADDRESS_DB_NAME: &'static str = "TxHashesByAddress";
+    const LOG_DB_NAME: &'static str = "LogsByTypeAndBlock";
+    const NUM_LOGS_DB_NAME: &'static str = "NumLogsByBlock";
 
     /// Creates a new HistoryStore.
     pub fn new(db: DatabaseProxy) -> Self {
@@ -74,6 +87,8 @@ impl HistoryStore {
             Self::ADDRESS_DB_NAME.to_string(),
             TableFlags::DUPLICATE_KEYS | TableFlags::DUP_FIXED_SIZE_VALUES,
         );
+        let log_table = db.open_table(Self::LOG_DB_NAME.to_string());
+        let num_logs_table = db.open_table(Self::NUM_LOGS_DB_NAME.to_string());
 
         HistoryStore {
             db,
@@ -82,6 +97,8 @@ impl HistoryStore {
             tx_hash_table,
             last_leaf_table,
             address_table,
+            log_table,
+            num_logs_table,
         }
     }
 
@@ -91,6 +108,8 @@ impl HistoryStore {
         txn.clear_database(&self.tx_hash_table);
         txn.clear_database(&self.last_leaf_table);
         txn.clear_database(&self.address_table);
+        txn.clear_database(&self.log_table);
+        txn.clear_database(&self.num_logs_table);
     }
 
     /// Returns the length (i.e. the number of leaves) of the History Tree at a given block height.
@@ -257,11 +276,17 @@ impl HistoryStore {
                     affected_addresses.insert(tx.sender.clone());
                     affected_addresses.insert(tx.recipient.clone());
                 }
-                ExtTxData::Inherent(tx) => {
-                    if let Inherent::Reward { target, .. } = tx {
+                ExtTxData::Inherent(tx) => match tx {
+                    Inherent::Reward { target, .. } => {
                         affected_addresses.insert(target.clone());
                     }
-                }
+                    Inherent::DistributeReward {
+                        validator_address, ..
+                    } => {
+                        affected_addresses.insert(validator_address.clone());
+                    }
+                    _ => {}
+                },
             }
         }
 
@@ -347,9 +372,209 @@ impl HistoryStore {
 
         self.remove_txns_from_history(txn, hashes);
 
+        // Remove the logs of all blocks in the epoch.
+        if let (Some(first_block), Some(last_block)) = (
+            Policy::first_block_of(epoch_number),
+            Policy::election_block_of(epoch_number),
+        ) {
+            self.remove_logs(txn, first_block, last_block);
+        }
+
         Some(())
     }
 
+    /// Adds the logs produced by the given block to the log index. Transaction logs are stored
+    /// before the inherent logs, each in the order they were produced.
+    pub fn add_logs(
+        &self,
+        txn: &mut WriteTransactionProxy,
+        block_number: u32,
+        tx_logs: &[TransactionLog],
+        inherent_logs: &[Log],
+    ) {
+        let tx_logs = tx_logs.iter().flat_map(|tx_log| {
+            tx_log
+                .logs
+                .iter()
+                .map(|log| (Some(tx_log.tx_hash.clone()), log))
+        });
+        let inherent_logs = inherent_logs.iter().map(|log| (None, log));
+
+        let mut num_logs = 0u32;
+        for (tx_hash, log) in tx_logs.chain(inherent_logs) {
+            txn.put_reserve(
+                &self.log_table,
+                &LogIndexKey::new(log.log_type(), block_number, num_logs),
+                &IndexedLog {
+                    block_number,
+                    index: num_logs,
+                    tx_hash,
+                    log: log.clone(),
+                },
+            );
+            num_logs += 1;
+        }
+
+        // We need to convert the block number to big-endian since that's how the LMDB database
+        // orders the keys.
+        txn.put(&self.num_logs_table, &block_number.to_be(), &num_logs);
+    }
+
+    /// Removes the logs of all blocks between `from_block` and `to_block` (both inclusive) from
+    /// the log index.
+    pub fn remove_logs(&self, txn: &mut WriteTransactionProxy, from_block: u32, to_block: u32) {
+        let mut keys = vec![];
+
+        for log_type in LogType::ALL {
+            let mut cursor = WriteTransaction::cursor(txn, &self.log_table);
+            let mut entry = cursor
+                .seek_range_key::<LogIndexKey, Vec<u8>>(&LogIndexKey::new(log_type, from_block, 0));
+
+            while let Some((key, _)) = entry {
+                if key.log_type != log_type as u8 || key.block_number > to_block {
+                    break;
+                }
+                keys.push(key);
+                entry = cursor.next::<LogIndexKey, Vec<u8>>();
+            }
+        }
+
+        for key in keys {
+            txn.remove(&self.log_table, &key);
+        }
+
+        let mut block_numbers = vec![];
+        {
+            let mut cursor = WriteTransaction::cursor(txn, &self.num_logs_table);
+            let mut entry = cursor.seek_range_key::<u32, u32>(&from_block.to_be());
+
+            while let Some((block_number, _)) = entry {
+                if u32::from_be(block_number) > to_block {
+                    break;
+                }
+                block_numbers.push(block_number);
+                entry = cursor.next::<u32, u32>();
+            }
+        }
+
+        for block_number in block_numbers {
+            txn.remove(&self.num_logs_table, &block_number);
+        }
+    }
+
+    /// Returns true if the logs of all blocks between `from_block` and `to_block` (both inclusive)
+    /// are in the log index. An empty range is always indexed.
+    pub fn has_logs_of_range(
+        &self,
+        from_block: u32,
+        to_block: u32,
+        txn_option: Option<&TransactionProxy>,
+    ) -> bool {
+        let read_txn: TransactionProxy;
+        let txn = match txn_option {
+            Some(txn) => txn,
+            None => {
+                read_txn = self.db.read_transaction();
+                &read_txn
+            }
+        };
+
+        // Every block of the range must have an entry, without any gaps.
+        let mut cursor = txn.cursor(&self.num_logs_table);
+        let mut entry = cursor.seek_range_key::<u32, u32>(&from_block.to_be());
+        for expected_block in from_block..=to_block {
+            match entry {
+                Some((block_number, _)) if u32::from_be(block_number) == expected_block => {}
+                _ => return false,
+            }
+            entry = cursor.next::<u32, u32>();
+        }
+
+        true
+    }
+
+    /// Returns the logs produced by the blocks between `from_block` and `to_block` (both
+    /// inclusive) ordered by their position, i.e. by block number and position within the block.
+    /// Only logs of the given types that are related to any of the given addresses are returned.
+    /// If no log types are given, logs of all types are returned. Likewise, if no addresses are
+    /// given, logs are returned regardless of the addresses they relate to.
+    /// At most `max` logs are returned. If `start_after` is given, only logs positioned after it
+    /// are returned, which allows to page through the logs of a range.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_logs(
+        &self,
+        from_block: u32,
+        to_block: u32,
+        addresses: &[Address],
+        log_types: &[LogType],
+        start_after: Option<LogPosition>,
+        max: usize,
+        txn_option: Option<&TransactionProxy>,
+    ) -> Vec<IndexedLog> {
+        let read_txn: TransactionProxy;
+        let txn = match txn_option {
+            Some(txn) => txn,
+            None => {
+                read_txn = self.db.read_transaction();
+                &read_txn
+            }
+        };
+
+        let mut log_types = if log_types.is_empty() {
+            LogType::ALL.to_vec()
+        } else {
+            log_types.to_vec()
+        };
+        log_types.sort();
+        log_types.dedup();
+
+        // Start at the given position if it lies within the range.
+        let start = match start_after {
+            Some(position) if position.block_number >= from_block => position,
+            _ => LogPosition {
+                block_number: from_block,
+                index: 0,
+            },
+        };
+
+        let mut logs = vec![];
+
+        for log_type in log_types {
+            let mut cursor = txn.cursor(&self.log_table);
+            let mut entry = cursor.seek_range_key::<LogIndexKey, IndexedLog>(&LogIndexKey::new(
+                log_type,
+                start.block_number,
+                start.index,
+            ));
+
+            // Logs of the same type are ordered by their position, so we can stop once we have
+            // found `max` logs of this type.
+            let mut num_logs = 0;
+            while let Some((key, indexed_log)) = entry {
+                if key.log_type != log_type as u8 || key.block_number > to_block || num_logs == max
+                {
+                    break;
+                }
+                if start_after.map_or(true, |position| indexed_log.position() > position)
+                    && (addresses.is_empty()
+                        || addresses
+                            .iter()
+                            .any(|address| indexed_log.log.is_related_to_address(address)))
+                {
+                    logs.push(indexed_log);
+                    num_logs += 1;
+                }
+                entry = cursor.next::<LogIndexKey, IndexedLog>();
+            }
+        }
+
+        // Restore the order in which the logs were produced.
+        logs.sort_by_key(IndexedLog::position);
+        logs.truncate(max);
+
+        logs
+    }
+
     /// Gets the history tree root for a given epoch.
     pub fn get_history_tree_root(
         &self,
@@ -661,13 +886,18 @@ impl HistoryStore {
         ext_txs.into()
     }
 
-    /// Returns a vector containing all transaction (and reward inherents) hashes corresponding to the given
-    /// address. It fetches the transactions from most recent to least recent up to the maximum
-    /// number given.
+    /// Returns a vector containing the transaction (and reward inherents) hashes corresponding to
+    /// the given address, up to the maximum number given.
+    /// The hashes are returned from the most recent to the least recent transaction when going
+    /// backward and the other way round when going forward. If `start_after` is given, the hashes start
+    /// right after that transaction hash, which allows to page through the whole history of an
+    /// address. If `start_after` is not a transaction of the given address, no hashes are returned.
     pub fn get_tx_hashes_by_address(
         &self,
         address: &Address,
         max: u16,
+        start_after: Option<&Blake2bHash>,
+        direction: Direction,
         txn_option: Option<&TransactionProxy>,
     ) -> Vec<Blake2bHash> {
         if max == 0 {
@@ -692,21 +922,147 @@ impl HistoryStore {
             return tx_hashes;
         }
 
-        // Then go to the last transaction hash at the given address and add it to the transaction
-        // hashes list.
-        tx_hashes.push(cursor.last_duplicate::<OrderedHash>().expect("This shouldn't panic since we already verified before that there is at least one transactions at this address!").hash);
+        // Moves the cursor to the next transaction hash in the given direction.
+        let step = |cursor: &mut CursorProxy| match direction {
+            Direction::Backward => cursor
+                .prev_duplicate::<Address, OrderedHash>()
+                .map(|(_, v)| v),
+            Direction::Forward => cursor
+                .next_duplicate::<Address, OrderedHash>()
+                .map(|(_, v)| v),
+        };
+
+        // Position the cursor on the first transaction hash to return.
+        let mut entry = match start_after {
+            None => match direction {
+                Direction::Backward => cursor.last_duplicate::<OrderedHash>(),
+                Direction::Forward => cursor.first_duplicate::<OrderedHash>(),
+            },
+            Some(start_after) => {
+                if !self.seek_tx_hash_of_address(&mut cursor, address, start_after, txn) {
+                    return tx_hashes;
+                }
+                step(&mut cursor)
+            }
+        };
 
-        while tx_hashes.len() < max as usize {
-            // Get previous transaction hash.
-            match cursor.prev_duplicate::<Address, OrderedHash>() {
-                Some((_, v)) => tx_hashes.push(v.hash),
-                None => break,
-            };
+        while let Some(v) = entry {
+            tx_hashes.push(v.hash);
+
+            if tx_hashes.len() >= max as usize {
+                break;
+            }
+            entry = step(&mut cursor);
         }
 
         tx_hashes
     }
 
+    /// Positions the cursor, which must be on the transaction hashes of the given address, at the
+    /// given transaction hash. Returns false if the hash is not among the transaction hashes of the
+    /// address.
+    /// The hashes of an address are ordered by the order in which they were added to the history,
+    /// so we binary search for the first hash from the same block as the given one using its
+    /// block number. This way we only need to look at the hashes of that block.
+    fn seek_tx_hash_of_address(
+        &self,
+        cursor: &mut CursorProxy,
+        address: &Address,
+        tx_hash: &Blake2bHash,
+        txn: &TransactionProxy,
+    ) -> bool {
+        let block_number_of = |tx_hash: &Blake2bHash| {
+            self.get_ext_tx_by_hash(tx_hash, Some(txn))
+                .first()
+                .map(|ext_tx| ext_tx.block_number)
+        };
+
+        let block_number = match block_number_of(tx_hash) {
+            Some(block_number) => block_number,
+            None => return false,
+        };
+
+        let (first, last) = match (
+            cursor.first_duplicate::<OrderedHash>(),
+            cursor.last_duplicate::<OrderedHash>(),
+        ) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return false,
+        };
+        if block_number_of(&last.hash) < Some(block_number) {
+            return false;
+        }
+
+        // Find the smallest index whose first hash at or after it is from the block of the given
+        // hash or a later one.
+        let mut low = first.index;
+        let mut high = last.index;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let entry = cursor
+                .seek_key_range_value(
+                    address,
+                    &OrderedHash {
+                        index: mid,
+                        hash: Blake2bHash::default(),
+                    },
+                )
+                .expect("There must be a hash at or after the last one");
+            if block_number_of(&entry.hash) < Some(block_number) {
+                low = entry.index + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        // Walk the hashes of the block until we find the given one.
+        let mut entry = cursor.seek_key_range_value(
+            address,
+            &OrderedHash {
+                index: low,
+                hash: Blake2bHash::default(),
+            },
+        );
+        while let Some(v) = entry {
+            if v.hash == *tx_hash {
+                return true;
+            }
+            if block_number_of(&v.hash) != Some(block_number) {
+                return false;
+            }
+            entry = cursor
+                .next_duplicate::<Address, OrderedHash>()
+                .map(|(_, v)| v);
+        }
+
+        false
+    }
+
+    /// Returns the total number of transaction (and reward inherents) hashes corresponding to the
+    /// given address.
+    pub fn num_tx_hashes_by_address(
+        &self,
+        address: &Address,
+        txn_option: Option<&TransactionProxy>,
+    ) -> usize {
+        let read_txn: TransactionProxy;
+        let txn = match txn_option {
+            Some(txn) => txn,
+            None => {
+                read_txn = self.db.read_transaction();
+                &read_txn
+            }
+        };
+
+        let mut cursor = txn.cursor(&self.address_table);
+
+        if cursor.seek_key::<Address, OrderedHash>(address).is_none() {
+            return 0;
+        }
+
+        cursor.count_duplicates()
+    }
+
     /// Returns a proof for transactions with the given hashes. The proof also includes the extended
     /// transactions.
     /// The verifier state is used for those cases where the verifier might have an incomplete MMR,
@@ -948,8 +1304,16 @@ impl HistoryStore {
                 );
             }
             ExtTxData::Inherent(tx) => {
-                // We only add reward inherents to the address database.
-                if let Inherent::Reward { target, .. } = tx {
+                // We only add reward inherents to the address database. Rewards distributed to
+                // the stakers of a validator are added to the address of the validator.
+                let target = match tx {
+                    Inherent::Reward { target, .. } => Some(target),
+                    Inherent::DistributeReward {
+                        validator_address, ..
+                    } => Some(validator_address),
+                    _ => None,
+                };
+                if let Some(target) = target {
                     let index_tx_recipient =
                         self.get_last_tx_index_for_address(target, Some(txn)) + 1;
 
@@ -1482,6 +1846,8 @@ mod tests {
             &Address::from_user_friendly_address("NQ09 VF5Y 1PKV MRM4 5LE1 55KV P6R2 GXYJ XYQF")
                 .unwrap(),
             99,
+            None,
+            Direction::Backward,
             Some(&txn),
         );
 
@@ -1492,8 +1858,13 @@ mod tests {
         assert_eq!(query_1[3], ext_txs[1].tx_hash());
         assert_eq!(query_1[4], ext_txs[0].tx_hash());
 
-        let query_2 =
-            history_store.get_tx_hashes_by_address(&Address::burn_address(), 2, Some(&txn));
+        let query_2 = history_store.get_tx_hashes_by_address(
+            &Address::burn_address(),
+            2,
+            None,
+            Direction::Backward,
+            Some(&txn),
+        );
 
         assert_eq!(query_2.len(), 2);
         assert_eq!(query_2[0], ext_txs[6].tx_hash());
@@ -1503,6 +1874,8 @@ mod tests {
             &Address::from_user_friendly_address("NQ04 B79B R4FF 4NGU A9H0 2PT9 9ART 5A88 J73T")
                 .unwrap(),
             99,
+            None,
+            Direction::Backward,
             Some(&txn),
         );
 
@@ -1515,12 +1888,200 @@ mod tests {
             &Address::from_user_friendly_address("NQ28 1U7R M38P GN5A 7J8R GE62 8QS7 PK2S 4S31")
                 .unwrap(),
             99,
+            None,
+            Direction::Backward,
             Some(&txn),
         );
 
         assert_eq!(query_4.len(), 0);
     }
 
+    #[test]
+    fn get_tx_hashes_by_address_paginates() {
+        // Initialize History Store.
+        let env = VolatileDatabase::new(20).unwrap();
+        let history_store = HistoryStore::new(env.clone());
+
+        // Create extended transactions.
+        let ext_txs = gen_ext_txs();
+
+        // Add extended transactions to History Store.
+        let mut txn = env.write_transaction();
+        history_store.add_to_history(&mut txn, 0, &ext_txs[..3]);
+        history_store.add_to_history(&mut txn, 1, &ext_txs[3..]);
+
+        let address =
+            Address::from_user_friendly_address("NQ09 VF5Y 1PKV MRM4 5LE1 55KV P6R2 GXYJ XYQF")
+                .unwrap();
+
+        assert_eq!(
+            history_store.num_tx_hashes_by_address(&address, Some(&txn)),
+            5
+        );
+
+        // Walk the history from newest to oldest in pages of two.
+        let page_1 = history_store.get_tx_hashes_by_address(
+            &address,
+            2,
+            None,
+            Direction::Backward,
+            Some(&txn),
+        );
+        assert_eq!(page_1, vec![ext_txs[6].tx_hash(), ext_txs[5].tx_hash()]);
+
+        let page_2 = history_store.get_tx_hashes_by_address(
+            &address,
+            2,
+            page_1.last(),
+            Direction::Backward,
+            Some(&txn),
+        );
+        assert_eq!(page_2, vec![ext_txs[3].tx_hash(), ext_txs[1].tx_hash()]);
+
+        let page_3 = history_store.get_tx_hashes_by_address(
+            &address,
+            2,
+            page_2.last(),
+            Direction::Backward,
+            Some(&txn),
+        );
+        assert_eq!(page_3, vec![ext_txs[0].tx_hash()]);
+
+        // Walk the history from oldest to newest.
+        let page_1 = history_store.get_tx_hashes_by_address(
+            &address,
+            3,
+            None,
+            Direction::Forward,
+            Some(&txn),
+        );
+        assert_eq!(
+            page_1,
+            vec![
+                ext_txs[0].tx_hash(),
+                ext_txs[1].tx_hash(),
+                ext_txs[3].tx_hash()
+            ]
+        );
+
+        let page_2 = history_store.get_tx_hashes_by_address(
+            &address,
+            3,
+            page_1.last(),
+            Direction::Forward,
+            Some(&txn),
+        );
+        assert_eq!(page_2, vec![ext_txs[5].tx_hash(), ext_txs[6].tx_hash()]);
+
+        // Walk the history one hash at a time.
+        let mut tx_hashes = vec![];
+        let mut start_after = None;
+        loop {
+            let page = history_store.get_tx_hashes_by_address(
+                &address,
+                1,
+                start_after.as_ref(),
+                Direction::Forward,
+                Some(&txn),
+            );
+            match page.first() {
+                Some(tx_hash) => start_after = Some(tx_hash.clone()),
+                None => break,
+            }
+            tx_hashes.extend(page);
+        }
+        assert_eq!(
+            tx_hashes,
+            vec![
+                ext_txs[0].tx_hash(),
+                ext_txs[1].tx_hash(),
+                ext_txs[3].tx_hash(),
+                ext_txs[5].tx_hash(),
+                ext_txs[6].tx_hash()
+            ]
+        );
+
+        // Unknown start hashes yield no results.
+        assert!(history_store
+            .get_tx_hashes_by_address(
+                &address,
+                3,
+                Some(&ext_txs[2].tx_hash()),
+                Direction::Forward,
+                Some(&txn),
+            )
+            .is_empty());
+    }
+
+    #[test]
+    fn get_tx_hashes_by_address_pages_through_large_history() {
+        // Initialize History Store.
+        let env = VolatileDatabase::new(20).unwrap();
+        let history_store = HistoryStore::new(env.clone());
+
+        let address =
+            Address::from_user_friendly_address("NQ09 VF5Y 1PKV MRM4 5LE1 55KV P6R2 GXYJ XYQF")
+                .unwrap();
+
+        // Add transactions of the address to several blocks of several epochs, interleaved with
+        // inherents for another address.
+        let mut txn = env.write_transaction();
+        let mut expected = vec![];
+        let mut value = 0;
+        for epoch_number in 0..4 {
+            let mut ext_txs = vec![];
+            for block_number in epoch_number * 10..epoch_number * 10 + 5 {
+                for _ in 0..3 {
+                    let ext_tx = create_transaction(block_number, value);
+                    expected.push(ext_tx.tx_hash());
+                    ext_txs.push(ext_tx);
+                    value += 1;
+                }
+                ext_txs.push(create_inherent(block_number, value));
+                value += 1;
+            }
+            history_store.add_to_history(&mut txn, epoch_number, &ext_txs);
+        }
+        assert_eq!(expected.len(), 60);
+        assert_eq!(
+            history_store.num_tx_hashes_by_address(&address, Some(&txn)),
+            expected.len()
+        );
+
+        // Pages through the whole history of the address in the given direction.
+        let page_through = |max: u16, direction: Direction| {
+            let mut tx_hashes = vec![];
+            loop {
+                let page = history_store.get_tx_hashes_by_address(
+                    &address,
+                    max,
+                    tx_hashes.last(),
+                    direction,
+                    Some(&txn),
+                );
+                assert!(page.len() <= max as usize);
+                if page.is_empty() {
+                    break;
+                }
+                tx_hashes.extend(page);
+            }
+            tx_hashes
+        };
+
+        for max in [1, 7, 60, 100] {
+            // Forward, the hashes are returned in the order they were added, without
+            // duplicates or gaps.
+            let forward = page_through(max, Direction::Forward);
+            assert_eq!(forward, expected);
+            assert_eq!(forward.iter().collect::<HashSet<_>>().len(), expected.len());
+
+            // Backward, the same hashes are returned in reverse order.
+            let mut backward = page_through(max, Direction::Backward);
+            backward.reverse();
+            assert_eq!(backward, expected);
+        }
+    }
+
     #[test]
     fn prove_works() {
         // Initialize History Store.
@@ -1650,6 +2211,288 @@ mod tests {
         }
     }
 
+    #[test]
+    fn get_logs_works() {
+        // Initialize History Store.
+        let env = VolatileDatabase::new(20).unwrap();
+        let history_store = HistoryStore::new(env.clone());
+
+        let sender =
+            Address::from_user_friendly_address("NQ09 VF5Y 1PKV MRM4 5LE1 55KV P6R2 GXYJ XYQF")
+                .unwrap();
+        let validator =
+            Address::from_user_friendly_address("NQ04 B79B R4FF 4NGU A9H0 2PT9 9ART 5A88 J73T")
+                .unwrap();
+
+        // Add the logs of three blocks.
+        let mut txn = env.write_transaction();
+        for block_number in 1..=3 {
+            let tx_log = TransactionLog::new(
+                Blake2bHash::default(),
+                vec![
+                    Log::PayFee {
+                        from: sender.clone(),
+                        fee: Coin::from_u64_unchecked(1),
+                    },
+                    Log::Transfer {
+                        from: sender.clone(),
+                        to: Address::burn_address(),
+                        amount: Coin::from_u64_unchecked(block_number as u64),
+                        data: None,
+                    },
+                ],
+            );
+            let inherent_log = Log::PayoutReward {
+                to: validator.clone(),
+                value: Coin::from_u64_unchecked(10),
+            };
+            history_store.add_logs(&mut txn, block_number, &[tx_log], &[inherent_log]);
+        }
+
+        // Without filters, all logs in the range are returned in the order they were produced.
+        let logs = history_store.get_logs(2, 3, &[], &[], None, usize::MAX, Some(&txn));
+        assert_eq!(logs.len(), 6);
+        assert_eq!(logs[0].block_number, 2);
+        assert_eq!(logs[0].log.log_type(), LogType::PayFee);
+        assert_eq!(logs[1].log.log_type(), LogType::Transfer);
+        assert_eq!(logs[2].log.log_type(), LogType::PayoutReward);
+        assert_eq!(logs[2].tx_hash, None);
+        assert_eq!(logs[3].block_number, 3);
+
+        // Filter by log type.
+        let logs = history_store.get_logs(
+            1,
+            3,
+            &[],
+            &[LogType::Transfer],
+            None,
+            usize::MAX,
+            Some(&txn),
+        );
+        assert_eq!(logs.len(), 3);
+        assert!(logs
+            .iter()
+            .all(|log| log.log.log_type() == LogType::Transfer));
+
+        // Filter by address.
+        let logs = history_store.get_logs(
+            1,
+            3,
+            &[validator.clone()],
+            &[],
+            None,
+            usize::MAX,
+            Some(&txn),
+        );
+        assert_eq!(logs.len(), 3);
+        assert!(logs
+            .iter()
+            .all(|log| log.log.log_type() == LogType::PayoutReward));
+
+        // Filter by address and log type.
+        let logs = history_store.get_logs(
+            1,
+            3,
+            &[sender],
+            &[LogType::PayFee, LogType::PayoutReward],
+            None,
+            usize::MAX,
+            Some(&txn),
+        );
+        assert_eq!(logs.len(), 3);
+        assert!(logs.iter().all(|log| log.log.log_type() == LogType::PayFee));
+
+        // Page through the logs.
+        let logs = history_store.get_logs(1, 3, &[], &[], None, 4, Some(&txn));
+        assert_eq!(logs.len(), 4);
+        assert_eq!(
+            logs[3].position(),
+            LogPosition {
+                block_number: 2,
+                index: 0
+            }
+        );
+        let next_logs =
+            history_store.get_logs(1, 3, &[], &[], Some(logs[3].position()), 4, Some(&txn));
+        assert_eq!(next_logs.len(), 4);
+        assert_eq!(
+            next_logs[0].position(),
+            LogPosition {
+                block_number: 2,
+                index: 1
+            }
+        );
+        assert_eq!(
+            next_logs[3].position(),
+            LogPosition {
+                block_number: 3,
+                index: 1
+            }
+        );
+        let last_logs =
+            history_store.get_logs(1, 3, &[], &[], Some(next_logs[3].position()), 4, Some(&txn));
+        assert_eq!(last_logs.len(), 1);
+        assert_eq!(last_logs[0].log.log_type(), LogType::PayoutReward);
+
+        assert!(history_store.has_logs_of_range(1, 3, Some(&txn)));
+        assert!(history_store.has_logs_of_range(4, 3, Some(&txn)));
+        assert!(!history_store.has_logs_of_range(0, 3, Some(&txn)));
+        assert!(!history_store.has_logs_of_range(2, 4, Some(&txn)));
+
+        // Remove the logs of the last block.
+        history_store.remove_logs(&mut txn, 3, 3);
+        assert_eq!(
+            history_store
+                .get_logs(1, 3, &[], &[], None, usize::MAX, Some(&txn))
+                .len(),
+            6
+        );
+        assert!(history_store
+            .get_logs(3, 3, &[], &[], None, usize::MAX, Some(&txn))
+            .is_empty());
+        assert!(!history_store.has_logs_of_range(1, 3, Some(&txn)));
+
+        // A block missing in the middle of the range is detected as well.
+        history_store.add_logs(&mut txn, 3, &[], &[]);
+        history_store.remove_logs(&mut txn, 2, 2);
+        assert!(!history_store.has_logs_of_range(1, 3, Some(&txn)));
+        assert!(history_store.has_logs_of_range(1, 1, Some(&txn)));
+        assert!(history_store.has_logs_of_range(3, 3, Some(&txn)));
+    }
+
+    #[test]
+    fn get_logs_respects_range_boundaries() {
+        // Initialize History Store.
+        let env = VolatileDatabase::new(20).unwrap();
+        let history_store = HistoryStore::new(env.clone());
+
+        // Add one payout log to each of the blocks 1 to 5.
+        let mut txn = env.write_transaction();
+        for block_number in 1..=5 {
+            let inherent_log = Log::PayoutReward {
+                to: Address::burn_address(),
+                value: Coin::from_u64_unchecked(block_number as u64),
+            };
+            history_store.add_logs(&mut txn, block_number, &[], &[inherent_log]);
+        }
+
+        // Both ends of the range are inclusive.
+        let logs = history_store.get_logs(2, 4, &[], &[], None, usize::MAX, Some(&txn));
+        let block_numbers: Vec<u32> = logs.iter().map(|log| log.block_number).collect();
+        assert_eq!(block_numbers, vec![2, 3, 4]);
+
+        // A single block range.
+        let logs = history_store.get_logs(5, 5, &[], &[], None, usize::MAX, Some(&txn));
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].block_number, 5);
+
+        // Ranges outside of the indexed blocks or with from > to are empty.
+        assert!(history_store
+            .get_logs(6, 10, &[], &[], None, usize::MAX, Some(&txn))
+            .is_empty());
+        assert!(history_store
+            .get_logs(4, 2, &[], &[], None, usize::MAX, Some(&txn))
+            .is_empty());
+
+        // A cursor before the range is ignored.
+        let logs = history_store.get_logs(
+            3,
+            4,
+            &[],
+            &[],
+            Some(LogPosition {
+                block_number: 1,
+                index: 0,
+            }),
+            usize::MAX,
+            Some(&txn),
+        );
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].block_number, 3);
+
+        // A cursor at the last log of the range yields no more logs.
+        let logs = history_store.get_logs(
+            3,
+            4,
+            &[],
+            &[],
+            Some(LogPosition {
+                block_number: 4,
+                index: 0,
+            }),
+            usize::MAX,
+            Some(&txn),
+        );
+        assert!(logs.is_empty());
+
+        // A cursor after the range yields no logs either.
+        let logs = history_store.get_logs(
+            1,
+            3,
+            &[],
+            &[],
+            Some(LogPosition {
+                block_number: 4,
+                index: 0,
+            }),
+            usize::MAX,
+            Some(&txn),
+        );
+        assert!(logs.is_empty());
+    }
+
+    #[test]
+    fn remove_history_prunes_logs() {
+        // Initialize History Store.
+        let env = VolatileDatabase::new(20).unwrap();
+        let history_store = HistoryStore::new(env.clone());
+
+        let last_block_1 = Policy::election_block_of(1).unwrap();
+        let first_block_2 = Policy::first_block_of(2).unwrap();
+
+        // Add a transaction and its logs to the last block of epoch 1 and the first block of
+        // epoch 2.
+        let mut txn = env.write_transaction();
+        for (epoch_number, block_number) in [(1, last_block_1), (2, first_block_2)] {
+            let ext_tx = create_transaction(block_number, block_number as u64);
+            history_store.add_to_history(&mut txn, epoch_number, &[ext_tx.clone()]);
+            let tx_log = TransactionLog::new(
+                ext_tx.tx_hash(),
+                vec![Log::PayFee {
+                    from: Address::burn_address(),
+                    fee: Coin::from_u64_unchecked(1),
+                }],
+            );
+            history_store.add_logs(&mut txn, block_number, &[tx_log], &[]);
+        }
+        assert!(history_store.has_logs_of_range(last_block_1, first_block_2, Some(&txn)));
+
+        // Pruning epoch 1 removes its logs, but keeps those of epoch 2.
+        history_store.remove_history(&mut txn, 1).unwrap();
+
+        assert!(!history_store.has_logs_of_range(last_block_1, last_block_1, Some(&txn)));
+        assert!(!history_store.has_logs_of_range(last_block_1, first_block_2, Some(&txn)));
+        assert!(history_store.has_logs_of_range(first_block_2, first_block_2, Some(&txn)));
+
+        let logs = history_store.get_logs(
+            last_block_1,
+            first_block_2,
+            &[],
+            &[],
+            None,
+            usize::MAX,
+            Some(&txn),
+        );
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].block_number, first_block_2);
+        assert_eq!(
+            history_store
+                .get_ext_tx_by_hash(&logs[0].tx_hash.clone().unwrap(), Some(&txn))
+                .len(),
+            1
+        );
+    }
+
     fn create_inherent(block: u32, value: u64) -> ExtendedTransaction {
         ExtendedTransaction {
             network_id: NetworkId::UnitAlbatross,
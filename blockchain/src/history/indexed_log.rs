@@ -0,0 +1,413 @@
+use std::{borrow::Cow, convert::TryInto, io};
+
+use nimiq_account::{Log, LogType};
+use nimiq_database_value::{AsDatabaseBytes, FromDatabaseValue, IntoDatabaseValue};
+use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
+use nimiq_primitives::{account::FailReason, coin::Coin};
+use nimiq_serde::{Deserialize, Serialize};
+use nimiq_transaction::account::htlc_contract::{AnyHash, PreImage};
+
+/// A log stored in the log index of the history store, together with the block and (if any)
+/// the transaction that produced it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedLog {
+    /// The number of the block that produced the log.
+    pub block_number: u32,
+    /// The position of the log within the logs produced by its block.
+    pub index: u32,
+    /// The hash of the transaction that produced the log, or None for inherent logs.
+    pub tx_hash: Option<Blake2bHash>,
+    /// The log itself.
+    #[serde(with = "LogDef")]
+    pub log: Log,
+}
+
+impl IndexedLog {
+    /// Returns the position of the log in the log index.
+    pub fn position(&self) -> LogPosition {
+        LogPosition {
+            block_number: self.block_number,
+            index: self.index,
+        }
+    }
+}
+
+impl IntoDatabaseValue for IndexedLog {
+    fn database_byte_size(&self) -> usize {
+        self.serialized_size()
+    }
+
+    fn copy_into_database(&self, mut bytes: &mut [u8]) {
+        Serialize::serialize_to_writer(&self, &mut bytes).unwrap();
+    }
+}
+
+impl FromDatabaseValue for IndexedLog {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        Deserialize::deserialize_from_vec(bytes)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+}
+
+/// The position of a log in the log index, i.e. the number of the block that produced it and its
+/// position within the logs of that block. Logs are ordered by their position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogPosition {
+    pub block_number: u32,
+    pub index: u32,
+}
+
+/// The binary encoding of a [`Log`] in the log index. `Log` is an internally tagged enum, which
+/// the binary serialization format does not support, so the log index uses this externally tagged
+/// mirror of it instead. The variants must be kept in the same order as in `Log`.
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Log")]
+enum LogDef {
+    PayFee {
+        from: Address,
+        fee: Coin,
+    },
+    Transfer {
+        from: Address,
+        to: Address,
+        amount: Coin,
+        data: Option<Vec<u8>>,
+    },
+    HTLCCreate {
+        contract_address: Address,
+        sender: Address,
+        recipient: Address,
+        hash_root: AnyHash,
+        hash_count: u8,
+        timeout: u64,
+        total_amount: Coin,
+    },
+    HTLCTimeoutResolve {
+        contract_address: Address,
+    },
+    HTLCRegularTransfer {
+        contract_address: Address,
+        pre_image: PreImage,
+        hash_depth: u8,
+    },
+    HTLCEarlyResolve {
+        contract_address: Address,
+    },
+    VestingCreate {
+        contract_address: Address,
+        owner: Address,
+        start_time: u64,
+        time_step: u64,
+        step_amount: Coin,
+        total_amount: Coin,
+    },
+    CreateValidator {
+        validator_address: Address,
+        reward_address: Address,
+    },
+    UpdateValidator {
+        validator_address: Address,
+        old_reward_address: Address,
+        new_reward_address: Option<Address>,
+    },
+    UpdateValidatorCommission {
+        validator_address: Address,
+        old_commission: Option<u16>,
+        new_commission: Option<u16>,
+    },
+    ValidatorFeeDeduction {
+        validator_address: Address,
+        fee: Coin,
+    },
+    DeactivateValidator {
+        validator_address: Address,
+    },
+    ReactivateValidator {
+        validator_address: Address,
+    },
+    CreateStaker {
+        staker_address: Address,
+        validator_address: Option<Address>,
+        value: Coin,
+    },
+    Stake {
+        staker_address: Address,
+        validator_address: Option<Address>,
+        value: Coin,
+    },
+    StakerFeeDeduction {
+        staker_address: Address,
+        fee: Coin,
+    },
+    UpdateStaker {
+        staker_address: Address,
+        old_validator_address: Option<Address>,
+        new_validator_address: Option<Address>,
+    },
+    SetInactiveStake {
+        staker_address: Address,
+        validator_address: Option<Address>,
+        value: Coin,
+        inactive_from: Option<u32>,
+    },
+    SetDelegationStake {
+        staker_address: Address,
+        validator_address: Address,
+        old_stake: Coin,
+        new_stake: Coin,
+    },
+    RetireValidator {
+        validator_address: Address,
+    },
+    DeleteValidator {
+        validator_address: Address,
+        reward_address: Address,
+    },
+    Unstake {
+        staker_address: Address,
+        validator_address: Option<Address>,
+        value: Coin,
+    },
+    PayoutReward {
+        to: Address,
+        value: Coin,
+    },
+    StakerReward {
+        staker_address: Address,
+        validator_address: Address,
+        value: Coin,
+    },
+    Slash {
+        validator_address: Address,
+        event_block: u32,
+        slot: u16,
+        newly_disabled: bool,
+        newly_deactivated: bool,
+    },
+    RevertContract {
+        contract_address: Address,
+    },
+    FailedTransaction {
+        from: Address,
+        to: Address,
+        failure_reason: FailReason,
+    },
+}
+
+/// The key of the log index. Keys are ordered by log type first, then by block number and finally
+/// by the position of the log within its block, such that all logs of a given type in a range of
+/// blocks are stored consecutively.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LogIndexKey {
+    pub log_type: u8,
+    pub block_number: u32,
+    pub index: u32,
+}
+
+impl LogIndexKey {
+    pub fn new(log_type: LogType, block_number: u32, index: u32) -> Self {
+        LogIndexKey {
+            log_type: log_type as u8,
+            block_number,
+            index,
+        }
+    }
+}
+
+impl AsDatabaseBytes for LogIndexKey {
+    fn as_database_bytes(&self) -> Cow<[u8]> {
+        let bytes = [
+            &[self.log_type][..],
+            &self.block_number.to_be_bytes(),
+            &self.index.to_be_bytes(),
+        ]
+        .concat();
+        Cow::Owned(bytes)
+    }
+}
+
+impl FromDatabaseValue for LogIndexKey {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(LogIndexKey {
+            log_type: bytes[0],
+            block_number: u32::from_be_bytes(bytes[1..5].try_into().unwrap()),
+            index: u32::from_be_bytes(bytes[5..9].try_into().unwrap()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nimiq_test_log::test;
+    use nimiq_transaction::account::htlc_contract::AnyHash32;
+
+    use super::*;
+
+    fn sample_logs() -> Vec<Log> {
+        let address = Address::from([1u8; Address::SIZE]);
+        let other = Address::from([2u8; Address::SIZE]);
+        let value = Coin::from_u64_unchecked(42);
+
+        vec![
+            Log::PayFee {
+                from: address.clone(),
+                fee: value,
+            },
+            Log::Transfer {
+                from: address.clone(),
+                to: other.clone(),
+                amount: value,
+                data: Some(vec![1, 2, 3]),
+            },
+            Log::HTLCCreate {
+                contract_address: address.clone(),
+                sender: other.clone(),
+                recipient: address.clone(),
+                hash_root: AnyHash::Sha256(AnyHash32([3u8; 32])),
+                hash_count: 4,
+                timeout: 1000,
+                total_amount: value,
+            },
+            Log::HTLCTimeoutResolve {
+                contract_address: address.clone(),
+            },
+            Log::HTLCRegularTransfer {
+                contract_address: address.clone(),
+                pre_image: PreImage::PreImage32(AnyHash32([5u8; 32])),
+                hash_depth: 2,
+            },
+            Log::HTLCEarlyResolve {
+                contract_address: address.clone(),
+            },
+            Log::VestingCreate {
+                contract_address: address.clone(),
+                owner: other.clone(),
+                start_time: 1,
+                time_step: 2,
+                step_amount: value,
+                total_amount: value,
+            },
+            Log::CreateValidator {
+                validator_address: address.clone(),
+                reward_address: other.clone(),
+            },
+            Log::UpdateValidator {
+                validator_address: address.clone(),
+                old_reward_address: other.clone(),
+                new_reward_address: None,
+            },
+            Log::ValidatorFeeDeduction {
+                validator_address: address.clone(),
+                fee: value,
+            },
+            Log::DeactivateValidator {
+                validator_address: address.clone(),
+            },
+            Log::ReactivateValidator {
+                validator_address: address.clone(),
+            },
+            Log::CreateStaker {
+                staker_address: address.clone(),
+                validator_address: Some(other.clone()),
+                value,
+            },
+            Log::Stake {
+                staker_address: address.clone(),
+                validator_address: None,
+                value,
+            },
+            Log::StakerFeeDeduction {
+                staker_address: address.clone(),
+                fee: value,
+            },
+            Log::UpdateStaker {
+                staker_address: address.clone(),
+                old_validator_address: None,
+                new_validator_address: Some(other.clone()),
+            },
+            Log::SetInactiveStake {
+                staker_address: address.clone(),
+                validator_address: Some(other.clone()),
+                value,
+                inactive_from: Some(7),
+            },
+            Log::SetDelegationStake {
+                staker_address: address.clone(),
+                validator_address: other.clone(),
+                old_stake: value,
+                new_stake: Coin::ZERO,
+            },
+            Log::RetireValidator {
+                validator_address: address.clone(),
+            },
+            Log::DeleteValidator {
+                validator_address: address.clone(),
+                reward_address: other.clone(),
+            },
+            Log::Unstake {
+                staker_address: address.clone(),
+                validator_address: Some(other.clone()),
+                value,
+            },
+            Log::PayoutReward {
+                to: address.clone(),
+                value,
+            },
+            Log::StakerReward {
+                staker_address: address.clone(),
+                validator_address: other.clone(),
+                value,
+            },
+            Log::Slash {
+                validator_address: address.clone(),
+                event_block: 9,
+                slot: 3,
+                newly_disabled: true,
+                newly_deactivated: false,
+            },
+            Log::RevertContract {
+                contract_address: address.clone(),
+            },
+            Log::FailedTransaction {
+                from: address.clone(),
+                to: other,
+                failure_reason: FailReason::InsufficientFunds,
+            },
+            Log::UpdateValidatorCommission {
+                validator_address: address,
+                old_commission: None,
+                new_commission: Some(1_000),
+            },
+        ]
+    }
+
+    #[test]
+    fn samples_cover_every_log_type() {
+        let log_types: Vec<LogType> = sample_logs().iter().map(Log::log_type).collect();
+        assert_eq!(log_types, LogType::ALL.to_vec());
+    }
+
+    #[test]
+    fn indexed_logs_round_trip() {
+        for (index, log) in sample_logs().into_iter().enumerate() {
+            let indexed_log = IndexedLog {
+                block_number: 12,
+                index: index as u32,
+                tx_hash: (index % 2 == 0).then(Blake2bHash::default),
+                log,
+            };
+
+            let bytes = indexed_log.serialize_to_vec();
+            let decoded: IndexedLog = Deserialize::deserialize_from_vec(&bytes).unwrap();
+            assert_eq!(decoded, indexed_log);
+        }
+    }
+}
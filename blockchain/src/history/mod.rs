@@ -1,7 +1,9 @@
 pub use history_store::HistoryStore;
 pub use history_tree_chunk::{HistoryTreeChunk, CHUNK_SIZE};
+pub use indexed_log::{IndexedLog, LogPosition};
 
 mod history_store;
 mod history_tree_chunk;
+mod indexed_log;
 mod mmr_store;
 mod ordered_hash;
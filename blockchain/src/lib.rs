@@ -1,7 +1,11 @@
 #[macro_use]
 extern crate log;
 
-pub use blockchain::blockchain::{Blockchain, BlockchainConfig, TransactionVerificationCache};
+pub use blockchain::{
+    accounts::AccountAt,
+    blockchain::{Blockchain, BlockchainConfig, TransactionVerificationCache},
+};
+pub use chain_store::ChainStore;
 pub use history::*;
 
 pub(crate) mod blockchain;
@@ -1,6 +1,7 @@
 use std::convert::TryInto;
 
 use nimiq_block::MacroHeader;
+use nimiq_blockchain_interface::ChainInfo;
 use nimiq_primitives::{coin::Coin, policy::Policy};
 
 /// Parses the genesis supply and timestamp from the genesis block. We require both values to
@@ -86,3 +87,22 @@ pub fn block_reward_for_batch_with_genesis(
 
     block_reward_for_batch(current_block, previous_macro, supply, timestamp)
 }
+
+/// Compute the reward pot of a batch from the current macro block, the chain info of the previous
+/// macro block, and the genesis parameters. The reward pot is the block reward for the batch plus
+/// the transaction fees collected in it, and is distributed among all slots.
+pub fn reward_pot_for_batch(
+    current_block: &MacroHeader,
+    previous_macro_info: &ChainInfo,
+    genesis_supply: Coin,
+    genesis_timestamp: u64,
+) -> Coin {
+    let block_reward = block_reward_for_batch(
+        current_block,
+        &previous_macro_info.head.unwrap_macro_ref().header,
+        genesis_supply,
+        genesis_timestamp,
+    );
+
+    block_reward + previous_macro_info.cum_tx_fees
+}
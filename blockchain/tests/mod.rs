@@ -1,13 +1,22 @@
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc};
 
+use nimiq_account::{Account, Accounts, BasicAccount, VestingContract};
 use nimiq_block::Block;
 use nimiq_blockchain::Blockchain;
-use nimiq_blockchain_interface::{AbstractBlockchain, PushResult};
-use nimiq_primitives::{policy::Policy, trie::trie_diff::TrieDiff};
+use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainError, PushResult};
+use nimiq_database::{traits::Database, volatile::VolatileDatabase};
+use nimiq_keys::{Address, KeyPair, PrivateKey};
+use nimiq_primitives::{
+    coin::Coin, networks::NetworkId, policy::Policy, trie::trie_diff::TrieDiff,
+};
 use nimiq_test_log::test;
 use nimiq_test_utils::{
-    block_production::TemporaryBlockProducer, blockchain::produce_macro_blocks,
+    block_production::TemporaryBlockProducer,
+    blockchain::{
+        fill_micro_blocks_with_txns, generate_transactions, produce_macro_blocks, UNIT_KEY,
+    },
 };
+use nimiq_transaction_builder::TransactionBuilder;
 use tokio_stream::{wrappers::BroadcastStream, StreamExt};
 
 #[test]
@@ -449,3 +458,185 @@ async fn can_enforce_validity_window() {
         "Blockchain with blocks > validity window should be able to enforce it"
     );
 }
+
+#[test]
+fn it_can_reconstruct_accounts_at_past_blocks() {
+    let temp_producer = TemporaryBlockProducer::new();
+
+    // Each micro block of the batch contains a transaction of value 1 and fee 2 from the unit
+    // key to the same recipient.
+    fill_micro_blocks_with_txns(&temp_producer.producer, &temp_producer.blockchain, 1, 0);
+
+    let key_pair = KeyPair::from(PrivateKey::from_str(UNIT_KEY).unwrap());
+    let sender = Address::from(&key_pair.public);
+    let recipient = generate_transactions(&key_pair, 1, NetworkId::UnitAlbatross, 1, 0)[0]
+        .recipient
+        .clone();
+
+    let blockchain = temp_producer.blockchain.read();
+    let head = blockchain.block_number();
+    let sender_balance = blockchain
+        .get_account_if_complete(&sender)
+        .unwrap()
+        .balance();
+
+    for block_number in 0..=head {
+        assert_eq!(
+            blockchain
+                .get_account_at(&sender, block_number)
+                .unwrap()
+                .balance(),
+            sender_balance + Coin::from_u64_unchecked(3 * (head - block_number) as u64)
+        );
+
+        assert_eq!(
+            blockchain.get_account_at(&recipient, block_number).unwrap(),
+            Account::Basic(BasicAccount {
+                balance: Coin::from_u64_unchecked(block_number as u64)
+            })
+        );
+    }
+
+    assert_eq!(
+        blockchain.get_account_at(&sender, head + 1),
+        Err(BlockchainError::BlockOutsideStoredHistory(head + 1))
+    );
+}
+
+#[test]
+fn it_can_reconstruct_pruned_contracts_at_past_blocks() {
+    let temp_producer = TemporaryBlockProducer::new();
+    let key_pair = KeyPair::from(PrivateKey::from_str(UNIT_KEY).unwrap());
+    let owner = Address::from(&key_pair.public);
+
+    // Create a vesting contract that is vested right away and empty it in two steps, after which
+    // it is pruned.
+    let create = TransactionBuilder::new_create_vesting(
+        &key_pair,
+        owner.clone(),
+        0,
+        1,
+        1,
+        Coin::from_u64_unchecked(1000),
+        Coin::ZERO,
+        temp_producer.blockchain.read().block_number(),
+        NetworkId::UnitAlbatross,
+    )
+    .unwrap();
+    let contract_address = create.contract_creation_address();
+    temp_producer.next_block_with_txs(vec![], false, vec![create]);
+    let created_at = temp_producer.blockchain.read().block_number();
+
+    for value in [400, 600] {
+        let redeem = TransactionBuilder::new_redeem_vesting(
+            &key_pair,
+            contract_address.clone(),
+            owner.clone(),
+            Coin::from_u64_unchecked(value),
+            Coin::ZERO,
+            temp_producer.blockchain.read().block_number(),
+            NetworkId::UnitAlbatross,
+        )
+        .unwrap();
+        temp_producer.next_block_with_txs(vec![], false, vec![redeem]);
+    }
+
+    let blockchain = temp_producer.blockchain.read();
+    assert_eq!(
+        blockchain.get_account_if_complete(&contract_address),
+        Some(Account::default())
+    );
+
+    let vesting_contract = |balance| {
+        Account::Vesting(VestingContract {
+            balance: Coin::from_u64_unchecked(balance),
+            owner: owner.clone(),
+            start_time: 0,
+            time_step: 1,
+            step_amount: Coin::from_u64_unchecked(1000),
+            total_amount: Coin::from_u64_unchecked(1000),
+        })
+    };
+    assert_eq!(
+        blockchain.get_account_at(&contract_address, created_at - 1),
+        Ok(Account::default())
+    );
+    assert_eq!(
+        blockchain.get_account_at(&contract_address, created_at),
+        Ok(vesting_contract(1000))
+    );
+    assert_eq!(
+        blockchain.get_account_at(&contract_address, created_at + 1),
+        Ok(vesting_contract(600))
+    );
+    assert_eq!(
+        blockchain.get_account_at(&contract_address, created_at + 2),
+        Ok(Account::default())
+    );
+}
+
+#[test]
+fn it_can_revert_a_copy_of_the_accounts() {
+    let temp_producer = TemporaryBlockProducer::new();
+    fill_micro_blocks_with_txns(&temp_producer.producer, &temp_producer.blockchain, 1, 0);
+
+    let blockchain = temp_producer.blockchain.read();
+    let txn = blockchain.read_transaction();
+    let head = blockchain.block_number();
+    let macro_block_number = Policy::last_macro_block(head);
+
+    let copy_accounts = || {
+        let env = VolatileDatabase::new(1).unwrap();
+        let accounts = Accounts::new(env.clone());
+        let mut accounts_txn = env.write_transaction();
+        accounts.init(
+            &mut (&mut accounts_txn).into(),
+            blockchain.state().accounts.tree.iter_items(&txn),
+        );
+        accounts_txn.commit();
+        accounts
+    };
+
+    for block_number in macro_block_number..=head {
+        let accounts = copy_accounts();
+        let mut accounts_txn = accounts.env.write_transaction();
+        blockchain
+            .revert_accounts_copy_to(
+                &txn,
+                &accounts,
+                &mut (&mut accounts_txn).into(),
+                block_number,
+            )
+            .unwrap();
+
+        let block = blockchain
+            .chain_store
+            .get_block_at(block_number, false, Some(&txn))
+            .unwrap();
+        assert_eq!(
+            &accounts.get_root_hash_assert(Some(&accounts_txn)),
+            block.state_root()
+        );
+    }
+
+    // The accounts of the blockchain itself are not modified.
+    assert_eq!(
+        &blockchain.state().accounts.get_root_hash_assert(None),
+        blockchain.head().state_root()
+    );
+
+    // Blocks before the last macro block can't be reverted.
+    let accounts = copy_accounts();
+    let mut accounts_txn = accounts.env.write_transaction();
+    assert_eq!(
+        blockchain.revert_accounts_copy_to(
+            &txn,
+            &accounts,
+            &mut (&mut accounts_txn).into(),
+            macro_block_number - 1,
+        ),
+        Err(BlockchainError::BlockOutsideStoredHistory(
+            macro_block_number - 1
+        ))
+    );
+}
@@ -11,7 +11,7 @@ pub use nimiq::{
         logging::{initialize_logging, log_error_cause_chain},
         metrics_server::NimiqTaskMonitor,
         panic::initialize_panic_reporting,
-        signal_handling::initialize_signal_handler,
+        signal_handling::{initialize_signal_handler, register_shutdown_hook},
     },
 };
 
@@ -76,6 +76,11 @@ async fn main_inner() -> Result<(), Error> {
     .await?;
     log::info!("Client initialized");
 
+    // Persist the mempool content when the client is shut down.
+    if let Some(mempool) = client.mempool() {
+        register_shutdown_hook(move || mempool.save_journal());
+    }
+
     // Initialize RPC server
     if let Some(rpc_config) = rpc_config {
         use nimiq::extras::rpc_server::initialize_rpc_server;
@@ -19,6 +19,7 @@ use nimiq_network_interface::{
     request::{OutboundRequestError, RequestError},
 };
 use nimiq_primitives::{account::AccountType, key_nibbles::KeyNibbles, policy::Policy};
+use nimiq_serde::Deserialize;
 use nimiq_transaction::{
     extended_transaction::ExtendedTransaction, ControlTransactionTopic, Transaction,
     TransactionTopic,
@@ -27,7 +28,7 @@ use tokio::sync::broadcast::Sender as BroadcastSender;
 use tokio_stream::wrappers::BroadcastStream;
 
 use crate::{
-    consensus::remote_data_store::RemoteDataStore,
+    consensus::remote_data_store::{RemoteDataStore, TrieItemKind, VerifiedTrieProof},
     messages::{
         AddressNotification, AddressSubscriptionOperation, AddressSubscriptionTopic,
         RequestBlocksProof, RequestSubscribeToAddress, RequestTransactionReceiptsByAddress,
@@ -466,6 +467,74 @@ impl<N: Network> ConsensusProxy<N> {
         Ok(accounts)
     }
 
+    /// Gets a set of accounts given their addresses, together with the proof they were verified
+    /// with and the block the proof was verified against. If an account was not found, then
+    /// `None` is returned in its corresponding entry.
+    pub async fn request_accounts_with_proof(
+        &self,
+        addresses: Vec<Address>,
+        min_peers: usize,
+    ) -> Result<(BTreeMap<Address, Option<Account>>, VerifiedTrieProof), RequestError> {
+        self.request_items_with_proof(TrieItemKind::Account, addresses, min_peers)
+            .await
+    }
+
+    /// Gets a set of stakers given their addresses, together with the proof they were verified
+    /// with and the block the proof was verified against. If a staker was not found, then
+    /// `None` is returned in its corresponding entry.
+    pub async fn request_stakers_with_proof(
+        &self,
+        addresses: Vec<Address>,
+        min_peers: usize,
+    ) -> Result<(BTreeMap<Address, Option<Staker>>, VerifiedTrieProof), RequestError> {
+        self.request_items_with_proof(TrieItemKind::Staker, addresses, min_peers)
+            .await
+    }
+
+    /// Gets a set of validators given their addresses, together with the proof they were
+    /// verified with and the block the proof was verified against. If a validator was not
+    /// found, then `None` is returned in its corresponding entry.
+    pub async fn request_validators_with_proof(
+        &self,
+        addresses: Vec<Address>,
+        min_peers: usize,
+    ) -> Result<(BTreeMap<Address, Option<Validator>>, VerifiedTrieProof), RequestError> {
+        self.request_items_with_proof(TrieItemKind::Validator, addresses, min_peers)
+            .await
+    }
+
+    async fn request_items_with_proof<T: Deserialize>(
+        &self,
+        kind: TrieItemKind,
+        addresses: Vec<Address>,
+        min_peers: usize,
+    ) -> Result<(BTreeMap<Address, Option<T>>, VerifiedTrieProof), RequestError> {
+        let mut keys = HashMap::<KeyNibbles, Address>::from_iter(
+            addresses
+                .iter()
+                .map(|address| (kind.key(address), address.clone())),
+        );
+        let (items, proof): (BTreeMap<KeyNibbles, Option<T>>, _) =
+            RemoteDataStore::get_trie_with_proof(
+                Arc::clone(&self.network),
+                self.blockchain.clone(),
+                &keys.keys().cloned().collect::<Vec<KeyNibbles>>(),
+                min_peers,
+            )
+            .await?;
+
+        let items = items
+            .into_iter()
+            .map(|(key, item)| {
+                (
+                    keys.remove(&key).expect("Key must be in the proven items"),
+                    item,
+                )
+            })
+            .collect();
+        Ok((items, proof))
+    }
+
     /// Gets a set of validators given their addresses. The returned type is a
     /// BTreeMap of addresses to an optional `Validator`. If a validator was not
     /// found, then `None` is returned in its corresponding entry.
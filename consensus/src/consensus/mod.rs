@@ -42,6 +42,8 @@ use crate::{
 pub mod consensus_proxy;
 mod head_requests;
 mod remote_data_store;
+
+pub use remote_data_store::{TrieItemKind, VerifiedTrieProof};
 #[cfg(feature = "full")]
 mod remote_event_dispatcher;
 
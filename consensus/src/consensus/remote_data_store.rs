@@ -1,5 +1,6 @@
 use std::{
     collections::{BTreeMap, HashMap},
+    str::FromStr,
     sync::Arc,
 };
 
@@ -8,16 +9,17 @@ use nimiq_account::{
 };
 use nimiq_blockchain_interface::AbstractBlockchain;
 use nimiq_blockchain_proxy::BlockchainProxy;
+use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
 use nimiq_network_interface::{
     network::{CloseReason, Network},
     peer_info::Services,
     request::{OutboundRequestError, RequestError},
 };
-use nimiq_primitives::{key_nibbles::KeyNibbles, policy::Policy};
+use nimiq_primitives::{key_nibbles::KeyNibbles, policy::Policy, trie::trie_proof::TrieProof};
 use nimiq_serde::Deserialize;
 
-use crate::messages::RequestTrieProof;
+use crate::{error::TrieProofError, messages::RequestTrieProof};
 
 /// The Remote Data Store is a component to remotely request data from the staking
 /// contract such as:
@@ -35,6 +37,100 @@ pub(crate) struct RemoteDataStore<N: Network> {
     pub(crate) min_peers: usize,
 }
 
+/// A proof of items in the accounts trie that was verified against the state root of a block
+/// we know. It can be stored to verify the items again later.
+#[derive(Clone, Debug)]
+pub struct VerifiedTrieProof {
+    /// The proof of the items.
+    pub proof: TrieProof,
+    /// The hash of the block whose state the proof was created for.
+    pub block_hash: Blake2bHash,
+    /// The number of the block whose state the proof was created for.
+    pub block_number: u32,
+    /// The state root the proof was verified against.
+    pub state_root: Blake2bHash,
+}
+
+/// The kinds of items in the accounts trie that can be requested with a proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrieItemKind {
+    /// The account at an address.
+    Account,
+    /// The staker with an address in the staking contract.
+    Staker,
+    /// The validator with an address in the staking contract.
+    Validator,
+}
+
+impl TrieItemKind {
+    /// Returns the key of the item of this kind for the given address in the accounts trie.
+    pub fn key(&self, address: &Address) -> KeyNibbles {
+        let staking_contract_key = KeyNibbles::from(&Policy::STAKING_CONTRACT_ADDRESS);
+        match self {
+            TrieItemKind::Account => KeyNibbles::from(address),
+            TrieItemKind::Staker => {
+                &staking_contract_key + &StakingContractStore::staker_key(address)
+            }
+            TrieItemKind::Validator => {
+                &staking_contract_key + &StakingContractStore::validator_key(address)
+            }
+        }
+    }
+}
+
+impl VerifiedTrieProof {
+    /// Verifies the proof again against its state root and returns the proven items of the given
+    /// kind. If an item was proven to not exist, then `None` is returned in its corresponding entry.
+    pub fn verify<T: Deserialize>(
+        &self,
+        kind: TrieItemKind,
+        addresses: &[Address],
+    ) -> Result<BTreeMap<Address, Option<T>>, TrieProofError> {
+        Self::verify_items(self.proof.clone(), &self.state_root, kind, addresses)
+    }
+
+    /// Verifies a hex-encoded serialized proof against a hex-encoded state root, as they are
+    /// handed out to clients, and returns the proven items of the given kind. If an item was
+    /// proven to not exist, then `None` is returned in its corresponding entry.
+    pub fn verify_hex<T: Deserialize>(
+        proof: &str,
+        state_root: &str,
+        kind: TrieItemKind,
+        addresses: &[Address],
+    ) -> Result<BTreeMap<Address, Option<T>>, TrieProofError> {
+        let proof = TrieProof::deserialize_from_vec(&hex::decode(proof)?)?;
+        let state_root = Blake2bHash::from_str(state_root)?;
+        Self::verify_items(proof, &state_root, kind, addresses)
+    }
+
+    fn verify_items<T: Deserialize>(
+        proof: TrieProof,
+        state_root: &Blake2bHash,
+        kind: TrieItemKind,
+        addresses: &[Address],
+    ) -> Result<BTreeMap<Address, Option<T>>, TrieProofError> {
+        let mut keys = HashMap::<KeyNibbles, Address>::from_iter(
+            addresses
+                .iter()
+                .map(|address| (kind.key(address), address.clone())),
+        );
+        let values = proof
+            .verify_values(state_root, &keys.keys().collect::<Vec<_>>())
+            .map_err(|_| TrieProofError::InvalidProof)?;
+
+        let mut items = BTreeMap::new();
+        for (key, value) in values {
+            let address = keys.remove(&key).expect("Key must be in the proven items");
+            let item = match value {
+                Some(value) => Some(T::deserialize_from_vec(&value)?),
+                None => None,
+            };
+            items.insert(address, item);
+        }
+        Ok(items)
+    }
+}
+
 /// Internal Remote operations the Remote Data Store can perform over addresses on
 /// wasm
 enum RemoteDataStoreOps {
@@ -55,6 +151,19 @@ impl<N: Network> RemoteDataStore<N> {
         keys: &[KeyNibbles],
         min_peers: usize,
     ) -> Result<BTreeMap<KeyNibbles, Option<T>>, RequestError> {
+        Self::get_trie_with_proof(network, blockchain, keys, min_peers)
+            .await
+            .map(|(items, _)| items)
+    }
+
+    /// Gets a proof for deserializable items in a remote accounts trie and returns the items
+    /// together with the proof they were verified with.
+    pub(crate) async fn get_trie_with_proof<T: Deserialize>(
+        network: Arc<N>,
+        blockchain: BlockchainProxy,
+        keys: &[KeyNibbles],
+        min_peers: usize,
+    ) -> Result<(BTreeMap<KeyNibbles, Option<T>>, VerifiedTrieProof), RequestError> {
         // First we tell the network to provide us with a vector that contains all the connected peers that support such services
         // Note: If the network could not provide enough peers that satisfies our requirement, then an error would be returned
         let peers = network
@@ -93,11 +202,11 @@ impl<N: Network> RemoteDataStore<N> {
 
                             if let Some(block) = block {
                                 // Now we need to verify the proof
-                                if let Ok(values) = proof.verify_values(
+                                if let Ok(values) = proof.clone().verify_values(
                                     block.state_root(),
                                     &keys.iter().collect::<Vec<_>>(),
                                 ) {
-                                    return Ok(values
+                                    let items = values
                                         .into_iter()
                                         .map(|(key, value)| {
                                             (
@@ -105,7 +214,14 @@ impl<N: Network> RemoteDataStore<N> {
                                                 value.map(|v| T::deserialize_from_vec(&v).unwrap()),
                                             )
                                         })
-                                        .collect());
+                                        .collect();
+                                    let verified_proof = VerifiedTrieProof {
+                                        proof,
+                                        block_hash,
+                                        block_number: block.block_number(),
+                                        state_root: block.state_root().clone(),
+                                    };
+                                    return Ok((items, verified_proof));
                                 } else {
                                     // If the proof does not verify, we disconnect from the peer
                                     log::debug!(peer=%peer_id, "Disconnecting from peer because the accounts proof didn't verify");
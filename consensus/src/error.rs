@@ -1,4 +1,5 @@
 use nimiq_blockchain_interface::BlockchainError;
+use nimiq_serde::DeserializeError;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -8,6 +9,20 @@ pub enum Error {
     BlockchainError(#[from] BlockchainError),
 }
 
+/// Different errors that can be obtained when verifying a stored trie proof.
+#[derive(Debug, Error)]
+pub enum TrieProofError {
+    /// The proof or the state root is not valid hex
+    #[error("Invalid hex encoding: {0}")]
+    Hex(#[from] hex::FromHexError),
+    /// The proof or one of the proven items could not be deserialized
+    #[error("Invalid encoding: {0}")]
+    Deserialize(#[from] DeserializeError),
+    /// The proof does not prove the requested items against the state root
+    #[error("The proof does not verify against the state root")]
+    InvalidProof,
+}
+
 #[derive(Debug, Error)]
 pub enum SyncError {
     #[error("Other")]
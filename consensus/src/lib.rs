@@ -1,8 +1,11 @@
 #[macro_use]
 extern crate log;
 
-pub use consensus::{consensus_proxy::ConsensusProxy, Consensus, ConsensusEvent, RemoteEvent};
-pub use error::{Error, SubscribeToAddressesError};
+pub use consensus::{
+    consensus_proxy::ConsensusProxy, Consensus, ConsensusEvent, RemoteEvent, TrieItemKind,
+    VerifiedTrieProof,
+};
+pub use error::{Error, SubscribeToAddressesError, TrieProofError};
 
 pub mod consensus;
 pub mod error;
@@ -591,6 +591,8 @@ impl<N: Network> Handle<N, ResponseTransactionReceiptsByAddress, Arc<RwLock<Bloc
             &self.address,
             self.max.unwrap_or(500).min(500),
             None,
+            Direction::Backward,
+            None,
         );
 
         let mut receipts = vec![];
@@ -0,0 +1,139 @@
+use std::sync::Arc;
+
+use nimiq_account::{Account, Staker, Validator};
+use nimiq_blockchain::{Blockchain, BlockchainConfig};
+use nimiq_blockchain_interface::AbstractBlockchain;
+use nimiq_consensus::{TrieItemKind, TrieProofError, VerifiedTrieProof};
+use nimiq_database::volatile::VolatileDatabase;
+use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
+use nimiq_primitives::networks::NetworkId;
+use nimiq_serde::Serialize;
+use nimiq_test_log::test;
+use nimiq_utils::time::OffsetTime;
+
+fn validator_address() -> Address {
+    Address::from_user_friendly_address("NQ20 TSB0 DFSM UH9C 15GQ GAGJ TTE4 D3MA 859E").unwrap()
+}
+
+fn staker_address() -> Address {
+    Address::from_user_friendly_address("NQ39 VBTN P2HX Q3MF KHF1 CCLA G6FS 9B8S VY28").unwrap()
+}
+
+fn blockchain() -> Blockchain {
+    Blockchain::new(
+        VolatileDatabase::new(20).unwrap(),
+        BlockchainConfig::default(),
+        NetworkId::UnitAlbatross,
+        Arc::new(OffsetTime::new()),
+    )
+    .unwrap()
+}
+
+fn prove(blockchain: &Blockchain, kind: TrieItemKind, addresses: &[Address]) -> VerifiedTrieProof {
+    let keys: Vec<_> = addresses.iter().map(|address| kind.key(address)).collect();
+    let head = blockchain.head();
+    VerifiedTrieProof {
+        proof: blockchain
+            .get_accounts_proof(keys.iter().collect())
+            .unwrap(),
+        block_hash: head.hash(),
+        block_number: head.block_number(),
+        state_root: head.state_root().clone(),
+    }
+}
+
+#[test]
+fn it_verifies_stakers_and_validators() {
+    let blockchain = blockchain();
+
+    let proof = prove(&blockchain, TrieItemKind::Staker, &[staker_address()]);
+    let stakers = proof
+        .verify::<Staker>(TrieItemKind::Staker, &[staker_address()])
+        .unwrap();
+    let staker = stakers[&staker_address()].as_ref().unwrap();
+    assert_eq!(staker.delegation, Some(validator_address()));
+
+    let proof = prove(&blockchain, TrieItemKind::Validator, &[validator_address()]);
+    let validators = proof
+        .verify::<Validator>(TrieItemKind::Validator, &[validator_address()])
+        .unwrap();
+    let validator = validators[&validator_address()].as_ref().unwrap();
+    assert_eq!(validator.address, validator_address());
+}
+
+#[test]
+fn it_verifies_missing_items() {
+    let blockchain = blockchain();
+
+    // The staker is not a validator.
+    let proof = prove(&blockchain, TrieItemKind::Validator, &[staker_address()]);
+    let validators = proof
+        .verify::<Validator>(TrieItemKind::Validator, &[staker_address()])
+        .unwrap();
+    assert!(validators[&staker_address()].is_none());
+}
+
+#[test]
+fn it_verifies_hex_encoded_proofs() {
+    let blockchain = blockchain();
+    let proof = prove(&blockchain, TrieItemKind::Account, &[staker_address()]);
+    let proof_hex = hex::encode(proof.proof.serialize_to_vec());
+
+    let accounts = VerifiedTrieProof::verify_hex::<Account>(
+        &proof_hex,
+        &proof.state_root.to_hex(),
+        TrieItemKind::Account,
+        &[staker_address()],
+    )
+    .unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert!(accounts[&staker_address()].is_some());
+
+    // The proof was created for the account, not for the staker.
+    assert!(matches!(
+        VerifiedTrieProof::verify_hex::<Staker>(
+            &proof_hex,
+            &proof.state_root.to_hex(),
+            TrieItemKind::Staker,
+            &[staker_address()],
+        ),
+        Err(TrieProofError::InvalidProof)
+    ));
+}
+
+#[test]
+fn it_rejects_invalid_proofs() {
+    let blockchain = blockchain();
+    let proof = prove(&blockchain, TrieItemKind::Account, &[staker_address()]);
+    let proof_bytes = proof.proof.serialize_to_vec();
+    let proof_hex = hex::encode(&proof_bytes);
+
+    assert!(matches!(
+        VerifiedTrieProof::verify_hex::<Account>(
+            &proof_hex,
+            &Blake2bHash::default().to_hex(),
+            TrieItemKind::Account,
+            &[staker_address()],
+        ),
+        Err(TrieProofError::InvalidProof)
+    ));
+    assert!(matches!(
+        VerifiedTrieProof::verify_hex::<Account>(
+            "not hex",
+            &proof.state_root.to_hex(),
+            TrieItemKind::Account,
+            &[staker_address()],
+        ),
+        Err(TrieProofError::Hex(_))
+    ));
+    assert!(matches!(
+        VerifiedTrieProof::verify_hex::<Account>(
+            &hex::encode(&proof_bytes[..proof_bytes.len() / 2]),
+            &proof.state_root.to_hex(),
+            TrieItemKind::Account,
+            &[staker_address()],
+        ),
+        Err(TrieProofError::Deserialize(_))
+    ));
+}
@@ -29,7 +29,8 @@ impl<'txn, Kind> ReadCursor<'txn> for MdbxCursor<'txn, Kind>
 where
     Kind: TransactionKind,
 {
-    type IntoIter<K, V> = IntoIter<'txn, Kind, K, V>
+    type IntoIter<K, V>
+        = IntoIter<'txn, Kind, K, V>
     where
         K: FromDatabaseValue,
         V: FromDatabaseValue;
@@ -191,6 +192,20 @@ where
         ))
     }
 
+    fn seek_key_range_value<K, V>(&mut self, key: &K, value: &V) -> Option<V>
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: AsDatabaseBytes + FromDatabaseValue,
+    {
+        let key = AsDatabaseBytes::as_database_bytes(key);
+        let value = AsDatabaseBytes::as_database_bytes(value);
+        let result: Option<Cow<[u8]>> = self
+            .cursor
+            .get_both_range(key.as_ref(), value.as_ref())
+            .unwrap();
+        Some(FromDatabaseValue::copy_from_database(&result?).unwrap())
+    }
+
     fn count_duplicates(&mut self) -> usize {
         let result: Option<DbKvPair> = self.cursor.get_current().unwrap();
 
@@ -13,6 +13,8 @@ pub(super) type DbKvPair<'a> = (Cow<'a, [u8]>, Cow<'a, [u8]>);
 #[derive(Clone, Debug)]
 pub struct MdbxDatabase {
     pub(super) db: Arc<libmdbx::Database<NoWriteMap>>,
+    /// Whether the database was opened read-only. Tables are never created in this mode.
+    read_only: bool,
 }
 
 impl Database for MdbxDatabase {
@@ -29,6 +31,11 @@ impl Database for MdbxDatabase {
     }
 
     fn open_table_with_flags(&self, name: String, flags: TableFlags) -> Self::Table {
+        // A read-only database cannot create tables, they are opened by name in each transaction.
+        if self.read_only {
+            return MdbxTable { name };
+        }
+
         // This is an implicit transaction, so take the lock first.
         let mut table_flags = libmdbx::TableFlags::CREATE;
 
@@ -91,6 +98,29 @@ impl MdbxDatabase {
         )?))
     }
 
+    /// Opens an existing database without write access, e.g. to inspect the database of a
+    /// node from an external tool. Any attempt to write to the database will fail.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new_read_only<P: AsRef<Path>>(path: P, max_tables: u32) -> Result<DatabaseProxy, Error> {
+        let mut db = libmdbx::Database::new();
+
+        let db_flags = libmdbx::DatabaseFlags {
+            no_rdahead: true,
+            mode: libmdbx::Mode::ReadOnly,
+            ..Default::default()
+        };
+
+        db.set_flags(db_flags);
+        db.set_max_tables(max_tables as usize);
+
+        let db = db.open(path.as_ref())?;
+
+        Ok(DatabaseProxy::Persistent(MdbxDatabase {
+            db: Arc::new(db),
+            read_only: true,
+        }))
+    }
+
     pub(crate) fn new_mdbx_database(
         path: &Path,
         size: usize,
@@ -131,7 +161,10 @@ impl MdbxDatabase {
         let cur_mapsize = info.map_size();
         info!(cur_mapsize, "MDBX memory map size");
 
-        let mdbx = MdbxDatabase { db: Arc::new(db) };
+        let mdbx = MdbxDatabase {
+            db: Arc::new(db),
+            read_only: false,
+        };
         if mdbx.need_resize(0) {
             info!("MDBX memory needs to be resized.");
         }
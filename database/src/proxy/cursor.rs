@@ -14,7 +14,8 @@ pub enum CursorProxy<'txn> {
 }
 
 impl<'txn> ReadCursor<'txn> for CursorProxy<'txn> {
-    type IntoIter<K, V> = IntoIterProxy<'txn, K, V>
+    type IntoIter<K, V>
+        = IntoIterProxy<'txn, K, V>
     where
         K: FromDatabaseValue,
         V: FromDatabaseValue;
@@ -160,6 +161,17 @@ impl<'txn> ReadCursor<'txn> for CursorProxy<'txn> {
         }
     }
 
+    fn seek_key_range_value<K, V>(&mut self, key: &K, value: &V) -> Option<V>
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: AsDatabaseBytes + FromDatabaseValue,
+    {
+        match self {
+            CursorProxy::ReadCursor(cursor) => cursor.seek_key_range_value(key, value),
+            CursorProxy::WriteCursor(cursor) => cursor.seek_key_range_value(key, value),
+        }
+    }
+
     fn count_duplicates(&mut self) -> usize {
         match self {
             CursorProxy::ReadCursor(cursor) => cursor.count_duplicates(),
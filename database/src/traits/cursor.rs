@@ -73,6 +73,13 @@ pub trait ReadCursor<'txn>: Clone {
         K: AsDatabaseBytes + FromDatabaseValue,
         V: FromDatabaseValue;
 
+    /// Positions the cursor at the first duplicate of `key` that is greater than or equal to
+    /// `value` and returns it.
+    fn seek_key_range_value<K, V>(&mut self, key: &K, value: &V) -> Option<V>
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: AsDatabaseBytes + FromDatabaseValue;
+
     fn count_duplicates(&mut self) -> usize;
 
     fn into_iter_start<K, V>(self) -> Self::IntoIter<K, V>
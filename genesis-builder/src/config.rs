@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use nimiq_bls::PublicKey as BlsPublicKey;
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::{Address, PublicKey as SchnorrPublicKey};
@@ -47,6 +49,10 @@ pub struct GenesisConfig {
     /// Set of HTLC accounts for the genesis state.
     #[serde(default)]
     pub htlc_accounts: Vec<GenesisHTLC>,
+
+    /// Dump of an existing chain's state to import into the genesis state.
+    #[serde(default)]
+    pub state_dump: Option<GenesisStateDump>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -55,13 +61,31 @@ pub struct GenesisValidator {
     pub signing_key: SchnorrPublicKey,
     pub voting_key: BlsPublicKey,
     pub reward_address: Address,
+    /// Whether the validator starts out deactivated
+    #[serde(default)]
+    pub inactive: bool,
+    /// Whether the validator starts out retired
+    #[serde(default)]
+    pub retired: bool,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct GenesisStaker {
     pub staker_address: Address,
     pub balance: Coin,
-    pub delegation: Address,
+    pub delegation: Option<Address>,
+    /// Part of the balance that is delegated to validators other than `delegation`
+    #[serde(default)]
+    pub delegations: Vec<GenesisDelegation>,
+    /// Part of the balance that starts out inactive. Its unbonding period starts at genesis.
+    #[serde(default)]
+    pub inactive_balance: Coin,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct GenesisDelegation {
+    pub validator_address: Address,
+    pub stake: Coin,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -109,3 +133,45 @@ pub struct GenesisHTLC {
     /// HTLC total amount
     pub total_amount: Coin,
 }
+
+/// Struct that represents a state dump in the toml file that is used to generate the genesis
+#[derive(Clone, Debug, Deserialize)]
+pub struct GenesisStateDump {
+    /// Path to the dumped accounts trie, relative to the genesis file
+    pub path: PathBuf,
+    /// Options to apply when importing the dump
+    #[serde(flatten)]
+    pub options: StateDumpOptions,
+}
+
+/// Options that control which parts of a state dump are imported into the genesis state and
+/// how their balances are adjusted.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct StateDumpOptions {
+    /// All balances are divided by this value before they are imported
+    pub balance_divisor: u64,
+    /// Accounts and stakers with a (divided) balance below this value are dropped
+    pub min_balance: Coin,
+    /// Whether vesting contracts are imported
+    pub include_vesting: bool,
+    /// Whether HTLCs are imported
+    pub include_htlc: bool,
+    /// Whether validators and stakers are imported
+    pub include_staking: bool,
+    /// Whether the keys of the imported validators are replaced with newly generated ones
+    pub replace_validator_keys: bool,
+}
+
+impl Default for StateDumpOptions {
+    fn default() -> Self {
+        StateDumpOptions {
+            balance_divisor: 1,
+            min_balance: Coin::ZERO,
+            include_vesting: true,
+            include_htlc: true,
+            include_staking: true,
+            replace_validator_keys: false,
+        }
+    }
+}
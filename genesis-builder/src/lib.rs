@@ -2,28 +2,32 @@
 extern crate log;
 
 use std::{
-    fs::{read_to_string, OpenOptions},
-    io::Error as IoError,
+    collections::{HashMap, HashSet},
+    fs::{self, read_to_string, OpenOptions},
+    io::{Error as IoError, Write},
     path::Path,
 };
 
 use nimiq_account::{
-    Account, Accounts, BasicAccount, HashedTimeLockedContract, StakingContract,
-    StakingContractStoreWrite, TransactionLog, VestingContract,
+    Account, Accounts, BasicAccount, HashedTimeLockedContract, Staker, StakerDelegation,
+    StakingContract, StakingContractStore, StakingContractStoreWrite, TransactionLog, Validator,
+    VestingContract,
 };
 use nimiq_block::{Block, MacroBlock, MacroBody, MacroHeader};
-use nimiq_bls::PublicKey as BlsPublicKey;
+use nimiq_bls::{KeyPair as BlsKeyPair, PublicKey as BlsPublicKey};
 use nimiq_database::{
     traits::{Database, WriteTransaction},
     DatabaseProxy,
 };
 use nimiq_hash::{Blake2bHash, Blake2sHash, Hash};
-use nimiq_keys::{Address, PublicKey as SchnorrPublicKey};
+use nimiq_keys::{
+    Address, KeyPair as SchnorrKeyPair, PublicKey as SchnorrPublicKey, SecureGenerate,
+};
 use nimiq_primitives::{
     account::AccountError, coin::Coin, key_nibbles::KeyNibbles, policy::Policy, trie::TrieItem,
     TreeProof,
 };
-use nimiq_serde::{DeserializeError, Serialize};
+use nimiq_serde::{Deserialize, DeserializeError, Serialize};
 use nimiq_trie::WriteTransactionProxy;
 use nimiq_vrf::VrfSeed;
 use thiserror::Error;
@@ -32,6 +36,8 @@ use toml::de::Error as TomlError;
 
 mod config;
 
+pub use config::StateDumpOptions;
+
 /// Errors that can be reported building the genesis
 #[derive(Debug, Error)]
 pub enum GenesisBuilderError {
@@ -50,6 +56,15 @@ pub enum GenesisBuilderError {
     /// Failure at staking
     #[error("Failed to stake")]
     StakingError(#[from] AccountError),
+    /// The balance divisor of a state dump is zero.
+    #[error("Balance divisor must not be zero")]
+    InvalidBalanceDivisor,
+    /// A staker of a state dump delegates to a validator that isn't part of the dump.
+    #[error("Staker {staker_address} delegates to validator {validator_address}, which is not part of the state dump")]
+    MissingValidator {
+        staker_address: Address,
+        validator_address: Address,
+    },
 }
 
 /// Output of the Genesis builder that represents the Genesis block and its
@@ -64,6 +79,17 @@ pub struct GenesisInfo {
     pub accounts: Vec<TrieItem>,
 }
 
+/// Keys that were generated to replace the keys of a validator imported from a state dump.
+#[derive(Clone)]
+pub struct GeneratedValidatorKeys {
+    /// The address of the validator.
+    pub validator_address: Address,
+    /// The new signing key of the validator.
+    pub signing_key: SchnorrKeyPair,
+    /// The new voting key of the validator.
+    pub voting_key: BlsKeyPair,
+}
+
 /// Auxiliary struct for generating `GenesisInfo`.
 pub struct GenesisBuilder {
     /// The genesis seed message.
@@ -86,6 +112,8 @@ pub struct GenesisBuilder {
     pub vesting_accounts: Vec<config::GenesisVestingContract>,
     /// The set of HTLC accounts for the genesis state.
     pub htlc_accounts: Vec<config::GenesisHTLC>,
+    /// The keys generated for validators imported from a state dump.
+    pub generated_validator_keys: Vec<GeneratedValidatorKeys>,
 }
 
 impl Default for GenesisBuilder {
@@ -109,6 +137,7 @@ impl GenesisBuilder {
             basic_accounts: vec![],
             vesting_accounts: vec![],
             htlc_accounts: vec![],
+            generated_validator_keys: vec![],
         }
     }
 
@@ -160,6 +189,8 @@ impl GenesisBuilder {
             signing_key,
             voting_key,
             reward_address,
+            inactive: false,
+            retired: false,
         });
         self
     }
@@ -173,7 +204,9 @@ impl GenesisBuilder {
         self.stakers.push(config::GenesisStaker {
             staker_address,
             balance,
-            delegation: validator_address,
+            delegation: Some(validator_address),
+            delegations: vec![],
+            inactive_balance: Coin::ZERO,
         });
         self
     }
@@ -184,6 +217,217 @@ impl GenesisBuilder {
         self
     }
 
+    /// Imports the accounts, validators and stakers of an existing chain from a dump of its
+    /// accounts trie, i.e. a serialized list of trie items as written by `nimiq-export` or
+    /// contained in the `accounts.dat` of a genesis. The staking contract is not copied but
+    /// rebuilt from the imported validators, stakers and delegations: validators keep whether
+    /// they are inactive or retired but start out with the default deposit, and stakers keep
+    /// their inactive balance, whose unbonding period restarts at genesis.
+    /// The import fails if a staker delegates to a validator that isn't part of the dump, e.g.
+    /// because it has been deleted.
+    pub fn with_state_dump<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        options: &StateDumpOptions,
+    ) -> Result<&mut Self, GenesisBuilderError> {
+        if options.balance_divisor == 0 {
+            return Err(GenesisBuilderError::InvalidBalanceDivisor);
+        }
+        let scale =
+            |balance: Coin| Coin::from_u64_unchecked(u64::from(balance) / options.balance_divisor);
+        let keep = |balance: Coin| !balance.is_zero() && balance >= options.min_balance;
+
+        let items = Vec::<TrieItem>::deserialize_from_vec(&fs::read(path)?)?;
+        let staking_prefix = KeyNibbles::from(&Policy::STAKING_CONTRACT_ADDRESS);
+
+        let mut validators = HashSet::new();
+        let mut stakers = vec![];
+        let mut delegations: HashMap<Address, Vec<StakerDelegation>> = HashMap::new();
+
+        for item in items {
+            if let Some(address) = item.key.to_address() {
+                match Account::deserialize_from_vec(&item.value)? {
+                    Account::Basic(account) => {
+                        let balance = scale(account.balance);
+                        if keep(balance) {
+                            self.with_basic_account(address, balance);
+                        }
+                    }
+                    Account::Vesting(contract) if options.include_vesting => {
+                        let balance = scale(contract.balance);
+                        if keep(balance) {
+                            self.vesting_accounts.push(config::GenesisVestingContract {
+                                address,
+                                owner: contract.owner,
+                                balance,
+                                start_time: contract.start_time,
+                                time_step: contract.time_step,
+                                step_amount: scale(contract.step_amount),
+                                total_amount: scale(contract.total_amount),
+                            });
+                        }
+                    }
+                    Account::HTLC(contract) if options.include_htlc => {
+                        let balance = scale(contract.balance);
+                        if keep(balance) {
+                            self.htlc_accounts.push(config::GenesisHTLC {
+                                address,
+                                sender: contract.sender,
+                                recipient: contract.recipient,
+                                balance,
+                                hash_root: contract.hash_root,
+                                hash_count: contract.hash_count,
+                                timeout: contract.timeout,
+                                total_amount: scale(contract.total_amount),
+                            });
+                        }
+                    }
+                    // Excluded contracts are dropped and the staking contract is rebuilt from
+                    // its validators and stakers.
+                    _ => {}
+                }
+            } else if options.include_staking && staking_prefix.is_prefix_of(&item.key) {
+                // Values of the staking contract are identified by checking that their key
+                // matches the key they would be stored at. Tombstones are dropped.
+                if let Ok(validator) = Validator::deserialize_from_vec(&item.value) {
+                    let key =
+                        &staking_prefix + &StakingContractStore::validator_key(&validator.address);
+                    if item.key == key {
+                        validators.insert(validator.address.clone());
+                        self.import_validator(validator, options.replace_validator_keys)?;
+                        continue;
+                    }
+                }
+                if let Ok(staker) = Staker::deserialize_from_vec(&item.value) {
+                    let key = &staking_prefix + &StakingContractStore::staker_key(&staker.address);
+                    if item.key == key {
+                        stakers.push(staker);
+                        continue;
+                    }
+                }
+                if let Ok(delegation) = StakerDelegation::deserialize_from_vec(&item.value) {
+                    let key = &staking_prefix
+                        + &StakingContractStore::delegation_key(
+                            &delegation.staker_address,
+                            &delegation.validator_address,
+                        );
+                    if item.key == key {
+                        delegations
+                            .entry(delegation.staker_address.clone())
+                            .or_default()
+                            .push(delegation);
+                    }
+                }
+            }
+        }
+
+        for staker in stakers {
+            // Delegations that are scaled down to zero are dropped.
+            let delegations: Vec<_> = delegations
+                .remove(&staker.address)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|delegation| config::GenesisDelegation {
+                    validator_address: delegation.validator_address,
+                    stake: scale(delegation.stake),
+                })
+                .filter(|delegation| !delegation.stake.is_zero())
+                .collect();
+            let balance = delegations
+                .iter()
+                .fold(scale(staker.primary_balance()), |sum, delegation| {
+                    sum + delegation.stake
+                });
+            let inactive_balance = scale(staker.inactive_balance);
+            if !keep(balance + inactive_balance) {
+                continue;
+            }
+
+            let delegated_validators = delegations
+                .iter()
+                .map(|delegation| &delegation.validator_address)
+                .chain(staker.delegation.as_ref());
+            for validator_address in delegated_validators {
+                if !validators.contains(validator_address) {
+                    return Err(GenesisBuilderError::MissingValidator {
+                        staker_address: staker.address,
+                        validator_address: validator_address.clone(),
+                    });
+                }
+            }
+
+            self.stakers.push(config::GenesisStaker {
+                staker_address: staker.address,
+                balance,
+                delegation: staker.delegation,
+                delegations,
+                inactive_balance,
+            });
+        }
+
+        Ok(self)
+    }
+
+    fn import_validator(
+        &mut self,
+        validator: Validator,
+        replace_keys: bool,
+    ) -> Result<(), GenesisBuilderError> {
+        let (signing_key, voting_key) = if replace_keys {
+            let keys = GeneratedValidatorKeys {
+                validator_address: validator.address.clone(),
+                signing_key: SchnorrKeyPair::generate_default_csprng(),
+                voting_key: BlsKeyPair::generate_default_csprng(),
+            };
+            let public_keys = (keys.signing_key.public, keys.voting_key.public_key);
+            self.generated_validator_keys.push(keys);
+            public_keys
+        } else {
+            (validator.signing_key, validator.voting_key.uncompress()?)
+        };
+
+        self.validators.push(config::GenesisValidator {
+            validator_address: validator.address,
+            signing_key,
+            voting_key,
+            reward_address: validator.reward_address,
+            inactive: !validator.is_active(),
+            retired: validator.retired,
+        });
+        Ok(())
+    }
+
+    /// Writes the keys that were generated for validators imported from a state dump to a new
+    /// file. The file is only readable by the current user since it contains private keys.
+    pub fn write_generated_validator_keys<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<(), GenesisBuilderError> {
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(path)?;
+
+        for keys in &self.generated_validator_keys {
+            writeln!(file, "[[validator]]")?;
+            writeln!(file, "validator_address = \"{}\"", keys.validator_address)?;
+            writeln!(
+                file,
+                "signing_key = \"{}\"",
+                keys.signing_key.private.to_hex()
+            )?;
+            writeln!(
+                file,
+                "voting_key = \"{}\"",
+                hex::encode(keys.voting_key.secret_key.serialize_to_vec())
+            )?;
+            writeln!(file)?;
+        }
+
+        Ok(())
+    }
+
     fn with_config_file<P: AsRef<Path>>(
         &mut self,
         path: P,
@@ -199,7 +443,8 @@ impl GenesisBuilder {
             mut basic_accounts,
             mut vesting_accounts,
             mut htlc_accounts,
-        } = toml::from_str(&read_to_string(path)?)?;
+            state_dump,
+        } = toml::from_str(&read_to_string(path.as_ref())?)?;
         vrf_seed.map(|vrf_seed| self.with_vrf_seed(vrf_seed));
         seed_message.map(|msg| self.with_seed_message(msg));
         timestamp.map(|t| self.with_timestamp(t));
@@ -211,6 +456,14 @@ impl GenesisBuilder {
         self.vesting_accounts.append(&mut vesting_accounts);
         self.htlc_accounts.append(&mut htlc_accounts);
 
+        if let Some(state_dump) = state_dump {
+            let dump_path = match path.as_ref().parent() {
+                Some(directory) => directory.join(&state_dump.path),
+                None => state_dump.path,
+            };
+            self.with_state_dump(dump_path, &state_dump.options)?;
+        }
+
         Ok(self)
     }
 
@@ -389,16 +642,52 @@ impl GenesisBuilder {
                 deposit,
                 &mut TransactionLog::empty(),
             )?;
+            if validator.retired {
+                staking_contract.retire_validator(
+                    &mut store,
+                    &validator.validator_address,
+                    0,
+                    &mut TransactionLog::empty(),
+                )?;
+            } else if validator.inactive {
+                staking_contract.deactivate_validator(
+                    &mut store,
+                    &validator.validator_address,
+                    &Address::from(&validator.signing_key),
+                    0,
+                    &mut TransactionLog::empty(),
+                )?;
+            }
         }
 
         for staker in &self.stakers {
+            // The staker is created with its whole balance in the main delegation, from which
+            // the additional delegations and the inactive balance are split off.
             staking_contract.create_staker(
                 &mut store,
                 &staker.staker_address,
-                staker.balance,
-                Some(staker.delegation.clone()),
+                staker.balance + staker.inactive_balance,
+                staker.delegation.clone(),
                 &mut TransactionLog::empty(),
             )?;
+            for delegation in &staker.delegations {
+                staking_contract.set_delegation_stake(
+                    &mut store,
+                    &staker.staker_address,
+                    &delegation.validator_address,
+                    delegation.stake,
+                    &mut TransactionLog::empty(),
+                )?;
+            }
+            if !staker.inactive_balance.is_zero() {
+                staking_contract.set_inactive_stake(
+                    &mut store,
+                    &staker.staker_address,
+                    staker.inactive_balance,
+                    0,
+                    &mut TransactionLog::empty(),
+                )?;
+            }
         }
 
         accounts
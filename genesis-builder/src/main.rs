@@ -5,7 +5,7 @@ use nimiq_genesis_builder::{GenesisBuilder, GenesisInfo};
 
 fn usage(args: Vec<String>) -> ! {
     eprintln!(
-        "Usage: {} GENESIS_FILE",
+        "Usage: {} GENESIS_FILE [VALIDATOR_KEYS_FILE]",
         args.get(0).unwrap_or(&String::from("nimiq-genesis"))
     );
     exit(1);
@@ -20,14 +20,25 @@ fn main() {
     let args = env::args().collect::<Vec<String>>();
 
     if let Some(file) = args.get(1) {
+        let builder = GenesisBuilder::from_config_file(file).unwrap();
+        if !builder.generated_validator_keys.is_empty() {
+            // Private keys are never printed, they are written to a file only the current user
+            // can read.
+            let keys_file = match args.get(2) {
+                Some(keys_file) => keys_file,
+                None => {
+                    eprintln!("Validator keys were generated, a VALIDATOR_KEYS_FILE is required");
+                    usage(args);
+                }
+            };
+            builder.write_generated_validator_keys(keys_file).unwrap();
+            eprintln!("Wrote generated validator keys to {keys_file}");
+        }
         let GenesisInfo {
             block,
             hash,
             accounts,
-        } = GenesisBuilder::from_config_file(file)
-            .unwrap()
-            .generate(env)
-            .unwrap();
+        } = builder.generate(env).unwrap();
 
         println!("Genesis Block: {hash}");
         println!("{block:#?}");
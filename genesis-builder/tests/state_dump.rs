@@ -0,0 +1,148 @@
+use nimiq_account::{Account, Staker, StakingContractStore, Validator};
+use nimiq_bls::KeyPair as BlsKeyPair;
+use nimiq_database::volatile::VolatileDatabase;
+use nimiq_genesis_builder::{GenesisBuilder, GenesisInfo, StateDumpOptions};
+use nimiq_keys::{Address, KeyPair as SchnorrKeyPair, SecureGenerate};
+use nimiq_primitives::{coin::Coin, key_nibbles::KeyNibbles, policy::Policy};
+use nimiq_serde::{Deserialize, Serialize};
+use nimiq_test_log::test;
+
+fn address(byte: u8) -> Address {
+    Address::from([byte; Address::SIZE])
+}
+
+fn item<T: Deserialize>(genesis: &GenesisInfo, key: &KeyNibbles) -> Option<T> {
+    genesis
+        .accounts
+        .iter()
+        .find(|item| item.key == *key)
+        .map(|item| T::deserialize_from_vec(&item.value).unwrap())
+}
+
+fn staking_key(key: KeyNibbles) -> KeyNibbles {
+    &KeyNibbles::from(&Policy::STAKING_CONTRACT_ADDRESS) + &key
+}
+
+/// Generates the genesis the state dump is taken from.
+fn source_genesis(validator_keys: &SchnorrKeyPair) -> GenesisInfo {
+    let mut genesis_builder = GenesisBuilder::default();
+    genesis_builder
+        .with_genesis_validator(
+            address(1),
+            validator_keys.public,
+            BlsKeyPair::generate_default_csprng().public_key,
+            address(2),
+        )
+        .with_genesis_staker(
+            address(3),
+            address(1),
+            Coin::from_u64_unchecked(50_000_000_000),
+        )
+        .with_basic_account(address(4), Coin::from_u64_unchecked(1_000_000))
+        .with_basic_account(address(5), Coin::from_u64_unchecked(500));
+    genesis_builder
+        .generate(VolatileDatabase::new(20).unwrap())
+        .unwrap()
+}
+
+#[test]
+fn it_imports_a_state_dump() {
+    let validator_keys = SchnorrKeyPair::generate_default_csprng();
+    let source = source_genesis(&validator_keys);
+
+    let dump = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(dump.path(), source.accounts.serialize_to_vec()).unwrap();
+
+    let mut genesis_builder = GenesisBuilder::default();
+    genesis_builder
+        .with_state_dump(
+            dump.path(),
+            &StateDumpOptions {
+                balance_divisor: 10,
+                min_balance: Coin::from_u64_unchecked(100),
+                replace_validator_keys: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let genesis = genesis_builder
+        .generate(VolatileDatabase::new(20).unwrap())
+        .unwrap();
+
+    // Balances are scaled down and accounts below the minimum balance are dropped.
+    let account: Account = item(&genesis, &KeyNibbles::from(&address(4))).unwrap();
+    assert_eq!(account.balance(), Coin::from_u64_unchecked(100_000));
+    assert!(item::<Account>(&genesis, &KeyNibbles::from(&address(5))).is_none());
+
+    // The staker keeps its delegation with a scaled down stake.
+    let staker: Staker = item(
+        &genesis,
+        &staking_key(StakingContractStore::staker_key(&address(3))),
+    )
+    .unwrap();
+    assert_eq!(staker.delegation, Some(address(1)));
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(5_000_000_000));
+
+    // The validator keeps its reward address, but its keys are replaced.
+    assert_eq!(genesis_builder.generated_validator_keys.len(), 1);
+    let keys = &genesis_builder.generated_validator_keys[0];
+    assert_eq!(keys.validator_address, address(1));
+
+    let validator: Validator = item(
+        &genesis,
+        &staking_key(StakingContractStore::validator_key(&address(1))),
+    )
+    .unwrap();
+    assert_eq!(validator.reward_address, address(2));
+    assert_eq!(validator.signing_key, keys.signing_key.public);
+    assert_ne!(validator.signing_key, validator_keys.public);
+    assert_eq!(validator.voting_key, keys.voting_key.public_key.compress());
+    assert_eq!(validator.total_stake, staker.balance + validator.deposit);
+}
+
+#[test]
+fn it_keeps_validator_keys_by_default() {
+    let validator_keys = SchnorrKeyPair::generate_default_csprng();
+    let source = source_genesis(&validator_keys);
+
+    let dump = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(dump.path(), source.accounts.serialize_to_vec()).unwrap();
+
+    let mut genesis_builder = GenesisBuilder::default();
+    genesis_builder
+        .with_state_dump(dump.path(), &StateDumpOptions::default())
+        .unwrap();
+    let genesis = genesis_builder
+        .generate(VolatileDatabase::new(20).unwrap())
+        .unwrap();
+
+    assert!(genesis_builder.generated_validator_keys.is_empty());
+    let validator: Validator = item(
+        &genesis,
+        &staking_key(StakingContractStore::validator_key(&address(1))),
+    )
+    .unwrap();
+    assert_eq!(validator.signing_key, validator_keys.public);
+
+    // Without scaling, all accounts are imported with their original balance.
+    let account: Account = item(&genesis, &KeyNibbles::from(&address(5))).unwrap();
+    assert_eq!(account.balance(), Coin::from_u64_unchecked(500));
+}
+
+#[test]
+fn it_rejects_a_zero_balance_divisor() {
+    let source = source_genesis(&SchnorrKeyPair::generate_default_csprng());
+
+    let dump = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(dump.path(), source.accounts.serialize_to_vec()).unwrap();
+
+    assert!(GenesisBuilder::default()
+        .with_state_dump(
+            dump.path(),
+            &StateDumpOptions {
+                balance_divisor: 0,
+                ..Default::default()
+            },
+        )
+        .is_err());
+}
@@ -13,7 +13,9 @@ use nimiq_consensus::{
 #[cfg(feature = "zkp-prover")]
 use nimiq_genesis::NetworkId;
 use nimiq_genesis::NetworkInfo;
-use nimiq_light_blockchain::LightBlockchain;
+#[cfg(feature = "database-storage")]
+use nimiq_light_blockchain::DBLightChainStorage;
+use nimiq_light_blockchain::{LightBlockchain, LightChainStorage};
 #[cfg(feature = "validator")]
 use nimiq_mempool::mempool::Mempool;
 use nimiq_network_interface::{
@@ -28,6 +30,8 @@ use nimiq_network_libp2p::{
 use nimiq_primitives::{policy::Policy, task_executor::TaskExecutor};
 use nimiq_utils::time::OffsetTime;
 #[cfg(feature = "validator")]
+use nimiq_validator::signer::{remote::RemoteSigner, LocalSigner, ValidatorSigner};
+#[cfg(feature = "validator")]
 use nimiq_validator::validator::Validator as AbstractValidator;
 #[cfg(feature = "validator")]
 use nimiq_validator::validator::ValidatorProxy as AbstractValidatorProxy;
@@ -132,6 +136,7 @@ impl ClientInner {
     async fn from_config(
         config: ClientConfig,
         executor: impl TaskExecutor + Send + 'static + Clone,
+        light_chain_storage: Option<Arc<dyn LightChainStorage>>,
     ) -> Result<Client, Error> {
         // Get network info (i.e. which specific blockchain we're on)
         if !config.network_id.is_albatross() {
@@ -442,7 +447,14 @@ impl ClientInner {
                 (blockchain_proxy, syncer, zkp_component)
             }
             SyncMode::Light => {
-                let blockchain = Arc::new(RwLock::new(LightBlockchain::new(config.network_id)));
+                #[cfg(feature = "database-storage")]
+                let light_chain_storage = light_chain_storage
+                    .or_else(|| Some(Arc::new(DBLightChainStorage::new(environment.clone())) as _));
+                let blockchain = match light_chain_storage {
+                    Some(storage) => LightBlockchain::with_storage(config.network_id, storage),
+                    None => LightBlockchain::new(config.network_id),
+                };
+                let blockchain = Arc::new(RwLock::new(blockchain));
                 let blockchain_proxy = BlockchainProxy::from(&blockchain);
                 let zkp_component = ZKPComponent::new(
                     blockchain_proxy.clone(),
@@ -488,14 +500,28 @@ impl ClientInner {
                     // Load validator address
                     let automatic_reactivate = validator_config.automatic_reactivate;
 
-                    // Load signing key (before we give away ownership of the storage config)
-                    let signing_key = config.storage.signing_keypair()?;
-
-                    // Load validator key (before we give away ownership of the storage config)
-                    let voting_key = config.storage.voting_keypair()?;
-
-                    // Load fee key (before we give away ownership of the storage config)
-                    let fee_key = config.storage.fee_keypair()?;
+                    // Load the number of blocks to watch for another instance of the validator
+                    let doppelganger_protection_blocks =
+                        validator_config.doppelganger_protection_blocks;
+
+                    // Connect to the remote signer or load the signing, voting and fee keys
+                    // (before we give away ownership of the storage config)
+                    let signer: Arc<dyn ValidatorSigner> = match &validator_config.remote_signer {
+                        Some(endpoint) => {
+                            let signer =
+                                RemoteSigner::connect(endpoint.clone()).await.map_err(|e| {
+                                    Error::config_error(format!(
+                                        "Failed to connect to remote signer {endpoint}: {e}"
+                                    ))
+                                })?;
+                            Arc::new(signer)
+                        }
+                        None => Arc::new(LocalSigner::new(
+                            config.storage.signing_keypair()?,
+                            config.storage.voting_keypair()?,
+                            config.storage.fee_keypair()?,
+                        )),
+                    };
 
                     let validator_network =
                         Arc::new(ValidatorNetworkImpl::new(Arc::clone(&network)));
@@ -507,9 +533,8 @@ impl ClientInner {
                         validator_network,
                         validator_address,
                         automatic_reactivate,
-                        signing_key,
-                        voting_key,
-                        fee_key,
+                        doppelganger_protection_blocks,
+                        signer,
                         config.mempool,
                     );
 
@@ -580,7 +605,17 @@ impl Client {
         config: ClientConfig,
         executor: impl TaskExecutor + Send + 'static + Clone,
     ) -> Result<Self, Error> {
-        ClientInner::from_config(config, executor).await
+        ClientInner::from_config(config, executor, None).await
+    }
+
+    /// Creates a client that persists the light blockchain in the given storage instead of the
+    /// database. The storage is only used by light nodes.
+    pub async fn from_config_with_light_chain_storage(
+        config: ClientConfig,
+        executor: impl TaskExecutor + Send + 'static + Clone,
+        light_chain_storage: Arc<dyn LightChainStorage>,
+    ) -> Result<Self, Error> {
+        ClientInner::from_config(config, executor, Some(light_chain_storage)).await
     }
 
     pub fn take_consensus(&mut self) -> Option<Consensus> {
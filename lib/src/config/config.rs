@@ -2,6 +2,8 @@
 use std::net::IpAddr;
 #[cfg(feature = "metrics-server")]
 use std::net::SocketAddr;
+#[cfg(feature = "rpc-server")]
+use std::time::Duration;
 use std::{
     path::{Path, PathBuf},
     string::ToString,
@@ -23,6 +25,8 @@ use nimiq_serde::Deserialize;
 use nimiq_utils::file_store::FileStore;
 #[cfg(feature = "validator")]
 use nimiq_utils::key_rng::SecureGenerate;
+#[cfg(feature = "validator")]
+use nimiq_validator::signer::remote::RemoteSignerEndpoint;
 use nimiq_zkp_circuits::DEFAULT_KEYS_PATH;
 use strum_macros::Display;
 
@@ -32,6 +36,8 @@ use crate::config::config_file::DatabaseSettings;
 use crate::config::consts;
 #[cfg(feature = "metrics-server")]
 use crate::config::consts::default_bind;
+#[cfg(feature = "rpc-server")]
+use crate::extras::{rpc_access_control::IpRange, rpc_permissions::ApiToken};
 use crate::{
     config::{
         command_line::CommandLine,
@@ -231,8 +237,8 @@ pub struct DatabaseConfig {
     #[builder(default = "1024 * 1024 * 1024 * 1024")]
     size: usize,
 
-    /// Max number of DBs. Recommended: 20
-    #[builder(default = "20")]
+    /// Max number of DBs. Recommended: 32
+    #[builder(default = "32")]
     max_dbs: u32,
 
     /// Max number of threads that can open read transactions.
@@ -248,7 +254,7 @@ impl Default for DatabaseConfig {
         Self {
             // 1 TB
             size: 1024 * 1024 * 1024 * 1024,
-            max_dbs: 20,
+            max_dbs: 32,
             max_readers: 600,
         }
     }
@@ -476,6 +482,14 @@ pub struct ValidatorConfig {
 
     /// Config if the validator automatically reactivates itself.
     pub automatic_reactivate: bool,
+
+    /// Number of blocks the validator watches for another instance using its keys before it
+    /// starts signing. Zero disables the doppelganger protection.
+    pub doppelganger_protection_blocks: u32,
+
+    /// The remote signer holding the signing and voting keys. If not set, the keys are loaded
+    /// from the storage config.
+    pub remote_signer: Option<RemoteSignerEndpoint>,
 }
 
 /// Credentials for JSON RPC server, metrics server or websocket RPC server
@@ -518,14 +532,51 @@ pub struct RpcServerConfig {
     #[builder(default = "consts::RPC_DEFAULT_PORT")]
     pub port: u16,
 
-    /// TODO
+    /// Enable the WebSocket transport, which is required for subscriptions.
+    ///
+    /// Default: `false`
+    ///
+    #[builder(default)]
+    pub enable_websocket: bool,
+
+    /// The maximum number of subscriptions a single WebSocket connection can hold.
+    ///
+    /// Default: `16`
+    ///
+    #[builder(default = "consts::RPC_DEFAULT_MAX_SUBSCRIPTIONS")]
+    pub max_subscriptions: usize,
+
+    /// The maximum number of requests in a JSON-RPC batch.
+    ///
+    /// Default: `100`
+    ///
+    #[builder(default = "consts::RPC_DEFAULT_MAX_BATCH_SIZE")]
+    pub max_batch_size: usize,
+
+    /// The maximum size of a request body or WebSocket message in bytes.
+    ///
+    /// Default: `10 MiB`
+    ///
+    #[builder(default = "consts::RPC_DEFAULT_MAX_BODY_SIZE")]
+    pub max_body_size: usize,
+
+    /// How long the RPC server may take to answer a single request.
+    ///
+    /// Default: `30s`
+    ///
+    #[builder(default = "consts::RPC_DEFAULT_REQUEST_TIMEOUT")]
+    pub request_timeout: Duration,
+
+    /// If specified, allow cross-origin requests from these origins, e.g.
+    /// `https://dashboard.example.com`. `*` allows any origin.
+    ///
     #[builder(setter(strip_option))]
     pub corsdomain: Option<Vec<String>>,
 
-    /// If specified, only allow connections from these IP addresses
+    /// If specified, only allow connections from these IP addresses or CIDR ranges
     ///
     #[builder(setter(strip_option))]
-    pub allow_ips: Option<Vec<IpAddr>>,
+    pub allow_ips: Option<Vec<IpRange>>,
 
     /// If specified, only allow these RPC methods
     ///
@@ -535,6 +586,12 @@ pub struct RpcServerConfig {
     /// If specified, require HTTP basic auth with these credentials
     #[builder(setter(strip_option))]
     pub credentials: Option<Credentials>,
+
+    /// If not empty, require every request to authenticate with one of these tokens. Each token
+    /// can only call its own set of methods, on top of `allowed_methods`, and can be rate
+    /// limited. The `credentials` then grant access to all methods.
+    #[builder(default)]
+    pub tokens: Vec<ApiToken>,
 }
 
 #[cfg(feature = "metrics-server")]
@@ -682,6 +739,7 @@ impl ClientConfigBuilder {
             control_size_limit,
             filter_rules,
             filter_limit,
+            ..Default::default()
         });
         self
     }
@@ -753,6 +811,13 @@ impl ClientConfigBuilder {
             self.validator(ValidatorConfig {
                 validator_address: Address::from_any_str(&validator_config.validator_address)?,
                 automatic_reactivate: validator_config.automatic_reactivate,
+                doppelganger_protection_blocks: validator_config.doppelganger_protection_blocks,
+                remote_signer: validator_config
+                    .remote_signer
+                    .as_deref()
+                    .map(str::parse)
+                    .transpose()
+                    .map_err(Error::config_error)?,
             });
 
             if let Some(key_path) = &validator_config.voting_key_file {
@@ -816,10 +881,10 @@ impl ClientConfigBuilder {
                         .allowip
                         .iter()
                         .map(|s| {
-                            s.parse::<IpAddr>()
+                            s.parse::<IpRange>()
                                 .map_err(|e| Error::config_error(format!("Invalid IP: {e}")))
                         })
-                        .collect::<Result<Vec<IpAddr>, Error>>();
+                        .collect::<Result<Vec<IpRange>, Error>>();
                     Some(result?)
                 };
 
@@ -833,13 +898,53 @@ impl ClientConfigBuilder {
                     }
                 };
 
+                let tokens = rpc_config
+                    .tokens
+                    .iter()
+                    .map(|token| {
+                        let credentials = match (&token.username, &token.password) {
+                            (Some(u), Some(p)) => Some(Credentials::new(u, p)),
+                            (None, None) => None,
+                            _ => {
+                                return Err(Error::config_error(format!(
+                                    "RPC token {}: Either both username and password have to be set or none.",
+                                    token.name
+                                )))
+                            }
+                        };
+                        ApiToken::new(
+                            token.name.clone(),
+                            token.token.clone(),
+                            credentials,
+                            &token.methods,
+                            token.rate_limit,
+                        )
+                        .map_err(|e| Error::config_error(format!("Invalid RPC token: {e}")))
+                    })
+                    .collect::<Result<Vec<ApiToken>, Error>>()?;
+
                 self.rpc_server = Some(Some(RpcServerConfig {
                     bind_to,
                     port: rpc_config.port.unwrap_or(consts::RPC_DEFAULT_PORT),
+                    enable_websocket: rpc_config.websocket,
+                    max_subscriptions: rpc_config
+                        .max_subscriptions
+                        .unwrap_or(consts::RPC_DEFAULT_MAX_SUBSCRIPTIONS),
+                    max_batch_size: rpc_config
+                        .max_batch_size
+                        .unwrap_or(consts::RPC_DEFAULT_MAX_BATCH_SIZE),
+                    max_body_size: rpc_config
+                        .max_body_size
+                        .unwrap_or(consts::RPC_DEFAULT_MAX_BODY_SIZE),
+                    request_timeout: rpc_config
+                        .request_timeout
+                        .map(Duration::from_secs)
+                        .unwrap_or(consts::RPC_DEFAULT_REQUEST_TIMEOUT),
                     corsdomain: Some(rpc_config.corsdomain.clone()),
                     allow_ips,
                     allowed_methods: Some(rpc_config.methods.clone()),
                     credentials,
+                    tokens,
                 }));
             }
         }
@@ -3,7 +3,7 @@ use std::{collections::HashMap, fs::read_to_string, path::Path, str::FromStr};
 use log::level_filters::LevelFilter;
 #[cfg(feature = "nimiq-mempool")]
 use nimiq_mempool::{
-    config::MempoolConfig,
+    config::{MempoolConfig, MempoolLimits},
     filter::{MempoolFilter, MempoolRules},
     mempool::Mempool,
 };
@@ -262,6 +262,12 @@ pub struct RpcServerSettings {
     pub bind: Option<String>,
     pub port: Option<u16>,
     #[serde(default)]
+    pub websocket: bool,
+    pub max_subscriptions: Option<usize>,
+    pub max_batch_size: Option<usize>,
+    pub max_body_size: Option<usize>,
+    pub request_timeout: Option<u64>,
+    #[serde(default)]
     pub corsdomain: Vec<String>,
     #[serde(default)]
     pub allowip: Vec<String>,
@@ -269,6 +275,20 @@ pub struct RpcServerSettings {
     pub methods: Vec<String>,
     pub username: Option<String>,
     pub password: Option<String>,
+    #[serde(default)]
+    pub tokens: Vec<RpcTokenSettings>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RpcTokenSettings {
+    pub name: String,
+    pub token: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    #[serde(default)]
+    pub methods: Vec<String>,
+    pub rate_limit: Option<u32>,
 }
 
 #[derive(Clone, Debug, Deserialize, Default)]
@@ -353,6 +373,11 @@ pub struct MempoolSettings {
     pub size_limit: Option<usize>,
     pub control_size_limit: Option<usize>,
     pub blacklist_limit: Option<usize>,
+    pub journal: Option<bool>,
+    pub sender_txns_limit: Option<usize>,
+    pub sender_size_limit: Option<usize>,
+    pub peer_txns_limit: Option<usize>,
+    pub peer_size_limit: Option<usize>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -405,6 +430,21 @@ impl From<MempoolSettings> for MempoolConfig {
                 .blacklist_limit
                 .unwrap_or(MempoolFilter::DEFAULT_BLACKLIST_SIZE),
             filter_rules: mempool.filter.map(MempoolRules::from).unwrap_or_default(),
+            journal: mempool.journal.unwrap_or_default(),
+            limits: MempoolLimits {
+                sender_txns_limit: mempool
+                    .sender_txns_limit
+                    .unwrap_or(Mempool::DEFAULT_SENDER_TXNS_LIMIT),
+                sender_size_limit: mempool
+                    .sender_size_limit
+                    .unwrap_or(Mempool::DEFAULT_SENDER_SIZE_LIMIT),
+                peer_txns_limit: mempool
+                    .peer_txns_limit
+                    .unwrap_or(Mempool::DEFAULT_PEER_TXNS_LIMIT),
+                peer_size_limit: mempool
+                    .peer_size_limit
+                    .unwrap_or(Mempool::DEFAULT_PEER_SIZE_LIMIT),
+            },
         }
     }
 }
@@ -442,6 +482,9 @@ pub struct ValidatorSettings {
     pub fee_key: Option<String>,
     #[serde(default)]
     pub automatic_reactivate: bool,
+    #[serde(default)]
+    pub doppelganger_protection_blocks: u32,
+    pub remote_signer: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Default)]
@@ -1,4 +1,7 @@
-use std::net::{IpAddr, Ipv4Addr};
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    time::Duration,
+};
 
 /// The default port for `ws` and `wss`.
 pub const WS_DEFAULT_PORT: u16 = 8443;
@@ -9,6 +12,18 @@ pub const REVERSE_PROXY_DEFAULT_PORT: u16 = 8444;
 /// The default port for the RPC server
 pub const RPC_DEFAULT_PORT: u16 = 8648;
 
+/// The default maximum number of subscriptions per WebSocket connection to the RPC server
+pub const RPC_DEFAULT_MAX_SUBSCRIPTIONS: usize = 16;
+
+/// The default maximum number of requests in a JSON-RPC batch
+pub const RPC_DEFAULT_MAX_BATCH_SIZE: usize = 100;
+
+/// The default maximum size of a request to the RPC server, in bytes
+pub const RPC_DEFAULT_MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// The default time the RPC server may take to answer a single request
+pub const RPC_DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// The default port for the metrics server
 pub const METRICS_DEFAULT_PORT: u16 = 9100;
 
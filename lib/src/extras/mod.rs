@@ -9,7 +9,17 @@ pub mod metrics_server;
 #[cfg(feature = "panic")]
 pub mod panic;
 #[cfg(feature = "rpc-server")]
+pub mod rpc_access_control;
+#[cfg(feature = "rpc-server")]
+pub mod rpc_permissions;
+#[cfg(feature = "rpc-server")]
+pub mod rpc_proxy;
+#[cfg(feature = "rpc-server")]
 pub mod rpc_server;
+#[cfg(feature = "rpc-server")]
+pub mod rpc_subscriptions;
+#[cfg(feature = "rpc-server")]
+pub mod rpc_upstream;
 #[cfg(feature = "signal-handling")]
 pub mod signal_handling;
 #[cfg(feature = "web-logging")]
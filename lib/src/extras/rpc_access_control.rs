@@ -0,0 +1,151 @@
+use std::{
+    fmt,
+    net::{AddrParseError, IpAddr},
+    str::FromStr,
+};
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum IpRangeParseError {
+    #[error("Invalid IP address: {0}")]
+    Address(#[from] AddrParseError),
+
+    #[error("Invalid prefix length: {0}")]
+    PrefixLength(String),
+}
+
+/// A range of IP addresses in CIDR notation, e.g. `10.0.0.0/8` or `fd00::/8`.
+/// A plain IP address is parsed as the range containing only that address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IpRange {
+    address: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpRange {
+    /// Returns whether the given IP address is part of this range. IPv4-mapped IPv6 addresses
+    /// are treated as the IPv4 address they map to.
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.address, canonical_ip(ip)) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => prefix_matches(
+                u32::from(network).into(),
+                u32::from(ip).into(),
+                32,
+                self.prefix_len,
+            ),
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                prefix_matches(network.into(), ip.into(), 128, self.prefix_len)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl From<IpAddr> for IpRange {
+    fn from(address: IpAddr) -> Self {
+        let address = canonical_ip(&address);
+        let prefix_len = if address.is_ipv4() { 32 } else { 128 };
+        Self {
+            address,
+            prefix_len,
+        }
+    }
+}
+
+impl FromStr for IpRange {
+    type Err = IpRangeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, prefix_len) = match s.split_once('/') {
+            Some((address, prefix_len)) => (address, Some(prefix_len)),
+            None => (s, None),
+        };
+
+        let range = IpRange::from(address.trim().parse::<IpAddr>()?);
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => match prefix_len.trim().parse::<u8>() {
+                Ok(len) if len <= range.prefix_len => len,
+                _ => return Err(IpRangeParseError::PrefixLength(prefix_len.to_string())),
+            },
+            None => range.prefix_len,
+        };
+
+        Ok(Self {
+            address: range.address,
+            prefix_len,
+        })
+    }
+}
+
+impl fmt::Display for IpRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix_len)
+    }
+}
+
+fn canonical_ip(ip: &IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(*ip),
+        IpAddr::V4(_) => *ip,
+    }
+}
+
+fn prefix_matches(network: u128, ip: u128, bits: u8, prefix_len: u8) -> bool {
+    if prefix_len == 0 {
+        return true;
+    }
+    let shift = bits - prefix_len;
+    network >> shift == ip >> shift
+}
+
+/// The access rules of the RPC server: which origins may make cross-origin requests and which
+/// client IPs may connect at all.
+#[derive(Clone, Debug, Default)]
+pub struct AccessControl {
+    /// The origins allowed to make cross-origin requests. `*` allows any origin.
+    /// If empty, no CORS headers are sent and the origin of a request is not checked.
+    cors_origins: Vec<String>,
+    /// If set, only clients with an IP in one of these ranges are allowed.
+    allowed_ips: Option<Vec<IpRange>>,
+}
+
+impl AccessControl {
+    pub fn new(cors_origins: Vec<String>, allowed_ips: Option<Vec<IpRange>>) -> Self {
+        let cors_origins = cors_origins
+            .into_iter()
+            .map(|origin| origin.trim().trim_end_matches('/').to_ascii_lowercase())
+            .filter(|origin| !origin.is_empty())
+            .collect();
+        Self {
+            cors_origins,
+            allowed_ips,
+        }
+    }
+
+    /// Returns whether any access rules are configured.
+    pub fn is_enabled(&self) -> bool {
+        self.is_cors_enabled() || self.allowed_ips.is_some()
+    }
+
+    /// Returns whether cross-origin requests are restricted to the configured origins.
+    pub fn is_cors_enabled(&self) -> bool {
+        !self.cors_origins.is_empty()
+    }
+
+    /// Returns whether a client with the given IP is allowed to connect.
+    pub fn is_ip_allowed(&self, ip: &IpAddr) -> bool {
+        match &self.allowed_ips {
+            Some(ranges) => ranges.iter().any(|range| range.contains(ip)),
+            None => true,
+        }
+    }
+
+    /// Returns whether the given origin is allowed to make cross-origin requests.
+    pub fn is_origin_allowed(&self, origin: &str) -> bool {
+        let origin = origin.trim_end_matches('/').to_ascii_lowercase();
+        self.cors_origins
+            .iter()
+            .any(|allowed| allowed == "*" || *allowed == origin)
+    }
+}
@@ -0,0 +1,484 @@
+use std::{collections::HashSet, sync::Arc, time::Instant};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hyper::header::HeaderValue;
+use parking_lot::Mutex;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{config::config::Credentials, extras::rpc_upstream::INVALID_REQUEST_ERROR};
+
+/// The JSON-RPC error code returned if a token is not allowed to call a method.
+pub const METHOD_NOT_ALLOWED_ERROR: i64 = -32001;
+
+/// The JSON-RPC error code returned if a token exceeded its rate limit.
+pub const RATE_LIMITED_ERROR: i64 = -32002;
+
+/// The group allowing all methods.
+const ALL_METHODS: &str = "*";
+
+const BLOCKCHAIN_METHODS: &[&str] = &[
+    "getBlockNumber",
+    "getBatchNumber",
+    "getEpochNumber",
+    "getBlockByHash",
+    "getBlockByNumber",
+    "getLatestBlock",
+    "getSlotAt",
+    "getTransactionByHash",
+    "getTransactionsByBlockNumber",
+    "getInherentsByBlockNumber",
+    "getTransactionsByBatchNumber",
+    "getInherentsByBatchNumber",
+    "getTransactionHashesByAddress",
+    "getTransactionsByAddress",
+    "getTransactionCountByAddress",
+    "getLogs",
+    "getAccountByAddress",
+    "getAccountByAddressAt",
+    "getAccounts",
+    "getActiveValidators",
+    "getCurrentSlashedSlots",
+    "getPreviousSlashedSlots",
+    "getValidatorByAddress",
+    "getValidatorPerformance",
+    "getValidators",
+    "getStakersByValidatorAddress",
+    "getStakerByAddress",
+    "subscribeForHeadBlock",
+    "subscribeForHeadBlockHash",
+    "subscribeForValidatorElectionByAddress",
+    "subscribeForLogsByAddressesAndTypes",
+];
+
+const CONSENSUS_READ_METHODS: &[&str] = &["isConsensusEstablished", "getRawTransactionInfo"];
+
+// Creating transactions signs them with unlocked wallets, so these are not read-only.
+const CONSENSUS_WRITE_METHODS: &[&str] = &[
+    "sendRawTransaction",
+    "createBasicTransaction",
+    "sendBasicTransaction",
+    "createBasicTransactionWithData",
+    "sendBasicTransactionWithData",
+    "createNewVestingTransaction",
+    "sendNewVestingTransaction",
+    "createRedeemVestingTransaction",
+    "sendRedeemVestingTransaction",
+    "createNewHtlcTransaction",
+    "sendNewHtlcTransaction",
+    "createRedeemRegularHtlcTransaction",
+    "sendRedeemRegularHtlcTransaction",
+    "createRedeemTimeoutHtlcTransaction",
+    "sendRedeemTimeoutHtlcTransaction",
+    "createRedeemEarlyHtlcTransaction",
+    "sendRedeemEarlyHtlcTransaction",
+    "signRedeemEarlyHtlcTransaction",
+    "createNewStakerTransaction",
+    "sendNewStakerTransaction",
+    "createStakeTransaction",
+    "sendStakeTransaction",
+    "createUpdateStakerTransaction",
+    "sendUpdateStakerTransaction",
+    "createSetInactiveStakeTransaction",
+    "sendSetInactiveStakeTransaction",
+    "createUnstakeTransaction",
+    "sendUnstakeTransaction",
+    "createNewValidatorTransaction",
+    "sendNewValidatorTransaction",
+    "createUpdateValidatorTransaction",
+    "sendUpdateValidatorTransaction",
+    "createUpdateValidatorCommissionTransaction",
+    "sendUpdateValidatorCommissionTransaction",
+    "createDeactivateValidatorTransaction",
+    "sendDeactivateValidatorTransaction",
+    "createReactivateValidatorTransaction",
+    "sendReactivateValidatorTransaction",
+    "createRetireValidatorTransaction",
+    "sendRetireValidatorTransaction",
+    "createDeleteValidatorTransaction",
+    "sendDeleteValidatorTransaction",
+];
+
+const MEMPOOL_READ_METHODS: &[&str] = &[
+    "mempoolContent",
+    "mempool",
+    "getMinFeePerByte",
+    "estimateFee",
+    "getMempoolHistory",
+    "getTransactionFromMempool",
+    "subscribeForMempoolTransactions",
+];
+
+const MEMPOOL_WRITE_METHODS: &[&str] = &["pushTransaction", "pushHighPriorityTransaction"];
+
+const NETWORK_METHODS: &[&str] = &["getPeerId", "getPeerCount", "getPeerList"];
+
+const POLICY_METHODS: &[&str] = &[
+    "getPolicyConstants",
+    "getEpochAt",
+    "getEpochIndexAt",
+    "getBatchAt",
+    "getBatchIndexAt",
+    "getElectionBlockAfter",
+    "getElectionBlockBefore",
+    "getLastElectionBlock",
+    "getIsElectionBlockAt",
+    "getMacroBlockAfter",
+    "getMacroBlockBefore",
+    "getLastMacroBlock",
+    "getIsMacroBlockAt",
+    "getIsMicroBlockAt",
+    "getFirstBlockOf",
+    "getFirstBlockOfBatch",
+    "getElectionBlockOf",
+    "getMacroBlockOf",
+    "getFirstBatchOfEpoch",
+    "getSupplyAt",
+];
+
+const VALIDATOR_METHODS: &[&str] = &[
+    "getAddress",
+    "getSigningKey",
+    "getVotingKey",
+    "setAutomaticReactivation",
+    "exportSlashingProtection",
+    "importSlashingProtection",
+];
+
+const WALLET_METHODS: &[&str] = &[
+    "importRawKey",
+    "isAccountImported",
+    "listAccounts",
+    "lockAccount",
+    "createAccount",
+    "unlockAccount",
+    "isAccountUnlocked",
+    "sign",
+    "verifySignature",
+];
+
+const ZKP_METHODS: &[&str] = &["getZkpState", "listStoredZkps", "getStoredZkp"];
+
+/// Returns the methods of a method group, or None if there is no group with that name.
+fn group_methods(group: &str) -> Option<Vec<&'static [&'static str]>> {
+    let methods = match group {
+        "blockchain:read" => vec![BLOCKCHAIN_METHODS],
+        "consensus:read" => vec![CONSENSUS_READ_METHODS],
+        "consensus" => vec![CONSENSUS_READ_METHODS, CONSENSUS_WRITE_METHODS],
+        "mempool:read" => vec![MEMPOOL_READ_METHODS],
+        "mempool" => vec![MEMPOOL_READ_METHODS, MEMPOOL_WRITE_METHODS],
+        "network:read" => vec![NETWORK_METHODS],
+        "policy:read" => vec![POLICY_METHODS],
+        "validator" => vec![VALIDATOR_METHODS],
+        "wallet" => vec![WALLET_METHODS],
+        "zkp:read" => vec![ZKP_METHODS],
+        "read" => vec![
+            BLOCKCHAIN_METHODS,
+            CONSENSUS_READ_METHODS,
+            MEMPOOL_READ_METHODS,
+            NETWORK_METHODS,
+            POLICY_METHODS,
+            ZKP_METHODS,
+        ],
+        _ => return None,
+    };
+    Some(methods)
+}
+
+#[derive(Debug, Error)]
+pub enum PermissionsError {
+    #[error("Unknown method group: {0}")]
+    UnknownGroup(String),
+
+    #[error("Token {0} needs either a bearer token or a username and password")]
+    MissingCredentials(String),
+}
+
+/// The RPC methods a client may call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MethodPermissions {
+    // None allows all methods.
+    methods: Option<HashSet<String>>,
+}
+
+impl MethodPermissions {
+    /// Returns permissions allowing all methods.
+    pub fn all() -> Self {
+        Self { methods: None }
+    }
+
+    /// Parses a list of method names and method groups. A method group is one of
+    /// `blockchain:read`, `consensus:read`, `consensus`, `mempool:read`, `mempool`,
+    /// `network:read`, `policy:read`, `validator`, `wallet`, `zkp:read`, `read` (all read-only
+    /// groups) or `*` (all methods). An empty list allows all methods.
+    pub fn from_entries<S: AsRef<str>>(entries: &[S]) -> Result<Self, PermissionsError> {
+        let mut methods = HashSet::new();
+        for entry in entries {
+            let entry = entry.as_ref().trim();
+            if entry == ALL_METHODS {
+                return Ok(Self::all());
+            }
+            if let Some(groups) = group_methods(entry) {
+                methods.extend(groups.into_iter().flatten().map(|m| m.to_string()));
+            } else if entry.contains(':') {
+                return Err(PermissionsError::UnknownGroup(entry.to_string()));
+            } else {
+                methods.insert(entry.to_string());
+            }
+        }
+
+        if methods.is_empty() {
+            return Ok(Self::all());
+        }
+        Ok(Self {
+            methods: Some(methods),
+        })
+    }
+
+    /// Returns whether the given method may be called.
+    pub fn is_allowed(&self, method: &str) -> bool {
+        match &self.methods {
+            Some(methods) => methods.contains(method),
+            None => true,
+        }
+    }
+
+    /// Returns the allowed methods, or `None` if all methods are allowed.
+    pub fn methods(&self) -> Option<&HashSet<String>> {
+        self.methods.as_ref()
+    }
+}
+
+/// How a client authenticates with an [`ApiToken`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ApiCredentials {
+    /// `Authorization: Bearer <token>`
+    Bearer(String),
+    /// HTTP basic auth
+    Basic(Credentials),
+}
+
+/// A named credential for the RPC server with its own permissions and rate limit.
+#[derive(Clone, Debug)]
+pub struct ApiToken {
+    /// The name of the token, used in logs.
+    pub name: String,
+    pub credentials: ApiCredentials,
+    pub permissions: MethodPermissions,
+    /// The maximum number of requests per second. Unlimited if not set.
+    pub rate_limit: Option<u32>,
+}
+
+impl ApiToken {
+    pub fn new(
+        name: String,
+        token: Option<String>,
+        credentials: Option<Credentials>,
+        methods: &[String],
+        rate_limit: Option<u32>,
+    ) -> Result<Self, PermissionsError> {
+        let credentials = match (token, credentials) {
+            (Some(token), None) => ApiCredentials::Bearer(token),
+            (None, Some(credentials)) => ApiCredentials::Basic(credentials),
+            _ => return Err(PermissionsError::MissingCredentials(name)),
+        };
+        Ok(Self {
+            name,
+            credentials,
+            permissions: MethodPermissions::from_entries(methods)?,
+            rate_limit,
+        })
+    }
+
+    fn matches(&self, authorization: &Authorization) -> bool {
+        match (&self.credentials, authorization) {
+            (ApiCredentials::Bearer(expected), Authorization::Bearer(token)) => {
+                constant_time_eq(expected.as_bytes(), token.as_bytes())
+            }
+            (ApiCredentials::Basic(expected), Authorization::Basic(username, password)) => {
+                // Both are compared, so that the time taken does not reveal which one is wrong.
+                let username = constant_time_eq(expected.username.as_bytes(), username.as_bytes());
+                let password = constant_time_eq(expected.password.as_bytes(), password.as_bytes());
+                username & password
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Why a request was denied.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Denial {
+    /// The token is not allowed to call this method.
+    MethodNotAllowed(String),
+    /// The token exceeded its rate limit.
+    RateLimited,
+    /// The batch has more requests than the token may make per second, so it would never be
+    /// allowed.
+    BatchTooLarge(u32),
+}
+
+impl Denial {
+    /// Returns the JSON-RPC error code and message for this denial.
+    pub fn error(&self) -> (i64, String) {
+        match self {
+            Denial::MethodNotAllowed(method) => (
+                METHOD_NOT_ALLOWED_ERROR,
+                format!("Method not allowed: {method}"),
+            ),
+            Denial::RateLimited => (RATE_LIMITED_ERROR, "Rate limit exceeded".to_string()),
+            Denial::BatchTooLarge(rate_limit) => (
+                INVALID_REQUEST_ERROR,
+                format!(
+                    "Batch too large, at most {rate_limit} requests are allowed for this token"
+                ),
+            ),
+        }
+    }
+}
+
+/// A token bucket that allows `rate` requests per second, with bursts of up to `rate` requests.
+struct RateLimiter {
+    rate: f64,
+    available: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    fn new(rate: u32) -> Self {
+        Self {
+            rate: rate as f64,
+            available: rate as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn try_acquire(&mut self, requests: usize) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.available = (self.available + elapsed * self.rate).min(self.rate);
+        self.last_refill = now;
+
+        let requests = requests as f64;
+        if self.available < requests {
+            return false;
+        }
+        self.available -= requests;
+        true
+    }
+}
+
+/// An authenticated client, identified by its token.
+pub struct AuthorizedToken {
+    token: ApiToken,
+    rate_limiter: Option<Mutex<RateLimiter>>,
+}
+
+impl AuthorizedToken {
+    pub fn name(&self) -> &str {
+        &self.token.name
+    }
+
+    /// Checks whether the token may call the given methods now. All methods count towards the
+    /// rate limit, but only if all of them are allowed.
+    pub fn check(&self, methods: &[&str]) -> Result<(), Denial> {
+        if let Some(method) = methods
+            .iter()
+            .find(|method| !self.token.permissions.is_allowed(method))
+        {
+            return Err(Denial::MethodNotAllowed(method.to_string()));
+        }
+
+        if let Some(rate_limit) = self.token.rate_limit {
+            if methods.len() > rate_limit as usize {
+                return Err(Denial::BatchTooLarge(rate_limit));
+            }
+        }
+        if let Some(rate_limiter) = &self.rate_limiter {
+            if !rate_limiter.lock().try_acquire(methods.len().max(1)) {
+                return Err(Denial::RateLimited);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The API tokens of the RPC server. If there are any, every request must authenticate with one
+/// of them.
+#[derive(Clone, Default)]
+pub struct ApiTokens {
+    tokens: Vec<Arc<AuthorizedToken>>,
+}
+
+impl ApiTokens {
+    pub fn new(tokens: Vec<ApiToken>) -> Self {
+        let tokens = tokens
+            .into_iter()
+            .map(|token| {
+                let rate_limiter = token
+                    .rate_limit
+                    .map(|rate| Mutex::new(RateLimiter::new(rate)));
+                Arc::new(AuthorizedToken {
+                    token,
+                    rate_limiter,
+                })
+            })
+            .collect();
+        Self { tokens }
+    }
+
+    /// Returns whether requests need to authenticate with a token.
+    pub fn is_enabled(&self) -> bool {
+        !self.tokens.is_empty()
+    }
+
+    /// Returns the token matching the `Authorization` header of a request, if any.
+    pub fn authenticate(
+        &self,
+        authorization: Option<&HeaderValue>,
+    ) -> Option<Arc<AuthorizedToken>> {
+        let authorization = Authorization::parse(authorization?)?;
+        self.tokens
+            .iter()
+            .find(|token| token.token.matches(&authorization))
+            .cloned()
+    }
+}
+
+/// The credentials sent in an `Authorization` header.
+enum Authorization {
+    Bearer(String),
+    Basic(String, String),
+}
+
+impl Authorization {
+    fn parse(header: &HeaderValue) -> Option<Self> {
+        let (scheme, value) = header.to_str().ok()?.trim().split_once(' ')?;
+        let value = value.trim();
+        if scheme.eq_ignore_ascii_case("bearer") {
+            Some(Authorization::Bearer(value.to_string()))
+        } else if scheme.eq_ignore_ascii_case("basic") {
+            let decoded = String::from_utf8(BASE64.decode(value).ok()?).ok()?;
+            let (username, password) = decoded.split_once(':')?;
+            Some(Authorization::Basic(
+                username.to_string(),
+                password.to_string(),
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Returns the methods called by a JSON-RPC request or batch of requests.
+pub(crate) fn request_methods(request: &Value) -> Vec<&str> {
+    let method = |request: &Value| request.get("method").and_then(Value::as_str);
+    match request {
+        Value::Array(requests) => requests.iter().filter_map(method).collect(),
+        request => method(request).into_iter().collect(),
+    }
+}
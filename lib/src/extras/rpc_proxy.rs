@@ -0,0 +1,399 @@
+use std::{
+    convert::Infallible,
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener},
+    sync::Arc,
+};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hyper::{
+    header::{self, HeaderMap, HeaderValue},
+    server::conn::AddrStream,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode, Uri,
+};
+use serde_json::Value;
+use tokio_tungstenite::{
+    tungstenite::{
+        handshake::derive_accept_key,
+        protocol::{Role, WebSocketConfig},
+    },
+    WebSocketStream,
+};
+
+use crate::{
+    config::config::Credentials,
+    extras::{
+        rpc_access_control::AccessControl,
+        rpc_permissions::{request_methods, ApiTokens, AuthorizedToken, Denial},
+        rpc_subscriptions::{WebSocketSession, WebSocketSettings},
+        rpc_upstream::{error_response, read_body, ForwardError, RequestLimits, Upstream},
+    },
+};
+
+/// The methods allowed in cross-origin requests.
+const CORS_ALLOWED_METHODS: &str = "GET, POST, OPTIONS";
+/// The headers allowed in cross-origin requests, unless the preflight request asks for others.
+const CORS_ALLOWED_HEADERS: &str = "Content-Type, Authorization";
+/// How long (in seconds) browsers may cache the result of a preflight request.
+const CORS_MAX_AGE: &str = "86400";
+
+/// Returns a loopback address whose port is currently unused.
+pub fn unused_local_address() -> io::Result<SocketAddr> {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?.local_addr()
+}
+
+/// An HTTP proxy in front of the RPC server. It enforces the [`AccessControl`] rules and the
+/// permissions of the [`ApiTokens`] and, if enabled, serves the WebSocket transport with its
+/// subscription management.
+///
+/// Requests from IPs that are not allowed and cross-origin requests from origins that are not
+/// allowed are rejected with `403 Forbidden`. CORS preflight requests are answered by the proxy
+/// itself. If API tokens are configured, requests without a valid token are rejected with
+/// `401 Unauthorized`, requests calling methods the token is not allowed to call with
+/// `403 Forbidden` and requests exceeding the rate limit of the token with
+/// `429 Too Many Requests`. WebSocket connections are handled by a [`WebSocketSession`]. All
+/// other requests are forwarded to the RPC server, which then should only listen on a loopback
+/// address and only accept the credentials of the proxy, see
+/// [`RpcProxy::with_upstream_credentials`]. JSON-RPC batches are forwarded as a whole, see [`RequestLimits`] for the limits on
+/// batches and requests.
+#[derive(Clone)]
+pub struct RpcProxy {
+    bind_to: SocketAddr,
+    upstream: SocketAddr,
+    access_control: Arc<AccessControl>,
+    api_tokens: Arc<ApiTokens>,
+    websocket: Option<WebSocketSettings>,
+    limits: RequestLimits,
+    upstream_credentials: Option<Credentials>,
+}
+
+impl RpcProxy {
+    pub fn new(
+        bind_to: SocketAddr,
+        upstream: SocketAddr,
+        access_control: AccessControl,
+        websocket: Option<WebSocketSettings>,
+    ) -> Self {
+        Self {
+            bind_to,
+            upstream,
+            access_control: Arc::new(access_control),
+            api_tokens: Default::default(),
+            websocket,
+            limits: RequestLimits::default(),
+            upstream_credentials: None,
+        }
+    }
+
+    /// Authenticates all requests to the RPC server with the given basic auth credentials. The
+    /// `Authorization` headers of the clients are never passed on.
+    pub fn with_upstream_credentials(mut self, credentials: Credentials) -> Self {
+        self.upstream_credentials = Some(credentials);
+        self
+    }
+
+    /// Sets the limits on batches and requests.
+    pub fn with_request_limits(mut self, limits: RequestLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Forwards the requests to the RPC server listening on the given address instead.
+    pub fn with_upstream(mut self, upstream: SocketAddr) -> Self {
+        self.upstream = upstream;
+        self
+    }
+
+    /// Requires every request to authenticate with one of the given tokens.
+    pub fn with_api_tokens(mut self, api_tokens: ApiTokens) -> Self {
+        self.api_tokens = Arc::new(api_tokens);
+        self
+    }
+
+    /// Runs the proxy until its server fails.
+    pub async fn run(self) -> Result<(), hyper::Error> {
+        let authorization = self.upstream_credentials.as_ref().map(basic_authorization);
+        let upstream = Arc::new(Upstream::new(self.upstream, authorization, self.limits));
+        let access_control = self.access_control;
+        let api_tokens = self.api_tokens;
+        let websocket = self.websocket;
+
+        let make_service = make_service_fn(move |conn: &AddrStream| {
+            let remote_ip = conn.remote_addr().ip();
+            let upstream = Arc::clone(&upstream);
+            let access_control = Arc::clone(&access_control);
+            let api_tokens = Arc::clone(&api_tokens);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    handle_request(
+                        request,
+                        remote_ip,
+                        Arc::clone(&upstream),
+                        Arc::clone(&access_control),
+                        Arc::clone(&api_tokens),
+                        websocket,
+                    )
+                }))
+            }
+        });
+
+        let server = Server::try_bind(&self.bind_to)?.serve(make_service);
+        log::info!(
+            "RPC proxy on {}, forwarding to {}",
+            server.local_addr(),
+            self.upstream
+        );
+        server.await
+    }
+}
+
+async fn handle_request(
+    request: Request<Body>,
+    remote_ip: IpAddr,
+    upstream: Arc<Upstream>,
+    access_control: Arc<AccessControl>,
+    api_tokens: Arc<ApiTokens>,
+    websocket: Option<WebSocketSettings>,
+) -> Result<Response<Body>, Infallible> {
+    if !access_control.is_ip_allowed(&remote_ip) {
+        log::debug!(%remote_ip, "Rejected RPC request from an IP that is not allowed");
+        return Ok(status_response(StatusCode::FORBIDDEN));
+    }
+
+    // Only check the origin of cross-origin requests if CORS is configured.
+    let origin = request
+        .headers()
+        .get(header::ORIGIN)
+        .filter(|_| access_control.is_cors_enabled())
+        .cloned();
+    if let Some(origin) = &origin {
+        let allowed = origin
+            .to_str()
+            .map(|origin| access_control.is_origin_allowed(origin))
+            .unwrap_or(false);
+        if !allowed {
+            log::debug!(
+                ?origin,
+                "Rejected RPC request from an origin that is not allowed"
+            );
+            return Ok(status_response(StatusCode::FORBIDDEN));
+        }
+
+        if request.method() == Method::OPTIONS
+            && request
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD)
+        {
+            return Ok(preflight_response(origin, request.headers()));
+        }
+    }
+
+    let token = if api_tokens.is_enabled() {
+        match api_tokens.authenticate(request.headers().get(header::AUTHORIZATION)) {
+            Some(token) => Some(token),
+            None => {
+                log::debug!(%remote_ip, "Rejected RPC request without a valid API token");
+                let mut response = status_response(StatusCode::UNAUTHORIZED);
+                response.headers_mut().insert(
+                    header::WWW_AUTHENTICATE,
+                    HeaderValue::from_static("Bearer, Basic"),
+                );
+                return Ok(with_cors_headers(response, origin));
+            }
+        }
+    } else {
+        None
+    };
+
+    if let Some(settings) = websocket {
+        if is_websocket_upgrade(&request) {
+            return Ok(upgrade_websocket(request, upstream, settings, token));
+        }
+    }
+
+    if request.method() != Method::POST {
+        return Ok(with_cors_headers(forward(request, &upstream).await, origin));
+    }
+
+    let (parts, body) = request.into_parts();
+    let body = match read_body(body, upstream.limits().max_body_size).await {
+        Ok(body) => body,
+        Err(status) => return Ok(with_cors_headers(status_response(status), origin)),
+    };
+    // Requests that fail to parse are answered by the RPC server, but still count towards the
+    // rate limit.
+    let json: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+    if let Some(token) = token {
+        if let Some(response) = deny_request(&token, &json) {
+            return Ok(with_cors_headers(response, origin));
+        }
+    }
+
+    let response = match json {
+        Value::Array(requests) => {
+            let response = upstream.call_batch(requests).await;
+            json_response(StatusCode::OK, response)
+        }
+        _ => forward(Request::from_parts(parts, Body::from(body)), &upstream).await,
+    };
+    Ok(with_cors_headers(response, origin))
+}
+
+/// Forwards a request to the RPC server and returns its response.
+async fn forward(mut request: Request<Body>, upstream: &Upstream) -> Response<Body> {
+    let path_and_query = request
+        .uri()
+        .path_and_query()
+        .map(|path_and_query| path_and_query.as_str())
+        .unwrap_or("/");
+    let uri = format!("http://{}{}", upstream.address(), path_and_query);
+    *request.uri_mut() = match uri.parse::<Uri>() {
+        Ok(uri) => uri,
+        Err(_) => return status_response(StatusCode::BAD_REQUEST),
+    };
+
+    match upstream.forward(request).await {
+        Ok(response) => response,
+        Err(ForwardError::Timeout) => status_response(StatusCode::GATEWAY_TIMEOUT),
+        Err(e) => {
+            log::error!(error = %e, "Failed to forward RPC request");
+            status_response(StatusCode::BAD_GATEWAY)
+        }
+    }
+}
+
+/// Checks whether the token may make the given JSON-RPC request. Returns the response to send
+/// instead if not.
+fn deny_request(token: &AuthorizedToken, request: &Value) -> Option<Response<Body>> {
+    let denial = match token.check(&request_methods(request)) {
+        Ok(()) => return None,
+        Err(denial) => denial,
+    };
+    log::debug!(token = token.name(), ?denial, "Denied RPC request");
+
+    let status = match denial {
+        Denial::MethodNotAllowed(_) => StatusCode::FORBIDDEN,
+        Denial::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+        Denial::BatchTooLarge(_) => StatusCode::BAD_REQUEST,
+    };
+    let (code, message) = denial.error();
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    Some(json_response(
+        status,
+        Some(error_response(id, code, &message)),
+    ))
+}
+
+/// Returns a response with the given JSON body. Without a body, the response is empty.
+fn json_response(status: StatusCode, body: Option<Value>) -> Response<Body> {
+    let mut response = match body {
+        Some(body) => Response::new(Body::from(body.to_string())),
+        None => Response::new(Body::empty()),
+    };
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+fn is_websocket_upgrade(request: &Request<Body>) -> bool {
+    request
+        .headers()
+        .get(header::UPGRADE)
+        .and_then(|upgrade| upgrade.to_str().ok())
+        .map(|upgrade| upgrade.eq_ignore_ascii_case("websocket"))
+        .unwrap_or(false)
+}
+
+/// Accepts a WebSocket connection and serves it in a [`WebSocketSession`].
+fn upgrade_websocket(
+    request: Request<Body>,
+    upstream: Arc<Upstream>,
+    settings: WebSocketSettings,
+    token: Option<Arc<AuthorizedToken>>,
+) -> Response<Body> {
+    let accept_key = match request.headers().get(header::SEC_WEBSOCKET_KEY) {
+        Some(key) => derive_accept_key(key.as_bytes()),
+        None => return status_response(StatusCode::BAD_REQUEST),
+    };
+    tokio::spawn(async move {
+        match hyper::upgrade::on(request).await {
+            Ok(upgraded) => {
+                let config = WebSocketConfig {
+                    max_message_size: Some(upstream.limits().max_body_size),
+                    ..Default::default()
+                };
+                let socket =
+                    WebSocketStream::from_raw_socket(upgraded, Role::Server, Some(config)).await;
+                WebSocketSession::run(socket, upstream, settings, token).await;
+            }
+            Err(e) => log::debug!(error = %e, "Failed to upgrade RPC connection"),
+        }
+    });
+
+    let mut response = status_response(StatusCode::SWITCHING_PROTOCOLS);
+    let headers = response.headers_mut();
+    headers.insert(header::UPGRADE, HeaderValue::from_static("websocket"));
+    headers.insert(header::CONNECTION, HeaderValue::from_static("Upgrade"));
+    headers.insert(
+        header::SEC_WEBSOCKET_ACCEPT,
+        HeaderValue::from_str(&accept_key).expect("The accept key must be a valid header"),
+    );
+    response
+}
+
+fn preflight_response(origin: &HeaderValue, request_headers: &HeaderMap) -> Response<Body> {
+    let allowed_headers = request_headers
+        .get(header::ACCESS_CONTROL_REQUEST_HEADERS)
+        .cloned()
+        .unwrap_or_else(|| HeaderValue::from_static(CORS_ALLOWED_HEADERS));
+
+    let mut response = status_response(StatusCode::NO_CONTENT);
+    let headers = response.headers_mut();
+    add_cors_headers(headers, origin.clone());
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_METHODS,
+        HeaderValue::from_static(CORS_ALLOWED_METHODS),
+    );
+    headers.insert(header::ACCESS_CONTROL_ALLOW_HEADERS, allowed_headers);
+    headers.insert(
+        header::ACCESS_CONTROL_MAX_AGE,
+        HeaderValue::from_static(CORS_MAX_AGE),
+    );
+    response
+}
+
+fn with_cors_headers(mut response: Response<Body>, origin: Option<HeaderValue>) -> Response<Body> {
+    if let Some(origin) = origin {
+        add_cors_headers(response.headers_mut(), origin);
+    }
+    response
+}
+
+fn add_cors_headers(headers: &mut HeaderMap, origin: HeaderValue) {
+    // The origin is echoed instead of sending `*`, so that requests with credentials work too.
+    if origin != "*" {
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+            HeaderValue::from_static("true"),
+        );
+    }
+    headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    headers.append(header::VARY, HeaderValue::from_static("Origin"));
+}
+
+/// Returns the `Authorization` header for the given basic auth credentials.
+fn basic_authorization(credentials: &Credentials) -> HeaderValue {
+    let encoded = BASE64.encode(format!("{}:{}", credentials.username, credentials.password));
+    HeaderValue::from_str(&format!("Basic {encoded}")).expect("Base64 must be a valid header value")
+}
+
+fn status_response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
@@ -1,30 +1,119 @@
-use std::{collections::HashSet, iter::FromIterator, sync::Arc};
+use std::{
+    collections::HashSet, iter::FromIterator, net::SocketAddr, panic::AssertUnwindSafe, sync::Arc,
+};
 
+use futures::{
+    future::{self, Either},
+    FutureExt,
+};
 use nimiq_jsonrpc_core::Credentials;
 use nimiq_jsonrpc_server::{AllowListDispatcher, Config, ModularDispatcher, Server as _Server};
 use nimiq_rpc_server::dispatchers::*;
 use nimiq_wallet::WalletStore;
+use rand::{distributions::Alphanumeric, Rng};
 
 #[cfg(feature = "rpc-server")]
 use crate::config::config::RpcServerConfig;
-use crate::{client::Client, config::consts::default_bind, error::Error};
+use crate::{
+    client::Client,
+    config::{config::Credentials as ProxyCredentials, consts::default_bind},
+    error::Error,
+    extras::{
+        rpc_access_control::AccessControl,
+        rpc_permissions::{ApiCredentials, ApiToken, ApiTokens, MethodPermissions},
+        rpc_proxy::{unused_local_address, RpcProxy},
+        rpc_subscriptions::WebSocketSettings,
+        rpc_upstream::RequestLimits,
+    },
+};
 
 pub type Server = _Server<AllowListDispatcher<ModularDispatcher>>;
 
+/// The username of the proxy for the RPC server.
+const PROXY_USERNAME: &str = "rpc-proxy";
+
+/// The length of the random password of the proxy for the RPC server.
+const PROXY_PASSWORD_LENGTH: usize = 32;
+
+/// How often the RPC server is started on another local port if it fails.
+const MAX_SERVER_ATTEMPTS: usize = 3;
+
+/// The RPC server, together with the proxy in front of it.
+pub struct RpcServer {
+    // Creates the RPC server listening on the given local address.
+    new_server: Box<dyn Fn(SocketAddr) -> Server + Send>,
+    proxy: RpcProxy,
+    // The local address the RPC server is started on first.
+    upstream: SocketAddr,
+}
+
+impl RpcServer {
+    /// Runs the RPC server and the proxy. If either of them stops, e.g. because its address
+    /// could not be bound, the other one is stopped as well.
+    ///
+    /// The RPC server listens on an unused local port, which is only bound once the server runs.
+    /// If another process takes the port in the meantime, the RPC server fails. It is then
+    /// started again on another port, together with a proxy forwarding to that port.
+    pub async fn run(self) {
+        let mut upstream = self.upstream;
+        for _ in 0..MAX_SERVER_ATTEMPTS {
+            let server = (self.new_server)(upstream);
+            let server = AssertUnwindSafe(async move { server.run().await }).catch_unwind();
+            let proxy = self.proxy.clone().with_upstream(upstream).run();
+            match future::select(Box::pin(server), Box::pin(proxy)).await {
+                Either::Left((Err(_), _)) => {
+                    log::warn!(%upstream, "RPC server failed, restarting it on another port");
+                    upstream = match unused_local_address() {
+                        Ok(upstream) => upstream,
+                        Err(e) => {
+                            log::error!(error = %e, "Failed to find a local address for the RPC server");
+                            return;
+                        }
+                    };
+                    continue;
+                }
+                Either::Left((Ok(()), _)) => {
+                    log::error!("RPC server stopped, stopping the RPC proxy")
+                }
+                Either::Right((Err(e), _)) => log::error!(error = %e, "RPC proxy failed"),
+                Either::Right((Ok(()), _)) => log::error!("RPC proxy stopped"),
+            }
+            return;
+        }
+        log::error!("RPC server failed too often, stopping the RPC proxy");
+    }
+}
+
 #[cfg(feature = "rpc-server")]
 pub fn initialize_rpc_server(
     client: &Client,
     config: RpcServerConfig,
     wallet_store: Arc<WalletStore>,
-) -> Result<Server, Error> {
+) -> Result<RpcServer, Error> {
     let ip = config.bind_to.unwrap_or_else(default_bind);
     log::info!("Initializing RPC server: {}:{}", ip, config.port);
 
-    // Configure RPC server
-    let basic_auth = config.credentials.map(|credentials| Credentials {
-        username: credentials.username,
-        password: credentials.password,
-    });
+    // The proxy authenticates all requests, the basic auth credentials become a token that can
+    // call all methods.
+    let mut tokens = config.tokens;
+    if let Some(credentials) = config.credentials {
+        tokens.push(ApiToken {
+            name: credentials.username.clone(),
+            credentials: ApiCredentials::Basic(credentials),
+            permissions: MethodPermissions::all(),
+            rate_limit: None,
+        });
+    }
+    let api_tokens = ApiTokens::new(tokens);
+
+    // The RPC server only accepts requests of the proxy, authenticated with a random password
+    // that is never shown to anyone.
+    let password: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(PROXY_PASSWORD_LENGTH)
+        .map(char::from)
+        .collect();
+    let proxy_credentials = ProxyCredentials::new(PROXY_USERNAME, password);
 
     let allowed_methods = config.allowed_methods.unwrap_or_default();
     let allowed_methods = if allowed_methods.is_empty() {
@@ -33,39 +122,78 @@ pub fn initialize_rpc_server(
         Some(HashSet::from_iter(allowed_methods))
     };
 
-    // TODO: Pass this to the rpc server config
-    let _corsdomain = config.corsdomain.unwrap_or_default();
+    // The RPC server only listens on a local address and is reached through a proxy, which
+    // enforces the access rules and request limits and answers batches.
+    let access_control =
+        AccessControl::new(config.corsdomain.unwrap_or_default(), config.allow_ips);
+    let websocket = config.enable_websocket.then_some(WebSocketSettings {
+        max_subscriptions: config.max_subscriptions,
+    });
+    let limits = RequestLimits {
+        max_batch_size: config.max_batch_size,
+        max_body_size: config.max_body_size,
+        request_timeout: config.request_timeout,
+    };
+    let upstream = unused_local_address()?;
+    let proxy = RpcProxy::new(
+        (ip, config.port).into(),
+        upstream,
+        access_control,
+        websocket,
+    )
+    .with_api_tokens(api_tokens)
+    .with_request_limits(limits)
+    .with_upstream_credentials(proxy_credentials.clone());
 
-    let mut dispatcher = ModularDispatcher::default();
+    let blockchain = client.blockchain();
+    let consensus = client.consensus_proxy();
+    let network = client.network();
+    let mempool = client.mempool();
+    let validator_proxy = client.validator_proxy();
+    let zkp_component = client.zkp_component();
+    let enable_websocket = config.enable_websocket;
 
-    let wallet_dispatcher = WalletDispatcher::new(wallet_store);
-    let unlocked_wallets = Arc::clone(&wallet_dispatcher.unlocked_wallets);
+    let new_server = move |upstream: SocketAddr| {
+        let mut dispatcher = ModularDispatcher::default();
 
-    dispatcher.add(BlockchainDispatcher::new(client.blockchain()));
+        let wallet_dispatcher = WalletDispatcher::new(Arc::clone(&wallet_store));
+        let unlocked_wallets = Arc::clone(&wallet_dispatcher.unlocked_wallets);
 
-    dispatcher.add(ConsensusDispatcher::new(
-        client.consensus_proxy(),
-        Some(unlocked_wallets),
-    ));
-    dispatcher.add(NetworkDispatcher::new(client.network()));
-    if let Some(mempool) = client.mempool() {
-        dispatcher.add(MempoolDispatcher::new(mempool));
-    }
-    dispatcher.add(PolicyDispatcher {});
-    if let Some(validator_proxy) = client.validator_proxy() {
-        dispatcher.add(ValidatorDispatcher::new(validator_proxy));
-    }
-    dispatcher.add(wallet_dispatcher);
-
-    dispatcher.add(ZKPComponentDispatcher::new(client.zkp_component()));
-
-    Ok(Server::new(
-        Config {
-            bind_to: (config.bind_to.unwrap_or_else(default_bind), config.port).into(),
-            enable_websocket: false,
-            ip_whitelist: None,
-            basic_auth,
-        },
-        AllowListDispatcher::new(dispatcher, allowed_methods),
-    ))
+        dispatcher.add(BlockchainDispatcher::new(blockchain.clone()));
+
+        dispatcher.add(ConsensusDispatcher::new(
+            consensus.clone(),
+            Some(unlocked_wallets),
+        ));
+        dispatcher.add(NetworkDispatcher::new(Arc::clone(&network)));
+        if let Some(mempool) = &mempool {
+            dispatcher.add(MempoolDispatcher::new(Arc::clone(mempool)));
+        }
+        dispatcher.add(PolicyDispatcher {});
+        if let Some(validator_proxy) = &validator_proxy {
+            dispatcher.add(ValidatorDispatcher::new(validator_proxy.clone()));
+        }
+        dispatcher.add(wallet_dispatcher);
+
+        dispatcher.add(ZKPComponentDispatcher::new(zkp_component.clone()));
+
+        Server::new(
+            Config {
+                bind_to: upstream,
+                enable_websocket,
+                ip_whitelist: Some([upstream.ip()].into_iter().collect()),
+                basic_auth: Some(Credentials {
+                    username: proxy_credentials.username.clone(),
+                    password: proxy_credentials.password.clone(),
+                }),
+            },
+            AllowListDispatcher::new(dispatcher, allowed_methods.clone()),
+        )
+    };
+
+    Ok(RpcServer {
+        new_server: Box::new(new_server),
+        proxy,
+        upstream,
+    })
 }
@@ -0,0 +1,309 @@
+use std::{collections::HashMap, sync::Arc};
+
+use futures::{SinkExt, StreamExt};
+use hyper::{header, upgrade::Upgraded};
+use serde_json::{json, Value};
+use tokio::{
+    net::TcpStream,
+    sync::{
+        mpsc::{self, error::TrySendError},
+        Notify,
+    },
+    task::JoinHandle,
+};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+
+use crate::extras::{
+    rpc_permissions::{request_methods, AuthorizedToken},
+    rpc_upstream::{error_response, Upstream, INTERNAL_ERROR},
+};
+
+/// The JSON-RPC method that cancels a subscription. It takes the subscription id as its only
+/// parameter and returns whether a subscription was cancelled.
+pub const UNSUBSCRIBE_METHOD: &str = "unsubscribe";
+
+/// The prefix of the JSON-RPC methods that open a subscription.
+const SUBSCRIBE_METHOD_PREFIX: &str = "subscribe";
+
+/// The JSON-RPC error code returned if a connection already holds the maximum number of
+/// subscriptions.
+const TOO_MANY_SUBSCRIPTIONS_ERROR: i64 = -32000;
+
+/// The maximum number of messages that can be queued for a client. If a message doesn't fit, the
+/// client doesn't keep up with reading its responses and notifications, and it is disconnected.
+const OUTGOING_CAPACITY: usize = 256;
+
+/// The settings of the WebSocket transport of the RPC server.
+#[derive(Clone, Copy, Debug)]
+pub struct WebSocketSettings {
+    /// The maximum number of subscriptions a single connection can hold at the same time.
+    pub max_subscriptions: usize,
+}
+
+type UpstreamSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// A WebSocket connection of a client to the RPC server.
+///
+/// Requests are forwarded to the RPC server over HTTP, except for subscriptions: each
+/// subscription gets its own connection to the RPC server, so that it can be cancelled
+/// individually by closing that connection. The subscription ids the RPC server assigns are
+/// replaced by ids unique to this connection. Batches are answered like batches sent over HTTP,
+/// so they cannot contain subscriptions. If the client authenticated with an API token, every
+/// request except for `unsubscribe` is checked against its permissions and rate limit. Clients
+/// that don't read their messages fast enough are disconnected.
+pub(crate) struct WebSocketSession {
+    upstream: Arc<Upstream>,
+    token: Option<Arc<AuthorizedToken>>,
+    settings: WebSocketSettings,
+    // The channel to the task writing to the client.
+    outgoing: mpsc::Sender<Message>,
+    // Notified if a message didn't fit into the outgoing queue.
+    overflow: Arc<Notify>,
+    // The tasks forwarding the notifications of the subscriptions, by subscription id.
+    subscriptions: HashMap<u64, JoinHandle<()>>,
+    next_subscription_id: u64,
+}
+
+impl WebSocketSession {
+    /// Serves a client connection until it is closed. All of its subscriptions are cancelled
+    /// afterwards.
+    pub(crate) async fn run(
+        socket: WebSocketStream<Upgraded>,
+        upstream: Arc<Upstream>,
+        settings: WebSocketSettings,
+        token: Option<Arc<AuthorizedToken>>,
+    ) {
+        let (mut sink, stream) = socket.split();
+        let (outgoing, mut outgoing_rx) = mpsc::channel(OUTGOING_CAPACITY);
+        let writer = tokio::spawn(async move {
+            while let Some(message) = outgoing_rx.recv().await {
+                if sink.send(message).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let overflow = Arc::new(Notify::new());
+        let mut session = WebSocketSession {
+            upstream,
+            token,
+            settings,
+            outgoing,
+            overflow: Arc::clone(&overflow),
+            subscriptions: HashMap::new(),
+            next_subscription_id: 1,
+        };
+
+        // Stop serving the client once it fell behind on reading its messages.
+        let mut stream = stream.take_until(Box::pin(async move { overflow.notified().await }));
+        while let Some(message) = stream.next().await {
+            match message {
+                Ok(Message::Text(text)) => session.handle_message(text).await,
+                Ok(Message::Close(_)) | Err(_) => break,
+                Ok(_) => {}
+            }
+        }
+
+        if stream.is_stopped() {
+            log::debug!("Closing WebSocket connection of a client that fell behind");
+        }
+        session.close();
+        writer.abort();
+    }
+
+    async fn handle_message(&mut self, text: String) {
+        let request: Value = match serde_json::from_str(&text) {
+            Ok(request) => request,
+            // Let the RPC server answer invalid requests.
+            Err(_) => return self.forward(text, Value::Null).await,
+        };
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let method = request.get("method").and_then(Value::as_str);
+
+        if let Some(token) = self
+            .token
+            .as_ref()
+            .filter(|_| method != Some(UNSUBSCRIBE_METHOD))
+        {
+            if let Err(denial) = token.check(&request_methods(&request)) {
+                log::debug!(token = token.name(), ?denial, "Denied RPC request");
+                let (code, message) = denial.error();
+                return self.send_error(id, code, &message);
+            }
+        }
+
+        if let Value::Array(requests) = &request {
+            let response = self.upstream.call_batch(requests.clone()).await;
+            if let Some(response) = response {
+                self.send(response.to_string());
+            }
+            return;
+        }
+
+        match method {
+            Some(UNSUBSCRIBE_METHOD) => self.unsubscribe(&request, id),
+            Some(method) if method.starts_with(SUBSCRIBE_METHOD_PREFIX) => {
+                self.subscribe(text, id).await
+            }
+            _ => self.forward(text, id).await,
+        }
+    }
+
+    /// Forwards a request to the RPC server over HTTP and sends its response to the client.
+    async fn forward(&self, text: String, id: Value) {
+        match self.upstream.call(text).await {
+            Ok(Some(response)) => self.send(response),
+            // Notifications are not answered.
+            Ok(None) => {}
+            Err(e) => {
+                log::error!(error = %e, "Failed to forward RPC request");
+                self.send_error(id, e.code(), &e.to_string());
+            }
+        }
+    }
+
+    /// Opens a subscription on a new connection to the RPC server.
+    async fn subscribe(&mut self, text: String, id: Value) {
+        self.subscriptions.retain(|_, task| !task.is_finished());
+        if self.subscriptions.len() >= self.settings.max_subscriptions {
+            return self.send_error(
+                id,
+                TOO_MANY_SUBSCRIPTIONS_ERROR,
+                &format!(
+                    "Too many subscriptions, at most {} are allowed per connection",
+                    self.settings.max_subscriptions
+                ),
+            );
+        }
+
+        let (mut socket, response) = match self.open_subscription(text).await {
+            Ok(result) => result,
+            Err(e) => {
+                log::error!(error = %e, "Failed to open RPC subscription");
+                return self.send_error(id, INTERNAL_ERROR, "Failed to open subscription");
+            }
+        };
+
+        let mut response: Value = match serde_json::from_str(&response) {
+            Ok(response) => response,
+            Err(_) => return self.send(response),
+        };
+        if response.get("result").is_none() {
+            // The subscription was rejected, pass on the error.
+            return self.send(response.to_string());
+        }
+
+        let subscription_id = self.next_subscription_id;
+        self.next_subscription_id += 1;
+        response["result"] = json!(subscription_id);
+        self.send(response.to_string());
+
+        let outgoing = self.outgoing.clone();
+        let overflow = Arc::clone(&self.overflow);
+        let task = tokio::spawn(async move {
+            while let Some(Ok(message)) = socket.next().await {
+                if let Message::Text(text) = message {
+                    let notification = replace_subscription_id(text, subscription_id);
+                    if !try_send(&outgoing, &overflow, Message::Text(notification)) {
+                        break;
+                    }
+                }
+            }
+        });
+        self.subscriptions.insert(subscription_id, task);
+    }
+
+    /// Connects to the RPC server, sends the subscription request and waits for its response.
+    async fn open_subscription(
+        &self,
+        text: String,
+    ) -> Result<(UpstreamSocket, String), tokio_tungstenite::tungstenite::Error> {
+        let mut request = format!("ws://{}/ws", self.upstream.address()).into_client_request()?;
+        if let Some(authorization) = self.upstream.authorization() {
+            request
+                .headers_mut()
+                .insert(header::AUTHORIZATION, authorization.clone());
+        }
+
+        let (mut socket, _) = connect_async(request).await?;
+        socket.send(Message::Text(text)).await?;
+        while let Some(message) = socket.next().await {
+            if let Message::Text(response) = message? {
+                return Ok((socket, response));
+            }
+        }
+        Err(tokio_tungstenite::tungstenite::Error::ConnectionClosed)
+    }
+
+    /// Cancels a subscription by closing its connection to the RPC server.
+    fn unsubscribe(&mut self, request: &Value, id: Value) {
+        let subscription_id = request
+            .get("params")
+            .map(|params| params.get(0).unwrap_or(params))
+            .and_then(Value::as_u64);
+        let cancelled = match subscription_id
+            .and_then(|subscription_id| self.subscriptions.remove(&subscription_id))
+        {
+            Some(task) => {
+                task.abort();
+                true
+            }
+            None => false,
+        };
+
+        if !id.is_null() {
+            self.send(json!({ "jsonrpc": "2.0", "id": id, "result": cancelled }).to_string());
+        }
+    }
+
+    /// Cancels all subscriptions.
+    fn close(&mut self) {
+        for (_, task) in self.subscriptions.drain() {
+            task.abort();
+        }
+    }
+
+    fn send(&self, text: String) {
+        // If the client is gone or fell behind, the session ends anyway.
+        try_send(&self.outgoing, &self.overflow, Message::Text(text));
+    }
+
+    fn send_error(&self, id: Value, code: i64, message: &str) {
+        self.send(error_response(id, code, message).to_string());
+    }
+}
+
+/// Queues a message for the client. Returns false if the client is gone or the queue is full, in
+/// which case `overflow` is notified.
+fn try_send(outgoing: &mpsc::Sender<Message>, overflow: &Notify, message: Message) -> bool {
+    match outgoing.try_send(message) {
+        Ok(()) => true,
+        Err(TrySendError::Full(_)) => {
+            overflow.notify_one();
+            false
+        }
+        Err(TrySendError::Closed(_)) => false,
+    }
+}
+
+/// Replaces the subscription id of a notification of the RPC server.
+fn replace_subscription_id(text: String, subscription_id: u64) -> String {
+    let mut notification: Value = match serde_json::from_str(&text) {
+        Ok(notification) => notification,
+        Err(_) => return text,
+    };
+    match notification
+        .get_mut("params")
+        .and_then(|params| params.get_mut("subscription"))
+    {
+        Some(id) => {
+            *id = json!(subscription_id);
+            notification.to_string()
+        }
+        None => text,
+    }
+}
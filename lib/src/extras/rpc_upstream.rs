@@ -0,0 +1,215 @@
+use std::{net::SocketAddr, time::Duration};
+
+use hyper::{
+    body::{Bytes, HttpBody},
+    client::HttpConnector,
+    header::{self, HeaderValue},
+    Body, Client, Method, Request, Response, StatusCode,
+};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::config::consts;
+
+/// The JSON-RPC error code returned for invalid requests, e.g. empty or too large batches.
+pub const INVALID_REQUEST_ERROR: i64 = -32600;
+
+/// The JSON-RPC error code returned if the RPC server did not answer a request in time.
+pub const TIMEOUT_ERROR: i64 = -32003;
+
+/// The JSON-RPC error code returned if a request could not be forwarded to the RPC server.
+pub(crate) const INTERNAL_ERROR: i64 = -32603;
+
+/// The limits on the requests to the RPC server.
+#[derive(Clone, Copy, Debug)]
+pub struct RequestLimits {
+    /// The maximum number of requests in a batch.
+    pub max_batch_size: usize,
+    /// The maximum size of a request body or WebSocket message in bytes.
+    pub max_body_size: usize,
+    /// How long the RPC server may take to answer a single request.
+    pub request_timeout: Duration,
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            max_batch_size: consts::RPC_DEFAULT_MAX_BATCH_SIZE,
+            max_body_size: consts::RPC_DEFAULT_MAX_BODY_SIZE,
+            request_timeout: consts::RPC_DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ForwardError {
+    #[error("Failed to forward request: {0}")]
+    Http(#[from] hyper::Error),
+
+    #[error("Request timed out")]
+    Timeout,
+
+    #[error("Request failed: {0}")]
+    Status(StatusCode),
+}
+
+impl ForwardError {
+    /// Returns the JSON-RPC error code for this error.
+    pub(crate) fn code(&self) -> i64 {
+        match self {
+            ForwardError::Timeout => TIMEOUT_ERROR,
+            ForwardError::Http(_) | ForwardError::Status(_) => INTERNAL_ERROR,
+        }
+    }
+}
+
+/// The RPC server behind the proxy. Requests are forwarded to it over HTTP, subject to the
+/// [`RequestLimits`].
+///
+/// Batches are checked against the limits and then forwarded to the RPC server as a whole.
+///
+/// Clients are authenticated by the proxy, so the `Authorization` header of their requests is
+/// replaced by the credentials of the proxy for the RPC server.
+pub(crate) struct Upstream {
+    address: SocketAddr,
+    authorization: Option<HeaderValue>,
+    client: Client<HttpConnector>,
+    limits: RequestLimits,
+}
+
+impl Upstream {
+    pub(crate) fn new(
+        address: SocketAddr,
+        authorization: Option<HeaderValue>,
+        limits: RequestLimits,
+    ) -> Self {
+        Self {
+            address,
+            authorization,
+            client: Client::new(),
+            limits,
+        }
+    }
+
+    pub(crate) fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// The `Authorization` header to send to the RPC server, if it requires one.
+    pub(crate) fn authorization(&self) -> Option<&HeaderValue> {
+        self.authorization.as_ref()
+    }
+
+    pub(crate) fn limits(&self) -> &RequestLimits {
+        &self.limits
+    }
+
+    /// Forwards an HTTP request to the RPC server and returns its response.
+    pub(crate) async fn forward(
+        &self,
+        mut request: Request<Body>,
+    ) -> Result<Response<Body>, ForwardError> {
+        let headers = request.headers_mut();
+        headers.remove(header::AUTHORIZATION);
+        if let Some(authorization) = &self.authorization {
+            headers.insert(header::AUTHORIZATION, authorization.clone());
+        }
+
+        tokio::time::timeout(self.limits.request_timeout, self.client.request(request))
+            .await
+            .map_err(|_| ForwardError::Timeout)?
+            .map_err(ForwardError::from)
+    }
+
+    /// Sends a single JSON-RPC request to the RPC server and returns the body of its response.
+    /// Notifications are not answered, so they return None.
+    pub(crate) async fn call(&self, request: String) -> Result<Option<String>, ForwardError> {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(format!("http://{}/", self.address))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(request))
+            .expect("The request must be valid");
+
+        let response = self.forward(request).await?;
+        let status = response.status();
+        let body = tokio::time::timeout(
+            self.limits.request_timeout,
+            hyper::body::to_bytes(response.into_body()),
+        )
+        .await
+        .map_err(|_| ForwardError::Timeout)??;
+
+        if !body.is_empty() {
+            Ok(Some(String::from_utf8_lossy(&body).into_owned()))
+        } else if status.is_success() {
+            Ok(None)
+        } else {
+            Err(ForwardError::Status(status))
+        }
+    }
+
+    /// Answers a batch of JSON-RPC requests. Returns None if the batch only consists of
+    /// notifications.
+    pub(crate) async fn call_batch(&self, requests: Vec<Value>) -> Option<Value> {
+        if requests.is_empty() {
+            return Some(error_response(
+                Value::Null,
+                INVALID_REQUEST_ERROR,
+                "Empty batch",
+            ));
+        }
+        if requests.len() > self.limits.max_batch_size {
+            return Some(error_response(
+                Value::Null,
+                INVALID_REQUEST_ERROR,
+                &format!(
+                    "Batch too large, at most {} requests are allowed",
+                    self.limits.max_batch_size
+                ),
+            ));
+        }
+
+        // Notifications are not answered, so only the requests with an id get a response.
+        let ids: Vec<Value> = requests
+            .iter()
+            .filter_map(|request| request.get("id").cloned())
+            .collect();
+        match self.call(Value::Array(requests).to_string()).await {
+            Ok(Some(response)) => Some(serde_json::from_str(&response).unwrap_or_else(|_| {
+                error_response(Value::Null, INTERNAL_ERROR, "Invalid response")
+            })),
+            Ok(None) => None,
+            Err(e) => {
+                // Answer every request with the error, so that clients can match it to them.
+                let responses: Vec<Value> = ids
+                    .into_iter()
+                    .map(|id| error_response(id, e.code(), &e.to_string()))
+                    .collect();
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+        }
+    }
+}
+
+/// Reads a request body, rejecting it with `413 Payload Too Large` if it exceeds the maximum size.
+pub(crate) async fn read_body(mut body: Body, max_size: usize) -> Result<Bytes, StatusCode> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|_| StatusCode::BAD_REQUEST)?;
+        if bytes.len() + chunk.len() > max_size {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes.into())
+}
+
+/// Returns a JSON-RPC error response.
+pub(crate) fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
@@ -1,13 +1,34 @@
-use signal_hook::{consts::SIGINT, iterator::Signals};
+use parking_lot::Mutex;
+use signal_hook::{
+    consts::{SIGINT, SIGTERM},
+    iterator::Signals,
+};
 use tokio::time::{sleep, Duration};
 
+type ShutdownHook = Box<dyn FnOnce() + Send>;
+
+/// Hooks that are run before the client exits on a signal.
+static SHUTDOWN_HOOKS: Mutex<Vec<ShutdownHook>> = Mutex::new(Vec::new());
+
+/// Registers a hook that is run when the client is shut down by a signal, e.g. to persist state.
+pub fn register_shutdown_hook<F: FnOnce() + Send + 'static>(hook: F) {
+    SHUTDOWN_HOOKS.lock().push(Box::new(hook));
+}
+
 pub fn initialize_signal_handler() {
-    let signals = Signals::new([SIGINT]);
+    let signals = Signals::new([SIGINT, SIGTERM]);
 
     if let Ok(mut signals) = signals {
         tokio::spawn(async move {
-            for _ in signals.forever() {
-                log::warn!("Received Ctrl+C. Closing client");
+            for signal in signals.forever() {
+                if signal == SIGINT {
+                    log::warn!("Received Ctrl+C. Closing client");
+                } else {
+                    log::warn!("Received SIGTERM. Closing client");
+                }
+                for hook in std::mem::take(&mut *SHUTDOWN_HOOKS.lock()) {
+                    hook();
+                }
                 // Add some delay for the log message to propagate into loki
                 sleep(Duration::from_millis(200)).await;
                 std::process::exit(0);
@@ -0,0 +1,201 @@
+use std::{convert::Infallible, net::SocketAddr};
+
+use hyper::{
+    header,
+    service::{make_service_fn, service_fn},
+    Body, Client, Method, Request, Response, Server, StatusCode,
+};
+use nimiq_lib::{
+    config::config::Credentials,
+    extras::{
+        rpc_access_control::{AccessControl, IpRange},
+        rpc_proxy::{unused_local_address, RpcProxy},
+    },
+};
+use nimiq_test_log::test;
+
+const ORIGIN: &str = "https://dashboard.example.com";
+
+/// Starts an upstream server answering every request with `ok` and a proxy in front of it.
+/// Returns the address of the proxy.
+async fn start_proxy(access_control: AccessControl) -> SocketAddr {
+    let upstream = unused_local_address().unwrap();
+    let server = Server::bind(&upstream).serve(make_service_fn(|_| async {
+        Ok::<_, Infallible>(service_fn(|_| async {
+            Ok::<_, Infallible>(Response::new(Body::from("ok")))
+        }))
+    }));
+    tokio::spawn(server);
+
+    let proxy_address = unused_local_address().unwrap();
+    tokio::spawn(RpcProxy::new(proxy_address, upstream, access_control, None).run());
+    // Let the proxy bind its listener.
+    tokio::task::yield_now().await;
+
+    proxy_address
+}
+
+fn request(address: SocketAddr, method: Method, origin: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(format!("http://{address}/"));
+    if let Some(origin) = origin {
+        builder = builder.header(header::ORIGIN, origin);
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+#[test]
+fn it_parses_ip_ranges() {
+    let range: IpRange = "10.0.0.0/8".parse().unwrap();
+    assert!(range.contains(&"10.1.2.3".parse().unwrap()));
+    assert!(range.contains(&"::ffff:10.1.2.3".parse().unwrap()));
+    assert!(!range.contains(&"11.0.0.1".parse().unwrap()));
+
+    let range: IpRange = "127.0.0.1".parse().unwrap();
+    assert!(range.contains(&"127.0.0.1".parse().unwrap()));
+    assert!(!range.contains(&"127.0.0.2".parse().unwrap()));
+
+    let range: IpRange = "fd00::/8".parse().unwrap();
+    assert!(range.contains(&"fd12::1".parse().unwrap()));
+    assert!(!range.contains(&"fe80::1".parse().unwrap()));
+
+    let range: IpRange = "0.0.0.0/0".parse().unwrap();
+    assert!(range.contains(&"8.8.8.8".parse().unwrap()));
+
+    assert!("10.0.0.0/33".parse::<IpRange>().is_err());
+    assert!("10.0.0/8".parse::<IpRange>().is_err());
+}
+
+#[test(tokio::test)]
+async fn it_forwards_requests_from_allowed_origins() {
+    let address = start_proxy(AccessControl::new(vec![ORIGIN.to_string()], None)).await;
+
+    let response = Client::new()
+        .request(request(address, Method::POST, Some(ORIGIN)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+        ORIGIN
+    );
+    assert_eq!(
+        response.headers()[header::ACCESS_CONTROL_ALLOW_CREDENTIALS],
+        "true"
+    );
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    assert_eq!(&body[..], b"ok");
+
+    // Requests without an origin are not cross-origin requests.
+    let response = Client::new()
+        .request(request(address, Method::POST, None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!response
+        .headers()
+        .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+}
+
+#[test(tokio::test)]
+async fn it_rejects_requests_from_other_origins() {
+    let address = start_proxy(AccessControl::new(vec![ORIGIN.to_string()], None)).await;
+
+    let response = Client::new()
+        .request(request(
+            address,
+            Method::POST,
+            Some("https://evil.example.com"),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[test(tokio::test)]
+async fn it_answers_preflight_requests() {
+    let address = start_proxy(AccessControl::new(vec!["*".to_string()], None)).await;
+
+    let mut preflight = request(address, Method::OPTIONS, Some(ORIGIN));
+    preflight.headers_mut().insert(
+        header::ACCESS_CONTROL_REQUEST_METHOD,
+        "POST".parse().unwrap(),
+    );
+    preflight.headers_mut().insert(
+        header::ACCESS_CONTROL_REQUEST_HEADERS,
+        "content-type".parse().unwrap(),
+    );
+
+    let response = Client::new().request(preflight).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let headers = response.headers();
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], ORIGIN);
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+    assert_eq!(
+        headers[header::ACCESS_CONTROL_ALLOW_HEADERS],
+        "content-type"
+    );
+    assert!(headers
+        .get(header::ACCESS_CONTROL_ALLOW_METHODS)
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .contains("POST"));
+}
+
+#[test(tokio::test)]
+async fn it_only_allows_listed_ips() {
+    let allowed = vec!["127.0.0.0/8".parse().unwrap()];
+    let address = start_proxy(AccessControl::new(vec![], Some(allowed))).await;
+    let response = Client::new()
+        .request(request(address, Method::POST, None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let allowed = vec!["10.0.0.0/8".parse().unwrap()];
+    let address = start_proxy(AccessControl::new(vec![], Some(allowed))).await;
+    let response = Client::new()
+        .request(request(address, Method::POST, None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[test(tokio::test)]
+async fn it_authenticates_with_its_own_credentials() {
+    // The upstream server answers with the authorization header it received.
+    let upstream = unused_local_address().unwrap();
+    let server = Server::bind(&upstream).serve(make_service_fn(|_| async {
+        Ok::<_, Infallible>(service_fn(|request: Request<Body>| async move {
+            let authorization = request
+                .headers()
+                .get(header::AUTHORIZATION)
+                .map(|authorization| authorization.as_bytes().to_vec())
+                .unwrap_or_default();
+            Ok::<_, Infallible>(Response::new(Body::from(authorization)))
+        }))
+    }));
+    tokio::spawn(server);
+
+    let proxy_address = unused_local_address().unwrap();
+    tokio::spawn(
+        RpcProxy::new(proxy_address, upstream, AccessControl::default(), None)
+            .with_upstream_credentials(Credentials::new("proxy", "secret"))
+            .run(),
+    );
+    tokio::task::yield_now().await;
+
+    let mut request = request(proxy_address, Method::POST, None);
+    // `client:password`
+    request.headers_mut().insert(
+        header::AUTHORIZATION,
+        "Basic Y2xpZW50OnBhc3N3b3Jk".parse().unwrap(),
+    );
+    let response = Client::new().request(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    // `proxy:secret`
+    assert_eq!(&body[..], b"Basic cHJveHk6c2VjcmV0");
+}
@@ -0,0 +1,155 @@
+use std::{convert::Infallible, net::SocketAddr, time::Duration};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Client, Method, Request, Response, Server, StatusCode,
+};
+use nimiq_lib::extras::{
+    rpc_access_control::AccessControl,
+    rpc_proxy::{unused_local_address, RpcProxy},
+    rpc_upstream::{RequestLimits, INVALID_REQUEST_ERROR, TIMEOUT_ERROR},
+};
+use nimiq_test_log::test;
+use serde_json::{json, Value};
+use tokio::time::sleep;
+
+/// Answers a request of the upstream server with its method.
+fn answer(request: &Value) -> Option<Value> {
+    // Notifications are not answered.
+    let id = request.get("id")?;
+    Some(json!({ "jsonrpc": "2.0", "id": id, "result": request["method"] }))
+}
+
+/// Answers a request or batch of the upstream server. Requests for `slow` are answered after a
+/// second.
+async fn handle_upstream(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+    let request: Value = serde_json::from_slice(&body).unwrap();
+
+    let requests = match &request {
+        Value::Array(requests) => requests.clone(),
+        request => vec![request.clone()],
+    };
+    if requests.iter().any(|request| request["method"] == "slow") {
+        sleep(Duration::from_secs(1)).await;
+    }
+
+    let response = match request {
+        Value::Array(requests) => {
+            let responses: Vec<Value> = requests.iter().filter_map(answer).collect();
+            (!responses.is_empty()).then_some(Value::Array(responses))
+        }
+        request => answer(&request),
+    };
+    let body = response
+        .map(|response| response.to_string())
+        .unwrap_or_default();
+    Ok(Response::new(Body::from(body)))
+}
+
+/// Starts an upstream server and a proxy with the given limits in front of it. Returns the
+/// address of the proxy.
+async fn start_proxy(limits: RequestLimits) -> SocketAddr {
+    let upstream = unused_local_address().unwrap();
+    let server = Server::bind(&upstream).serve(make_service_fn(|_| async {
+        Ok::<_, Infallible>(service_fn(handle_upstream))
+    }));
+    tokio::spawn(server);
+
+    let proxy_address = unused_local_address().unwrap();
+    tokio::spawn(
+        RpcProxy::new(proxy_address, upstream, AccessControl::default(), None)
+            .with_request_limits(limits)
+            .run(),
+    );
+    // Let the proxy bind its listener.
+    tokio::task::yield_now().await;
+
+    proxy_address
+}
+
+async fn post(address: SocketAddr, body: String) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(format!("http://{address}/"))
+        .body(Body::from(body))
+        .unwrap();
+    let response = Client::new().request(request).await.unwrap();
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    (status, body)
+}
+
+fn call(id: u64, method: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": [] })
+}
+
+#[test(tokio::test)]
+async fn it_answers_batches() {
+    let address = start_proxy(RequestLimits::default()).await;
+
+    let notification = json!({ "jsonrpc": "2.0", "method": "notify", "params": [] });
+    let batch = json!([
+        call(1, "getBlockNumber"),
+        notification,
+        call(2, "getBlockByNumber"),
+    ]);
+    let (status, response) = post(address, batch.to_string()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        response,
+        json!([
+            { "jsonrpc": "2.0", "id": 1, "result": "getBlockNumber" },
+            { "jsonrpc": "2.0", "id": 2, "result": "getBlockByNumber" },
+        ])
+    );
+
+    // Single requests are forwarded as they are.
+    let (status, response) = post(address, call(3, "getBlockNumber").to_string()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["result"], "getBlockNumber");
+}
+
+#[test(tokio::test)]
+async fn it_limits_batches_and_bodies() {
+    let address = start_proxy(RequestLimits {
+        max_batch_size: 2,
+        max_body_size: 1024,
+        ..Default::default()
+    })
+    .await;
+
+    let (_, response) = post(address, "[]".to_string()).await;
+    assert_eq!(response["error"]["code"], INVALID_REQUEST_ERROR);
+
+    let batch = json!([call(1, "a"), call(2, "b"), call(3, "c")]);
+    let (_, response) = post(address, batch.to_string()).await;
+    assert!(response["id"].is_null());
+    assert_eq!(response["error"]["code"], INVALID_REQUEST_ERROR);
+
+    let (status, _) = post(address, call(1, &"a".repeat(2048)).to_string()).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[test(tokio::test)]
+async fn it_times_out_slow_requests() {
+    let address = start_proxy(RequestLimits {
+        request_timeout: Duration::from_millis(100),
+        ..Default::default()
+    })
+    .await;
+
+    // The batch is answered at once, so all of its requests time out.
+    let notification = json!({ "jsonrpc": "2.0", "method": "notify", "params": [] });
+    let batch = json!([call(1, "slow"), notification, call(2, "fast")]);
+    let (_, response) = post(address, batch.to_string()).await;
+    assert_eq!(response.as_array().unwrap().len(), 2);
+    assert_eq!(response[0]["id"], 1);
+    assert_eq!(response[0]["error"]["code"], TIMEOUT_ERROR);
+    assert_eq!(response[1]["id"], 2);
+    assert_eq!(response[1]["error"]["code"], TIMEOUT_ERROR);
+
+    let (status, _) = post(address, call(3, "slow").to_string()).await;
+    assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+}
@@ -0,0 +1,256 @@
+use std::{collections::HashSet, convert::Infallible, net::SocketAddr};
+
+use hyper::{
+    header::{self, HeaderValue},
+    service::{make_service_fn, service_fn},
+    Body, Client, Method, Request, Response, Server, StatusCode,
+};
+use nimiq_lib::{
+    config::config::Credentials,
+    extras::{
+        rpc_access_control::AccessControl,
+        rpc_permissions::{
+            ApiToken, ApiTokens, Denial, MethodPermissions, METHOD_NOT_ALLOWED_ERROR,
+        },
+        rpc_proxy::{unused_local_address, RpcProxy},
+    },
+};
+use nimiq_test_log::test;
+use serde_json::{json, Value};
+
+const TOKEN: &str = "reader-secret";
+
+fn bearer(token: &str) -> HeaderValue {
+    format!("Bearer {token}").parse().unwrap()
+}
+
+fn reader(rate_limit: Option<u32>) -> ApiToken {
+    ApiToken::new(
+        "reader".to_string(),
+        Some(TOKEN.to_string()),
+        None,
+        &["blockchain:read".to_string()],
+        rate_limit,
+    )
+    .unwrap()
+}
+
+/// Starts an upstream server answering every request with `ok` and a proxy requiring the given
+/// tokens in front of it. Returns the address of the proxy.
+async fn start_proxy(tokens: Vec<ApiToken>) -> SocketAddr {
+    let upstream = unused_local_address().unwrap();
+    let server = Server::bind(&upstream).serve(make_service_fn(|_| async {
+        Ok::<_, Infallible>(service_fn(|_| async {
+            Ok::<_, Infallible>(Response::new(Body::from("ok")))
+        }))
+    }));
+    tokio::spawn(server);
+
+    let proxy_address = unused_local_address().unwrap();
+    tokio::spawn(
+        RpcProxy::new(proxy_address, upstream, AccessControl::default(), None)
+            .with_api_tokens(ApiTokens::new(tokens))
+            .run(),
+    );
+    // Let the proxy bind its listener.
+    tokio::task::yield_now().await;
+
+    proxy_address
+}
+
+fn request(address: SocketAddr, method: &str, authorization: Option<HeaderValue>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method(Method::POST)
+        .uri(format!("http://{address}/"));
+    if let Some(authorization) = authorization {
+        builder = builder.header(header::AUTHORIZATION, authorization);
+    }
+    let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": [] });
+    builder.body(Body::from(body.to_string())).unwrap()
+}
+
+#[test]
+fn it_expands_method_groups() {
+    let permissions =
+        MethodPermissions::from_entries(&["blockchain:read", "getPeerCount"]).unwrap();
+    assert!(permissions.is_allowed("getBlockNumber"));
+    assert!(permissions.is_allowed("subscribeForHeadBlock"));
+    assert!(permissions.is_allowed("getPeerCount"));
+    assert!(!permissions.is_allowed("getPeerList"));
+    assert!(!permissions.is_allowed("sendBasicTransaction"));
+    assert!(!permissions.is_allowed("unlockAccount"));
+
+    let permissions = MethodPermissions::from_entries(&["read"]).unwrap();
+    assert!(permissions.is_allowed("getRawTransactionInfo"));
+    assert!(permissions.is_allowed("estimateFee"));
+    assert!(!permissions.is_allowed("pushTransaction"));
+    assert!(!permissions.is_allowed("createBasicTransaction"));
+    assert!(!permissions.is_allowed("getSigningKey"));
+
+    assert_eq!(
+        MethodPermissions::from_entries(&["wallet", "*"]).unwrap(),
+        MethodPermissions::all()
+    );
+    assert_eq!(
+        MethodPermissions::from_entries::<&str>(&[]).unwrap(),
+        MethodPermissions::all()
+    );
+    assert!(MethodPermissions::from_entries(&["wallet:read"]).is_err());
+}
+
+/// Returns the camelCase names of the RPC methods declared in the given `rpc-interface` source.
+fn interface_methods(source: &str) -> HashSet<String> {
+    source
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("async fn "))
+        .map(|line| {
+            let name = line.split(['(', '<']).next().unwrap();
+            let mut words = name.split('_');
+            let mut method = words.next().unwrap().to_string();
+            for word in words {
+                let mut chars = word.chars();
+                if let Some(first) = chars.next() {
+                    method.push(first.to_ascii_uppercase());
+                    method.extend(chars);
+                }
+            }
+            method
+        })
+        .collect()
+}
+
+fn group(entry: &str) -> HashSet<String> {
+    MethodPermissions::from_entries(&[entry])
+        .unwrap()
+        .methods()
+        .unwrap()
+        .clone()
+}
+
+#[test]
+fn it_groups_the_methods_of_the_rpc_interface() {
+    let blockchain = interface_methods(include_str!("../../rpc-interface/src/blockchain.rs"));
+    let consensus = interface_methods(include_str!("../../rpc-interface/src/consensus.rs"));
+    let mempool = interface_methods(include_str!("../../rpc-interface/src/mempool.rs"));
+    let network = interface_methods(include_str!("../../rpc-interface/src/network.rs"));
+    let policy = interface_methods(include_str!("../../rpc-interface/src/policy.rs"));
+    let validator = interface_methods(include_str!("../../rpc-interface/src/validator.rs"));
+    let wallet = interface_methods(include_str!("../../rpc-interface/src/wallet.rs"));
+    let zkp = interface_methods(include_str!("../../rpc-interface/src/zkp_component.rs"));
+
+    // Every group covers exactly the methods of its interface.
+    assert_eq!(group("blockchain:read"), blockchain);
+    assert_eq!(group("consensus"), consensus);
+    assert_eq!(group("mempool"), mempool);
+    assert_eq!(group("network:read"), network);
+    assert_eq!(group("policy:read"), policy);
+    assert_eq!(group("validator"), validator);
+    assert_eq!(group("wallet"), wallet);
+    assert_eq!(group("zkp:read"), zkp);
+
+    // The read-only groups are subsets of their interfaces.
+    assert!(group("consensus:read").is_subset(&consensus));
+    assert!(group("mempool:read").is_subset(&mempool));
+    let all: HashSet<_> = [
+        blockchain, consensus, mempool, network, policy, validator, wallet, zkp,
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    assert!(group("read").is_subset(&all));
+}
+
+#[test]
+fn it_authenticates_tokens() {
+    let admin = ApiToken::new(
+        "admin".to_string(),
+        None,
+        Some(Credentials::new("admin", "admin-secret")),
+        &[],
+        None,
+    )
+    .unwrap();
+    let tokens = ApiTokens::new(vec![reader(None), admin]);
+
+    let token = tokens.authenticate(Some(&bearer(TOKEN))).unwrap();
+    assert_eq!(token.name(), "reader");
+
+    // `admin:admin-secret`
+    let basic = HeaderValue::from_static("Basic YWRtaW46YWRtaW4tc2VjcmV0");
+    let token = tokens.authenticate(Some(&basic)).unwrap();
+    assert_eq!(token.name(), "admin");
+
+    assert!(tokens.authenticate(Some(&bearer("wrong"))).is_none());
+    // `admin:wrong`
+    let basic = HeaderValue::from_static("Basic YWRtaW46d3Jvbmc=");
+    assert!(tokens.authenticate(Some(&basic)).is_none());
+    assert!(tokens.authenticate(None).is_none());
+
+    assert!(ApiToken::new("none".to_string(), None, None, &[], None).is_err());
+}
+
+#[test]
+fn it_rate_limits_tokens() {
+    let tokens = ApiTokens::new(vec![reader(Some(2))]);
+    let token = tokens.authenticate(Some(&bearer(TOKEN))).unwrap();
+
+    assert_eq!(
+        token.check(&["sendBasicTransaction"]),
+        Err(Denial::MethodNotAllowed("sendBasicTransaction".to_string()))
+    );
+    // Batches that are larger than the rate limit can never be allowed.
+    assert_eq!(
+        token.check(&["getBlockNumber"; 3]),
+        Err(Denial::BatchTooLarge(2))
+    );
+    // Denied methods and batches do not count towards the rate limit.
+    assert_eq!(token.check(&["getBlockNumber"]), Ok(()));
+    assert_eq!(token.check(&["getBlockNumber"]), Ok(()));
+    assert_eq!(token.check(&["getBlockNumber"]), Err(Denial::RateLimited));
+}
+
+#[test(tokio::test)]
+async fn it_requires_a_token() {
+    let address = start_proxy(vec![reader(None)]).await;
+
+    let response = Client::new()
+        .request(request(address, "getBlockNumber", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = Client::new()
+        .request(request(address, "getBlockNumber", Some(bearer("wrong"))))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[test(tokio::test)]
+async fn it_enforces_token_permissions() {
+    let address = start_proxy(vec![reader(Some(1))]).await;
+
+    let response = Client::new()
+        .request(request(address, "getBlockNumber", Some(bearer(TOKEN))))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    assert_eq!(&body[..], b"ok");
+
+    let response = Client::new()
+        .request(request(address, "unlockAccount", Some(bearer(TOKEN))))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let error: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error["id"], 1);
+    assert_eq!(error["error"]["code"], METHOD_NOT_ALLOWED_ERROR);
+
+    let response = Client::new()
+        .request(request(address, "getBlockNumber", Some(bearer(TOKEN))))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
@@ -0,0 +1,225 @@
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use futures::{SinkExt, StreamExt};
+use hyper::{
+    header,
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+use nimiq_lib::extras::{
+    rpc_access_control::AccessControl,
+    rpc_proxy::{unused_local_address, RpcProxy},
+    rpc_subscriptions::WebSocketSettings,
+};
+use nimiq_test_log::test;
+use serde_json::{json, Value};
+use tokio::{net::TcpStream, time::sleep};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{handshake::derive_accept_key, protocol::Role, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// The subscription id the upstream server assigns to every subscription.
+const UPSTREAM_SUBSCRIPTION_ID: u64 = 7;
+
+/// Serves a subscription of the upstream server: answers the request and then sends a
+/// notification every few milliseconds until the connection is closed.
+async fn serve_subscription(request: Request<Body>, active: Arc<AtomicUsize>) {
+    let upgraded = hyper::upgrade::on(request).await.unwrap();
+    let mut socket = WebSocketStream::from_raw_socket(upgraded, Role::Server, None).await;
+
+    let request: Value = match socket.next().await {
+        Some(Ok(Message::Text(text))) => serde_json::from_str(&text).unwrap(),
+        _ => return,
+    };
+    let response =
+        json!({ "jsonrpc": "2.0", "id": request["id"], "result": UPSTREAM_SUBSCRIPTION_ID });
+    socket
+        .send(Message::Text(response.to_string()))
+        .await
+        .unwrap();
+
+    active.fetch_add(1, Ordering::SeqCst);
+    loop {
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": request["method"],
+            "params": { "subscription": UPSTREAM_SUBSCRIPTION_ID, "result": "head" },
+        });
+        if socket
+            .send(Message::Text(notification.to_string()))
+            .await
+            .is_err()
+        {
+            break;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    active.fetch_sub(1, Ordering::SeqCst);
+}
+
+/// Answers a request of the upstream server, either by accepting a subscription or by
+/// answering with `pong`.
+async fn handle_upstream(
+    request: Request<Body>,
+    active: Arc<AtomicUsize>,
+) -> Result<Response<Body>, Infallible> {
+    if let Some(key) = request.headers().get(header::SEC_WEBSOCKET_KEY) {
+        let accept_key = derive_accept_key(key.as_bytes());
+        tokio::spawn(serve_subscription(request, active));
+        let response = Response::builder()
+            .status(StatusCode::SWITCHING_PROTOCOLS)
+            .header(header::UPGRADE, "websocket")
+            .header(header::CONNECTION, "Upgrade")
+            .header(header::SEC_WEBSOCKET_ACCEPT, accept_key)
+            .body(Body::empty())
+            .unwrap();
+        return Ok(response);
+    }
+
+    let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+    let request: Value = serde_json::from_slice(&body).unwrap();
+    let response = json!({ "jsonrpc": "2.0", "id": request["id"], "result": "pong" });
+    Ok(Response::new(Body::from(response.to_string())))
+}
+
+/// Starts an upstream server and a proxy with the WebSocket transport in front of it.
+/// Returns a connection to the proxy and the number of subscriptions active upstream.
+async fn connect(max_subscriptions: usize) -> (Socket, Arc<AtomicUsize>) {
+    let active = Arc::new(AtomicUsize::new(0));
+
+    let upstream = unused_local_address().unwrap();
+    let upstream_active = Arc::clone(&active);
+    let server = Server::bind(&upstream).serve(make_service_fn(move |_| {
+        let active = Arc::clone(&upstream_active);
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                handle_upstream(request, Arc::clone(&active))
+            }))
+        }
+    }));
+    tokio::spawn(server);
+
+    let proxy_address: SocketAddr = unused_local_address().unwrap();
+    let settings = WebSocketSettings { max_subscriptions };
+    tokio::spawn(
+        RpcProxy::new(
+            proxy_address,
+            upstream,
+            AccessControl::default(),
+            Some(settings),
+        )
+        .run(),
+    );
+    // Let the proxy bind its listener.
+    tokio::task::yield_now().await;
+
+    let (socket, _) = connect_async(format!("ws://{proxy_address}/ws"))
+        .await
+        .unwrap();
+    (socket, active)
+}
+
+async fn call(socket: &mut Socket, id: u64, method: &str, params: Value) -> Value {
+    let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+    socket
+        .send(Message::Text(request.to_string()))
+        .await
+        .unwrap();
+    // Skip notifications until the response arrives.
+    loop {
+        let message = socket.next().await.unwrap().unwrap();
+        let message: Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+        if message["id"] == json!(id) {
+            return message;
+        }
+    }
+}
+
+async fn wait_for_active(active: &AtomicUsize, expected: usize) {
+    for _ in 0..100 {
+        if active.load(Ordering::SeqCst) == expected {
+            return;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    panic!(
+        "Expected {expected} active subscriptions, found {}",
+        active.load(Ordering::SeqCst)
+    );
+}
+
+#[test(tokio::test)]
+async fn it_forwards_requests() {
+    let (mut socket, _) = connect(1).await;
+
+    let response = call(&mut socket, 1, "getBlockNumber", json!([])).await;
+    assert_eq!(response["result"], "pong");
+}
+
+#[test(tokio::test)]
+async fn it_streams_notifications_until_unsubscribed() {
+    let (mut socket, active) = connect(2).await;
+
+    let response = call(&mut socket, 1, "subscribeForHeadBlockHash", json!([])).await;
+    let subscription_id = response["result"].as_u64().unwrap();
+    wait_for_active(&active, 1).await;
+
+    let notification = socket.next().await.unwrap().unwrap();
+    let notification: Value = serde_json::from_str(notification.to_text().unwrap()).unwrap();
+    assert_eq!(notification["params"]["subscription"], subscription_id);
+    assert_eq!(notification["params"]["result"], "head");
+
+    let response = call(&mut socket, 2, "unsubscribe", json!([subscription_id])).await;
+    assert_eq!(response["result"], true);
+    wait_for_active(&active, 0).await;
+
+    let response = call(&mut socket, 3, "unsubscribe", json!([subscription_id])).await;
+    assert_eq!(response["result"], false);
+}
+
+#[test(tokio::test)]
+async fn it_limits_subscriptions_per_connection() {
+    let (mut socket, active) = connect(1).await;
+
+    let response = call(&mut socket, 1, "subscribeForHeadBlockHash", json!([])).await;
+    let subscription_id = response["result"].as_u64().unwrap();
+
+    let response = call(&mut socket, 2, "subscribeForHeadBlockHash", json!([])).await;
+    assert_eq!(response["error"]["code"], -32000);
+    wait_for_active(&active, 1).await;
+
+    // Cancelling a subscription makes room for a new one.
+    call(&mut socket, 3, "unsubscribe", json!([subscription_id])).await;
+    let response = call(&mut socket, 4, "subscribeForHeadBlockHash", json!([])).await;
+    assert!(response["result"].is_u64());
+}
+
+#[test(tokio::test)]
+async fn it_cancels_subscriptions_on_disconnect() {
+    let (mut socket, active) = connect(2).await;
+
+    call(&mut socket, 1, "subscribeForHeadBlockHash", json!([])).await;
+    call(
+        &mut socket,
+        2,
+        "subscribeForLogsByAddressesAndTypes",
+        json!([[], []]),
+    )
+    .await;
+    wait_for_active(&active, 2).await;
+
+    socket.close(None).await.unwrap();
+    wait_for_active(&active, 0).await;
+}
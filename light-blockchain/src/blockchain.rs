@@ -15,7 +15,10 @@ use nimiq_utils::time::OffsetTime;
 use nimiq_vrf::{Rng, VrfEntropy, VrfUseCase};
 use tokio::sync::broadcast::{channel as broadcast, Sender as BroadcastSender};
 
-use crate::chain_store::ChainStore;
+use crate::{
+    chain_store::ChainStore,
+    storage::{LightChainStorage, LightChainUpdate, StoredLightChain, StoredZKP},
+};
 
 const BROADCAST_MAX_CAPACITY: usize = 256;
 
@@ -38,6 +41,8 @@ pub struct LightBlockchain {
     pub genesis_block: Block,
     /// The chain store is a database containing all of the chain infos in the current batch.
     pub chain_store: ChainStore,
+    /// The most recent ZK proof we synced with, together with the election block it proves.
+    pub latest_zkp: Option<StoredZKP>,
     /// The notifier processes events relative to the blockchain.
     pub notifier: BroadcastSender<BlockchainEvent>,
     /// The fork notifier processes fork events.
@@ -75,11 +80,117 @@ impl LightBlockchain {
             current_validators: genesis_block.validators(),
             genesis_block,
             chain_store,
+            latest_zkp: None,
             notifier: tx,
             fork_notifier: tx_fork,
         }
     }
 
+    /// Creates a new blockchain from a given network ID that persists its state in the given
+    /// storage. If the storage holds the state of a previous run, the blockchain resumes from it.
+    pub fn with_storage(network_id: NetworkId, storage: Arc<dyn LightChainStorage>) -> Self {
+        let mut blockchain = Self::new(network_id);
+
+        match storage.load() {
+            Some(stored) => blockchain.restore(stored, storage),
+            None => {
+                // Persist the genesis state, so that the storage is never half populated.
+                let update = LightChainUpdate {
+                    clear_chain_infos: true,
+                    chain_infos: blockchain
+                        .chain_store
+                        .get_all_chain_infos()
+                        .cloned()
+                        .collect(),
+                    election_head: Some(blockchain.election_head.clone()),
+                    ..Default::default()
+                };
+                storage.write(&update);
+                blockchain.chain_store.set_storage(storage);
+            }
+        }
+
+        blockchain
+    }
+
+    /// Resumes from a persisted state. If the chain infos of the current batch were not persisted
+    /// completely, it resumes from the election block.
+    fn restore(&mut self, stored: StoredLightChain, storage: Arc<dyn LightChainStorage>) {
+        let election_head = stored.election_head;
+
+        let main_chain_head = |macro_only: bool| {
+            stored
+                .chain_infos
+                .iter()
+                .filter(|chain_info| chain_info.on_main_chain)
+                .filter(|chain_info| !macro_only || chain_info.head.is_macro())
+                .max_by_key(|chain_info| chain_info.head.block_number())
+                .map(|chain_info| chain_info.head.clone())
+        };
+        let (head, macro_head, chain_infos) = match (main_chain_head(false), main_chain_head(true))
+        {
+            (Some(head), Some(Block::Macro(macro_head)))
+                if macro_head.block_number() >= election_head.block_number() =>
+            {
+                (head, macro_head, stored.chain_infos)
+            }
+            _ => {
+                log::warn!(
+                    block_number = election_head.block_number(),
+                    "Incomplete light chain storage, resuming from the election block"
+                );
+                let chain_info = ChainInfo::new(Block::Macro(election_head.clone()), true);
+                storage.write(&LightChainUpdate {
+                    clear_chain_infos: true,
+                    chain_infos: vec![chain_info.clone()],
+                    ..Default::default()
+                });
+                (
+                    chain_info.head.clone(),
+                    election_head.clone(),
+                    vec![chain_info],
+                )
+            }
+        };
+
+        let mut chain_store = ChainStore::default();
+        for header in stored.elections {
+            chain_store.put_election(header);
+        }
+        for chain_info in chain_infos {
+            chain_store.put_chain_info(chain_info);
+        }
+        chain_store.set_storage(storage);
+
+        log::info!(
+            block_number = head.block_number(),
+            "Resuming the light blockchain from storage"
+        );
+
+        self.head = head;
+        self.macro_head = macro_head;
+        self.current_validators = election_head.get_validators();
+        self.election_head = election_head;
+        self.chain_store = chain_store;
+        self.latest_zkp = stored.zkp;
+    }
+
+    /// Sets the election head. It is persisted with the next commit of the chain store.
+    pub(crate) fn set_election_head(&mut self, election_head: MacroBlock) {
+        if let Some(update) = self.chain_store.pending_update() {
+            update.election_head = Some(election_head.clone());
+        }
+        self.election_head = election_head;
+    }
+
+    /// Sets the most recent ZK proof. It is persisted with the next commit of the chain store.
+    pub(crate) fn set_latest_zkp(&mut self, zkp: StoredZKP) {
+        if let Some(update) = self.chain_store.pending_update() {
+            update.zkp = Some(zkp.clone());
+        }
+        self.latest_zkp = Some(zkp);
+    }
+
     /// Gets the active validators for a given epoch.
     pub fn get_validators_for_epoch(&self, epoch: u32) -> Result<Validators, BlockchainError> {
         let current_epoch = Policy::epoch_at(self.head.block_number());
@@ -1,12 +1,14 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, mem, sync::Arc};
 
 use nimiq_block::{Block, MacroHeader};
 use nimiq_blockchain_interface::{BlockchainError, ChainInfo, Direction};
 use nimiq_hash::Blake2bHash;
 use nimiq_primitives::policy::Policy;
 
+use crate::storage::{LightChainStorage, LightChainUpdate};
+
 /// A struct that stores the blocks for the blockchain.
-#[derive(Debug, Default)]
+#[derive(Default)]
 pub struct ChainStore {
     // A store of chain infos indexed by their block hashes. Contains only headers.
     chain_db: HashMap<Blake2bHash, ChainInfo>,
@@ -14,9 +16,38 @@ pub struct ChainStore {
     height_idx: HashMap<u32, Vec<Blake2bHash>>,
     // A store of election block headers indexed by their epoch number.
     election_db: HashMap<u32, MacroHeader>,
+    // The persistent storage the changes are written to, if any.
+    storage: Option<Arc<dyn LightChainStorage>>,
+    // The changes not written to the storage yet.
+    pending_update: LightChainUpdate,
 }
 
 impl ChainStore {
+    /// Sets the storage every subsequent change is written to.
+    pub fn set_storage(&mut self, storage: Arc<dyn LightChainStorage>) {
+        self.storage = Some(storage);
+    }
+
+    /// Gets the changes not written to the storage yet. Returns None if there is no storage.
+    pub(crate) fn pending_update(&mut self) -> Option<&mut LightChainUpdate> {
+        self.storage.as_ref()?;
+        Some(&mut self.pending_update)
+    }
+
+    /// Writes the changes made since the last commit to the storage, all at once.
+    pub fn commit(&mut self) {
+        if let Some(storage) = &self.storage {
+            if !self.pending_update.is_empty() {
+                storage.write(&mem::take(&mut self.pending_update));
+            }
+        }
+    }
+
+    /// Gets all the stored chain infos.
+    pub fn get_all_chain_infos(&self) -> impl Iterator<Item = &ChainInfo> {
+        self.chain_db.values()
+    }
+
     /// Gets a chain info by its hash. Returns None if the chain info doesn't exist.
     pub fn get_chain_info(
         &self,
@@ -84,6 +115,10 @@ impl ChainStore {
             }
         }
 
+        if let Some(update) = self.pending_update() {
+            update.chain_infos.push(chain_info.clone());
+        }
+
         // Add the chain info to the chain_db. If there was already a chain info at the same hash, it
         // will return an Option with the previous chain info.
         let previous = self.chain_db.insert(hash.clone(), chain_info.clone());
@@ -335,6 +370,9 @@ impl ChainStore {
 
     /// Adds an election block header to the ChainStore.
     pub fn put_election(&mut self, header: MacroHeader) {
+        if let Some(update) = self.pending_update() {
+            update.elections.push(header.clone());
+        }
         self.election_db
             .insert(Policy::epoch_at(header.block_number), header);
     }
@@ -342,6 +380,9 @@ impl ChainStore {
     /// Clears the ChainStore of all blocks (except the election blocks). This can be used at the
     /// end of each batch, so that we don't keep unnecessary micro blocks.
     pub fn clear(&mut self) {
+        if let Some(update) = self.pending_update() {
+            update.clear_chain_infos();
+        }
         self.chain_db.clear();
         self.height_idx.clear();
     }
@@ -1,8 +1,14 @@
 pub use blockchain::LightBlockchain;
 pub use chain_store::ChainStore;
+#[cfg(feature = "database-storage")]
+pub use storage::DBLightChainStorage;
+pub use storage::{
+    LightChainStorage, LightChainUpdate, StoredChainInfo, StoredLightChain, StoredZKP,
+};
 
 pub(crate) mod abstract_blockchain;
 pub(crate) mod blockchain;
 pub(crate) mod chain_store;
 pub(crate) mod push;
+pub(crate) mod storage;
 pub(crate) mod sync;
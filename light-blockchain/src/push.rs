@@ -144,6 +144,7 @@ impl LightBlockchain {
         let mut this = RwLockUpgradableReadGuard::upgrade(this);
         // Otherwise, we are creating/extending a fork. Store ChainInfo.
         this.chain_store.put_chain_info(chain_info);
+        this.chain_store.commit();
 
         Ok(result)
     }
@@ -182,7 +183,7 @@ impl LightBlockchain {
 
             // If the block is also an election block, then we have more fields to update.
             if macro_block.is_election_block() {
-                this.election_head = macro_block.clone();
+                this.set_election_head(macro_block.clone());
 
                 this.current_validators = macro_block.get_validators();
 
@@ -191,8 +192,9 @@ impl LightBlockchain {
             }
         }
 
-        // Store the current chain info.
+        // Store the current chain info and persist all changes at once.
         this.chain_store.put_chain_info(chain_info);
+        this.chain_store.commit();
 
         log::debug!(
             block = %this.head,
@@ -317,6 +319,9 @@ impl LightBlockchain {
             this.chain_store.put_chain_info(fork_block.1.clone());
         }
 
+        // Persist all changes at once.
+        this.chain_store.commit();
+
         // Update head.
         let new_head_info = &fork_chain[0].1;
 
@@ -390,6 +395,7 @@ impl LightBlockchain {
 
         // Store the election block header.
         this.chain_store.put_election(header);
+        this.chain_store.commit();
 
         Ok(PushResult::Extended)
     }
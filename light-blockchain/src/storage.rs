@@ -0,0 +1,247 @@
+use nimiq_block::{Block, MacroBlock, MacroHeader};
+use nimiq_blockchain_interface::ChainInfo;
+#[cfg(feature = "database-storage")]
+use nimiq_database::{
+    traits::{Database, ReadCursor, ReadTransaction, WriteTransaction},
+    DatabaseProxy, TableProxy,
+};
+use nimiq_hash::Blake2bHash;
+#[cfg(feature = "database-storage")]
+use nimiq_primitives::policy::Policy;
+use nimiq_serde::{Deserialize, Serialize};
+use nimiq_zkp::NanoProof;
+
+/// Defines an interface for persisting the state of a light blockchain, so that a light node can
+/// resume from its last known election block after a restart.
+///
+/// Writes are expected to be cheap: they happen while the blockchain lock is held.
+pub trait LightChainStorage: Send + Sync {
+    /// Loads the persisted state. Returns None if nothing was persisted yet.
+    fn load(&self) -> Option<StoredLightChain>;
+
+    /// Writes all changes of an update at once, such that either all or none of them are
+    /// persisted.
+    fn write(&self, update: &LightChainUpdate);
+}
+
+/// The changes to the state of a light blockchain made by a single operation, e.g. pushing a
+/// block.
+#[derive(Clone, Debug, Default)]
+pub struct LightChainUpdate {
+    /// Whether all stored chain infos are removed before storing the new ones.
+    pub clear_chain_infos: bool,
+    /// The chain infos to store, replacing the chain infos of the same blocks if there are any.
+    pub chain_infos: Vec<ChainInfo>,
+    /// The election block headers to store.
+    pub elections: Vec<MacroHeader>,
+    /// The new most recent election block.
+    pub election_head: Option<MacroBlock>,
+    /// The new most recent ZK proof together with the election block it proves.
+    pub zkp: Option<StoredZKP>,
+}
+
+impl LightChainUpdate {
+    /// Whether the update doesn't change anything.
+    pub fn is_empty(&self) -> bool {
+        !self.clear_chain_infos
+            && self.chain_infos.is_empty()
+            && self.elections.is_empty()
+            && self.election_head.is_none()
+            && self.zkp.is_none()
+    }
+
+    /// Removes all chain infos, including the ones stored earlier in this update.
+    pub fn clear_chain_infos(&mut self) {
+        self.clear_chain_infos = true;
+        self.chain_infos.clear();
+    }
+}
+
+/// The persisted state of a light blockchain.
+#[derive(Clone, Debug)]
+pub struct StoredLightChain {
+    /// The most recent election block.
+    pub election_head: MacroBlock,
+    /// The chain infos of the current batch.
+    pub chain_infos: Vec<ChainInfo>,
+    /// The known election block headers.
+    pub elections: Vec<MacroHeader>,
+    /// The most recent ZK proof.
+    pub zkp: Option<StoredZKP>,
+}
+
+/// A chain info as it is persisted. Unlike the serialization of a [`ChainInfo`], it keeps the
+/// body of the block, since the light blockchain needs the bodies of macro blocks.
+/// The remaining fields of a chain info are not used by light nodes.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoredChainInfo {
+    pub head: Block,
+    pub on_main_chain: bool,
+    pub main_chain_successor: Option<Blake2bHash>,
+}
+
+impl From<&ChainInfo> for StoredChainInfo {
+    fn from(chain_info: &ChainInfo) -> Self {
+        Self {
+            head: chain_info.head.clone(),
+            on_main_chain: chain_info.on_main_chain,
+            main_chain_successor: chain_info.main_chain_successor.clone(),
+        }
+    }
+}
+
+impl From<StoredChainInfo> for ChainInfo {
+    fn from(stored: StoredChainInfo) -> Self {
+        let mut chain_info = ChainInfo::new(stored.head, stored.on_main_chain);
+        chain_info.main_chain_successor = stored.main_chain_successor;
+        chain_info
+    }
+}
+
+/// A ZK proof together with the election block it proves.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StoredZKP {
+    pub block: MacroBlock,
+    #[serde(with = "serde_proof")]
+    pub proof: NanoProof,
+}
+
+mod serde_proof {
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use nimiq_zkp::NanoProof;
+    use serde::{de::Error as DeError, ser::Error as SerError, Deserializer, Serializer};
+
+    pub fn serialize<S>(proof: &NanoProof, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut bytes = Vec::with_capacity(proof.compressed_size());
+        proof
+            .serialize_compressed(&mut bytes)
+            .map_err(|e| S::Error::custom(format!("Could not serialize proof: {e}")))?;
+        serde::Serialize::serialize(&bytes, serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<NanoProof, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let bytes: Vec<u8> = serde::Deserialize::deserialize(deserializer)?;
+        NanoProof::deserialize_compressed(&bytes[..])
+            .map_err(|e| D::Error::custom(format!("Could not deserialize proof: {e}")))
+    }
+}
+
+#[cfg(feature = "database-storage")]
+/// DB implementation of a LightChainStorage meant for persistent storage.
+#[derive(Debug)]
+pub struct DBLightChainStorage {
+    /// Environment for the DB creation and transaction handling.
+    env: DatabaseProxy,
+    // A database of the chain infos of the current batch, indexed by their block hashes.
+    chain_info_db: TableProxy,
+    // A database of election block headers, indexed by the big endian epoch number.
+    election_db: TableProxy,
+    // A database of the most recent election block and ZK proof.
+    head_db: TableProxy,
+}
+
+#[cfg(feature = "database-storage")]
+impl DBLightChainStorage {
+    const CHAIN_INFO_DB_NAME: &'static str = "LightChainInfos";
+    const ELECTION_DB_NAME: &'static str = "LightElections";
+    const HEAD_DB_NAME: &'static str = "LightHeads";
+    const ELECTION_HEAD_KEY: &'static str = "election_head";
+    const ZKP_KEY: &'static str = "zkp";
+
+    pub fn new(env: DatabaseProxy) -> Self {
+        let chain_info_db = env.open_table(Self::CHAIN_INFO_DB_NAME.to_string());
+        let election_db = env.open_table(Self::ELECTION_DB_NAME.to_string());
+        let head_db = env.open_table(Self::HEAD_DB_NAME.to_string());
+
+        Self {
+            env,
+            chain_info_db,
+            election_db,
+            head_db,
+        }
+    }
+
+    fn get<T: Deserialize>(&self, table: &TableProxy, key: &str) -> Option<T> {
+        let bytes: Vec<u8> = self.env.read_transaction().get(table, key)?;
+        match T::deserialize_from_vec(&bytes) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                log::error!(key, error = %e, "Failed to deserialize stored light chain data");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(feature = "database-storage")]
+impl LightChainStorage for DBLightChainStorage {
+    fn load(&self) -> Option<StoredLightChain> {
+        let election_head = self.get(&self.head_db, Self::ELECTION_HEAD_KEY)?;
+        let zkp = self.get(&self.head_db, Self::ZKP_KEY);
+
+        let tx = self.env.read_transaction();
+        let chain_infos = tx
+            .cursor(&self.chain_info_db)
+            .into_iter_start::<Blake2bHash, Vec<u8>>()
+            .filter_map(|(_, bytes)| StoredChainInfo::deserialize_from_vec(&bytes).ok())
+            .map(ChainInfo::from)
+            .collect();
+        let elections = tx
+            .cursor(&self.election_db)
+            .into_iter_start::<Vec<u8>, Vec<u8>>()
+            .filter_map(|(_, bytes)| MacroHeader::deserialize_from_vec(&bytes).ok())
+            .collect();
+
+        Some(StoredLightChain {
+            election_head,
+            chain_infos,
+            elections,
+            zkp,
+        })
+    }
+
+    fn write(&self, update: &LightChainUpdate) {
+        let mut tx = self.env.write_transaction();
+
+        if update.clear_chain_infos {
+            tx.clear_database(&self.chain_info_db);
+        }
+        for chain_info in &update.chain_infos {
+            tx.put(
+                &self.chain_info_db,
+                &chain_info.head.hash(),
+                &StoredChainInfo::from(chain_info).serialize_to_vec(),
+            );
+        }
+        for header in &update.elections {
+            let epoch_number = Policy::epoch_at(header.block_number);
+            tx.put(
+                &self.election_db,
+                &epoch_number.to_be_bytes()[..],
+                &header.serialize_to_vec(),
+            );
+        }
+        if let Some(election_head) = &update.election_head {
+            tx.put(
+                &self.head_db,
+                Self::ELECTION_HEAD_KEY.as_bytes(),
+                &election_head.serialize_to_vec(),
+            );
+        }
+        if let Some(zkp) = &update.zkp {
+            tx.put(
+                &self.head_db,
+                Self::ZKP_KEY.as_bytes(),
+                &zkp.serialize_to_vec(),
+            );
+        }
+
+        tx.commit();
+    }
+}
@@ -5,7 +5,7 @@ use nimiq_blockchain_interface::{
 use nimiq_zkp::{verify::verify, NanoProof, ZKP_VERIFYING_KEY};
 use parking_lot::RwLockUpgradableReadGuard;
 
-use crate::blockchain::LightBlockchain;
+use crate::{blockchain::LightBlockchain, storage::StoredZKP};
 
 /// Implements methods to sync a light node.
 impl LightBlockchain {
@@ -53,7 +53,7 @@ impl LightBlockchain {
             let verify_result = verify(
                 genesis_header_hash,
                 final_header_hash,
-                proof,
+                proof.clone(),
                 &ZKP_VERIFYING_KEY,
             );
 
@@ -86,10 +86,19 @@ impl LightBlockchain {
 
         this.macro_head = block.clone().unwrap_macro();
 
-        this.election_head = block.clone().unwrap_macro();
+        this.set_election_head(block.clone().unwrap_macro());
 
         this.current_validators = block.validators();
 
+        // Keep the proof, so that we can provide it after a restart.
+        this.set_latest_zkp(StoredZKP {
+            block: block.clone().unwrap_macro(),
+            proof,
+        });
+
+        // Persist all changes at once.
+        this.chain_store.commit();
+
         // We shouldn't log errors if there are no listeners.
         _ = this
             .notifier
@@ -154,7 +163,7 @@ impl LightBlockchain {
 
         // If it's an election block, you have more steps.
         if block.is_election() {
-            this.election_head = block.unwrap_macro_ref().clone();
+            this.set_election_head(block.unwrap_macro_ref().clone());
 
             this.current_validators = block.validators();
 
@@ -170,6 +179,9 @@ impl LightBlockchain {
             _ = this.notifier.send(BlockchainEvent::Finalized(block_hash));
         }
 
+        // Persist all changes at once.
+        this.chain_store.commit();
+
         Ok(PushResult::Extended)
     }
 }
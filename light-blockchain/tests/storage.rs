@@ -0,0 +1,120 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use nimiq_block::Block;
+use nimiq_blockchain_interface::{AbstractBlockchain, PushResult};
+use nimiq_database::volatile::VolatileDatabase;
+use nimiq_genesis::NetworkId;
+use nimiq_light_blockchain::{
+    DBLightChainStorage, LightBlockchain, LightChainStorage, LightChainUpdate, StoredLightChain,
+};
+use nimiq_primitives::policy::Policy;
+use nimiq_test_log::test;
+use nimiq_test_utils::block_production::TemporaryBlockProducer;
+use parking_lot::RwLock;
+
+/// Counts the writes to the storage it wraps.
+struct CountingStorage {
+    storage: DBLightChainStorage,
+    num_writes: AtomicUsize,
+}
+
+impl LightChainStorage for CountingStorage {
+    fn load(&self) -> Option<StoredLightChain> {
+        self.storage.load()
+    }
+
+    fn write(&self, update: &LightChainUpdate) {
+        self.num_writes.fetch_add(1, Ordering::SeqCst);
+        self.storage.write(update);
+    }
+}
+
+fn remove_micro_body(block: Block) -> Block {
+    match block {
+        Block::Macro(macro_block) => Block::Macro(macro_block),
+        Block::Micro(mut micro_block) => {
+            micro_block.body = None;
+            Block::Micro(micro_block)
+        }
+    }
+}
+
+#[test]
+fn it_resumes_from_storage() {
+    let env = VolatileDatabase::new(20).unwrap();
+    let storage: Arc<dyn LightChainStorage> = Arc::new(DBLightChainStorage::new(env.clone()));
+
+    let temp_producer = TemporaryBlockProducer::new();
+    let light_blockchain = Arc::new(RwLock::new(LightBlockchain::with_storage(
+        NetworkId::UnitAlbatross,
+        Arc::clone(&storage),
+    )));
+
+    // Progress the chain past the first election block and into the next batch.
+    for _ in 0..Policy::blocks_per_epoch() + 2 {
+        let block = temp_producer.next_block(vec![], false);
+        assert_eq!(
+            LightBlockchain::push(light_blockchain.upgradable_read(), remove_micro_body(block)),
+            Ok(PushResult::Extended)
+        );
+    }
+
+    let light_blockchain = light_blockchain.read();
+    let resumed = LightBlockchain::with_storage(
+        NetworkId::UnitAlbatross,
+        Arc::new(DBLightChainStorage::new(env)),
+    );
+
+    assert_eq!(resumed.head(), light_blockchain.head());
+    assert_eq!(resumed.macro_head(), light_blockchain.macro_head());
+    assert_eq!(resumed.election_head(), light_blockchain.election_head());
+    assert_eq!(
+        resumed.current_validators(),
+        light_blockchain.current_validators()
+    );
+    let epoch_number = Policy::epoch_at(light_blockchain.election_head().block_number());
+    assert!(resumed.chain_store.get_election(epoch_number).is_some());
+    assert_eq!(
+        resumed.chain_store.get_election(epoch_number),
+        light_blockchain.chain_store.get_election(epoch_number)
+    );
+
+    // The chain infos of the current batch are restored.
+    let head_number = light_blockchain.block_number();
+    assert_eq!(
+        resumed.get_block_at(head_number - 1, false).unwrap(),
+        light_blockchain
+            .get_block_at(head_number - 1, false)
+            .unwrap()
+    );
+}
+
+#[test]
+fn it_writes_each_block_at_once() {
+    let storage = Arc::new(CountingStorage {
+        storage: DBLightChainStorage::new(VolatileDatabase::new(20).unwrap()),
+        num_writes: AtomicUsize::new(0),
+    });
+
+    let temp_producer = TemporaryBlockProducer::new();
+    let light_blockchain = Arc::new(RwLock::new(LightBlockchain::with_storage(
+        NetworkId::UnitAlbatross,
+        Arc::clone(&storage) as Arc<dyn LightChainStorage>,
+    )));
+    // The genesis state is written at once.
+    assert_eq!(storage.num_writes.load(Ordering::SeqCst), 1);
+
+    // Micro blocks, as well as macro blocks clearing the batch and updating the election head,
+    // are written in a single write each.
+    for i in 0..Policy::blocks_per_epoch() {
+        let block = temp_producer.next_block(vec![], false);
+        assert_eq!(
+            LightBlockchain::push(light_blockchain.upgradable_read(), remove_micro_body(block)),
+            Ok(PushResult::Extended)
+        );
+        assert_eq!(storage.num_writes.load(Ordering::SeqCst), i as usize + 2);
+    }
+}
@@ -14,6 +14,10 @@ pub struct MempoolConfig {
     pub filter_rules: MempoolRules,
     /// Mempool filter limit or size
     pub filter_limit: usize,
+    /// Whether the mempool content is persisted in an on-disk journal across restarts
+    pub journal: bool,
+    /// Admission limits for transactions received from the network
+    pub limits: MempoolLimits,
 }
 
 impl Default for MempoolConfig {
@@ -23,6 +27,32 @@ impl Default for MempoolConfig {
             control_size_limit: Mempool::DEFAULT_CONTROL_SIZE_LIMIT,
             filter_rules: MempoolRules::default(),
             filter_limit: MempoolFilter::DEFAULT_BLACKLIST_SIZE,
+            journal: false,
+            limits: MempoolLimits::default(),
+        }
+    }
+}
+
+/// Struct defining the admission limits for transactions received from the network
+#[derive(Debug, Clone)]
+pub struct MempoolLimits {
+    /// Maximum number of pending transactions per sender address
+    pub sender_txns_limit: usize,
+    /// Maximum total size of pending transactions per sender address (bytes)
+    pub sender_size_limit: usize,
+    /// Maximum number of pending transactions per propagation source peer
+    pub peer_txns_limit: usize,
+    /// Maximum total size of pending transactions per propagation source peer (bytes)
+    pub peer_size_limit: usize,
+}
+
+impl Default for MempoolLimits {
+    fn default() -> MempoolLimits {
+        MempoolLimits {
+            sender_txns_limit: Mempool::DEFAULT_SENDER_TXNS_LIMIT,
+            sender_size_limit: Mempool::DEFAULT_SENDER_SIZE_LIMIT,
+            peer_txns_limit: Mempool::DEFAULT_PEER_TXNS_LIMIT,
+            peer_size_limit: Mempool::DEFAULT_PEER_SIZE_LIMIT,
         }
     }
 }
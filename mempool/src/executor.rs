@@ -1,5 +1,7 @@
 use std::{
+    collections::HashMap,
     future::Future,
+    hash::Hash as StdHash,
     marker::PhantomData,
     pin::Pin,
     sync::{
@@ -11,12 +13,15 @@ use std::{
 
 use futures::{ready, stream::BoxStream, StreamExt};
 use nimiq_blockchain::Blockchain;
-use nimiq_network_interface::network::{MsgAcceptance, Network, Topic};
+use nimiq_hash::{Blake2bHash, Hash};
+use nimiq_network_interface::network::{MsgAcceptance, Network, PubsubId, Topic};
 use nimiq_primitives::networks::NetworkId;
+use nimiq_serde::Serialize;
 use nimiq_transaction::Transaction;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 
 use crate::{
+    config::MempoolLimits,
     filter::MempoolFilter,
     mempool_state::MempoolState,
     mempool_transactions::TxPriority,
@@ -35,6 +40,12 @@ pub(crate) struct MempoolExecutor<N: Network, T: Topic + Unpin + Sync> {
     // Mempool filter
     filter: Arc<RwLock<MempoolFilter>>,
 
+    // Admission limits for transactions received from the network
+    limits: MempoolLimits,
+
+    // Pending transactions per propagation source peer, used to enforce the per-peer limits
+    txns_by_peer: Arc<Mutex<PeerPendingTxns<N::PeerId>>>,
+
     // Ongoing verification tasks counter
     verification_tasks: Arc<AtomicU32>,
 
@@ -56,14 +67,31 @@ impl<N: Network, T: Topic + Unpin + Sync> MempoolExecutor<N, T> {
         blockchain: Arc<RwLock<Blockchain>>,
         state: Arc<RwLock<MempoolState>>,
         filter: Arc<RwLock<MempoolFilter>>,
+        limits: MempoolLimits,
         network: Arc<N>,
         txn_stream: BoxStream<'static, (Transaction, <N as Network>::PubsubId)>,
         verification_tasks: Arc<AtomicU32>,
     ) -> Self {
+        // Release the transactions accounted to peers when they leave the mempool. The listener
+        // is dropped together with the executor.
+        let txns_by_peer = Arc::new(Mutex::new(PeerPendingTxns::new()));
+        let listener_txns_by_peer = Arc::downgrade(&txns_by_peer);
+        state.write().add_removal_listener(Box::new(move |tx_hash| {
+            match listener_txns_by_peer.upgrade() {
+                Some(txns_by_peer) => {
+                    txns_by_peer.lock().note_removed(tx_hash);
+                    true
+                }
+                None => false,
+            }
+        }));
+
         Self {
             blockchain: Arc::clone(&blockchain),
             state,
             filter,
+            limits,
+            txns_by_peer,
             network,
             network_id: blockchain.read().network_id,
             verification_tasks,
@@ -73,6 +101,112 @@ impl<N: Network, T: Topic + Unpin + Sync> MempoolExecutor<N, T> {
     }
 }
 
+// Number and total size of the pending transactions propagated by a single peer.
+#[derive(Default)]
+struct PeerCounters {
+    num_txns: usize,
+    size: usize,
+}
+
+// Pending transactions per propagation source peer. Transactions are accounted to a peer while
+// they are being verified and, once added, until they leave the mempool.
+struct PeerPendingTxns<P> {
+    // The counters of every peer with pending transactions.
+    counters: HashMap<P, PeerCounters>,
+
+    // The peer and size of every transaction that was added to the mempool.
+    added: HashMap<Blake2bHash, (P, usize)>,
+}
+
+impl<P: Clone + Eq + StdHash> PeerPendingTxns<P> {
+    fn new() -> Self {
+        Self {
+            counters: HashMap::new(),
+            added: HashMap::new(),
+        }
+    }
+
+    // Accounts a transaction of the given size to the peer if the peer stays within the limits.
+    fn reserve(&mut self, peer_id: P, size: usize, limits: &MempoolLimits) -> bool {
+        let counters = self.counters.entry(peer_id.clone()).or_default();
+        if counters.num_txns < limits.peer_txns_limit
+            && counters.size + size <= limits.peer_size_limit
+        {
+            counters.num_txns += 1;
+            counters.size += size;
+            return true;
+        }
+
+        if counters.num_txns == 0 {
+            self.counters.remove(&peer_id);
+        }
+        false
+    }
+
+    // Releases a transaction of the given size accounted to the peer.
+    fn release(&mut self, peer_id: &P, size: usize) {
+        if let Some(counters) = self.counters.get_mut(peer_id) {
+            counters.num_txns = counters.num_txns.saturating_sub(1);
+            counters.size = counters.size.saturating_sub(size);
+            if counters.num_txns == 0 {
+                self.counters.remove(peer_id);
+            }
+        }
+    }
+
+    // Keeps a verified transaction accounted to the peer until it leaves the mempool.
+    fn note_added(&mut self, tx_hash: Blake2bHash, peer_id: P, size: usize) {
+        if let Some((previous_peer_id, previous_size)) = self.added.insert(tx_hash, (peer_id, size))
+        {
+            self.release(&previous_peer_id, previous_size);
+        }
+    }
+
+    // Releases a transaction that left the mempool.
+    fn note_removed(&mut self, tx_hash: &Blake2bHash) {
+        if let Some((peer_id, size)) = self.added.remove(tx_hash) {
+            self.release(&peer_id, size);
+        }
+    }
+}
+
+impl<N: Network, T: Topic + Unpin + Sync> MempoolExecutor<N, T> {
+    /// Checks whether the given peer stays within the per-peer limits of pending transactions and
+    /// bytes if `tx` is added to the mempool. If so, `tx` is accounted to the peer while it is
+    /// being verified.
+    fn reserve_peer_limits(&self, peer_id: N::PeerId, tx: &Transaction) -> bool {
+        self.txns_by_peer
+            .lock()
+            .reserve(peer_id, tx.serialized_size(), &self.limits)
+    }
+
+    /// Updates the transactions accounted to the given peer once the verification of `tx` is done.
+    fn release_peer_limits(
+        txns_by_peer: &Mutex<PeerPendingTxns<N::PeerId>>,
+        state: &RwLock<MempoolState>,
+        peer_id: N::PeerId,
+        tx: &Transaction,
+        added: bool,
+    ) {
+        let tx_size = tx.serialized_size();
+        if !added {
+            txns_by_peer.lock().release(&peer_id, tx_size);
+            return;
+        }
+
+        let tx_hash: Blake2bHash = tx.hash();
+        txns_by_peer
+            .lock()
+            .note_added(tx_hash.clone(), peer_id, tx_size);
+
+        // The transaction might have left the mempool again before it was noted as added, in
+        // which case the removal listener did not release it.
+        if !state.read().contains(&tx_hash) {
+            txns_by_peer.lock().note_removed(&tx_hash);
+        }
+    }
+}
+
 impl<N: Network, T: Topic + Unpin + Sync> Future for MempoolExecutor<N, T> {
     type Output = ();
 
@@ -85,9 +219,20 @@ impl<N: Network, T: Topic + Unpin + Sync> Future for MempoolExecutor<N, T> {
                 continue;
             }
 
+            // Reject the message if the peer that propagated it exceeds its limits
+            let peer_id = pubsub_id.propagation_source();
+            if !self.reserve_peer_limits(peer_id, &tx) {
+                log::debug!(%peer_id, "Peer exceeds mempool limits");
+                self.network
+                    .validate_message::<T>(pubsub_id, MsgAcceptance::Reject);
+                continue;
+            }
+
             let blockchain = Arc::clone(&self.blockchain);
             let mempool_state = Arc::clone(&self.state);
             let filter = Arc::clone(&self.filter);
+            let limits = self.limits.clone();
+            let txns_by_peer = Arc::clone(&self.txns_by_peer);
             let tasks_count = Arc::clone(&self.verification_tasks);
             let network = Arc::clone(&self.network);
             let network_id = self.network_id;
@@ -103,9 +248,18 @@ impl<N: Network, T: Topic + Unpin + Sync> Future for MempoolExecutor<N, T> {
                     &mempool_state,
                     filter,
                     TxPriority::Medium,
+                    Some(&limits),
                 )
                 .await;
 
+                Self::release_peer_limits(
+                    &txns_by_peer,
+                    &mempool_state,
+                    peer_id,
+                    &tx,
+                    verify_tx_ret.is_ok(),
+                );
+
                 let acceptance = match verify_tx_ret {
                     Ok(_) => MsgAcceptance::Accept,
                     // Reject the message if signature verification fails or transaction is invalid
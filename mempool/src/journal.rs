@@ -0,0 +1,71 @@
+use nimiq_database::{
+    traits::{Database, ReadCursor, ReadTransaction, WriteTransaction},
+    DatabaseProxy, TableProxy,
+};
+use nimiq_hash::{Blake2bHash, Hash};
+use nimiq_transaction::Transaction;
+
+/// On-disk journal of the mempool content.
+///
+/// The journal holds a snapshot of the regular and control transactions of the mempool such that
+/// they can be restored after a restart of the node.
+#[derive(Debug)]
+pub struct MempoolJournal {
+    /// Environment for the DB creation and transaction handling.
+    env: DatabaseProxy,
+    /// A database of the journaled regular transactions, indexed by their hash.
+    regular_txns_db: TableProxy,
+    /// A database of the journaled control transactions, indexed by their hash.
+    control_txns_db: TableProxy,
+}
+
+impl MempoolJournal {
+    const REGULAR_TXNS_DB_NAME: &'static str = "MempoolRegularTransactions";
+    const CONTROL_TXNS_DB_NAME: &'static str = "MempoolControlTransactions";
+
+    /// Creates a new mempool journal using the given database environment
+    pub fn new(env: DatabaseProxy) -> Self {
+        let regular_txns_db = env.open_table(Self::REGULAR_TXNS_DB_NAME.to_string());
+        let control_txns_db = env.open_table(Self::CONTROL_TXNS_DB_NAME.to_string());
+
+        Self {
+            env,
+            regular_txns_db,
+            control_txns_db,
+        }
+    }
+
+    /// Replaces the content of the journal with the given regular and control transactions.
+    pub fn store(&self, regular_txns: &[Transaction], control_txns: &[Transaction]) {
+        let mut txn = self.env.write_transaction();
+        txn.clear_database(&self.regular_txns_db);
+        txn.clear_database(&self.control_txns_db);
+
+        for tx in regular_txns {
+            txn.put_reserve(&self.regular_txns_db, &tx.hash::<Blake2bHash>(), tx);
+        }
+        for tx in control_txns {
+            txn.put_reserve(&self.control_txns_db, &tx.hash::<Blake2bHash>(), tx);
+        }
+
+        txn.commit();
+    }
+
+    /// Returns the journaled regular and control transactions.
+    pub fn load(&self) -> (Vec<Transaction>, Vec<Transaction>) {
+        let txn = self.env.read_transaction();
+
+        let regular_txns = txn
+            .cursor(&self.regular_txns_db)
+            .into_iter_start::<Blake2bHash, Transaction>()
+            .map(|(_, tx)| tx)
+            .collect();
+        let control_txns = txn
+            .cursor(&self.control_txns_db)
+            .into_iter_start::<Blake2bHash, Transaction>()
+            .map(|(_, tx)| tx)
+            .collect();
+
+        (regular_txns, control_txns)
+    }
+}
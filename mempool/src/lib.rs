@@ -17,8 +17,14 @@ pub mod executor;
 
 /// Mempool filter module
 pub mod filter;
+/// Mempool journal module
+pub mod journal;
 /// Main mempool module
 pub mod mempool;
+/// Mempool events module
+pub mod mempool_events;
+/// Mempool history module
+pub mod mempool_history;
 /// Mempool metrics
 #[cfg(feature = "metrics")]
 mod mempool_metrics;
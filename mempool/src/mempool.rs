@@ -1,6 +1,9 @@
 use std::{
     collections::HashSet,
-    sync::{atomic::AtomicU32, Arc},
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering as AtomicOrdering},
+        Arc,
+    },
 };
 
 use futures::{
@@ -9,9 +12,10 @@ use futures::{
     stream::BoxStream,
 };
 use nimiq_account::ReservedBalance;
-use nimiq_block::Block;
+use nimiq_block::{Block, MicroBlock};
 use nimiq_blockchain::{Blockchain, TransactionVerificationCache};
 use nimiq_blockchain_interface::AbstractBlockchain;
+use nimiq_database::DatabaseProxy;
 use nimiq_hash::{Blake2bHash, Hash};
 use nimiq_keys::Address;
 use nimiq_network_interface::network::{Network, Topic};
@@ -19,13 +23,17 @@ use nimiq_serde::Serialize;
 use nimiq_transaction::{ControlTransactionTopic, Transaction, TransactionTopic};
 use parking_lot::RwLock;
 use tokio_metrics::TaskMonitor;
+use tokio_stream::wrappers::BroadcastStream;
 
 #[cfg(feature = "metrics")]
 use crate::mempool_metrics::MempoolMetrics;
 use crate::{
-    config::MempoolConfig,
+    config::{MempoolConfig, MempoolLimits},
     executor::MempoolExecutor,
     filter::{MempoolFilter, MempoolRules},
+    journal::MempoolJournal,
+    mempool_events::MempoolEvent,
+    mempool_history::{BlockFees, MempoolHistoryEntry},
     mempool_state::{EvictionReason, MempoolState},
     mempool_transactions::{MempoolTransactions, TxPriority},
     verify::{verify_tx, VerifyErr},
@@ -42,6 +50,9 @@ pub struct Mempool {
     /// Mempool filter
     pub(crate) filter: Arc<RwLock<MempoolFilter>>,
 
+    /// Admission limits for transactions received from the network
+    pub(crate) limits: MempoolLimits,
+
     /// Mempool executor handle used to stop the executor
     pub(crate) executor_handle: Mutex<Option<AbortHandle>>,
 
@@ -50,6 +61,12 @@ pub struct Mempool {
 
     /// Total number of ongoing verification tasks
     verification_tasks: Arc<AtomicU32>,
+
+    /// Optional on-disk journal used to persist the mempool content across restarts
+    journal: Option<MempoolJournal>,
+
+    /// Whether the content of the journal was already restored into the mempool
+    journal_restored: AtomicBool,
 }
 
 impl Mempool {
@@ -59,12 +76,57 @@ impl Mempool {
     /// Default total size limit of control transactions in the mempool (bytes)
     pub const DEFAULT_CONTROL_SIZE_LIMIT: usize = 6_000_000;
 
+    /// Default maximum number of pending transactions per sender address
+    pub const DEFAULT_SENDER_TXNS_LIMIT: usize = 1_000;
+
+    /// Default maximum total size of pending transactions per sender address (bytes)
+    pub const DEFAULT_SENDER_SIZE_LIMIT: usize = 1_200_000;
+
+    /// Default maximum number of pending transactions per propagation source peer
+    pub const DEFAULT_PEER_TXNS_LIMIT: usize = 25_000;
+
+    /// Default maximum total size of pending transactions per propagation source peer (bytes)
+    pub const DEFAULT_PEER_SIZE_LIMIT: usize = 6_000_000;
+
+    /// Number of blocks for which mempool statistics are kept
+    pub const HISTORY_SIZE: usize = 1_000;
+
+    /// Number of most recent blocks that are considered when estimating fees
+    pub const FEE_ESTIMATION_HISTORY_BLOCKS: u32 = 120;
+
+    /// Ratio of the available micro block body size above which a block is considered full
+    /// for fee estimation purposes
+    pub const FEE_ESTIMATION_FULL_BLOCK_RATIO: f64 = 0.9;
+
+    /// Probability with which a transaction paying the estimated fee should be included in time
+    pub const FEE_ESTIMATION_CONFIDENCE: f64 = 0.95;
+
     /// Creates a new mempool
     pub fn new(blockchain: Arc<RwLock<Blockchain>>, config: MempoolConfig) -> Self {
-        let state = Arc::new(RwLock::new(MempoolState::new(
+        let mut mempool_state = MempoolState::new(
             config.size_limit,
             config.control_size_limit,
-        )));
+            Self::HISTORY_SIZE,
+            Self::FEE_ESTIMATION_HISTORY_BLOCKS,
+        );
+
+        // Load the fee statistics of the recent blocks once, they are kept up to date as blocks
+        // are applied.
+        {
+            let blockchain = blockchain.read();
+            let head_block_number = blockchain.block_number();
+            let first_block_number = head_block_number
+                .saturating_sub(Self::FEE_ESTIMATION_HISTORY_BLOCKS)
+                .max(1);
+            for block_number in first_block_number..=head_block_number {
+                if let Ok(block) = blockchain.get_block_at(block_number, true, None) {
+                    if let Some(block_fees) = Self::block_fees(&block) {
+                        mempool_state.history.note_block_fees(block_fees);
+                    }
+                }
+            }
+        }
+        let state = Arc::new(RwLock::new(mempool_state));
 
         Self {
             blockchain: Arc::clone(&blockchain),
@@ -73,12 +135,28 @@ impl Mempool {
                 config.filter_rules,
                 config.filter_limit,
             ))),
+            limits: config.limits,
             executor_handle: Mutex::new(None),
             control_executor_handle: Mutex::new(None),
             verification_tasks: Arc::new(AtomicU32::new(0)),
+            journal: None,
+            journal_restored: AtomicBool::new(false),
         }
     }
 
+    /// Creates a new mempool that persists its content in an on-disk journal in the given database.
+    ///
+    /// The journal is only written after its previous content was restored with `restore_journal`.
+    pub fn new_with_journal(
+        blockchain: Arc<RwLock<Blockchain>>,
+        config: MempoolConfig,
+        env: DatabaseProxy,
+    ) -> Self {
+        let mut mempool = Self::new(blockchain, config);
+        mempool.journal = Some(MempoolJournal::new(env));
+        mempool
+    }
+
     /// Start the `MempoolExecutor` for `Topic` `T` and instrument a monitor for the task if given.
     /// An `AbortHandle` will be stored in `handle`.
     fn start_executor<N: Network, T: Topic + Unpin + Send + Sync + 'static>(
@@ -98,6 +176,7 @@ impl Mempool {
             Arc::clone(&self.blockchain),
             Arc::clone(&self.state),
             Arc::clone(&self.filter),
+            self.limits.clone(),
             Arc::clone(&network),
             txn_stream,
             Arc::clone(&self.verification_tasks),
@@ -323,6 +402,13 @@ impl Mempool {
         // First remove the transactions that are no longer valid due to age.
         self.prune_expired_transactions(&blockchain, &mut mempool_state);
 
+        // Update the fee statistics used for fee estimation.
+        for (_, block) in reverted_blocks {
+            mempool_state
+                .history
+                .revert_block_fees(block.block_number());
+        }
+
         // Now iterate over the transactions in the adopted blocks:
         //  if transaction was known:
         //    remove it from the mempool
@@ -333,6 +419,10 @@ impl Mempool {
         //      we don't care, since it won't affect our senders balance
         let mut affected_senders = HashSet::new();
         for (_, block) in adopted_blocks {
+            if let Some(block_fees) = Self::block_fees(block) {
+                mempool_state.history.note_block_fees(block_fees);
+            }
+
             if let Some(transactions) = block.transactions() {
                 for tx in transactions {
                     let tx = tx.get_raw_transaction();
@@ -394,6 +484,8 @@ impl Mempool {
                 }
             }
         }
+
+        mempool_state.record_history(blockchain.block_number());
     }
 
     /// Get the mempool into a consistent and up-to-date state.
@@ -452,10 +544,7 @@ impl Mempool {
                     // We don't have the sender account so we can't do any balance tracking.
                     // Remove all transactions from this sender.
                     for hash in &sender_state.txns {
-                        mempool_state
-                            .regular_transactions
-                            .delete(hash)
-                            .or_else(|| mempool_state.control_transactions.delete(hash));
+                        mempool_state.delete(hash);
                     }
                     continue;
                 }
@@ -614,6 +703,11 @@ impl Mempool {
     }
 
     /// Adds a transaction to the Mempool.
+    ///
+    /// If pending transactions with the same sender, recipient, value and validity start height
+    /// exist and the sender can't afford them together with the transaction, the transaction
+    /// replaces them if it pays a strictly higher fee than each of them and is rejected otherwise.
+    /// If the sender can afford all of them, all are kept.
     pub async fn add_transaction(
         &self,
         transaction: Transaction,
@@ -630,10 +724,84 @@ impl Mempool {
             &mempool_state,
             filter,
             tx_priority.unwrap_or(TxPriority::Medium),
+            None,
         )
         .await
     }
 
+    /// Stores a snapshot of the regular and control transactions currently in the mempool in the
+    /// journal, replacing the previous snapshot.
+    ///
+    /// This does nothing if the mempool has no journal or the journal was not restored yet.
+    pub fn save_journal(&self) {
+        let journal = match &self.journal {
+            Some(journal) => journal,
+            None => return,
+        };
+
+        if !self.journal_restored.load(AtomicOrdering::Acquire) {
+            return;
+        }
+
+        let (regular_txns, control_txns) = {
+            let state = self.state.read();
+            let regular_txns: Vec<Transaction> = state
+                .regular_transactions
+                .transactions
+                .values()
+                .cloned()
+                .collect();
+            let control_txns: Vec<Transaction> = state
+                .control_transactions
+                .transactions
+                .values()
+                .cloned()
+                .collect();
+            (regular_txns, control_txns)
+        };
+
+        journal.store(&regular_txns, &control_txns);
+
+        debug!(
+            regular_txs = regular_txns.len(),
+            control_txs = control_txns.len(),
+            "Stored mempool journal"
+        );
+    }
+
+    /// Re-adds the transactions stored in the journal to the mempool.
+    ///
+    /// Every transaction goes through the regular verification, such that transactions that expired,
+    /// were already included or became invalid in the meantime are dropped. This should be called
+    /// once the blockchain is up to date and only has an effect the first time it is called.
+    pub async fn restore_journal(&self) {
+        let journal = match &self.journal {
+            Some(journal) => journal,
+            None => return,
+        };
+
+        if self.journal_restored.swap(true, AtomicOrdering::AcqRel) {
+            return;
+        }
+
+        let (regular_txns, control_txns) = journal.load();
+        let num_txns = regular_txns.len() + control_txns.len();
+
+        let mut num_restored = 0;
+        for tx in control_txns.into_iter().chain(regular_txns) {
+            match self.add_transaction(tx, None).await {
+                Ok(_) => num_restored += 1,
+                Err(error) => trace!(%error, "Dropped transaction from mempool journal"),
+            }
+        }
+
+        info!(
+            restored_txs = num_restored,
+            dropped_txs = num_txns - num_restored,
+            "Restored mempool from journal"
+        );
+    }
+
     /// Checks whether a transaction has been filtered
     pub fn is_filtered(&self, hash: &Blake2bHash) -> bool {
         self.filter.read().blacklisted(hash)
@@ -644,6 +812,117 @@ impl Mempool {
         self.filter.read().rules.clone()
     }
 
+    /// Estimates the fee per byte a transaction needs to pay to be included within the next
+    /// `target_blocks` micro blocks.
+    ///
+    /// The estimate is the maximum of:
+    /// - the minimum fee per byte accepted by the mempool filter rules,
+    /// - the fee per byte of the cheapest transaction that would not fit into `target_blocks`
+    ///   blocks if the current mempool content was used to produce them (see
+    ///   `get_transactions_for_block`),
+    /// - the fee per byte needed to make it into recently produced micro blocks that were (almost)
+    ///   full, looking back at most `FEE_ESTIMATION_HISTORY_BLOCKS` blocks.
+    pub fn estimate_fee_per_byte(&self, target_blocks: u32) -> f64 {
+        let target_blocks = target_blocks.max(1);
+        let min_fee_per_byte = self.filter.read().rules.tx_fee_per_byte;
+
+        let state = self.state.read();
+
+        // Control transactions are put into blocks before regular transactions, so they reduce
+        // the space available to regular transactions.
+        let control_size: usize = state
+            .control_transactions
+            .transactions
+            .values()
+            .map(|tx| 1 + tx.serialized_size())
+            .sum();
+        let max_bytes = (target_blocks as usize * MicroBlock::get_available_bytes(0))
+            .saturating_sub(control_size);
+
+        let mempool_fee_per_byte =
+            Self::estimate_fee_from_transactions(&state.regular_transactions, max_bytes);
+        let history_fee_per_byte = Self::estimate_fee_from_history(&state, target_blocks);
+
+        min_fee_per_byte
+            .max(mempool_fee_per_byte)
+            .max(history_fee_per_byte)
+    }
+
+    /// Returns the fee per byte of the first transaction (in block building order) that
+    /// doesn't fit into `max_bytes` anymore, or zero if all transactions fit.
+    fn estimate_fee_from_transactions(transactions: &MempoolTransactions, max_bytes: usize) -> f64 {
+        let mut size = 0_usize;
+        for tx in transactions.iter_best() {
+            // We need to account for one extra byte per transaction to encode its final execution status
+            size += 1 + tx.serialized_size();
+            if size > max_bytes {
+                return tx.fee_per_byte();
+            }
+        }
+        0.0
+    }
+
+    /// Computes the fee statistics of a block for fee estimation, or None if the block is not a
+    /// micro block with transactions.
+    ///
+    /// The lowest fee per byte of its transactions is taken if the block was full (at least
+    /// `FEE_ESTIMATION_FULL_BLOCK_RATIO` of the available bytes used), otherwise any fee would
+    /// have been sufficient.
+    fn block_fees(block: &Block) -> Option<BlockFees> {
+        if !block.is_micro() || block.is_skip() {
+            return None;
+        }
+        let transactions = match block.transactions() {
+            Some(transactions) if !transactions.is_empty() => transactions,
+            _ => return None,
+        };
+
+        let size: usize = transactions
+            .iter()
+            .map(|tx| 1 + tx.get_raw_transaction().serialized_size())
+            .sum();
+        let available_bytes = MicroBlock::get_available_bytes(0);
+        let fee_per_byte =
+            if (size as f64) < available_bytes as f64 * Self::FEE_ESTIMATION_FULL_BLOCK_RATIO {
+                0.0
+            } else {
+                transactions
+                    .iter()
+                    .map(|tx| tx.get_raw_transaction().fee_per_byte())
+                    .fold(f64::INFINITY, f64::min)
+            };
+
+        Some(BlockFees {
+            block_number: block.block_number(),
+            fee_per_byte,
+        })
+    }
+
+    /// Estimates the fee per byte from the fee statistics of the recent micro blocks, which are
+    /// recorded as blocks are applied (see `block_fees`). Out of these values, the estimate is
+    /// chosen such that a transaction is included within `target_blocks` blocks with a
+    /// probability of `FEE_ESTIMATION_CONFIDENCE`.
+    fn estimate_fee_from_history(state: &MempoolState, target_blocks: u32) -> f64 {
+        let mut block_fees: Vec<f64> = state
+            .history
+            .block_fees()
+            .map(|fees| fees.fee_per_byte)
+            .collect();
+
+        if block_fees.is_empty() {
+            return 0.0;
+        }
+        block_fees.sort_by(|a, b| a.partial_cmp(b).expect("fees can't be NaN"));
+
+        // A transaction paying the q-quantile of the observed fees is included in a single block
+        // with probability q, and thus within n blocks with probability 1 - (1 - q)^n.
+        let quantile =
+            1.0 - (1.0 - Self::FEE_ESTIMATION_CONFIDENCE).powf(1.0 / target_blocks as f64);
+        let index =
+            ((quantile * block_fees.len() as f64).ceil() as usize).clamp(1, block_fees.len()) - 1;
+        block_fees[index]
+    }
+
     /// Checks if a transactions is in the mempool, by its hash.
     pub fn contains_transaction_by_hash(&self, hash: &Blake2bHash) -> bool {
         self.state.read().contains(hash)
@@ -687,6 +966,18 @@ impl Mempool {
             .collect()
     }
 
+    /// Returns the mempool statistics of the most recent blocks, from oldest to newest.
+    pub fn get_history(&self) -> Vec<MempoolHistoryEntry> {
+        self.state.read().history.entries()
+    }
+
+    /// Returns a stream of events about transactions entering or leaving the mempool.
+    /// If the subscriber falls too far behind, the stream yields an error with the number of
+    /// events it missed.
+    pub fn subscribe_events(&self) -> BroadcastStream<MempoolEvent> {
+        BroadcastStream::new(self.state.read().notifier.subscribe())
+    }
+
     /// Returns the current metrics
     #[cfg(feature = "metrics")]
     pub fn metrics(&self) -> Arc<MempoolMetrics> {
@@ -0,0 +1,22 @@
+use nimiq_transaction::Transaction;
+
+/// An event about a transaction entering or leaving the mempool.
+#[derive(Clone, Debug)]
+pub enum MempoolEvent {
+    /// The transaction was added to the mempool.
+    Added(Transaction),
+    /// The transaction was removed from the mempool because it was included in a block.
+    Removed(Transaction),
+    /// The transaction was evicted from the mempool without being included in a block, e.g.
+    /// because it expired, became invalid, was replaced or the mempool was full.
+    Evicted(Transaction),
+}
+
+impl MempoolEvent {
+    /// Returns the transaction this event is about.
+    pub fn transaction(&self) -> &Transaction {
+        match self {
+            MempoolEvent::Added(tx) | MempoolEvent::Removed(tx) | MempoolEvent::Evicted(tx) => tx,
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::VecDeque;
+
+use crate::mempool_state::EvictionReason;
+
+/// Number of transactions that were removed from (or not admitted to) the mempool for reasons
+/// other than being included in a block
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MempoolEvictions {
+    /// Transactions that expired
+    pub expired: u32,
+    /// Transactions that were rejected by the mempool filter
+    pub filtered: u32,
+    /// Transactions that were evicted because the mempool reached its size limit
+    pub too_full: u32,
+    /// Transactions that became invalid due to a block, e.g. because of insufficient funds
+    pub invalidated: u32,
+    /// Transactions that were replaced by a transaction paying a higher fee
+    pub replaced: u32,
+}
+
+/// Mempool statistics recorded after a block was applied to the mempool
+#[derive(Clone, Debug)]
+pub struct MempoolHistoryEntry {
+    /// The block number of the blockchain head at the time of recording
+    pub block_number: u32,
+    /// The number of pending regular transactions
+    pub num_regular_transactions: usize,
+    /// The number of pending control transactions
+    pub num_control_transactions: usize,
+    /// The average fee per byte of all pending transactions
+    pub avg_fee_per_byte: f64,
+    /// The evictions since the previous entry
+    pub evictions: MempoolEvictions,
+}
+
+/// Fee statistics of an applied micro block, used for fee estimation
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct BlockFees {
+    /// The number of the block
+    pub block_number: u32,
+    /// The lowest fee per byte of the block's transactions if the block was full, zero otherwise
+    pub fee_per_byte: f64,
+}
+
+/// Rolling window of mempool statistics, one entry per block
+pub(crate) struct MempoolHistory {
+    // The recorded entries, from oldest to newest
+    entries: VecDeque<MempoolHistoryEntry>,
+
+    // Evictions since the last recorded entry
+    evictions: MempoolEvictions,
+
+    // Maximum number of entries kept
+    max_entries: usize,
+
+    // Fee statistics of the recent micro blocks, from oldest to newest
+    block_fees: VecDeque<BlockFees>,
+
+    // Number of most recent blocks for which fee statistics are kept
+    fee_window: u32,
+}
+
+impl MempoolHistory {
+    pub fn new(max_entries: usize, fee_window: u32) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(max_entries),
+            evictions: MempoolEvictions::default(),
+            max_entries,
+            block_fees: VecDeque::new(),
+            fee_window,
+        }
+    }
+
+    pub fn note_evicted(&mut self, reason: &EvictionReason) {
+        match reason {
+            EvictionReason::Expired => self.evictions.expired += 1,
+            EvictionReason::Invalid => self.evictions.invalidated += 1,
+            EvictionReason::TooFull => self.evictions.too_full += 1,
+            EvictionReason::Replaced => self.evictions.replaced += 1,
+            EvictionReason::BlockBuilding | EvictionReason::AlreadyIncluded => {}
+        }
+    }
+
+    pub fn note_filtered(&mut self) {
+        self.evictions.filtered += 1;
+    }
+
+    /// Records a new entry with the evictions accumulated since the previous one.
+    pub fn record(
+        &mut self,
+        block_number: u32,
+        num_regular_transactions: usize,
+        num_control_transactions: usize,
+        avg_fee_per_byte: f64,
+    ) {
+        self.entries.push_back(MempoolHistoryEntry {
+            block_number,
+            num_regular_transactions,
+            num_control_transactions,
+            avg_fee_per_byte,
+            evictions: std::mem::take(&mut self.evictions),
+        });
+
+        while self.entries.len() > self.max_entries {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn entries(&self) -> Vec<MempoolHistoryEntry> {
+        self.entries.iter().cloned().collect()
+    }
+
+    /// Records the fee statistics of an adopted block. Statistics of blocks at or above its
+    /// block number are replaced, since they were reverted.
+    pub fn note_block_fees(&mut self, block_fees: BlockFees) {
+        self.revert_block_fees(block_fees.block_number);
+        self.block_fees.push_back(block_fees);
+
+        let first_block_number = block_fees.block_number.saturating_sub(self.fee_window);
+        while self
+            .block_fees
+            .front()
+            .map_or(false, |fees| fees.block_number < first_block_number)
+        {
+            self.block_fees.pop_front();
+        }
+    }
+
+    /// Removes the fee statistics of the blocks at or above the given block number.
+    pub fn revert_block_fees(&mut self, block_number: u32) {
+        while self
+            .block_fees
+            .back()
+            .map_or(false, |fees| fees.block_number >= block_number)
+        {
+            self.block_fees.pop_back();
+        }
+    }
+
+    /// Returns the fee statistics of the recent micro blocks, from oldest to newest.
+    pub fn block_fees(&self) -> impl Iterator<Item = &BlockFees> {
+        self.block_fees.iter()
+    }
+}
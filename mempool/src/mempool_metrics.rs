@@ -1,6 +1,8 @@
+use std::sync::atomic::AtomicU64;
+
 use prometheus_client::{
     encoding::{EncodeLabelSet, EncodeLabelValue},
-    metrics::{counter::Counter, family::Family},
+    metrics::{counter::Counter, family::Family, gauge::Gauge},
     registry::Registry,
 };
 
@@ -9,6 +11,9 @@ use crate::mempool_state::EvictionReason;
 #[derive(Default, Clone)]
 pub struct MempoolMetrics {
     evicted_tx: Family<RemovedReasonLabel, Counter>,
+    pending_regular_tx: Gauge,
+    pending_control_tx: Gauge,
+    avg_fee_per_byte: Gauge<f64, AtomicU64>,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
@@ -22,6 +27,8 @@ enum TxRemovedReason {
     AlreadyIncludedTx,
     Invalid,
     TooFull,
+    Replaced,
+    Filtered,
 }
 
 impl MempoolMetrics {
@@ -31,6 +38,21 @@ impl MempoolMetrics {
             "Number of transactions removed from mempool",
             self.evicted_tx.clone(),
         );
+        registry.register(
+            "pending_regular_tx_count",
+            "Number of pending regular transactions at the latest block",
+            self.pending_regular_tx.clone(),
+        );
+        registry.register(
+            "pending_control_tx_count",
+            "Number of pending control transactions at the latest block",
+            self.pending_control_tx.clone(),
+        );
+        registry.register(
+            "avg_fee_per_byte",
+            "Average fee per byte of pending transactions at the latest block",
+            self.avg_fee_per_byte.clone(),
+        );
     }
 
     pub(crate) fn note_evicted(&self, reason: EvictionReason) {
@@ -39,10 +61,30 @@ impl MempoolMetrics {
             EvictionReason::AlreadyIncluded => TxRemovedReason::AlreadyIncludedTx,
             EvictionReason::Invalid => TxRemovedReason::Invalid,
             EvictionReason::TooFull => TxRemovedReason::TooFull,
+            EvictionReason::Replaced => TxRemovedReason::Replaced,
             _ => return,
         };
         self.evicted_tx
             .get_or_create(&RemovedReasonLabel { reason })
             .inc();
     }
+
+    pub(crate) fn note_filtered(&self) {
+        self.evicted_tx
+            .get_or_create(&RemovedReasonLabel {
+                reason: TxRemovedReason::Filtered,
+            })
+            .inc();
+    }
+
+    pub(crate) fn note_block(
+        &self,
+        num_regular_transactions: usize,
+        num_control_transactions: usize,
+        avg_fee_per_byte: f64,
+    ) {
+        self.pending_regular_tx.set(num_regular_transactions as i64);
+        self.pending_control_tx.set(num_control_transactions as i64);
+        self.avg_fee_per_byte.set(avg_fee_per_byte);
+    }
 }
@@ -8,14 +8,21 @@ use nimiq_hash::{Blake2bHash, Hash};
 use nimiq_keys::Address;
 use nimiq_primitives::account::AccountType;
 use nimiq_transaction::Transaction;
+use tokio::sync::broadcast::{channel as broadcast, Sender as BroadcastSender};
 
 #[cfg(feature = "metrics")]
 use crate::mempool_metrics::MempoolMetrics;
 use crate::{
+    mempool_events::MempoolEvent,
+    mempool_history::MempoolHistory,
     mempool_transactions::{MempoolTransactions, TxPriority},
     verify::VerifyErr,
 };
 
+/// Called with the hash of every transaction that leaves the mempool. Listeners that return false
+/// are dropped.
+pub(crate) type RemovalListener = Box<dyn FnMut(&Blake2bHash) -> bool + Send + Sync>;
+
 pub(crate) struct MempoolState {
     // Container where the regular transactions are stored
     pub(crate) regular_transactions: MempoolTransactions,
@@ -26,16 +33,36 @@ pub(crate) struct MempoolState {
     // The pending balance per sender.
     pub(crate) state_by_sender: HashMap<Address, SenderPendingState>,
 
+    // Rolling window of mempool statistics per block.
+    pub(crate) history: MempoolHistory,
+
+    // Notifies subscribers about transactions entering or leaving the mempool.
+    pub(crate) notifier: BroadcastSender<MempoolEvent>,
+
+    // Listeners that are called synchronously when a transaction leaves the mempool.
+    removal_listeners: Vec<RemovalListener>,
+
     #[cfg(feature = "metrics")]
     pub(crate) metrics: Arc<MempoolMetrics>,
 }
 
 impl MempoolState {
-    pub fn new(regular_txns_limit: usize, control_txns_limit: usize) -> Self {
+    const NOTIFIER_CAPACITY: usize = 1024;
+
+    pub fn new(
+        regular_txns_limit: usize,
+        control_txns_limit: usize,
+        history_size: usize,
+        fee_window: u32,
+    ) -> Self {
+        let (notifier, _) = broadcast(Self::NOTIFIER_CAPACITY);
         MempoolState {
             regular_transactions: MempoolTransactions::new(regular_txns_limit),
             control_transactions: MempoolTransactions::new(control_txns_limit),
             state_by_sender: HashMap::new(),
+            history: MempoolHistory::new(history_size, fee_window),
+            notifier,
+            removal_listeners: vec![],
             #[cfg(feature = "metrics")]
             metrics: Default::default(),
         }
@@ -98,6 +125,7 @@ impl MempoolState {
         } else {
             self.regular_transactions.insert(tx, priority);
         }
+        self.notify(MempoolEvent::Added, tx);
 
         // After inserting the new txn, check if we need to remove txns
         while self.regular_transactions.total_size > self.regular_transactions.total_size_limit {
@@ -113,16 +141,120 @@ impl MempoolState {
         Ok(())
     }
 
+    /// Returns the hashes of all pending transactions that conflict with `tx`.
+    ///
+    /// A pending transaction conflicts with `tx` if it has the same sender, recipient, value and
+    /// validity start height, and the sender can't afford both transactions. Consensus then
+    /// executes at most one of them, so they are considered to be replacements of each other. If
+    /// the sender can afford both, they are independent payments and don't conflict.
+    pub(crate) fn conflicting_txns(
+        &self,
+        blockchain: &Blockchain,
+        tx: &Transaction,
+    ) -> Vec<Blake2bHash> {
+        let sender_state = match self.state_by_sender.get(&tx.sender) {
+            Some(state) => state,
+            None => return vec![],
+        };
+
+        let sender_account = match blockchain.get_account_if_complete(&tx.sender) {
+            Some(account) => account,
+            None => return vec![],
+        };
+        let mut reserved_balance = sender_state.reserved_balance.clone();
+        if blockchain
+            .reserve_balance(&sender_account, tx, &mut reserved_balance)
+            .is_ok()
+        {
+            return vec![];
+        }
+
+        let tx_hash = tx.hash();
+        sender_state
+            .txns
+            .iter()
+            .filter(|hash| **hash != tx_hash)
+            .filter(|hash| {
+                self.get(hash).map_or(false, |pending_tx| {
+                    pending_tx.recipient == tx.recipient
+                        && pending_tx.value == tx.value
+                        && pending_tx.validity_start_height == tx.validity_start_height
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Checks whether the sender of `tx` stays within the given limits of pending transactions
+    /// and bytes if `tx` is added to the mempool.
+    pub(crate) fn sender_within_limits(
+        &self,
+        tx: &Transaction,
+        txns_limit: usize,
+        size_limit: usize,
+    ) -> bool {
+        let sender_state = match self.state_by_sender.get(&tx.sender) {
+            Some(state) => state,
+            None => return tx.serialized_size() <= size_limit && txns_limit > 0,
+        };
+
+        let size: usize = sender_state
+            .txns
+            .iter()
+            .filter_map(|hash| self.get(hash))
+            .map(|tx| tx.serialized_size())
+            .sum();
+
+        sender_state.txns.len() < txns_limit && size + tx.serialized_size() <= size_limit
+    }
+
+    /// Adds `tx` to the mempool as a replacement of the pending transactions `replaced_hashes`.
+    ///
+    /// The sender must be able to afford `tx` once the replaced transactions are evicted. This is
+    /// checked before the mempool is modified, so if the replacement is rejected, the replaced
+    /// transactions stay in the mempool and no events are emitted.
+    pub(crate) fn replace(
+        &mut self,
+        blockchain: &Blockchain,
+        replaced_hashes: &[Blake2bHash],
+        tx: &Transaction,
+        priority: TxPriority,
+    ) -> Result<(), VerifyErr> {
+        if self.contains(&tx.hash()) {
+            return Err(VerifyErr::Known);
+        }
+
+        let sender_account = blockchain
+            .get_account_if_complete(&tx.sender)
+            .ok_or(VerifyErr::NoConsensus)?;
+
+        if let Some(sender_state) = self.state_by_sender.get(&tx.sender) {
+            let mut reserved_balance = sender_state.reserved_balance.clone();
+            for replaced_tx in replaced_hashes.iter().filter_map(|hash| self.get(hash)) {
+                blockchain
+                    .release_balance(&sender_account, replaced_tx, &mut reserved_balance)
+                    .expect("Failed to release balance");
+            }
+            blockchain
+                .reserve_balance(&sender_account, tx, &mut reserved_balance)
+                .map_err(|_| VerifyErr::InsufficientFunds)?;
+        }
+
+        for replaced_hash in replaced_hashes {
+            self.remove(blockchain, replaced_hash, EvictionReason::Replaced);
+        }
+
+        self.put(blockchain, tx, priority)
+    }
+
     pub(crate) fn remove(
         &mut self,
         blockchain: &Blockchain,
         tx_hash: &Blake2bHash,
-        #[allow(unused_variables)] reason: EvictionReason,
+        reason: EvictionReason,
     ) -> Option<Transaction> {
-        let tx = self
-            .regular_transactions
-            .delete(tx_hash)
-            .or_else(|| self.control_transactions.delete(tx_hash))?;
+        let tx = self.delete(tx_hash)?;
+        self.notify(reason.event(), &tx);
 
         let sender_state = match self.state_by_sender.get_mut(&tx.sender) {
             Some(state) => state,
@@ -139,10 +271,11 @@ impl MempoolState {
                     num_transactions = sender_state.txns.len(),
                     "Sender account is gone"
                 );
-                for hash in &sender_state.txns {
-                    self.regular_transactions
-                        .delete(hash)
-                        .or_else(|| self.control_transactions.delete(hash));
+                let hashes: Vec<Blake2bHash> = sender_state.txns.iter().cloned().collect();
+                for hash in &hashes {
+                    if let Some(tx) = self.delete(hash) {
+                        self.notify(MempoolEvent::Evicted, &tx);
+                    }
                 }
                 self.state_by_sender.remove(&tx.sender);
                 return Some(tx);
@@ -161,12 +294,79 @@ impl MempoolState {
             self.state_by_sender.remove(&tx.sender);
         }
 
+        self.history.note_evicted(&reason);
+
         #[cfg(feature = "metrics")]
         self.metrics.note_evicted(reason);
 
         Some(tx)
     }
 
+    /// Deletes a transaction from the transaction containers, without updating the sender state.
+    pub(crate) fn delete(&mut self, tx_hash: &Blake2bHash) -> Option<Transaction> {
+        let tx = self
+            .regular_transactions
+            .delete(tx_hash)
+            .or_else(|| self.control_transactions.delete(tx_hash))?;
+        self.removal_listeners
+            .retain_mut(|listener| listener(tx_hash));
+        Some(tx)
+    }
+
+    /// Registers a listener that is called whenever a transaction leaves the mempool.
+    pub(crate) fn add_removal_listener(&mut self, listener: RemovalListener) {
+        self.removal_listeners.push(listener);
+    }
+
+    /// Notifies the subscribers, if there are any, about a transaction entering or leaving the
+    /// mempool.
+    fn notify(&self, event: fn(Transaction) -> MempoolEvent, tx: &Transaction) {
+        if self.notifier.receiver_count() > 0 {
+            let _ = self.notifier.send(event(tx.clone()));
+        }
+    }
+
+    /// Notes that a transaction was rejected by the mempool filter.
+    pub(crate) fn note_filtered(&mut self) {
+        self.history.note_filtered();
+
+        #[cfg(feature = "metrics")]
+        self.metrics.note_filtered();
+    }
+
+    /// Records the current mempool statistics for the given block number.
+    pub(crate) fn record_history(&mut self, block_number: u32) {
+        let num_regular_transactions = self.regular_transactions.len();
+        let num_control_transactions = self.control_transactions.len();
+
+        let num_transactions = num_regular_transactions + num_control_transactions;
+        let avg_fee_per_byte = if num_transactions > 0 {
+            self.regular_transactions
+                .transactions
+                .values()
+                .chain(self.control_transactions.transactions.values())
+                .map(|tx| tx.fee_per_byte())
+                .sum::<f64>()
+                / num_transactions as f64
+        } else {
+            0.0
+        };
+
+        self.history.record(
+            block_number,
+            num_regular_transactions,
+            num_control_transactions,
+            avg_fee_per_byte,
+        );
+
+        #[cfg(feature = "metrics")]
+        self.metrics.note_block(
+            num_regular_transactions,
+            num_control_transactions,
+            avg_fee_per_byte,
+        );
+    }
+
     /// Retrieves all expired transaction hashes from both the `regular_transactions` and `control_transactions` vectors
     pub fn get_expired_txns(&mut self, block_number: u32) -> Vec<Blake2bHash> {
         let mut expired_txns = self.control_transactions.get_expired_txns(block_number);
@@ -182,6 +382,22 @@ pub(crate) enum EvictionReason {
     AlreadyIncluded,
     Invalid,
     TooFull,
+    Replaced,
+}
+
+impl EvictionReason {
+    /// Returns the kind of event notifying about the removal of a transaction for this reason.
+    fn event(&self) -> fn(Transaction) -> MempoolEvent {
+        match self {
+            EvictionReason::BlockBuilding | EvictionReason::AlreadyIncluded => {
+                MempoolEvent::Removed
+            }
+            EvictionReason::Expired
+            | EvictionReason::Invalid
+            | EvictionReason::TooFull
+            | EvictionReason::Replaced => MempoolEvent::Evicted,
+        }
+    }
 }
 
 pub(crate) struct SenderPendingState {
@@ -169,6 +169,15 @@ impl MempoolTransactions {
         self.transactions.get(hash)
     }
 
+    // Returns the transactions in the order in which they would be included in blocks,
+    // i.e. the order of `best_transactions`, without removing them from the container.
+    pub(crate) fn iter_best(&self) -> impl Iterator<Item = &Transaction> {
+        let mut best: Vec<_> = self.best_transactions.iter().collect();
+        best.sort_by(|(_, a), (_, b)| b.cmp(a));
+        best.into_iter()
+            .filter_map(|(tx_hash, _)| self.transactions.get(tx_hash))
+    }
+
     pub(crate) fn insert(&mut self, tx: &Transaction, priority: TxPriority) -> bool {
         let tx_hash = tx.hash();
 
@@ -2,13 +2,16 @@ use std::sync::Arc;
 
 use nimiq_blockchain::Blockchain;
 use nimiq_blockchain_interface::AbstractBlockchain;
-use nimiq_hash::Hash;
+use nimiq_hash::{Blake2bHash, Hash};
 use nimiq_primitives::{networks::NetworkId, transaction::TransactionError};
 use nimiq_transaction::Transaction;
 use parking_lot::RwLock;
 use thiserror::Error;
 
-use crate::{filter::MempoolFilter, mempool_state::MempoolState, mempool_transactions::TxPriority};
+use crate::{
+    config::MempoolLimits, filter::MempoolFilter, mempool_state::MempoolState,
+    mempool_transactions::TxPriority,
+};
 
 /// Error codes for the transaction verification
 #[derive(Error, Debug, PartialEq, Eq)]
@@ -28,9 +31,15 @@ pub enum VerifyErr {
     Filtered,
     #[error("Can't verify transaction without consensus")]
     NoConsensus,
+    #[error("Conflicting transaction with higher or equal fee already in mempool")]
+    ReplacementFeeTooLow,
+    #[error("Sender exceeds the limit of pending transactions")]
+    SenderLimitExceeded,
 }
 
 /// Verifies a transaction and adds it to the mempool.
+///
+/// If `limits` are given, the per-sender admission limits are enforced.
 pub(crate) async fn verify_tx(
     transaction: &Transaction,
     blockchain: Arc<RwLock<Blockchain>>,
@@ -38,6 +47,7 @@ pub(crate) async fn verify_tx(
     mempool_state: &Arc<RwLock<MempoolState>>,
     filter: Arc<RwLock<MempoolFilter>>,
     priority: TxPriority,
+    limits: Option<&MempoolLimits>,
 ) -> Result<(), VerifyErr> {
     // 1. Verify transaction signature (and other stuff)
     // FIXME Do we really gain anything by spawning here?
@@ -80,12 +90,50 @@ pub(crate) async fn verify_tx(
         if !filter.accepts_transaction(transaction) || filter.blacklisted(&transaction.hash()) {
             // FIXME add transaction to blacklist
             log::debug!("Transaction filtered");
+            mempool_state.note_filtered();
             return Err(VerifyErr::Filtered);
         }
     }
 
-    // 7. Add transaction to the mempool. Balance checks are performed within put().
-    mempool_state.put(&blockchain, transaction, priority)?;
+    // 7. Check if the transaction replaces pending transactions that the sender can't afford
+    // together with it. Only replacements paying a strictly higher fee than each of them are
+    // accepted.
+    let replaced_hashes = mempool_state.conflicting_txns(&blockchain, transaction);
+    if replaced_hashes
+        .iter()
+        .filter_map(|hash| mempool_state.get(hash))
+        .any(|replaced_tx| replaced_tx.fee >= transaction.fee)
+    {
+        log::debug!("Conflicting transaction with higher or equal fee in mempool");
+        return Err(VerifyErr::ReplacementFeeTooLow);
+    }
+
+    // 8. Check the per-sender limits. Replacements don't increase the number of pending
+    // transactions of the sender, so they are only limited by the balance checks.
+    if let Some(limits) = limits {
+        if replaced_hashes.is_empty()
+            && !mempool_state.sender_within_limits(
+                transaction,
+                limits.sender_txns_limit,
+                limits.sender_size_limit,
+            )
+        {
+            log::debug!(sender = %transaction.sender, "Sender exceeds mempool limits");
+            return Err(VerifyErr::SenderLimitExceeded);
+        }
+    }
+
+    // 9. Add transaction to the mempool. Balance checks are performed within put().
+    if replaced_hashes.is_empty() {
+        mempool_state.put(&blockchain, transaction, priority)?;
+    } else {
+        mempool_state.replace(&blockchain, &replaced_hashes, transaction, priority)?;
+        log::debug!(
+            replaced_txs = ?replaced_hashes,
+            tx = %transaction.hash::<Blake2bHash>(),
+            "Replaced pending transactions"
+        );
+    }
 
     Ok(())
 
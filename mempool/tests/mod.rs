@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use futures::StreamExt;
 use nimiq_block::{Block, MicroBlock, MicroBody, MicroHeader};
 use nimiq_block_production::BlockProducer;
 use nimiq_blockchain::{Blockchain, BlockchainConfig};
@@ -12,7 +13,14 @@ use nimiq_keys::{
     Address, KeyPair as SchnorrKeyPair, PrivateKey as SchnorrPrivateKey,
     PublicKey as SchnorrPublicKey, SecureGenerate,
 };
-use nimiq_mempool::{config::MempoolConfig, mempool::Mempool, mempool_transactions::TxPriority};
+use nimiq_mempool::{
+    config::{MempoolConfig, MempoolLimits},
+    journal::MempoolJournal,
+    mempool::Mempool,
+    mempool_events::MempoolEvent,
+    mempool_transactions::TxPriority,
+    verify::VerifyErr,
+};
 use nimiq_network_mock::{MockHub, MockId, MockNetwork, MockPeerId};
 use nimiq_primitives::{coin::Coin, networks::NetworkId, policy::Policy};
 use nimiq_serde::{Deserialize, Serialize};
@@ -1149,6 +1157,13 @@ async fn mempool_update_aged_transaction() {
         "Number of txns in the mempools is not what is expected"
     );
 
+    // The pruned transactions are recorded in the mempool history
+    let history = mempool.get_history();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].block_number, blockchain.read().block_number());
+    assert_eq!(history[0].num_regular_transactions, 0);
+    assert_eq!(history[0].evictions.expired, 30);
+
     // Get txns from mempool
     let (updated_txns, _) = mempool.get_transactions_for_block(10_000);
 
@@ -1740,6 +1755,591 @@ async fn applies_total_tx_size_limits() {
     assert_eq!(mempool_txns.len(), (num_txns - 1) as usize);
 }
 
+#[test(tokio::test)]
+async fn estimates_fee_from_mempool_content() {
+    let env = VolatileDatabase::new(20).unwrap();
+    let mut genesis_builder = GenesisBuilder::default();
+
+    // Generate more transactions than fit into a single micro block
+    let num_txns = 1000;
+    let sender_balances = vec![100_000_000; 1];
+    let recipient_balances = vec![0; 1];
+
+    let mut rng = test_rng(false);
+    let recipient_accounts =
+        generate_accounts(recipient_balances, &mut genesis_builder, false, &mut rng);
+    let sender_accounts = generate_accounts(sender_balances, &mut genesis_builder, true, &mut rng);
+
+    let mempool_transactions = (0..num_txns)
+        .map(|i| TestTransaction {
+            fee: (i + 1) * 100,
+            value: 1,
+            recipient: recipient_accounts[0].clone(),
+            sender: sender_accounts[0].clone(),
+        })
+        .collect();
+    let (txns, txns_len) = generate_transactions(mempool_transactions, true);
+    assert!(txns_len > MicroBlock::get_available_bytes(0));
+
+    let mut rng = test_rng(true);
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        SchnorrPublicKey::from([0u8; 32]),
+        BlsKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+    );
+
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env.clone(),
+            BlockchainConfig::default(),
+            Arc::new(OffsetTime::new()),
+            NetworkId::UnitAlbatross,
+            genesis_info.block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ));
+
+    let mempool = Mempool::new(blockchain, MempoolConfig::default());
+
+    // An empty mempool on a fresh chain doesn't require any fee
+    assert_eq!(mempool.estimate_fee_per_byte(1), 0.0);
+
+    for tx in txns.iter().cloned() {
+        mempool.add_transaction(tx, None).await.unwrap();
+    }
+
+    // The first transaction that doesn't fit into the next block determines the estimate
+    let mut sorted_txns = txns.clone();
+    sorted_txns.sort_by(|a, b| b.fee.cmp(&a.fee));
+    let mut size = 0;
+    let cutoff_tx = sorted_txns
+        .iter()
+        .find(|tx| {
+            size += 1 + tx.serialized_size();
+            size > MicroBlock::get_available_bytes(0)
+        })
+        .unwrap();
+    assert_eq!(mempool.estimate_fee_per_byte(1), cutoff_tx.fee_per_byte());
+
+    // Waiting for more blocks is cheaper
+    assert!(mempool.estimate_fee_per_byte(2) < mempool.estimate_fee_per_byte(1));
+    assert_eq!(mempool.estimate_fee_per_byte(100), 0.0);
+}
+
+#[test(tokio::test)]
+async fn replaces_transaction_with_higher_fee() {
+    let env = VolatileDatabase::new(20).unwrap();
+    let mut genesis_builder = GenesisBuilder::default();
+
+    // The sender can only afford one of the transactions at a time
+    let mut rng = test_rng(false);
+    let recipient_accounts = generate_accounts(vec![0; 1], &mut genesis_builder, false, &mut rng);
+    let sender_accounts = generate_accounts(vec![15; 1], &mut genesis_builder, true, &mut rng);
+
+    let mempool_transactions = [1, 3, 2]
+        .into_iter()
+        .map(|fee| TestTransaction {
+            fee,
+            value: 10,
+            recipient: recipient_accounts[0].clone(),
+            sender: sender_accounts[0].clone(),
+        })
+        .collect();
+    let (txns, _) = generate_transactions(mempool_transactions, true);
+
+    let mut rng = test_rng(true);
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        SchnorrPublicKey::from([0u8; 32]),
+        BlsKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+    );
+
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env.clone(),
+            BlockchainConfig::default(),
+            Arc::new(OffsetTime::new()),
+            NetworkId::UnitAlbatross,
+            genesis_info.block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ));
+
+    let mempool = Mempool::new(blockchain, MempoolConfig::default());
+
+    // The first transaction is accepted
+    mempool
+        .add_transaction(txns[0].clone(), None)
+        .await
+        .unwrap();
+
+    // The second transaction pays a higher fee and replaces the first one
+    mempool
+        .add_transaction(txns[1].clone(), None)
+        .await
+        .unwrap();
+    assert!(!mempool.contains_transaction_by_hash(&txns[0].hash()));
+    assert!(mempool.contains_transaction_by_hash(&txns[1].hash()));
+
+    // The third transaction pays less than the pending one and is rejected
+    assert_eq!(
+        mempool.add_transaction(txns[2].clone(), None).await,
+        Err(VerifyErr::ReplacementFeeTooLow)
+    );
+
+    // The replaced transaction is not accepted again either
+    assert_eq!(
+        mempool.add_transaction(txns[0].clone(), None).await,
+        Err(VerifyErr::ReplacementFeeTooLow)
+    );
+
+    let (mempool_txns, _) = mempool.get_transactions_for_block(usize::MAX);
+    assert_eq!(mempool_txns, vec![txns[1].clone()]);
+}
+
+#[test(tokio::test)]
+async fn replaces_all_conflicting_transactions() {
+    let env = VolatileDatabase::new(20).unwrap();
+    let mut genesis_builder = GenesisBuilder::default();
+
+    // The sender can afford two of the transactions at a time, but not three
+    let mut rng = test_rng(false);
+    let recipient_accounts = generate_accounts(vec![0; 1], &mut genesis_builder, false, &mut rng);
+    let sender_accounts = generate_accounts(vec![25; 1], &mut genesis_builder, true, &mut rng);
+
+    let mempool_transactions = [1, 2, 20, 5]
+        .into_iter()
+        .map(|fee| TestTransaction {
+            fee,
+            value: 10,
+            recipient: recipient_accounts[0].clone(),
+            sender: sender_accounts[0].clone(),
+        })
+        .collect();
+    let (txns, _) = generate_transactions(mempool_transactions, true);
+
+    let mut rng = test_rng(true);
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        SchnorrPublicKey::from([0u8; 32]),
+        BlsKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+    );
+
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env.clone(),
+            BlockchainConfig::default(),
+            Arc::new(OffsetTime::new()),
+            NetworkId::UnitAlbatross,
+            genesis_info.block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ));
+
+    let mempool = Mempool::new(blockchain, MempoolConfig::default());
+    let mut events = mempool.subscribe_events();
+
+    // The first two transactions are both accepted
+    for tx in &txns[..2] {
+        mempool.add_transaction(tx.clone(), None).await.unwrap();
+        assert!(
+            matches!(events.next().await, Some(Ok(MempoolEvent::Added(added))) if added == *tx)
+        );
+    }
+
+    // The third transaction conflicts with both, but the sender can't afford it even without
+    // them. It is rejected and the pending transactions are kept.
+    assert_eq!(
+        mempool.add_transaction(txns[2].clone(), None).await,
+        Err(VerifyErr::InsufficientFunds)
+    );
+    assert!(mempool.contains_transaction_by_hash(&txns[0].hash()));
+    assert!(mempool.contains_transaction_by_hash(&txns[1].hash()));
+
+    // The fourth transaction replaces both pending transactions
+    mempool
+        .add_transaction(txns[3].clone(), None)
+        .await
+        .unwrap();
+    let mut evicted = vec![];
+    for _ in 0..2 {
+        match events.next().await {
+            Some(Ok(MempoolEvent::Evicted(tx))) => evicted.push(tx),
+            event => panic!("Unexpected event {:?}", event),
+        }
+    }
+    assert!(evicted.contains(&txns[0]));
+    assert!(evicted.contains(&txns[1]));
+    assert!(matches!(events.next().await, Some(Ok(MempoolEvent::Added(tx))) if tx == txns[3]));
+
+    let (mempool_txns, _) = mempool.get_transactions_for_block(usize::MAX);
+    assert_eq!(mempool_txns, vec![txns[3].clone()]);
+}
+
+#[test(tokio::test)]
+async fn notifies_about_transactions_entering_and_leaving() {
+    let env = VolatileDatabase::new(20).unwrap();
+    let mut genesis_builder = GenesisBuilder::default();
+
+    // The sender can only afford one of the transactions at a time
+    let mut rng = test_rng(false);
+    let recipient_accounts = generate_accounts(vec![0; 1], &mut genesis_builder, false, &mut rng);
+    let sender_accounts = generate_accounts(vec![15; 1], &mut genesis_builder, true, &mut rng);
+
+    let mempool_transactions = [1, 3]
+        .into_iter()
+        .map(|fee| TestTransaction {
+            fee,
+            value: 10,
+            recipient: recipient_accounts[0].clone(),
+            sender: sender_accounts[0].clone(),
+        })
+        .collect();
+    let (txns, _) = generate_transactions(mempool_transactions, true);
+
+    let mut rng = test_rng(true);
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        SchnorrPublicKey::from([0u8; 32]),
+        BlsKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+    );
+
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env.clone(),
+            BlockchainConfig::default(),
+            Arc::new(OffsetTime::new()),
+            NetworkId::UnitAlbatross,
+            genesis_info.block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ));
+
+    let mempool = Mempool::new(blockchain, MempoolConfig::default());
+    let mut events = mempool.subscribe_events();
+
+    // The first transaction is added
+    mempool
+        .add_transaction(txns[0].clone(), None)
+        .await
+        .unwrap();
+    assert!(matches!(events.next().await, Some(Ok(MempoolEvent::Added(tx))) if tx == txns[0]));
+
+    // The second transaction evicts the first one
+    mempool
+        .add_transaction(txns[1].clone(), None)
+        .await
+        .unwrap();
+    assert!(matches!(events.next().await, Some(Ok(MempoolEvent::Evicted(tx))) if tx == txns[0]));
+    assert!(matches!(events.next().await, Some(Ok(MempoolEvent::Added(tx))) if tx == txns[1]));
+
+    // The second transaction is removed when it is included in a block
+    mempool.get_transactions_for_block(usize::MAX);
+    assert!(matches!(events.next().await, Some(Ok(MempoolEvent::Removed(tx))) if tx == txns[1]));
+}
+
+#[test(tokio::test)]
+async fn restores_transactions_from_journal() {
+    let env = VolatileDatabase::new(20).unwrap();
+    let mut genesis_builder = GenesisBuilder::default();
+
+    let num_txns = 5;
+    let mut rng = test_rng(false);
+    let recipient_accounts =
+        generate_accounts(vec![0; num_txns], &mut genesis_builder, false, &mut rng);
+    let sender_accounts =
+        generate_accounts(vec![100; num_txns], &mut genesis_builder, true, &mut rng);
+
+    let mempool_transactions = (0..num_txns)
+        .map(|i| TestTransaction {
+            fee: 1,
+            value: 10,
+            recipient: recipient_accounts[i].clone(),
+            sender: sender_accounts[i].clone(),
+        })
+        .collect();
+    let (txns, _) = generate_transactions(mempool_transactions, true);
+
+    let mut rng = test_rng(true);
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        SchnorrPublicKey::from([0u8; 32]),
+        BlsKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+    );
+
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env.clone(),
+            BlockchainConfig::default(),
+            Arc::new(OffsetTime::new()),
+            NetworkId::UnitAlbatross,
+            genesis_info.block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ));
+
+    let mempool = Mempool::new_with_journal(
+        Arc::clone(&blockchain),
+        MempoolConfig::default(),
+        env.clone(),
+    );
+    mempool.restore_journal().await;
+    assert_eq!(mempool.num_transactions(), 0);
+
+    for tx in txns.iter().cloned() {
+        mempool.add_transaction(tx, None).await.unwrap();
+    }
+    mempool.save_journal();
+
+    // A new mempool on the same database restores the transactions
+    let mempool = Mempool::new_with_journal(blockchain, MempoolConfig::default(), env);
+    assert_eq!(mempool.num_transactions(), 0);
+    mempool.restore_journal().await;
+    assert_eq!(mempool.num_transactions(), num_txns);
+    for tx in &txns {
+        assert!(mempool.contains_transaction_by_hash(&tx.hash()));
+    }
+}
+
+#[test(tokio::test)]
+async fn drops_invalid_and_expired_transactions_from_journal() {
+    let mut rng = test_rng(true);
+    let env = VolatileDatabase::new(20).unwrap();
+    let mut genesis_builder = GenesisBuilder::default();
+
+    let num_txns = 3;
+    let recipient_accounts =
+        generate_accounts(vec![0; num_txns], &mut genesis_builder, false, &mut rng);
+    let sender_accounts =
+        generate_accounts(vec![100; num_txns], &mut genesis_builder, true, &mut rng);
+
+    // The last transaction spends more than the sender's balance
+    let mempool_transactions = (0..num_txns)
+        .map(|i| TestTransaction {
+            fee: 1,
+            value: if i + 1 < num_txns { 10 } else { 1000 },
+            recipient: recipient_accounts[i].clone(),
+            sender: sender_accounts[i].clone(),
+        })
+        .collect();
+    let (txns, _) = generate_transactions(mempool_transactions, true);
+
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        signing_key().public,
+        voting_key().public_key,
+        Address::default(),
+    );
+
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env.clone(),
+            BlockchainConfig::default(),
+            Arc::new(OffsetTime::new()),
+            NetworkId::UnitAlbatross,
+            genesis_info.block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ));
+
+    // The journal contains the invalid transaction, e.g. because the sender's balance changed
+    MempoolJournal::new(env.clone()).store(&txns, &[]);
+
+    let mempool = Mempool::new_with_journal(
+        Arc::clone(&blockchain),
+        MempoolConfig::default(),
+        env.clone(),
+    );
+    mempool.restore_journal().await;
+    assert_eq!(mempool.num_transactions(), num_txns - 1);
+    assert!(!mempool.contains_transaction_by_hash(&txns[num_txns - 1].hash()));
+    mempool.save_journal();
+
+    // Produce blocks past the transaction validity window
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    let macro_blocks_to_be_produced =
+        Policy::transaction_validity_window() / Policy::blocks_per_batch();
+    produce_macro_blocks_with_txns(
+        &producer,
+        &blockchain,
+        (macro_blocks_to_be_produced + 1).try_into().unwrap(),
+        0,
+        0,
+    );
+
+    // The journaled transactions expired in the meantime
+    let mempool = Mempool::new_with_journal(blockchain, MempoolConfig::default(), env);
+    mempool.restore_journal().await;
+    assert_eq!(mempool.num_transactions(), 0);
+}
+
+#[test(tokio::test)]
+async fn applies_sender_and_peer_limits() {
+    let env = VolatileDatabase::new(20).unwrap();
+    let mut genesis_builder = GenesisBuilder::default();
+
+    // The first sender sends five transactions, the other senders one transaction each
+    let num_txns = 5;
+    let mut rng = test_rng(false);
+    let recipient_accounts =
+        generate_accounts(vec![0; num_txns], &mut genesis_builder, false, &mut rng);
+    let sender_accounts =
+        generate_accounts(vec![1000; num_txns], &mut genesis_builder, true, &mut rng);
+
+    let single_sender_transactions = (0..num_txns)
+        .map(|i| TestTransaction {
+            fee: 1,
+            value: 10,
+            recipient: recipient_accounts[i].clone(),
+            sender: sender_accounts[0].clone(),
+        })
+        .collect();
+    let (single_sender_txns, _) = generate_transactions(single_sender_transactions, true);
+
+    let multi_sender_transactions = (0..num_txns)
+        .map(|i| TestTransaction {
+            fee: 2,
+            value: 10,
+            recipient: recipient_accounts[i].clone(),
+            sender: sender_accounts[i].clone(),
+        })
+        .collect();
+    let (multi_sender_txns, _) = generate_transactions(multi_sender_transactions, true);
+
+    let mut rng = test_rng(true);
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        SchnorrPublicKey::from([0u8; 32]),
+        BlsKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+    );
+
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env.clone(),
+            BlockchainConfig::default(),
+            Arc::new(OffsetTime::new()),
+            NetworkId::UnitAlbatross,
+            genesis_info.block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ));
+
+    let mut hub = MockHub::new();
+    let mock_network = Arc::new(hub.new_network());
+
+    // Only two transactions per sender are accepted
+    let mempool_config = MempoolConfig {
+        limits: MempoolLimits {
+            sender_txns_limit: 2,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mempool = Mempool::new(Arc::clone(&blockchain), mempool_config);
+    let mock_id = MockId::new(hub.new_address().into());
+    send_txn_to_mempool(
+        &mempool,
+        Arc::clone(&mock_network),
+        mock_id,
+        single_sender_txns,
+    )
+    .await;
+    assert_eq!(mempool.num_transactions(), 2);
+
+    // Only three transactions per peer are accepted
+    let mempool_config = MempoolConfig {
+        limits: MempoolLimits {
+            peer_txns_limit: 3,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mempool = Mempool::new(blockchain, mempool_config);
+    let mock_id = MockId::new(hub.new_address().into());
+    send_txn_to_mempool(&mempool, mock_network, mock_id, multi_sender_txns).await;
+    assert_eq!(mempool.num_transactions(), 3);
+}
+
+#[test(tokio::test)]
+async fn keeps_repeated_payments_the_sender_can_afford() {
+    let env = VolatileDatabase::new(20).unwrap();
+    let mut genesis_builder = GenesisBuilder::default();
+
+    // The sender can afford both payments
+    let mut rng = test_rng(false);
+    let recipient_accounts = generate_accounts(vec![0; 1], &mut genesis_builder, false, &mut rng);
+    let sender_accounts = generate_accounts(vec![100; 1], &mut genesis_builder, true, &mut rng);
+
+    let mempool_transactions = [1, 2]
+        .into_iter()
+        .map(|fee| TestTransaction {
+            fee,
+            value: 10,
+            recipient: recipient_accounts[0].clone(),
+            sender: sender_accounts[0].clone(),
+        })
+        .collect();
+    let (txns, _) = generate_transactions(mempool_transactions, true);
+
+    let mut rng = test_rng(true);
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        SchnorrPublicKey::from([0u8; 32]),
+        BlsKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+    );
+
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env.clone(),
+            BlockchainConfig::default(),
+            Arc::new(OffsetTime::new()),
+            NetworkId::UnitAlbatross,
+            genesis_info.block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ));
+
+    let mempool = Mempool::new(blockchain, MempoolConfig::default());
+
+    // Both payments are kept, neither replaces the other
+    for tx in txns.iter().cloned() {
+        mempool.add_transaction(tx, None).await.unwrap();
+    }
+    assert!(mempool.contains_transaction_by_hash(&txns[0].hash()));
+    assert!(mempool.contains_transaction_by_hash(&txns[1].hash()));
+}
+
 #[tokio::test]
 async fn it_can_reject_invalid_vesting_contract_transaction() {
     let time = Arc::new(OffsetTime::new());
@@ -9,8 +9,11 @@ use nimiq_primitives::{
 };
 use nimiq_vrf::{AliasMethod, VrfSeed, VrfUseCase};
 pub use receipts::*;
+pub use reward_pool::{
+    PendingStake, RewardCheckpoint, RewardPool, StakeActivation, REWARD_PER_STAKE_PRECISION,
+};
 use serde::{Deserialize, Serialize};
-pub use staker::Staker;
+pub use staker::{Staker, StakerDelegation};
 pub use store::StakingContractStore;
 #[cfg(feature = "interaction-traits")]
 pub use store::StakingContractStoreWrite;
@@ -22,6 +25,7 @@ use crate::{
 };
 
 mod receipts;
+mod reward_pool;
 mod staker;
 mod store;
 #[cfg(feature = "interaction-traits")]
@@ -41,6 +45,11 @@ mod validator;
 ///     |--> PREFIX_TOMBSTONE || VALIDATOR_ADDRESS: Tombstone
 ///     |
 ///     |--> PREFIX_STAKER || STAKER_ADDRESS: Staker
+///     |--> PREFIX_DELEGATION || STAKER_ADDRESS || VALIDATOR_ADDRESS: StakerDelegation
+///     |
+///     |--> PREFIX_REWARD_POOL || VALIDATOR_ADDRESS: RewardPool
+///     |--> PREFIX_REWARD_CHECKPOINT || STAKER_ADDRESS || VALIDATOR_ADDRESS: RewardCheckpoint
+///     |--> PREFIX_STAKE_ACTIVATION || VALIDATOR_ADDRESS || ELECTION_BLOCK: StakeActivation
 /// ```
 ///
 /// So, for example, if you want to get the validator with a given address then you just fetch the
@@ -51,6 +60,12 @@ mod validator;
 ///     - A list of Validators. Each of them is a subtrie containing the Validator struct, with all
 ///       the information relative to the Validator.
 ///     - A list of Stakers, with each Staker struct containing all information about a staker.
+///     - A list of StakerDelegations, containing the stake that each staker delegates to
+///       validators other than the one given in its Staker struct.
+///     - The RewardPools of the validators that distribute their rewards to their stakers, and
+///       the RewardCheckpoints at which each staker last claimed its share of them. Stake
+///       delegated after an election block only earns rewards once they are based on a later
+///       election block, the StakeActivations record when that happened.
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StakingContract {
     // The total amount of coins staked (also includes validators deposits).
@@ -100,7 +115,97 @@ impl StakingContract {
         StakingContractStoreRead::new(data_store).get_tombstone(address)
     }
 
+    /// Get the reward pool of a validator, if it distributes (or used to distribute) its rewards
+    /// to its stakers.
+    pub fn get_reward_pool<T: DataStoreReadOps>(
+        &self,
+        data_store: &T,
+        address: &Address,
+    ) -> Option<RewardPool> {
+        StakingContractStoreRead::new(data_store).get_reward_pool(address)
+    }
+
+    /// Get the rewards a staker can claim from the stake it delegates to the given validator.
+    /// They are added to its stake with its next staker transaction.
+    pub fn get_pending_staker_reward<T: DataStoreReadOps>(
+        &self,
+        data_store: &T,
+        staker_address: &Address,
+        validator_address: &Address,
+    ) -> Coin {
+        let read = StakingContractStoreRead::new(data_store);
+
+        let pool = match read.get_reward_pool(validator_address) {
+            Some(pool) => pool,
+            None => return Coin::ZERO,
+        };
+
+        let checkpoint = match read.get_reward_checkpoint(staker_address, validator_address) {
+            Some(checkpoint) => checkpoint,
+            None => {
+                // Stake delegated before the reward pool was created earns since its start.
+                let stake = match read.get_staker(staker_address) {
+                    Some(staker) if staker.delegation.as_ref() == Some(validator_address) => {
+                        staker.primary_balance()
+                    }
+                    Some(_) => read
+                        .get_delegation(staker_address, validator_address)
+                        .map(|delegation| delegation.stake)
+                        .unwrap_or(Coin::ZERO),
+                    None => Coin::ZERO,
+                };
+                return pool.pending_reward(stake, 0);
+            }
+        };
+
+        // Pending stake only earns rewards since it was activated.
+        let mut reward =
+            pool.pending_reward(checkpoint.eligible_stake, checkpoint.reward_per_stake);
+        for pending in checkpoint.pending_stake {
+            if let Some(reward_per_stake) = read
+                .get_stake_activation(validator_address, pending.election_block)
+                .and_then(|activation| activation.reward_per_stake)
+            {
+                reward += pool.pending_reward(pending.stake, reward_per_stake);
+            }
+        }
+
+        reward
+    }
+
+    /// Get the additional delegations of a staker, i.e. the stake it delegates to validators
+    /// other than its main delegation.
+    pub fn get_staker_delegations<T: DataStoreReadOps + DataStoreIterOps>(
+        &self,
+        data_store: &T,
+        staker_address: &Address,
+    ) -> Vec<StakerDelegation> {
+        StakingContractStoreRead::new(data_store)
+            .iter_staker_delegations(staker_address)
+            .collect()
+    }
+
+    /// Checks whether the given staker delegates (part of) its stake to the given validator,
+    /// either as its main delegation or as an additional one.
+    pub fn is_delegating_to<T: DataStoreReadOps>(
+        &self,
+        data_store: &T,
+        staker_address: &Address,
+        validator_address: &Address,
+    ) -> bool {
+        let read = StakingContractStoreRead::new(data_store);
+
+        match read.get_staker(staker_address) {
+            Some(staker) if staker.delegation.as_ref() == Some(validator_address) => true,
+            Some(_) => read
+                .get_delegation(staker_address, validator_address)
+                .is_some(),
+            None => false,
+        }
+    }
+
     /// Get a list containing the addresses of all stakers that are delegating for a given validator.
+    /// This includes the stakers that only delegate part of their stake to the validator.
     /// IMPORTANT: This is a very expensive operation, iterating over all existing stakers in the contract.
     pub fn get_stakers_for_validator<T: DataStoreReadOps + DataStoreIterOps>(
         &self,
@@ -109,15 +214,25 @@ impl StakingContract {
     ) -> Vec<Staker> {
         let read = StakingContractStoreRead::new(data_store);
 
-        if let Some(validator) = read.get_validator(address) {
-            let num_stakers = validator.num_stakers;
-            return read
-                .iter_stakers()
-                .filter(|staker| staker.delegation.as_ref() == Some(address))
-                .take(num_stakers as usize)
-                .collect();
+        if read.get_validator(address).is_none() {
+            return vec![];
         }
-        vec![]
+
+        let mut stakers: Vec<Staker> = read
+            .iter_stakers()
+            .filter(|staker| staker.delegation.as_ref() == Some(address))
+            .collect();
+
+        for delegation in read.iter_delegations() {
+            if &delegation.validator_address == address {
+                stakers.push(
+                    read.get_staker(&delegation.staker_address)
+                        .expect("inconsistent contract state"),
+                );
+            }
+        }
+
+        stakers
     }
 
     /// Get a list containing all validators
@@ -133,6 +248,8 @@ impl StakingContract {
 
     /// Given a seed, it randomly distributes the validator slots across all validators. It is
     /// used to select the validators for the next epoch.
+    /// Only the active stake of the stakers is taken into account: the total stake of a validator
+    /// doesn't include the inactive balances of its stakers, which are being unbonded.
     pub fn select_validators<T: DataStoreReadOps>(
         &self,
         data_store: &T,
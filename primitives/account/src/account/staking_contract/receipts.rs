@@ -3,10 +3,13 @@ use std::collections::BTreeSet;
 use nimiq_bls::CompressedPublicKey as BlsPublicKey;
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::{Address, PublicKey as SchnorrPublicKey};
-use nimiq_primitives::account::AccountError;
+use nimiq_primitives::{account::AccountError, coin::Coin};
 use nimiq_serde::{Deserialize, Serialize};
 
-use crate::{convert_receipt, AccountReceipt};
+use crate::{
+    account::staking_contract::{PendingStake, RewardCheckpoint, RewardPool},
+    convert_receipt, AccountReceipt,
+};
 
 /// A collection of receipts for inherents/transactions. This is necessary to be able to revert
 /// those inherents/transactions.
@@ -27,6 +30,12 @@ pub struct UpdateValidatorReceipt {
 }
 convert_receipt!(UpdateValidatorReceipt);
 
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct UpdateValidatorCommissionReceipt {
+    pub old_pool: Option<RewardPool>,
+}
+convert_receipt!(UpdateValidatorCommissionReceipt);
+
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub struct ReactivateValidatorReceipt {
     pub was_inactive_since: u32,
@@ -46,6 +55,7 @@ pub struct DeleteValidatorReceipt {
     pub voting_key: BlsPublicKey,
     pub reward_address: Address,
     pub signal_data: Option<Blake2bHash>,
+    pub commission: Option<u16>,
     pub inactive_since: u32,
 }
 convert_receipt!(DeleteValidatorReceipt);
@@ -55,3 +65,56 @@ pub struct StakerReceipt {
     pub delegation: Option<Address>,
 }
 convert_receipt!(StakerReceipt);
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SetInactiveStakeReceipt {
+    pub old_inactive_balance: Coin,
+    pub old_inactive_from: Option<u32>,
+}
+convert_receipt!(SetInactiveStakeReceipt);
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct RemoveStakeReceipt {
+    pub delegation: Option<Address>,
+    pub inactive_from: Option<u32>,
+}
+convert_receipt!(RemoveStakeReceipt);
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct DelegationReceipt {
+    pub old_stake: Coin,
+}
+convert_receipt!(DelegationReceipt);
+
+/// The receipt of a reward distribution to the stakers of a validator. It contains the pending
+/// stake that started earning rewards with the distribution.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct DistributeRewardReceipt {
+    pub activated: Vec<PendingStake>,
+}
+convert_receipt!(DistributeRewardReceipt);
+
+/// The accumulated reward per stake at which the stake a staker delegated for the given election
+/// block started earning rewards.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ActivatedStake {
+    pub election_block: u32,
+    pub reward_per_stake: u128,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ClaimedReward {
+    pub validator_address: Address,
+    pub old_checkpoint: Option<RewardCheckpoint>,
+    pub activated: Vec<ActivatedStake>,
+    pub value: Coin,
+}
+
+/// The receipt of a staker transaction. Staker transactions claim the pending rewards of the
+/// staker first, so the receipt of the transaction itself is wrapped together with the claim.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct StakerRewardsReceipt {
+    pub claimed: Vec<ClaimedReward>,
+    pub receipt: Option<AccountReceipt>,
+}
+convert_receipt!(StakerRewardsReceipt);
@@ -0,0 +1,884 @@
+#[cfg(feature = "interaction-traits")]
+use std::{cmp, mem};
+
+use nimiq_keys::Address;
+#[cfg(feature = "interaction-traits")]
+use nimiq_primitives::account::AccountError;
+use nimiq_primitives::{coin::Coin, policy::Policy};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "interaction-traits")]
+use crate::{
+    account::staking_contract::{
+        receipts::{ActivatedStake, ClaimedReward, DistributeRewardReceipt, StakerRewardsReceipt},
+        store::{
+            StakingContractStoreReadOps, StakingContractStoreReadOpsExt, StakingContractStoreWrite,
+        },
+        StakingContract,
+    },
+    AccountReceipt, Log, TransactionLog,
+};
+
+/// The precision of the accumulated reward per unit of stake. The rewards are tracked with 18
+/// decimals so that the rounding errors stay negligible even for small rewards. The remaining
+/// dust is never claimed and stays in the balance of the staking contract.
+pub const REWARD_PER_STAKE_PRECISION: u128 = 1_000_000_000_000_000_000;
+
+/// The reward pool of a validator that distributes (or used to distribute) its rewards to its
+/// stakers. Rewards are not paid out to every staker when they are distributed. Instead, the
+/// reward per unit of delegated stake is accumulated here and each staker claims its share the
+/// next time its stake changes, see [`RewardCheckpoint`].
+///
+/// The rewards of a batch are split by the stake that was delegated at the election block that
+/// determined the slots of the batch. Stake delegated after that election block is pending until
+/// the rewards are based on a later election block. Stake removed after the election block stops
+/// earning immediately, its part of the rewards goes to the remaining stake.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewardPool {
+    /// The commission the validator charges on the rewards of its stakers, in basis points. If it
+    /// is None, the rewards are paid to the reward address of the validator in full.
+    pub commission: Option<u16>,
+    /// The accumulated reward per unit of eligible stake, scaled by
+    /// [`REWARD_PER_STAKE_PRECISION`]. It wraps around on overflow, only differences matter.
+    pub reward_per_stake: u128,
+    /// The delegated stake that earns a share of the distributed rewards.
+    pub eligible_stake: Coin,
+    /// The delegated stake that doesn't earn rewards yet, ordered by election block.
+    pub pending_stake: Vec<PendingStake>,
+}
+
+/// Stake that was delegated to a validator after an election block. It starts earning rewards
+/// with the first reward distribution that is based on `election_block`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingStake {
+    /// The first election block at which the stake was delegated.
+    pub election_block: u32,
+    pub stake: Coin,
+}
+
+impl RewardPool {
+    /// Returns the election block that determined the slots rewarded in the given macro block.
+    /// Macro blocks distribute the rewards of the previous batch.
+    pub fn rewarded_election_block(block_number: u32) -> u32 {
+        let rewarded_batch = Policy::last_macro_block(block_number.saturating_sub(1));
+        Policy::last_election_block(rewarded_batch.saturating_sub(1))
+    }
+
+    /// Returns the delegated stake that earns the rewards based on the given election block.
+    pub fn eligible_stake_at(&self, election_block: u32) -> Coin {
+        self.eligible_stake
+            + self
+                .pending_stake
+                .iter()
+                .filter(|pending| pending.election_block <= election_block)
+                .map(|pending| pending.stake)
+                .sum::<Coin>()
+    }
+
+    /// Returns the share of the given reward that goes to the stakers of the validator, given
+    /// its deposit and the macro block the reward is distributed in. The stakers get the part of
+    /// the reward that corresponds to the stake they delegated at the rewarded election block,
+    /// minus the commission. The validator keeps the rest.
+    pub fn stakers_share(&self, reward: Coin, deposit: Coin, block_number: u32) -> Coin {
+        let commission = match self.commission {
+            Some(commission) => commission,
+            None => return Coin::ZERO,
+        };
+
+        let election_block = Self::rewarded_election_block(block_number);
+        let delegated_stake = u64::from(self.eligible_stake_at(election_block)) as u128;
+        if delegated_stake == 0 {
+            return Coin::ZERO;
+        }
+
+        let total_stake = delegated_stake + u64::from(deposit) as u128;
+        let rate = (Policy::MAX_VALIDATOR_COMMISSION - commission) as u128;
+
+        // This can't overflow since all values are bounded by the total supply.
+        let share = u64::from(reward) as u128 * delegated_stake * rate
+            / (total_stake * Policy::MAX_VALIDATOR_COMMISSION as u128);
+
+        Coin::from_u64_unchecked(share as u64)
+    }
+
+    /// Returns the increase of the accumulated reward per stake when `value` is distributed
+    /// among `eligible_stake`.
+    fn reward_per_stake_increase(value: Coin, eligible_stake: Coin) -> u128 {
+        u64::from(value) as u128 * REWARD_PER_STAKE_PRECISION / u64::from(eligible_stake) as u128
+    }
+
+    /// Returns the rewards accumulated for `stake` since the accumulated reward per stake was at
+    /// the given checkpoint.
+    pub fn pending_reward(&self, stake: Coin, checkpoint: u128) -> Coin {
+        let delta = self.reward_per_stake.wrapping_sub(checkpoint);
+
+        // Split the multiplication to avoid overflows, the stake is bounded by the total supply.
+        let stake = u64::from(stake) as u128;
+        let reward = stake * (delta / REWARD_PER_STAKE_PRECISION)
+            + stake * (delta % REWARD_PER_STAKE_PRECISION) / REWARD_PER_STAKE_PRECISION;
+
+        Coin::from_u64_unchecked(reward as u64)
+    }
+
+    /// Adds stake that is pending for the given election block.
+    fn add_pending_stake(&mut self, election_block: u32, stake: Coin) {
+        match self
+            .pending_stake
+            .binary_search_by_key(&election_block, |pending| pending.election_block)
+        {
+            Ok(index) => self.pending_stake[index].stake += stake,
+            Err(index) => self.pending_stake.insert(
+                index,
+                PendingStake {
+                    election_block,
+                    stake,
+                },
+            ),
+        }
+    }
+
+    /// Removes stake that is pending for the given election block.
+    fn remove_pending_stake(&mut self, election_block: u32, stake: Coin) {
+        let index = self
+            .pending_stake
+            .binary_search_by_key(&election_block, |pending| pending.election_block)
+            .expect("inconsistent contract state");
+
+        self.pending_stake[index].stake -= stake;
+        if self.pending_stake[index].stake.is_zero() {
+            self.pending_stake.remove(index);
+        }
+    }
+}
+
+/// The value of the accumulated reward per stake of a validator's [`RewardPool`] at which a
+/// staker last claimed the rewards of the stake it delegates to that validator, together with
+/// the part of that stake that earns rewards. Stakers delegating to a validator with a reward
+/// pool always have a checkpoint after their stake changed. A missing checkpoint means the stake
+/// was delegated before the reward pool was created and is eligible in full.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewardCheckpoint {
+    /// The address of the staker.
+    pub staker_address: Address,
+    /// The address of the validator.
+    pub validator_address: Address,
+    /// The accumulated reward per stake of the validator at the last claim.
+    pub reward_per_stake: u128,
+    /// The stake that earned rewards since the last claim.
+    pub eligible_stake: Coin,
+    /// The stake that was delegated after an election block, ordered by election block. It
+    /// earns rewards since its [`StakeActivation`].
+    pub pending_stake: Vec<PendingStake>,
+}
+
+impl RewardCheckpoint {
+    /// Returns the stake tracked by the checkpoint, eligible or not.
+    fn stake(&self) -> Coin {
+        self.eligible_stake
+            + self
+                .pending_stake
+                .iter()
+                .map(|pending| pending.stake)
+                .sum::<Coin>()
+    }
+}
+
+/// Records when the stake that was delegated to a validator for an election block started
+/// earning rewards. It exists as long as stakers have stake pending for that election block in
+/// their checkpoints.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StakeActivation {
+    /// The address of the validator.
+    pub validator_address: Address,
+    /// The election block the stake was delegated for.
+    pub election_block: u32,
+    /// The number of stakers whose checkpoints have stake pending for the election block.
+    pub num_stakers: u64,
+    /// The accumulated reward per stake of the validator when the stake started earning
+    /// rewards. None if it doesn't earn rewards yet.
+    pub reward_per_stake: Option<u128>,
+}
+
+#[cfg(feature = "interaction-traits")]
+impl StakingContract {
+    /// Distributes the stakers' share of a reward of the given validator in the macro block with
+    /// the given number. The reward is added to the balance of the staking contract and the
+    /// reward pool of the validator is updated, the stakers claim their part later. The stake
+    /// that was pending for the rewarded election block starts earning rewards first.
+    pub fn distribute_reward(
+        &mut self,
+        store: &mut StakingContractStoreWrite,
+        validator_address: &Address,
+        value: Coin,
+        block_number: u32,
+    ) -> Result<DistributeRewardReceipt, AccountError> {
+        let mut pool = self.expect_reward_pool(store, validator_address)?;
+
+        let election_block = RewardPool::rewarded_election_block(block_number);
+        if pool.eligible_stake_at(election_block).is_zero() {
+            return Err(AccountError::InvalidForTarget);
+        }
+
+        let mut activated = vec![];
+        while !pool.pending_stake.is_empty()
+            && pool.pending_stake[0].election_block <= election_block
+        {
+            let pending = pool.pending_stake.remove(0);
+
+            let mut activation = store
+                .get_stake_activation(validator_address, pending.election_block)
+                .expect("inconsistent contract state");
+            activation.reward_per_stake = Some(pool.reward_per_stake);
+            store.put_stake_activation(activation);
+
+            pool.eligible_stake += pending.stake;
+            activated.push(pending);
+        }
+
+        pool.reward_per_stake =
+            pool.reward_per_stake
+                .wrapping_add(RewardPool::reward_per_stake_increase(
+                    value,
+                    pool.eligible_stake,
+                ));
+        store.put_reward_pool(validator_address, pool);
+
+        // Update our balance.
+        self.balance += value;
+
+        Ok(DistributeRewardReceipt { activated })
+    }
+
+    /// Reverts distributing a reward to the stakers of a validator.
+    pub fn revert_distribute_reward(
+        &mut self,
+        store: &mut StakingContractStoreWrite,
+        validator_address: &Address,
+        value: Coin,
+        receipt: DistributeRewardReceipt,
+    ) -> Result<(), AccountError> {
+        // The eligible stake can't have changed since the reward was distributed, stakers
+        // can't claim in the same block after the rewards were distributed.
+        let mut pool = self.expect_reward_pool(store, validator_address)?;
+
+        pool.reward_per_stake =
+            pool.reward_per_stake
+                .wrapping_sub(RewardPool::reward_per_stake_increase(
+                    value,
+                    pool.eligible_stake,
+                ));
+
+        for pending in receipt.activated.into_iter().rev() {
+            let mut activation = store
+                .get_stake_activation(validator_address, pending.election_block)
+                .expect("inconsistent contract state");
+            activation.reward_per_stake = None;
+            store.put_stake_activation(activation);
+
+            pool.eligible_stake -= pending.stake;
+            pool.pending_stake.insert(0, pending);
+        }
+        store.put_reward_pool(validator_address, pool);
+
+        // Update our balance.
+        self.balance -= value;
+
+        Ok(())
+    }
+
+    /// Returns the reward pool of a validator. Fails if the validator doesn't exist or doesn't
+    /// distribute its rewards.
+    fn expect_reward_pool(
+        &self,
+        store: &StakingContractStoreWrite,
+        validator_address: &Address,
+    ) -> Result<RewardPool, AccountError> {
+        store.expect_validator(validator_address)?;
+
+        store
+            .get_reward_pool(validator_address)
+            .filter(|pool| pool.commission.is_some())
+            .ok_or(AccountError::InvalidForTarget)
+    }
+
+    /// Claims the pending rewards of all delegations of a staker. The rewards are added to the
+    /// stake the staker delegates to the respective validator. The checkpoints of the claimed
+    /// delegations are moved to the current accumulated reward per stake.
+    pub(crate) fn claim_staker_rewards(
+        &mut self,
+        store: &mut StakingContractStoreWrite,
+        staker_address: &Address,
+        tx_logger: &mut TransactionLog,
+    ) -> Vec<ClaimedReward> {
+        let mut claimed = vec![];
+
+        let mut staker = match store.get_staker(staker_address) {
+            Some(staker) => staker,
+            None => return claimed,
+        };
+
+        // Claim the rewards of the main delegation.
+        if let Some(validator_address) = staker.delegation.clone() {
+            if let Some(claim) = self.claim_reward(
+                store,
+                staker_address,
+                &validator_address,
+                staker.primary_balance(),
+                tx_logger,
+            ) {
+                staker.balance += claim.value;
+                claimed.push(claim);
+            }
+        }
+
+        // Claim the rewards of the additional delegations.
+        let delegations: Vec<_> = store.iter_staker_delegations(staker_address).collect();
+        for mut delegation in delegations {
+            if let Some(claim) = self.claim_reward(
+                store,
+                staker_address,
+                &delegation.validator_address,
+                delegation.stake,
+                tx_logger,
+            ) {
+                if !claim.value.is_zero() {
+                    staker.balance += claim.value;
+                    staker.split_balance += claim.value;
+                    delegation.stake += claim.value;
+                    store.put_delegation(delegation);
+                }
+                claimed.push(claim);
+            }
+        }
+
+        if !claimed.is_empty() {
+            store.put_staker(staker_address, staker);
+        }
+
+        claimed
+    }
+
+    /// Claims the pending rewards of the stake a staker delegates to a validator and updates the
+    /// checkpoint. Pending stake that was activated in the meantime becomes eligible. Returns
+    /// None if the validator has no reward pool. The staker entry is not written.
+    fn claim_reward(
+        &mut self,
+        store: &mut StakingContractStoreWrite,
+        staker_address: &Address,
+        validator_address: &Address,
+        stake: Coin,
+        tx_logger: &mut TransactionLog,
+    ) -> Option<ClaimedReward> {
+        let pool = store.get_reward_pool(validator_address)?;
+        let old_checkpoint = store.get_reward_checkpoint(staker_address, validator_address);
+
+        let mut checkpoint = old_checkpoint.clone().unwrap_or_else(|| RewardCheckpoint {
+            staker_address: staker_address.clone(),
+            validator_address: validator_address.clone(),
+            reward_per_stake: 0,
+            eligible_stake: stake,
+            pending_stake: vec![],
+        });
+
+        let mut value = pool.pending_reward(checkpoint.eligible_stake, checkpoint.reward_per_stake);
+
+        let mut activated = vec![];
+        for pending in mem::take(&mut checkpoint.pending_stake) {
+            let activation = store
+                .get_stake_activation(validator_address, pending.election_block)
+                .expect("inconsistent contract state");
+
+            match activation.reward_per_stake {
+                Some(reward_per_stake) => {
+                    value += pool.pending_reward(pending.stake, reward_per_stake);
+                    checkpoint.eligible_stake += pending.stake;
+                    self.release_stake_activation(store, validator_address, pending.election_block);
+                    activated.push(ActivatedStake {
+                        election_block: pending.election_block,
+                        reward_per_stake,
+                    });
+                }
+                None => checkpoint.pending_stake.push(pending),
+            }
+        }
+
+        checkpoint.reward_per_stake = pool.reward_per_stake;
+        store.put_reward_checkpoint(checkpoint);
+
+        if !value.is_zero() {
+            // The rewards are already part of our balance.
+            self.add_stake_to_validator(store, validator_address, value)
+                .expect("inconsistent contract state");
+
+            tx_logger.push_log(Log::StakerReward {
+                staker_address: staker_address.clone(),
+                validator_address: validator_address.clone(),
+                value,
+            });
+        }
+
+        Some(ClaimedReward {
+            validator_address: validator_address.clone(),
+            old_checkpoint,
+            activated,
+            value,
+        })
+    }
+
+    /// Reverts claiming the rewards of a staker. The checkpoints left behind by the claim must
+    /// have been removed before, see `revert_staker_transaction_claim`.
+    pub(crate) fn revert_claim_staker_rewards(
+        &mut self,
+        store: &mut StakingContractStoreWrite,
+        staker_address: &Address,
+        claimed: Vec<ClaimedReward>,
+        tx_logger: &mut TransactionLog,
+    ) {
+        if claimed.is_empty() {
+            return;
+        }
+
+        let mut staker = store
+            .get_staker(staker_address)
+            .expect("inconsistent contract state");
+
+        for claim in claimed.into_iter().rev() {
+            if !claim.value.is_zero() {
+                self.remove_stake_from_validator(store, &claim.validator_address, claim.value)
+                    .expect("inconsistent contract state");
+
+                staker.balance -= claim.value;
+
+                if staker.delegation.as_ref() != Some(&claim.validator_address) {
+                    let mut delegation = store
+                        .get_delegation(staker_address, &claim.validator_address)
+                        .expect("inconsistent contract state");
+                    delegation.stake -= claim.value;
+                    staker.split_balance -= claim.value;
+                    store.put_delegation(delegation);
+                }
+
+                tx_logger.push_log(Log::StakerReward {
+                    staker_address: staker_address.clone(),
+                    validator_address: claim.validator_address.clone(),
+                    value: claim.value,
+                });
+            }
+
+            match claim.old_checkpoint {
+                Some(checkpoint) => {
+                    self.restore_reward_checkpoint(store, checkpoint, &claim.activated)
+                }
+                None => {
+                    // The stake was eligible in full without a checkpoint.
+                    let stake = if staker.delegation.as_ref() == Some(&claim.validator_address) {
+                        staker.primary_balance()
+                    } else {
+                        store
+                            .get_delegation(staker_address, &claim.validator_address)
+                            .expect("inconsistent contract state")
+                            .stake
+                    };
+
+                    let mut pool = store
+                        .get_reward_pool(&claim.validator_address)
+                        .expect("inconsistent contract state");
+                    pool.eligible_stake += stake;
+                    store.put_reward_pool(&claim.validator_address, pool);
+                }
+            }
+        }
+
+        store.put_staker(staker_address, staker);
+    }
+
+    /// Brings the reward checkpoints of a staker in line with its delegations: Every delegation
+    /// to a validator with a reward pool has a checkpoint that tracks its stake. Added stake is
+    /// pending until the next election block, removed stake is taken from the pending stake
+    /// first. Checkpoints of delegations that no longer exist are removed.
+    pub(crate) fn update_reward_checkpoints(
+        &self,
+        store: &mut StakingContractStoreWrite,
+        staker_address: &Address,
+        block_number: u32,
+    ) {
+        let mut delegations = vec![];
+        if let Some(staker) = store.get_staker(staker_address) {
+            if let Some(validator_address) = staker.delegation.clone() {
+                delegations.push((validator_address, staker.primary_balance()));
+            }
+            delegations.extend(
+                store
+                    .iter_staker_delegations(staker_address)
+                    .map(|delegation| (delegation.validator_address, delegation.stake)),
+            );
+        }
+
+        let checkpoints: Vec<_> = store.iter_reward_checkpoints(staker_address).collect();
+        for checkpoint in checkpoints {
+            if !delegations
+                .iter()
+                .any(|(validator_address, _)| validator_address == &checkpoint.validator_address)
+            {
+                self.remove_reward_checkpoint(store, checkpoint);
+            }
+        }
+
+        let election_block = Policy::election_block_after(block_number);
+        for (validator_address, stake) in delegations {
+            let mut pool = match store.get_reward_pool(&validator_address) {
+                Some(pool) => pool,
+                None => continue,
+            };
+
+            let mut checkpoint = store
+                .get_reward_checkpoint(staker_address, &validator_address)
+                .unwrap_or_else(|| RewardCheckpoint {
+                    staker_address: staker_address.clone(),
+                    validator_address: validator_address.clone(),
+                    reward_per_stake: pool.reward_per_stake,
+                    eligible_stake: Coin::ZERO,
+                    pending_stake: vec![],
+                });
+
+            let tracked_stake = checkpoint.stake();
+            if stake > tracked_stake {
+                let added = stake - tracked_stake;
+                match checkpoint.pending_stake.last_mut() {
+                    Some(pending) if pending.election_block == election_block => {
+                        pending.stake += added
+                    }
+                    _ => {
+                        self.retain_stake_activation(store, &validator_address, election_block);
+                        checkpoint.pending_stake.push(PendingStake {
+                            election_block,
+                            stake: added,
+                        });
+                    }
+                }
+                pool.add_pending_stake(election_block, added);
+            } else if stake < tracked_stake {
+                let mut removed = tracked_stake - stake;
+                while let Some(pending) = checkpoint.pending_stake.last_mut() {
+                    if removed.is_zero() {
+                        break;
+                    }
+
+                    let value = cmp::min(pending.stake, removed);
+                    pending.stake -= value;
+                    removed -= value;
+                    pool.remove_pending_stake(pending.election_block, value);
+
+                    if pending.stake.is_zero() {
+                        let pending_election_block = pending.election_block;
+                        checkpoint.pending_stake.pop();
+                        self.release_stake_activation(
+                            store,
+                            &validator_address,
+                            pending_election_block,
+                        );
+                    }
+                }
+                checkpoint.eligible_stake -= removed;
+                pool.eligible_stake -= removed;
+            }
+
+            store.put_reward_pool(&validator_address, pool);
+            store.put_reward_checkpoint(checkpoint);
+        }
+    }
+
+    /// Removes a reward checkpoint together with the stake it contributes to the reward pool.
+    fn remove_reward_checkpoint(
+        &self,
+        store: &mut StakingContractStoreWrite,
+        checkpoint: RewardCheckpoint,
+    ) {
+        let validator_address = &checkpoint.validator_address;
+        let mut pool = store
+            .get_reward_pool(validator_address)
+            .expect("inconsistent contract state");
+
+        pool.eligible_stake -= checkpoint.eligible_stake;
+        for pending in checkpoint.pending_stake {
+            match self.release_stake_activation(store, validator_address, pending.election_block) {
+                Some(_) => pool.eligible_stake -= pending.stake,
+                None => pool.remove_pending_stake(pending.election_block, pending.stake),
+            }
+        }
+
+        store.put_reward_pool(validator_address, pool);
+        store.remove_reward_checkpoint(&checkpoint.staker_address, validator_address);
+    }
+
+    /// Puts back a reward checkpoint that was replaced by a claim, together with the stake it
+    /// contributes to the reward pool. Stake activations that were removed in the meantime are
+    /// recreated from the activations recorded by the claim.
+    fn restore_reward_checkpoint(
+        &self,
+        store: &mut StakingContractStoreWrite,
+        checkpoint: RewardCheckpoint,
+        activated: &[ActivatedStake],
+    ) {
+        let validator_address = &checkpoint.validator_address;
+        let mut pool = store
+            .get_reward_pool(validator_address)
+            .expect("inconsistent contract state");
+
+        pool.eligible_stake += checkpoint.eligible_stake;
+        for pending in &checkpoint.pending_stake {
+            let mut activation = store
+                .get_stake_activation(validator_address, pending.election_block)
+                .unwrap_or_else(|| StakeActivation {
+                    validator_address: validator_address.clone(),
+                    election_block: pending.election_block,
+                    num_stakers: 0,
+                    reward_per_stake: activated
+                        .iter()
+                        .find(|activated| activated.election_block == pending.election_block)
+                        .map(|activated| activated.reward_per_stake),
+                });
+            activation.num_stakers += 1;
+
+            match activation.reward_per_stake {
+                Some(_) => pool.eligible_stake += pending.stake,
+                None => pool.add_pending_stake(pending.election_block, pending.stake),
+            }
+            store.put_stake_activation(activation);
+        }
+
+        store.put_reward_pool(validator_address, pool);
+        store.put_reward_checkpoint(checkpoint);
+    }
+
+    /// Registers a staker with stake pending for the given election block.
+    fn retain_stake_activation(
+        &self,
+        store: &mut StakingContractStoreWrite,
+        validator_address: &Address,
+        election_block: u32,
+    ) {
+        let mut activation = store
+            .get_stake_activation(validator_address, election_block)
+            .unwrap_or_else(|| StakeActivation {
+                validator_address: validator_address.clone(),
+                election_block,
+                num_stakers: 0,
+                reward_per_stake: None,
+            });
+        activation.num_stakers += 1;
+        store.put_stake_activation(activation);
+    }
+
+    /// Unregisters a staker with stake pending for the given election block. Returns the
+    /// accumulated reward per stake at which the stake was activated, if it was.
+    fn release_stake_activation(
+        &self,
+        store: &mut StakingContractStoreWrite,
+        validator_address: &Address,
+        election_block: u32,
+    ) -> Option<u128> {
+        let mut activation = store
+            .get_stake_activation(validator_address, election_block)
+            .expect("inconsistent contract state");
+        let reward_per_stake = activation.reward_per_stake;
+
+        activation.num_stakers -= 1;
+        if activation.num_stakers == 0 {
+            store.remove_stake_activation(validator_address, election_block);
+        } else {
+            store.put_stake_activation(activation);
+        }
+
+        reward_per_stake
+    }
+
+    /// Finishes a staker transaction that was preceded by `claim_staker_rewards`. If the
+    /// transaction failed, the claim is reverted as well. Otherwise the reward checkpoints are
+    /// updated to the new stake of the delegations and the claim is added to the receipt.
+    pub(crate) fn finish_staker_transaction(
+        &mut self,
+        store: &mut StakingContractStoreWrite,
+        staker_address: &Address,
+        claimed: Vec<ClaimedReward>,
+        block_number: u32,
+        result: Result<Option<AccountReceipt>, AccountError>,
+    ) -> Result<Option<AccountReceipt>, AccountError> {
+        match result {
+            Ok(receipt) => {
+                self.update_reward_checkpoints(store, staker_address, block_number);
+
+                Ok(Some(StakerRewardsReceipt { claimed, receipt }.into()))
+            }
+            Err(error) => {
+                // The logs of failed transactions are discarded.
+                self.revert_staker_transaction_claim(
+                    store,
+                    staker_address,
+                    claimed,
+                    &mut TransactionLog::empty(),
+                );
+
+                Err(error)
+            }
+        }
+    }
+
+    /// Reverts the claim of a staker transaction after the transaction itself has been reverted.
+    pub(crate) fn revert_staker_transaction_claim(
+        &mut self,
+        store: &mut StakingContractStoreWrite,
+        staker_address: &Address,
+        claimed: Vec<ClaimedReward>,
+        tx_logger: &mut TransactionLog,
+    ) {
+        let checkpoints: Vec<_> = store.iter_reward_checkpoints(staker_address).collect();
+        for checkpoint in checkpoints {
+            self.remove_reward_checkpoint(store, checkpoint);
+        }
+
+        self.revert_claim_staker_rewards(store, staker_address, claimed, tx_logger);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(commission: Option<u16>, eligible_stake: u64) -> RewardPool {
+        RewardPool {
+            commission,
+            reward_per_stake: 0,
+            eligible_stake: Coin::from_u64_unchecked(eligible_stake),
+            pending_stake: vec![],
+        }
+    }
+
+    #[test]
+    fn it_computes_the_stakers_share() {
+        let deposit = Coin::from_u64_unchecked(200);
+        let reward = Coin::from_u64_unchecked(1_000);
+        let block_number = Policy::blocks_per_batch();
+
+        // 80% of the stake is delegated and the validator charges a commission of 10%.
+        assert_eq!(
+            pool(Some(1_000), 800).stakers_share(reward, deposit, block_number),
+            Coin::from_u64_unchecked(720)
+        );
+        assert_eq!(
+            pool(Some(Policy::MAX_VALIDATOR_COMMISSION), 800).stakers_share(
+                reward,
+                deposit,
+                block_number
+            ),
+            Coin::ZERO
+        );
+        assert_eq!(
+            pool(None, 800).stakers_share(reward, deposit, block_number),
+            Coin::ZERO
+        );
+        assert_eq!(
+            pool(Some(0), 0).stakers_share(reward, deposit, block_number),
+            Coin::ZERO
+        );
+    }
+
+    #[test]
+    fn it_only_shares_rewards_with_stake_delegated_at_the_election_block() {
+        let deposit = Coin::from_u64_unchecked(200);
+        let reward = Coin::from_u64_unchecked(1_000);
+        let election_block = Policy::blocks_per_epoch();
+
+        let mut pool = pool(Some(1_000), 400);
+        pool.add_pending_stake(election_block, Coin::from_u64_unchecked(400));
+
+        // The batches of the first epoch are rewarded based on the genesis block.
+        assert_eq!(RewardPool::rewarded_election_block(election_block), 0);
+        assert_eq!(
+            pool.stakers_share(reward, deposit, election_block),
+            Coin::from_u64_unchecked(600)
+        );
+
+        // The last batch of the first epoch is rewarded in the first batch of the second epoch,
+        // the second batch of the second epoch is based on the election block.
+        assert_eq!(
+            RewardPool::rewarded_election_block(election_block + Policy::blocks_per_batch()),
+            0
+        );
+        let block_number = election_block + 2 * Policy::blocks_per_batch();
+        assert_eq!(
+            RewardPool::rewarded_election_block(block_number),
+            election_block
+        );
+        assert_eq!(
+            pool.stakers_share(reward, deposit, block_number),
+            Coin::from_u64_unchecked(720)
+        );
+    }
+
+    #[test]
+    fn it_tracks_pending_stake_by_election_block() {
+        let mut pool = pool(Some(0), 0);
+        pool.add_pending_stake(20, Coin::from_u64_unchecked(2));
+        pool.add_pending_stake(10, Coin::from_u64_unchecked(1));
+        pool.add_pending_stake(20, Coin::from_u64_unchecked(3));
+
+        assert_eq!(
+            pool.pending_stake,
+            vec![
+                PendingStake {
+                    election_block: 10,
+                    stake: Coin::from_u64_unchecked(1),
+                },
+                PendingStake {
+                    election_block: 20,
+                    stake: Coin::from_u64_unchecked(5),
+                },
+            ]
+        );
+        assert_eq!(pool.eligible_stake_at(15), Coin::from_u64_unchecked(1));
+        assert_eq!(pool.eligible_stake_at(20), Coin::from_u64_unchecked(6));
+
+        pool.remove_pending_stake(10, Coin::from_u64_unchecked(1));
+        assert_eq!(pool.pending_stake.len(), 1);
+    }
+
+    #[test]
+    fn it_computes_pending_rewards() {
+        let mut pool = pool(Some(0), 800);
+        pool.reward_per_stake = RewardPool::reward_per_stake_increase(
+            Coin::from_u64_unchecked(720),
+            pool.eligible_stake,
+        );
+
+        // Stakers get their part of the reward, the dust stays unclaimed.
+        assert_eq!(
+            pool.pending_reward(Coin::from_u64_unchecked(500), 0),
+            Coin::from_u64_unchecked(450)
+        );
+        assert_eq!(
+            pool.pending_reward(Coin::from_u64_unchecked(300), 0),
+            Coin::from_u64_unchecked(270)
+        );
+        assert_eq!(
+            pool.pending_reward(Coin::from_u64_unchecked(1), 0),
+            Coin::ZERO
+        );
+        assert_eq!(
+            pool.pending_reward(Coin::from_u64_unchecked(500), pool.reward_per_stake),
+            Coin::ZERO
+        );
+    }
+
+    #[test]
+    fn it_handles_wrapping_accumulators() {
+        let checkpoint = u128::MAX - REWARD_PER_STAKE_PRECISION + 1;
+        let mut pool = pool(Some(0), 10);
+        pool.reward_per_stake = checkpoint.wrapping_add(2 * REWARD_PER_STAKE_PRECISION);
+
+        assert_eq!(
+            pool.pending_reward(Coin::from_u64_unchecked(10), checkpoint),
+            Coin::from_u64_unchecked(20)
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use nimiq_keys::Address;
 #[cfg(feature = "interaction-traits")]
 use nimiq_primitives::account::AccountError;
-use nimiq_primitives::coin::Coin;
+use nimiq_primitives::{coin::Coin, policy::Policy};
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "interaction-traits")]
@@ -10,7 +10,8 @@ use crate::{
         store::{
             StakingContractStoreReadOps, StakingContractStoreReadOpsExt, StakingContractStoreWrite,
         },
-        StakerReceipt, StakingContract, Tombstone,
+        DelegationReceipt, RemoveStakeReceipt, SetInactiveStakeReceipt, StakerReceipt,
+        StakingContract, Tombstone,
     },
     Log, TransactionLog,
 };
@@ -19,21 +20,73 @@ use crate::{
 /// Actions concerning a staker are:
 /// 1. Create: Creates a staker.
 /// 2. Stake: Adds coins from any outside address to a staker's balance.
-/// 3. Update: Updates the validator.
-/// 4. Unstake: Removes coins from a staker's balance to outside the staking contract.
+/// 3. Update: Updates the validator or the stake delegated to an additional validator.
+/// 4. Set inactive stake: Moves coins between the staker's active and inactive balance. Inactive
+///    coins no longer count towards the stake of the validator and are released after the
+///    unbonding period.
+/// 5. Unstake: Removes coins from a staker's released inactive balance to outside the staking
+///    contract.
 ///
-/// Create, Stake and Update are incoming transactions to the staking contract.
-/// Unstake is an outgoing transaction from the staking contract.
+/// Create, Stake, Update and Set inactive stake are incoming transactions to the staking contract.
+/// Unstake is an outgoing transaction from the staking contract. Update and Set inactive stake can
+/// pay their fee from the staker's active balance, which doesn't require the inactive balance to be
+/// released.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Staker {
     /// The address of the staker. The corresponding key is used for all transactions (except Stake
     /// which is open to any address).
     pub address: Address,
-    /// The staker's balance.
+    /// The staker's active balance. This is the part of the stake that counts towards the stake of
+    /// the validator the staker is delegating to.
     pub balance: Coin,
     /// The address of the validator for which the staker is delegating its stake for. If it is not
     /// delegating to any validator, this will be set to None.
     pub delegation: Option<Address>,
+    /// The part of the active balance that is delegated to additional validators, see
+    /// [`StakerDelegation`]. The rest of the active balance is delegated to `delegation`.
+    pub split_balance: Coin,
+    /// The staker's inactive balance. It can only be withdrawn once it has been released.
+    pub inactive_balance: Coin,
+    /// The block number at which the inactive balance was last increased. It is None if the
+    /// inactive balance is zero.
+    pub inactive_from: Option<u32>,
+}
+
+impl Staker {
+    /// Returns the block number after which the inactive balance of the staker can be withdrawn.
+    /// The inactive balance is released once the epoch in which it was deactivated and the
+    /// following `Policy::STAKER_UNBONDING_EPOCHS` epochs have ended.
+    pub fn inactive_release(&self) -> Option<u32> {
+        self.inactive_from.map(|inactive_from| {
+            Policy::election_block_after(inactive_from)
+                + Policy::STAKER_UNBONDING_EPOCHS * Policy::blocks_per_epoch()
+        })
+    }
+
+    /// Returns the total balance of the staker, i.e. the sum of its active and inactive balance.
+    pub fn total_balance(&self) -> Coin {
+        self.balance + self.inactive_balance
+    }
+
+    /// Returns the part of the active balance that is delegated to the main delegation, i.e. that
+    /// isn't split off to additional validators.
+    pub fn primary_balance(&self) -> Coin {
+        self.balance - self.split_balance
+    }
+}
+
+/// Struct representing the stake a staker delegates to a validator other than its main
+/// delegation. A staker can split its active balance across any number of validators this way,
+/// but delegates to each validator at most once.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StakerDelegation {
+    /// The address of the staker.
+    pub staker_address: Address,
+    /// The address of the validator the stake is delegated to.
+    pub validator_address: Address,
+    /// The stake delegated to the validator. It is part of the staker's active balance and always
+    /// bigger than zero.
+    pub stake: Coin,
 }
 
 #[cfg(feature = "interaction-traits")]
@@ -64,11 +117,14 @@ impl StakingContract {
             address: staker_address.clone(),
             balance: value,
             delegation,
+            split_balance: Coin::ZERO,
+            inactive_balance: Coin::ZERO,
+            inactive_from: None,
         };
 
         // If we are delegating to a validator, we need to update it.
-        if staker.delegation.is_some() {
-            self.add_staker_to_validator(store, &staker)?;
+        if let Some(validator_address) = &staker.delegation {
+            self.add_staker_to_validator(store, validator_address, staker.balance)?;
         }
 
         // Update balance.
@@ -103,8 +159,8 @@ impl StakingContract {
         self.balance -= value;
 
         // If we are delegating to a validator, we need to update it.
-        if staker.delegation.is_some() {
-            self.remove_staker_from_validator(store, &staker)
+        if let Some(validator_address) = &staker.delegation {
+            self.remove_staker_from_validator(store, validator_address, staker.balance)
                 .expect("inconsistent contract state");
         }
 
@@ -195,7 +251,8 @@ impl StakingContract {
         Ok(())
     }
 
-    /// Updates the staker details. Right now you can only update the delegation.
+    /// Updates the staker details. Right now you can only update the delegation. The stake that
+    /// is delegated to additional validators is not affected.
     pub fn update_staker(
         &mut self,
         store: &mut StakingContractStoreWrite,
@@ -206,9 +263,21 @@ impl StakingContract {
         // Get the staker.
         let mut staker = store.expect_staker(staker_address)?;
 
-        // Check that the delegated validator exists.
+        // Check that the delegated validator exists and that the staker isn't already delegating
+        // to it as an additional validator.
         if let Some(new_validator_address) = &delegation {
             store.expect_validator(new_validator_address)?;
+
+            if store
+                .get_delegation(staker_address, new_validator_address)
+                .is_some()
+            {
+                debug!(
+                    "Staker {} is already delegating to validator {}",
+                    staker_address, new_validator_address
+                );
+                return Err(AccountError::InvalidForRecipient);
+            }
         }
 
         // All checks passed, not allowed to fail from here on!
@@ -226,8 +295,8 @@ impl StakingContract {
         };
 
         // If we were delegating to a validator, we remove ourselves from it.
-        if staker.delegation.is_some() {
-            self.remove_staker_from_validator(store, &staker)
+        if let Some(validator_address) = &staker.delegation {
+            self.remove_staker_from_validator(store, validator_address, staker.primary_balance())
                 .expect("inconsistent contract state");
         }
 
@@ -235,8 +304,8 @@ impl StakingContract {
         staker.delegation = delegation;
 
         // If we are now delegating to a validator, we add ourselves to it.
-        if staker.delegation.is_some() {
-            self.add_staker_to_validator(store, &staker)
+        if let Some(validator_address) = &staker.delegation {
+            self.add_staker_to_validator(store, validator_address, staker.primary_balance())
                 .expect("inconsistent contract state");
         }
 
@@ -258,8 +327,8 @@ impl StakingContract {
         let mut staker = store.expect_staker(staker_address)?;
 
         // Remove ourselves from the current delegation, if it exists.
-        if staker.delegation.is_some() {
-            self.remove_staker_from_validator(store, &staker)
+        if let Some(validator_address) = &staker.delegation {
+            self.remove_staker_from_validator(store, validator_address, staker.primary_balance())
                 .expect("inconsistent contract state");
         }
 
@@ -274,8 +343,8 @@ impl StakingContract {
         staker.delegation = receipt.delegation;
 
         // Add ourselves to the previous delegation, if it existed.
-        if staker.delegation.is_some() {
-            self.add_staker_to_validator(store, &staker)
+        if let Some(validator_address) = &staker.delegation {
+            self.add_staker_to_validator(store, validator_address, staker.primary_balance())
                 .expect("inconsistent contract state");
         }
 
@@ -285,37 +354,265 @@ impl StakingContract {
         Ok(())
     }
 
-    /// Removes coins from a staker's balance. If the entire staker's balance is removed then the
-    /// staker is deleted.
-    pub fn remove_stake(
+    /// Sets the stake a staker delegates to a validator other than its main delegation. The
+    /// difference to the current stake is moved from or to the main delegation. Setting the stake
+    /// to zero removes the delegation to this validator.
+    pub fn set_delegation_stake(
+        &mut self,
+        store: &mut StakingContractStoreWrite,
+        staker_address: &Address,
+        validator_address: &Address,
+        stake: Coin,
+        tx_logger: &mut TransactionLog,
+    ) -> Result<DelegationReceipt, AccountError> {
+        // Get the staker.
+        let mut staker = store.expect_staker(staker_address)?;
+
+        // The main delegation can't be an additional delegation at the same time.
+        if staker.delegation.as_ref() == Some(validator_address) {
+            debug!(
+                "Staker {} is already delegating to validator {}",
+                staker_address, validator_address
+            );
+            return Err(AccountError::InvalidForRecipient);
+        }
+
+        let old_stake = store
+            .get_delegation(staker_address, validator_address)
+            .map(|delegation| delegation.stake)
+            .unwrap_or(Coin::ZERO);
+
+        // Check that the delegated validator exists if we delegate more stake to it. Stake can
+        // still be moved away from deleted validators.
+        if stake > old_stake {
+            store.expect_validator(validator_address)?;
+        }
+
+        // Check that the staker has enough funds in its main delegation.
+        let available_stake = staker.primary_balance() + old_stake;
+        if stake > available_stake {
+            return Err(AccountError::InsufficientFunds {
+                needed: stake,
+                balance: available_stake,
+            });
+        }
+
+        // All checks passed, not allowed to fail from here on!
+
+        tx_logger.push_log(Log::SetDelegationStake {
+            staker_address: staker_address.clone(),
+            validator_address: validator_address.clone(),
+            old_stake,
+            new_stake: stake,
+        });
+
+        self.move_delegation_stake(store, &mut staker, validator_address, old_stake, stake);
+
+        // Update the staker entry.
+        store.put_staker(staker_address, staker);
+
+        Ok(DelegationReceipt { old_stake })
+    }
+
+    /// Reverts setting the stake delegated to an additional validator.
+    pub fn revert_set_delegation_stake(
+        &mut self,
+        store: &mut StakingContractStoreWrite,
+        staker_address: &Address,
+        validator_address: &Address,
+        stake: Coin,
+        receipt: DelegationReceipt,
+        tx_logger: &mut TransactionLog,
+    ) -> Result<(), AccountError> {
+        // Get the staker.
+        let mut staker = store.expect_staker(staker_address)?;
+
+        self.move_delegation_stake(
+            store,
+            &mut staker,
+            validator_address,
+            stake,
+            receipt.old_stake,
+        );
+
+        tx_logger.push_log(Log::SetDelegationStake {
+            staker_address: staker_address.clone(),
+            validator_address: validator_address.clone(),
+            old_stake: receipt.old_stake,
+            new_stake: stake,
+        });
+
+        // Update the staker entry.
+        store.put_staker(staker_address, staker);
+
+        Ok(())
+    }
+
+    /// Sets the inactive balance of a staker. The difference to the current inactive balance is
+    /// moved from or to the active balance of the staker, so this can be used both to deactivate
+    /// (parts of) the stake and to reactivate inactive stake. Increasing the inactive balance
+    /// restarts the unbonding period for the whole inactive balance.
+    /// Stake is always deactivated from and reactivated to the main delegation, stake delegated
+    /// to additional validators needs to be moved back first.
+    pub fn set_inactive_stake(
         &mut self,
         store: &mut StakingContractStoreWrite,
         staker_address: &Address,
         value: Coin,
+        block_number: u32,
         tx_logger: &mut TransactionLog,
-    ) -> Result<Option<StakerReceipt>, AccountError> {
+    ) -> Result<SetInactiveStakeReceipt, AccountError> {
         // Get the staker.
         let mut staker = store.expect_staker(staker_address)?;
 
-        // Compute the new balance of the staker. We can't update `staker` here yet as
-        // `remove_staker_from_validator` needs the original balance intact.
-        let new_balance = staker.balance.safe_sub(value)?;
+        // Check that the staker has enough funds. Only stake of the main delegation can be
+        // deactivated.
+        let total_balance = staker.total_balance();
+        let available_balance = total_balance - staker.split_balance;
+        if value > available_balance {
+            return Err(AccountError::InsufficientFunds {
+                needed: value,
+                balance: available_balance,
+            });
+        }
 
         // All checks passed, not allowed to fail from here on!
 
-        // If we are delegating to a validator, we update it.
+        // Create the receipt.
+        let receipt = SetInactiveStakeReceipt {
+            old_inactive_balance: staker.inactive_balance,
+            old_inactive_from: staker.inactive_from,
+        };
+
+        // If we are delegating to a validator, we update its stake.
         if let Some(validator_address) = &staker.delegation {
-            if new_balance.is_zero() {
-                self.remove_staker_from_validator(store, &staker)
-                    .expect("inconsistent contract state");
+            if value > staker.inactive_balance {
+                self.remove_stake_from_validator(
+                    store,
+                    validator_address,
+                    value - staker.inactive_balance,
+                )
+                .expect("inconsistent contract state");
             } else {
-                self.remove_stake_from_validator(store, validator_address, value)
-                    .expect("inconsistent contract state");
+                self.add_stake_to_validator(
+                    store,
+                    validator_address,
+                    staker.inactive_balance - value,
+                )
+                .expect("inconsistent contract state");
             }
         }
 
-        // Update the staker's balance.
-        staker.balance = new_balance;
+        // Update the staker's balances and restart the unbonding period if necessary.
+        if value.is_zero() {
+            staker.inactive_from = None;
+        } else if value > staker.inactive_balance {
+            staker.inactive_from = Some(block_number);
+        }
+        staker.balance = total_balance - value;
+        staker.inactive_balance = value;
+
+        tx_logger.push_log(Log::SetInactiveStake {
+            staker_address: staker_address.clone(),
+            validator_address: staker.delegation.clone(),
+            value,
+            inactive_from: staker.inactive_from,
+        });
+
+        // Update the staker entry.
+        store.put_staker(staker_address, staker);
+
+        Ok(receipt)
+    }
+
+    /// Reverts setting the inactive balance of a staker.
+    pub fn revert_set_inactive_stake(
+        &mut self,
+        store: &mut StakingContractStoreWrite,
+        staker_address: &Address,
+        value: Coin,
+        receipt: SetInactiveStakeReceipt,
+        tx_logger: &mut TransactionLog,
+    ) -> Result<(), AccountError> {
+        // Get the staker.
+        let mut staker = store.expect_staker(staker_address)?;
+
+        // If we are delegating to a validator, we restore its stake.
+        if let Some(validator_address) = &staker.delegation {
+            if value > receipt.old_inactive_balance {
+                self.add_stake_to_validator(
+                    store,
+                    validator_address,
+                    value - receipt.old_inactive_balance,
+                )
+                .expect("inconsistent contract state");
+            } else {
+                self.remove_stake_from_validator(
+                    store,
+                    validator_address,
+                    receipt.old_inactive_balance - value,
+                )
+                .expect("inconsistent contract state");
+            }
+        }
+
+        tx_logger.push_log(Log::SetInactiveStake {
+            staker_address: staker_address.clone(),
+            validator_address: staker.delegation.clone(),
+            value,
+            inactive_from: staker.inactive_from,
+        });
+
+        // Restore the staker's balances.
+        staker.balance = staker.total_balance() - receipt.old_inactive_balance;
+        staker.inactive_balance = receipt.old_inactive_balance;
+        staker.inactive_from = receipt.old_inactive_from;
+
+        // Update the staker entry.
+        store.put_staker(staker_address, staker);
+
+        Ok(())
+    }
+
+    /// Checks that the inactive balance of the given staker has been released and can be
+    /// withdrawn at the given block number.
+    pub fn can_remove_stake(&self, staker: &Staker, block_number: u32) -> Result<(), AccountError> {
+        match staker.inactive_release() {
+            Some(release) if block_number > release => Ok(()),
+            _ => {
+                debug!(
+                    "Tried to remove stake of staker {} before it was released",
+                    staker.address
+                );
+                Err(AccountError::InvalidForSender)
+            }
+        }
+    }
+
+    /// Removes coins from a staker's inactive balance. The inactive balance must have been
+    /// released already. If the staker has no balance left afterwards then the staker is deleted.
+    pub fn remove_stake(
+        &mut self,
+        store: &mut StakingContractStoreWrite,
+        staker_address: &Address,
+        value: Coin,
+        block_number: u32,
+        tx_logger: &mut TransactionLog,
+    ) -> Result<Option<RemoveStakeReceipt>, AccountError> {
+        // Get the staker.
+        let mut staker = store.expect_staker(staker_address)?;
+
+        // Check that the inactive balance has been released.
+        self.can_remove_stake(&staker, block_number)?;
+
+        // Compute the new inactive balance of the staker.
+        let new_inactive_balance = staker.inactive_balance.safe_sub(value)?;
+
+        // All checks passed, not allowed to fail from here on!
+
+        // The inactive balance doesn't count towards the stake of the validator, so only the
+        // staker's balance needs to be updated.
+        staker.inactive_balance = new_inactive_balance;
 
         // Update our balance.
         self.balance -= value;
@@ -326,18 +623,32 @@ impl StakingContract {
             value,
         });
 
-        // Update or remove the staker entry, depending on remaining balance.
+        // Nothing else to do if there is some inactive balance left.
+        if !staker.inactive_balance.is_zero() {
+            store.put_staker(staker_address, staker);
+            return Ok(None);
+        }
+
+        let receipt = RemoveStakeReceipt {
+            delegation: staker.delegation.clone(),
+            inactive_from: staker.inactive_from.take(),
+        };
+
+        // Update or remove the staker entry, depending on the remaining active balance. Since
+        // additional delegations are part of the active balance, there are none left in this case.
         if staker.balance.is_zero() {
-            store.remove_staker(staker_address);
+            // If we are delegating to a validator, we remove ourselves from it.
+            if let Some(validator_address) = &staker.delegation {
+                self.remove_staker_from_validator(store, validator_address, Coin::ZERO)
+                    .expect("inconsistent contract state");
+            }
 
-            Ok(Some(StakerReceipt {
-                delegation: staker.delegation,
-            }))
+            store.remove_staker(staker_address);
         } else {
             store.put_staker(staker_address, staker);
-
-            Ok(None)
         }
+
+        Ok(Some(receipt))
     }
 
     /// Reverts a remove_stake transaction.
@@ -346,47 +657,161 @@ impl StakingContract {
         store: &mut StakingContractStoreWrite,
         staker_address: &Address,
         value: Coin,
-        receipt: Option<StakerReceipt>,
+        receipt: Option<RemoveStakeReceipt>,
         tx_logger: &mut TransactionLog,
     ) -> Result<(), AccountError> {
-        let mut staker = store
-            .get_staker(staker_address)
-            .or_else(|| {
-                receipt.map(|receipt| {
-                    // Set the staker balance to zero here, it is updated later.
-                    let staker = Staker {
-                        address: staker_address.clone(),
-                        balance: Coin::ZERO,
-                        delegation: receipt.delegation,
-                    };
-
-                    // If we are delegating to a validator, re-add the staker to it.
-                    if staker.delegation.is_some() {
-                        self.add_staker_to_validator(store, &staker)
-                            .expect("inconsistent contract state");
-                    }
-
-                    staker
-                })
-            })
-            .ok_or(AccountError::InvalidReceipt)?;
+        let mut staker = match store.get_staker(staker_address) {
+            Some(staker) => staker,
+            None => {
+                let receipt = receipt.as_ref().ok_or(AccountError::InvalidReceipt)?;
+
+                // Set the staker balances to zero here, they are updated later.
+                let staker = Staker {
+                    address: staker_address.clone(),
+                    balance: Coin::ZERO,
+                    delegation: receipt.delegation.clone(),
+                    split_balance: Coin::ZERO,
+                    inactive_balance: Coin::ZERO,
+                    inactive_from: None,
+                };
+
+                // If we are delegating to a validator, re-add the staker to it.
+                if let Some(validator_address) = &staker.delegation {
+                    self.add_staker_to_validator(store, validator_address, Coin::ZERO)
+                        .expect("inconsistent contract state");
+                }
+
+                staker
+            }
+        };
 
-        // Update the staker's balance.
-        staker.balance += value;
+        // Restore the unbonding period if the entire inactive balance was removed.
+        if let Some(receipt) = receipt {
+            staker.inactive_from = receipt.inactive_from;
+        }
+
+        // Update the staker's inactive balance.
+        staker.inactive_balance += value;
 
         // Update our balance.
         self.balance += value;
 
-        // If we are delegating to a validator, we update it.
+        tx_logger.push_log(Log::Unstake {
+            staker_address: staker_address.clone(),
+            validator_address: staker.delegation.clone(),
+            value,
+        });
+
+        // Update the staker entry.
+        store.put_staker(staker_address, staker);
+
+        Ok(())
+    }
+
+    /// Deducts the fee of a signaling transaction from a staker. The fee is paid from the active
+    /// balance of the main delegation, so it doesn't require the inactive balance to be released.
+    /// If the staker has no balance left afterwards then the staker is deleted.
+    pub fn deduct_staker_fee(
+        &mut self,
+        store: &mut StakingContractStoreWrite,
+        staker_address: &Address,
+        fee: Coin,
+        tx_logger: &mut TransactionLog,
+    ) -> Result<Option<RemoveStakeReceipt>, AccountError> {
+        // Get the staker.
+        let mut staker = store.expect_staker(staker_address)?;
+
+        // Check that the main delegation has enough funds. Additional delegations can't pay fees.
+        staker.primary_balance().safe_sub(fee)?;
+
+        // All checks passed, not allowed to fail from here on!
+
+        // If we are delegating to a validator, we update its stake.
         if let Some(validator_address) = &staker.delegation {
-            self.add_stake_to_validator(store, validator_address, value)
+            self.remove_stake_from_validator(store, validator_address, fee)
                 .expect("inconsistent contract state");
         }
 
-        tx_logger.push_log(Log::Unstake {
+        // Update the staker's balance.
+        staker.balance -= fee;
+
+        // Update our balance.
+        self.balance -= fee;
+
+        tx_logger.push_log(Log::StakerFeeDeduction {
             staker_address: staker_address.clone(),
-            validator_address: staker.delegation.clone(),
-            value,
+            fee,
+        });
+
+        // Update or remove the staker entry, depending on the remaining balance.
+        if !staker.total_balance().is_zero() {
+            store.put_staker(staker_address, staker);
+            return Ok(None);
+        }
+
+        // If we are delegating to a validator, we remove ourselves from it.
+        if let Some(validator_address) = &staker.delegation {
+            self.remove_staker_from_validator(store, validator_address, Coin::ZERO)
+                .expect("inconsistent contract state");
+        }
+
+        store.remove_staker(staker_address);
+
+        Ok(Some(RemoveStakeReceipt {
+            delegation: staker.delegation,
+            inactive_from: staker.inactive_from,
+        }))
+    }
+
+    /// Reverts deducting the fee of a signaling transaction from a staker.
+    pub fn revert_deduct_staker_fee(
+        &mut self,
+        store: &mut StakingContractStoreWrite,
+        staker_address: &Address,
+        fee: Coin,
+        receipt: Option<RemoveStakeReceipt>,
+        tx_logger: &mut TransactionLog,
+    ) -> Result<(), AccountError> {
+        let mut staker = match store.get_staker(staker_address) {
+            Some(staker) => staker,
+            None => {
+                let receipt = receipt.ok_or(AccountError::InvalidReceipt)?;
+
+                // Set the staker balances to zero here, they are updated later.
+                let staker = Staker {
+                    address: staker_address.clone(),
+                    balance: Coin::ZERO,
+                    delegation: receipt.delegation,
+                    split_balance: Coin::ZERO,
+                    inactive_balance: Coin::ZERO,
+                    inactive_from: receipt.inactive_from,
+                };
+
+                // If we are delegating to a validator, re-add the staker to it.
+                if let Some(validator_address) = &staker.delegation {
+                    self.add_staker_to_validator(store, validator_address, Coin::ZERO)
+                        .expect("inconsistent contract state");
+                }
+
+                staker
+            }
+        };
+
+        // If we are delegating to a validator, we restore its stake.
+        if let Some(validator_address) = &staker.delegation {
+            self.add_stake_to_validator(store, validator_address, fee)
+                .expect("inconsistent contract state");
+        }
+
+        // Update the staker's balance.
+        staker.balance += fee;
+
+        // Update our balance.
+        self.balance += fee;
+
+        tx_logger.push_log(Log::StakerFeeDeduction {
+            staker_address: staker_address.clone(),
+            fee,
         });
 
         // Update the staker entry.
@@ -395,22 +820,71 @@ impl StakingContract {
         Ok(())
     }
 
-    /// Adds a new staker to the validator given in staker.delegation.
-    /// Panics if staker.delegation is None.
+    /// Moves stake between the main delegation of a staker and one of its additional
+    /// delegations, such that the additional delegation has `new_stake` afterwards. The
+    /// delegation entry is created or removed if necessary. The staker entry is not written.
+    fn move_delegation_stake(
+        &mut self,
+        store: &mut StakingContractStoreWrite,
+        staker: &mut Staker,
+        validator_address: &Address,
+        old_stake: Coin,
+        new_stake: Coin,
+    ) {
+        // Update the additional validator. A delegation with zero stake doesn't count as a
+        // staker of the validator.
+        if old_stake.is_zero() {
+            if !new_stake.is_zero() {
+                self.add_staker_to_validator(store, validator_address, new_stake)
+                    .expect("inconsistent contract state");
+            }
+        } else if new_stake.is_zero() {
+            self.remove_staker_from_validator(store, validator_address, old_stake)
+                .expect("inconsistent contract state");
+        } else if new_stake > old_stake {
+            self.add_stake_to_validator(store, validator_address, new_stake - old_stake)
+                .expect("inconsistent contract state");
+        } else {
+            self.remove_stake_from_validator(store, validator_address, old_stake - new_stake)
+                .expect("inconsistent contract state");
+        }
+
+        // Update the main delegation.
+        if let Some(primary_address) = &staker.delegation {
+            if new_stake > old_stake {
+                self.remove_stake_from_validator(store, primary_address, new_stake - old_stake)
+                    .expect("inconsistent contract state");
+            } else {
+                self.add_stake_to_validator(store, primary_address, old_stake - new_stake)
+                    .expect("inconsistent contract state");
+            }
+        }
+
+        // Update the staker's split balance and the delegation entry.
+        staker.split_balance = staker.split_balance - old_stake + new_stake;
+
+        if new_stake.is_zero() {
+            store.remove_delegation(&staker.address, validator_address);
+        } else {
+            store.put_delegation(StakerDelegation {
+                staker_address: staker.address.clone(),
+                validator_address: validator_address.clone(),
+                stake: new_stake,
+            });
+        }
+    }
+
+    /// Adds a new staker with the given stake to a validator.
     fn add_staker_to_validator(
         &mut self,
         store: &mut StakingContractStoreWrite,
-        staker: &Staker,
+        validator_address: &Address,
+        value: Coin,
     ) -> Result<(), AccountError> {
-        let validator_address = staker
-            .delegation
-            .as_ref()
-            .expect("Staker has no delegation");
-
         // Try to get the validator. It might have been deleted.
         if let Some(mut validator) = store.get_validator(validator_address) {
             // Validator exists, update it.
-            validator.total_stake += staker.balance;
+            validator.total_stake += value;
 
             if validator.is_active() {
                 self.active_validators
@@ -428,7 +902,7 @@ impl StakingContract {
         // Validator doesn't exist, check for tombstone.
         if let Some(mut tombstone) = store.get_tombstone(validator_address) {
             // Tombstone exists, update it.
-            tombstone.remaining_stake += staker.balance;
+            tombstone.remaining_stake += value;
 
             tombstone.num_remaining_stakers += 1;
 
@@ -442,7 +916,7 @@ impl StakingContract {
         // TODO We should consider guarding this functionality behind a flag. It's not obvious from
         //  the function name that this will create a tombstone if the validator doesn't exist.
         let tombstone = Tombstone {
-            remaining_stake: staker.balance,
+            remaining_stake: value,
             num_remaining_stakers: 1,
         };
         store.put_tombstone(validator_address, tombstone);
@@ -450,22 +924,17 @@ impl StakingContract {
         Ok(())
     }
 
-    /// Removes a staker from the validator given in staker.delegation.
-    /// Panics if staker.delegation is None.
+    /// Removes a staker with the given stake from a validator.
     fn remove_staker_from_validator(
         &mut self,
         store: &mut StakingContractStoreWrite,
-        staker: &Staker,
+        validator_address: &Address,
+        value: Coin,
     ) -> Result<(), AccountError> {
-        let validator_address = staker
-            .delegation
-            .as_ref()
-            .expect("Staker has no delegation");
-
         // Try to get the validator. It might have been deleted.
         if let Some(mut validator) = store.get_validator(validator_address) {
             // Validator exists, update it.
-            validator.total_stake -= staker.balance;
+            validator.total_stake -= value;
 
             if validator.is_active() {
                 self.active_validators
@@ -483,7 +952,7 @@ impl StakingContract {
         // Validator doesn't exist, check for tombstone.
         if let Some(mut tombstone) = store.get_tombstone(validator_address) {
             // Tombstone exists, update it.
-            tombstone.remaining_stake -= staker.balance;
+            tombstone.remaining_stake -= value;
 
             tombstone.num_remaining_stakers -= 1;
 
@@ -502,7 +971,7 @@ impl StakingContract {
     }
 
     /// Adds `value` coins to a given validator's total stake.
-    fn add_stake_to_validator(
+    pub(super) fn add_stake_to_validator(
         &mut self,
         store: &mut StakingContractStoreWrite,
         validator_address: &Address,
@@ -540,7 +1009,7 @@ impl StakingContract {
     }
 
     /// Removes `value` coins from a given validator's total stake.
-    fn remove_stake_from_validator(
+    pub(super) fn remove_stake_from_validator(
         &mut self,
         store: &mut StakingContractStoreWrite,
         validator_address: &Address,
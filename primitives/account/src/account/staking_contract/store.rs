@@ -4,7 +4,11 @@ use nimiq_primitives::{account::AccountError, key_nibbles::KeyNibbles};
 #[cfg(feature = "interaction-traits")]
 use crate::data_store::DataStoreWrite;
 use crate::{
-    account::staking_contract::{validator::Tombstone, Staker, Validator},
+    account::staking_contract::{
+        reward_pool::{RewardCheckpoint, RewardPool, StakeActivation},
+        validator::Tombstone,
+        Staker, StakerDelegation, Validator,
+    },
     data_store_ops::{DataStoreIterOps, DataStoreReadOps},
 };
 
@@ -15,6 +19,10 @@ impl StakingContractStore {
     const PREFIX_VALIDATOR: u8 = 0;
     const PREFIX_STAKER: u8 = 1;
     const PREFIX_TOMBSTONE: u8 = 2;
+    const PREFIX_REWARD_POOL: u8 = 3;
+    const PREFIX_DELEGATION: u8 = 4;
+    const PREFIX_REWARD_CHECKPOINT: u8 = 5;
+    const PREFIX_STAKE_ACTIVATION: u8 = 6;
 
     pub fn validator_key(address: &Address) -> KeyNibbles {
         Self::prefixed_address(Self::PREFIX_VALIDATOR, address)
@@ -28,6 +36,46 @@ impl StakingContractStore {
         Self::prefixed_address(Self::PREFIX_TOMBSTONE, address)
     }
 
+    pub fn reward_pool_key(address: &Address) -> KeyNibbles {
+        Self::prefixed_address(Self::PREFIX_REWARD_POOL, address)
+    }
+
+    /// Delegations are stored by staker first, such that all delegations of a staker can be
+    /// iterated over.
+    pub fn delegation_key(staker_address: &Address, validator_address: &Address) -> KeyNibbles {
+        Self::prefixed_address_pair(Self::PREFIX_DELEGATION, staker_address, validator_address)
+    }
+
+    /// Reward checkpoints are stored by staker first as well, such that all checkpoints of a
+    /// staker can be iterated over.
+    pub fn reward_checkpoint_key(
+        staker_address: &Address,
+        validator_address: &Address,
+    ) -> KeyNibbles {
+        Self::prefixed_address_pair(
+            Self::PREFIX_REWARD_CHECKPOINT,
+            staker_address,
+            validator_address,
+        )
+    }
+
+    /// Stake activations are stored by validator and election block.
+    pub fn stake_activation_key(validator_address: &Address, election_block: u32) -> KeyNibbles {
+        let mut key = [0u8; 25];
+        key[0] = Self::PREFIX_STAKE_ACTIVATION;
+        key[1..21].copy_from_slice(&validator_address.0);
+        key[21..].copy_from_slice(&election_block.to_be_bytes());
+        KeyNibbles::from(&key[..])
+    }
+
+    fn prefixed_address_pair(prefix: u8, first: &Address, second: &Address) -> KeyNibbles {
+        let mut key = [0u8; 41];
+        key[0] = prefix;
+        key[1..21].copy_from_slice(&first.0);
+        key[21..].copy_from_slice(&second.0);
+        KeyNibbles::from(&key[..])
+    }
+
     fn prefixed_address(prefix: u8, address: &Address) -> KeyNibbles {
         let mut key = [0u8; 21];
         key[0] = prefix;
@@ -42,6 +90,26 @@ pub trait StakingContractStoreReadOps {
     fn get_staker(&self, address: &Address) -> Option<Staker>;
 
     fn get_tombstone(&self, address: &Address) -> Option<Tombstone>;
+
+    fn get_reward_pool(&self, address: &Address) -> Option<RewardPool>;
+
+    fn get_reward_checkpoint(
+        &self,
+        staker_address: &Address,
+        validator_address: &Address,
+    ) -> Option<RewardCheckpoint>;
+
+    fn get_stake_activation(
+        &self,
+        validator_address: &Address,
+        election_block: u32,
+    ) -> Option<StakeActivation>;
+
+    fn get_delegation(
+        &self,
+        staker_address: &Address,
+        validator_address: &Address,
+    ) -> Option<StakerDelegation>;
 }
 
 pub(crate) struct StakingContractStoreRead<'read, T: DataStoreReadOps>(&'read T);
@@ -66,6 +134,43 @@ impl<'read, T: DataStoreReadOps> StakingContractStoreReadOps
     fn get_tombstone(&self, address: &Address) -> Option<Tombstone> {
         self.0.get(&StakingContractStore::tombstone_key(address))
     }
+
+    fn get_reward_pool(&self, address: &Address) -> Option<RewardPool> {
+        self.0.get(&StakingContractStore::reward_pool_key(address))
+    }
+
+    fn get_reward_checkpoint(
+        &self,
+        staker_address: &Address,
+        validator_address: &Address,
+    ) -> Option<RewardCheckpoint> {
+        self.0.get(&StakingContractStore::reward_checkpoint_key(
+            staker_address,
+            validator_address,
+        ))
+    }
+
+    fn get_stake_activation(
+        &self,
+        validator_address: &Address,
+        election_block: u32,
+    ) -> Option<StakeActivation> {
+        self.0.get(&StakingContractStore::stake_activation_key(
+            validator_address,
+            election_block,
+        ))
+    }
+
+    fn get_delegation(
+        &self,
+        staker_address: &Address,
+        validator_address: &Address,
+    ) -> Option<StakerDelegation> {
+        self.0.get(&StakingContractStore::delegation_key(
+            staker_address,
+            validator_address,
+        ))
+    }
 }
 
 impl<'read, T: DataStoreReadOps + DataStoreIterOps> StakingContractStoreRead<'read, T> {
@@ -82,6 +187,23 @@ impl<'read, T: DataStoreReadOps + DataStoreIterOps> StakingContractStoreRead<'re
             &StakingContractStore::validator_key(&Address::END_ADDRESS),
         )
     }
+
+    pub(crate) fn iter_delegations(&self) -> impl Iterator<Item = StakerDelegation> {
+        self.0.iter(
+            &StakingContractStore::delegation_key(&Address::START_ADDRESS, &Address::START_ADDRESS),
+            &StakingContractStore::delegation_key(&Address::END_ADDRESS, &Address::END_ADDRESS),
+        )
+    }
+
+    pub(crate) fn iter_staker_delegations(
+        &self,
+        staker_address: &Address,
+    ) -> impl Iterator<Item = StakerDelegation> {
+        self.0.iter(
+            &StakingContractStore::delegation_key(staker_address, &Address::START_ADDRESS),
+            &StakingContractStore::delegation_key(staker_address, &Address::END_ADDRESS),
+        )
+    }
 }
 
 #[cfg(feature = "interaction-traits")]
@@ -123,6 +245,93 @@ impl<'write, 'store, 'tree, 'txn, 'txni, 'env>
     pub fn remove_tombstone(&mut self, address: &Address) {
         self.0.remove(&StakingContractStore::tombstone_key(address))
     }
+
+    pub fn put_reward_pool(&mut self, address: &Address, pool: RewardPool) {
+        self.0
+            .put(&StakingContractStore::reward_pool_key(address), pool)
+    }
+
+    pub fn remove_reward_pool(&mut self, address: &Address) {
+        self.0
+            .remove(&StakingContractStore::reward_pool_key(address))
+    }
+
+    pub fn put_reward_checkpoint(&mut self, checkpoint: RewardCheckpoint) {
+        self.0.put(
+            &StakingContractStore::reward_checkpoint_key(
+                &checkpoint.staker_address,
+                &checkpoint.validator_address,
+            ),
+            checkpoint,
+        )
+    }
+
+    pub fn remove_reward_checkpoint(
+        &mut self,
+        staker_address: &Address,
+        validator_address: &Address,
+    ) {
+        self.0.remove(&StakingContractStore::reward_checkpoint_key(
+            staker_address,
+            validator_address,
+        ))
+    }
+
+    pub fn put_stake_activation(&mut self, activation: StakeActivation) {
+        self.0.put(
+            &StakingContractStore::stake_activation_key(
+                &activation.validator_address,
+                activation.election_block,
+            ),
+            activation,
+        )
+    }
+
+    pub fn remove_stake_activation(&mut self, validator_address: &Address, election_block: u32) {
+        self.0.remove(&StakingContractStore::stake_activation_key(
+            validator_address,
+            election_block,
+        ))
+    }
+
+    pub fn put_delegation(&mut self, delegation: StakerDelegation) {
+        self.0.put(
+            &StakingContractStore::delegation_key(
+                &delegation.staker_address,
+                &delegation.validator_address,
+            ),
+            delegation,
+        )
+    }
+
+    pub fn remove_delegation(&mut self, staker_address: &Address, validator_address: &Address) {
+        self.0.remove(&StakingContractStore::delegation_key(
+            staker_address,
+            validator_address,
+        ))
+    }
+
+    /// Iterates over the additional delegations of a staker.
+    pub fn iter_staker_delegations(
+        &self,
+        staker_address: &Address,
+    ) -> impl Iterator<Item = StakerDelegation> + '_ {
+        self.0.iter(
+            &StakingContractStore::delegation_key(staker_address, &Address::START_ADDRESS),
+            &StakingContractStore::delegation_key(staker_address, &Address::END_ADDRESS),
+        )
+    }
+
+    /// Iterates over the reward checkpoints of a staker.
+    pub fn iter_reward_checkpoints(
+        &self,
+        staker_address: &Address,
+    ) -> impl Iterator<Item = RewardCheckpoint> + '_ {
+        self.0.iter(
+            &StakingContractStore::reward_checkpoint_key(staker_address, &Address::START_ADDRESS),
+            &StakingContractStore::reward_checkpoint_key(staker_address, &Address::END_ADDRESS),
+        )
+    }
 }
 
 #[cfg(feature = "interaction-traits")]
@@ -140,6 +349,43 @@ impl<'write, 'store, 'tree, 'txn, 'txni, 'env> StakingContractStoreReadOps
     fn get_tombstone(&self, address: &Address) -> Option<Tombstone> {
         self.0.get(&StakingContractStore::tombstone_key(address))
     }
+
+    fn get_reward_pool(&self, address: &Address) -> Option<RewardPool> {
+        self.0.get(&StakingContractStore::reward_pool_key(address))
+    }
+
+    fn get_reward_checkpoint(
+        &self,
+        staker_address: &Address,
+        validator_address: &Address,
+    ) -> Option<RewardCheckpoint> {
+        self.0.get(&StakingContractStore::reward_checkpoint_key(
+            staker_address,
+            validator_address,
+        ))
+    }
+
+    fn get_stake_activation(
+        &self,
+        validator_address: &Address,
+        election_block: u32,
+    ) -> Option<StakeActivation> {
+        self.0.get(&StakingContractStore::stake_activation_key(
+            validator_address,
+            election_block,
+        ))
+    }
+
+    fn get_delegation(
+        &self,
+        staker_address: &Address,
+        validator_address: &Address,
+    ) -> Option<StakerDelegation> {
+        self.0.get(&StakingContractStore::delegation_key(
+            staker_address,
+            validator_address,
+        ))
+    }
 }
 
 pub trait StakingContractStoreReadOpsExt {
@@ -9,12 +9,12 @@ use nimiq_primitives::{
 use nimiq_transaction::{
     account::staking_contract::{IncomingStakingTransactionData, OutgoingStakingTransactionProof},
     inherent::Inherent,
-    Transaction,
+    Transaction, TransactionFlags,
 };
 
 use crate::{
     account::staking_contract::{
-        receipts::SlashReceipt,
+        receipts::{SlashReceipt, StakerRewardsReceipt},
         store::{
             StakingContractStoreRead, StakingContractStoreReadOps, StakingContractStoreReadOpsExt,
             StakingContractStoreWrite,
@@ -116,6 +116,21 @@ impl AccountTransactionInteraction for StakingContract {
                 )
                 .map(|receipt| Some(receipt.into()))
             }
+            IncomingStakingTransactionData::UpdateValidatorCommission {
+                new_commission,
+                proof,
+            } => {
+                // Get the validator address from the proof.
+                let validator_address = proof.compute_signer();
+
+                self.update_validator_commission(
+                    &mut store,
+                    &validator_address,
+                    new_commission,
+                    tx_logger,
+                )
+                .map(|receipt| Some(receipt.into()))
+            }
             IncomingStakingTransactionData::DeactivateValidator {
                 validator_address,
                 proof,
@@ -158,27 +173,101 @@ impl AccountTransactionInteraction for StakingContract {
                 // Get the staker address from the proof.
                 let staker_address = proof.compute_signer();
 
-                self.create_staker(
+                let claimed = self.claim_staker_rewards(&mut store, &staker_address, tx_logger);
+
+                let result = self
+                    .create_staker(
+                        &mut store,
+                        &staker_address,
+                        transaction.value,
+                        delegation,
+                        tx_logger,
+                    )
+                    .map(|_| None);
+
+                self.finish_staker_transaction(
                     &mut store,
                     &staker_address,
-                    transaction.value,
-                    delegation,
-                    tx_logger,
+                    claimed,
+                    block_state.number,
+                    result,
+                )
+            }
+            IncomingStakingTransactionData::AddStake { staker_address } => {
+                let claimed = self.claim_staker_rewards(&mut store, &staker_address, tx_logger);
+
+                let result = self
+                    .add_stake(&mut store, &staker_address, transaction.value, tx_logger)
+                    .map(|_| None);
+
+                self.finish_staker_transaction(
+                    &mut store,
+                    &staker_address,
+                    claimed,
+                    block_state.number,
+                    result,
                 )
-                .map(|_| None)
             }
-            IncomingStakingTransactionData::AddStake { staker_address } => self
-                .add_stake(&mut store, &staker_address, transaction.value, tx_logger)
-                .map(|_| None),
             IncomingStakingTransactionData::UpdateStaker {
                 new_delegation,
+                delegation_stake,
                 proof,
             } => {
                 // Get the staker address from the proof.
                 let staker_address = proof.compute_signer();
 
-                self.update_staker(&mut store, &staker_address, new_delegation, tx_logger)
-                    .map(|receipt| Some(receipt.into()))
+                let claimed = self.claim_staker_rewards(&mut store, &staker_address, tx_logger);
+
+                let result = match (new_delegation, delegation_stake) {
+                    (Some(validator_address), Some(stake)) => self
+                        .set_delegation_stake(
+                            &mut store,
+                            &staker_address,
+                            &validator_address,
+                            stake,
+                            tx_logger,
+                        )
+                        .map(|receipt| Some(receipt.into())),
+                    (new_delegation, None) => self
+                        .update_staker(&mut store, &staker_address, new_delegation, tx_logger)
+                        .map(|receipt| Some(receipt.into())),
+                    (None, Some(_)) => Err(AccountError::InvalidForRecipient),
+                };
+
+                self.finish_staker_transaction(
+                    &mut store,
+                    &staker_address,
+                    claimed,
+                    block_state.number,
+                    result,
+                )
+            }
+            IncomingStakingTransactionData::SetInactiveStake {
+                new_inactive_balance,
+                proof,
+            } => {
+                // Get the staker address from the proof.
+                let staker_address = proof.compute_signer();
+
+                let claimed = self.claim_staker_rewards(&mut store, &staker_address, tx_logger);
+
+                let result = self
+                    .set_inactive_stake(
+                        &mut store,
+                        &staker_address,
+                        new_inactive_balance,
+                        block_state.number,
+                        tx_logger,
+                    )
+                    .map(|receipt| Some(receipt.into()));
+
+                self.finish_staker_transaction(
+                    &mut store,
+                    &staker_address,
+                    claimed,
+                    block_state.number,
+                    result,
+                )
             }
         }
     }
@@ -216,6 +305,19 @@ impl AccountTransactionInteraction for StakingContract {
 
                 self.revert_update_validator(&mut store, &validator_address, receipt, tx_logger)
             }
+            IncomingStakingTransactionData::UpdateValidatorCommission { proof, .. } => {
+                // Get the validator address from the proof.
+                let validator_address = proof.compute_signer();
+
+                let receipt = receipt.ok_or(AccountError::InvalidReceipt)?.try_into()?;
+
+                self.revert_update_validator_commission(
+                    &mut store,
+                    &validator_address,
+                    receipt,
+                    tx_logger,
+                )
+            }
             IncomingStakingTransactionData::DeactivateValidator {
                 validator_address, ..
             } => self.revert_deactivate_validator(&mut store, &validator_address, tx_logger),
@@ -238,18 +340,108 @@ impl AccountTransactionInteraction for StakingContract {
                 // Get the staker address from the proof.
                 let staker_address = proof.compute_signer();
 
-                self.revert_create_staker(&mut store, &staker_address, transaction.value, tx_logger)
+                let receipt: StakerRewardsReceipt =
+                    receipt.ok_or(AccountError::InvalidReceipt)?.try_into()?;
+
+                self.revert_create_staker(
+                    &mut store,
+                    &staker_address,
+                    transaction.value,
+                    tx_logger,
+                )?;
+
+                self.revert_staker_transaction_claim(
+                    &mut store,
+                    &staker_address,
+                    receipt.claimed,
+                    tx_logger,
+                );
+
+                Ok(())
             }
             IncomingStakingTransactionData::AddStake { staker_address } => {
-                self.revert_add_stake(&mut store, &staker_address, transaction.value, tx_logger)
+                let receipt: StakerRewardsReceipt =
+                    receipt.ok_or(AccountError::InvalidReceipt)?.try_into()?;
+
+                self.revert_add_stake(&mut store, &staker_address, transaction.value, tx_logger)?;
+
+                self.revert_staker_transaction_claim(
+                    &mut store,
+                    &staker_address,
+                    receipt.claimed,
+                    tx_logger,
+                );
+
+                Ok(())
             }
-            IncomingStakingTransactionData::UpdateStaker { proof, .. } => {
+            IncomingStakingTransactionData::UpdateStaker {
+                new_delegation,
+                delegation_stake,
+                proof,
+            } => {
                 // Get the staker address from the proof.
                 let staker_address = proof.compute_signer();
 
-                let receipt = receipt.ok_or(AccountError::InvalidReceipt)?.try_into()?;
+                let receipt: StakerRewardsReceipt =
+                    receipt.ok_or(AccountError::InvalidReceipt)?.try_into()?;
+                let inner_receipt = receipt.receipt.ok_or(AccountError::InvalidReceipt)?;
+
+                match (new_delegation, delegation_stake) {
+                    (Some(validator_address), Some(stake)) => self.revert_set_delegation_stake(
+                        &mut store,
+                        &staker_address,
+                        &validator_address,
+                        stake,
+                        inner_receipt.try_into()?,
+                        tx_logger,
+                    )?,
+                    (_, None) => self.revert_update_staker(
+                        &mut store,
+                        &staker_address,
+                        inner_receipt.try_into()?,
+                        tx_logger,
+                    )?,
+                    (None, Some(_)) => return Err(AccountError::InvalidReceipt),
+                }
+
+                self.revert_staker_transaction_claim(
+                    &mut store,
+                    &staker_address,
+                    receipt.claimed,
+                    tx_logger,
+                );
+
+                Ok(())
+            }
+            IncomingStakingTransactionData::SetInactiveStake {
+                new_inactive_balance,
+                proof,
+            } => {
+                // Get the staker address from the proof.
+                let staker_address = proof.compute_signer();
+
+                let receipt: StakerRewardsReceipt =
+                    receipt.ok_or(AccountError::InvalidReceipt)?.try_into()?;
 
-                self.revert_update_staker(&mut store, &staker_address, receipt, tx_logger)
+                self.revert_set_inactive_stake(
+                    &mut store,
+                    &staker_address,
+                    new_inactive_balance,
+                    receipt
+                        .receipt
+                        .ok_or(AccountError::InvalidReceipt)?
+                        .try_into()?,
+                    tx_logger,
+                )?;
+
+                self.revert_staker_transaction_claim(
+                    &mut store,
+                    &staker_address,
+                    receipt.claimed,
+                    tx_logger,
+                );
+
+                Ok(())
             }
         }
     }
@@ -286,13 +478,35 @@ impl AccountTransactionInteraction for StakingContract {
                 // Get the staker address from the proof.
                 let staker_address = proof.compute_signer();
 
-                self.remove_stake(
+                let claimed = self.claim_staker_rewards(&mut store, &staker_address, tx_logger);
+
+                // Signaling transactions pay their fee from the active balance, everything else
+                // is withdrawn from the released inactive balance.
+                let result = if transaction.flags.contains(TransactionFlags::SIGNALING) {
+                    self.deduct_staker_fee(
+                        &mut store,
+                        &staker_address,
+                        transaction.total_value(),
+                        tx_logger,
+                    )
+                } else {
+                    self.remove_stake(
+                        &mut store,
+                        &staker_address,
+                        transaction.total_value(),
+                        block_state.number,
+                        tx_logger,
+                    )
+                }
+                .map(|receipt| receipt.map(|receipt| receipt.into()));
+
+                self.finish_staker_transaction(
                     &mut store,
                     &staker_address,
-                    transaction.total_value(),
-                    tx_logger,
+                    claimed,
+                    block_state.number,
+                    result,
                 )
-                .map(|receipt| receipt.map(|receipt| receipt.into()))
             }
         }
     }
@@ -329,18 +543,38 @@ impl AccountTransactionInteraction for StakingContract {
                 // Get the staker address from the proof.
                 let staker_address = proof.compute_signer();
 
-                let receipt = match receipt {
+                let receipt: StakerRewardsReceipt =
+                    receipt.ok_or(AccountError::InvalidReceipt)?.try_into()?;
+                let inner_receipt = match receipt.receipt {
                     Some(receipt) => Some(receipt.try_into()?),
                     None => None,
                 };
 
-                self.revert_remove_stake(
-                    &mut store,
-                    &staker_address,
-                    transaction.total_value(),
-                    receipt,
-                    tx_logger,
-                )
+                if transaction.flags.contains(TransactionFlags::SIGNALING) {
+                    self.revert_deduct_staker_fee(
+                        &mut store,
+                        &staker_address,
+                        transaction.total_value(),
+                        inner_receipt,
+                        tx_logger,
+                    )
+                } else {
+                    self.revert_remove_stake(
+                        &mut store,
+                        &staker_address,
+                        transaction.total_value(),
+                        inner_receipt,
+                        tx_logger,
+                    )
+                }
+                .map(|_| {
+                    self.revert_staker_transaction_claim(
+                        &mut store,
+                        &staker_address,
+                        receipt.claimed,
+                        tx_logger,
+                    )
+                })
             }
         };
 
@@ -413,17 +647,36 @@ impl AccountTransactionInteraction for StakingContract {
                 // Get the staker address from the proof.
                 let staker_address = proof.compute_signer();
 
-                // This is similar to an remove_stake operation except that what we deduct only the fee from the stake.
-                // We do not want the fee payment to be displayed as a successful unstake in the block logs,
-                // which is why we pass an empty logger.
-                let receipt = self
-                    .remove_stake(
+                let claimed = self.claim_staker_rewards(&mut store, &staker_address, tx_logger);
+
+                // This is similar to a successful transaction except that we deduct only the fee
+                // from the stake. We do not want the fee payment to be displayed as a successful
+                // unstake in the block logs, which is why we pass an empty logger.
+                let result = if transaction.flags.contains(TransactionFlags::SIGNALING) {
+                    self.deduct_staker_fee(
+                        &mut store,
+                        &staker_address,
+                        transaction.fee,
+                        &mut TransactionLog::empty(),
+                    )
+                } else {
+                    self.remove_stake(
                         &mut store,
                         &staker_address,
                         transaction.fee,
+                        block_state.number,
                         &mut TransactionLog::empty(),
                     )
-                    .map(|receipt| receipt.map(|receipt| receipt.into()))?;
+                }
+                .map(|receipt| receipt.map(|receipt| receipt.into()));
+
+                let receipt = self.finish_staker_transaction(
+                    &mut store,
+                    &staker_address,
+                    claimed,
+                    block_state.number,
+                    result,
+                )?;
 
                 tx_logger.push_log(Log::StakerFeeDeduction {
                     staker_address,
@@ -499,18 +752,38 @@ impl AccountTransactionInteraction for StakingContract {
                     fee: transaction.fee,
                 });
 
-                let receipt = match receipt {
+                let receipt: StakerRewardsReceipt =
+                    receipt.ok_or(AccountError::InvalidReceipt)?.try_into()?;
+                let inner_receipt = match receipt.receipt {
                     Some(receipt) => Some(receipt.try_into()?),
                     None => None,
                 };
 
-                self.revert_remove_stake(
-                    &mut store,
-                    &staker_address,
-                    transaction.fee,
-                    receipt,
-                    &mut TransactionLog::empty(),
-                )
+                if transaction.flags.contains(TransactionFlags::SIGNALING) {
+                    self.revert_deduct_staker_fee(
+                        &mut store,
+                        &staker_address,
+                        transaction.fee,
+                        inner_receipt,
+                        &mut TransactionLog::empty(),
+                    )
+                } else {
+                    self.revert_remove_stake(
+                        &mut store,
+                        &staker_address,
+                        transaction.fee,
+                        inner_receipt,
+                        &mut TransactionLog::empty(),
+                    )
+                }
+                .map(|_| {
+                    self.revert_staker_transaction_claim(
+                        &mut store,
+                        &staker_address,
+                        receipt.claimed,
+                        tx_logger,
+                    )
+                })
             }
         };
 
@@ -554,9 +827,22 @@ impl AccountTransactionInteraction for StakingContract {
 
                 let staker = store.expect_staker(&staker_address)?;
 
+                // Signaling transactions pay their fee from the active balance of the main
+                // delegation.
+                if transaction.flags.contains(TransactionFlags::SIGNALING) {
+                    return reserved_balance.reserve_for(
+                        &staker_address,
+                        staker.primary_balance(),
+                        transaction.total_value(),
+                    );
+                }
+
+                // Verify that the inactive balance of the staker has been released.
+                self.can_remove_stake(&staker, block_state.number)?;
+
                 reserved_balance.reserve_for(
                     &staker_address,
-                    staker.balance,
+                    staker.inactive_balance,
                     transaction.total_value(),
                 )
             }
@@ -703,6 +989,24 @@ impl AccountInherentInteraction for StakingContract {
                 // Since finalized epochs cannot be reverted, we don't need any receipts.
                 Ok(None)
             }
+            Inherent::DistributeReward {
+                validator_address,
+                value,
+            } => {
+                let receipt = self.distribute_reward(
+                    &mut StakingContractStoreWrite::new(&mut data_store),
+                    validator_address,
+                    *value,
+                    block_state.number,
+                )?;
+
+                inherent_logger.push_log(Log::PayoutReward {
+                    to: inherent.target().clone(),
+                    value: *value,
+                });
+
+                Ok(Some(receipt.into()))
+            }
             Inherent::Reward { .. } => Err(AccountError::InvalidForTarget),
         }
     }
@@ -783,6 +1087,24 @@ impl AccountInherentInteraction for StakingContract {
                 // We should not be able to revert finalized epochs or batches!
                 Err(AccountError::InvalidForTarget)
             }
+            Inherent::DistributeReward {
+                validator_address,
+                value,
+            } => {
+                self.revert_distribute_reward(
+                    &mut StakingContractStoreWrite::new(&mut data_store),
+                    validator_address,
+                    *value,
+                    receipt.ok_or(AccountError::InvalidReceipt)?.try_into()?,
+                )?;
+
+                inherent_logger.push_log(Log::PayoutReward {
+                    to: inherent.target().clone(),
+                    value: *value,
+                });
+
+                Ok(())
+            }
             Inherent::Reward { .. } => Err(AccountError::InvalidForTarget),
         }
     }
@@ -9,7 +9,11 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "interaction-traits")]
 use crate::{
     account::staking_contract::{
-        receipts::{DeleteValidatorReceipt, ReactivateValidatorReceipt, UpdateValidatorReceipt},
+        receipts::{
+            DeleteValidatorReceipt, ReactivateValidatorReceipt, UpdateValidatorCommissionReceipt,
+            UpdateValidatorReceipt,
+        },
+        reward_pool::RewardPool,
         store::{
             StakingContractStoreReadOps, StakingContractStoreReadOpsExt, StakingContractStoreWrite,
         },
@@ -186,7 +190,8 @@ impl StakingContract {
         Ok(())
     }
 
-    /// Updates some of the validator details (signing key, voting key, reward address and/or signal data).
+    /// Updates some of the validator details (signing key, voting key, reward address and/or
+    /// signal data).
     pub fn update_validator(
         &mut self,
         store: &mut StakingContractStoreWrite,
@@ -268,6 +273,81 @@ impl StakingContract {
         Ok(())
     }
 
+    /// Sets the commission a validator charges on the rewards of its stakers. Setting a
+    /// commission opts in to distributing the rewards to the stakers, setting None opts out
+    /// again. The reward pool is created on the first opt-in and kept afterwards, such that the
+    /// stakers can still claim the rewards distributed so far.
+    pub fn update_validator_commission(
+        &mut self,
+        store: &mut StakingContractStoreWrite,
+        validator_address: &Address,
+        new_commission: Option<u16>,
+        tx_logger: &mut TransactionLog,
+    ) -> Result<UpdateValidatorCommissionReceipt, AccountError> {
+        // Get the validator.
+        let validator = store.expect_validator(validator_address)?;
+
+        // All checks passed, not allowed to fail from here on!
+
+        let old_pool = store.get_reward_pool(validator_address);
+
+        // Nothing needs to be tracked for validators that never distributed their rewards.
+        if old_pool.is_some() || new_commission.is_some() {
+            // The stake delegated so far is eligible in full when the pool is created.
+            let pool = match old_pool.clone() {
+                Some(pool) => RewardPool {
+                    commission: new_commission,
+                    ..pool
+                },
+                None => RewardPool {
+                    commission: new_commission,
+                    reward_per_stake: 0,
+                    eligible_stake: validator.total_stake - validator.deposit,
+                    pending_stake: vec![],
+                },
+            };
+            store.put_reward_pool(validator_address, pool);
+        }
+
+        tx_logger.push_log(Log::UpdateValidatorCommission {
+            validator_address: validator_address.clone(),
+            old_commission: old_pool.as_ref().and_then(|pool| pool.commission),
+            new_commission,
+        });
+
+        Ok(UpdateValidatorCommissionReceipt { old_pool })
+    }
+
+    /// Reverts updating the commission of a validator.
+    pub fn revert_update_validator_commission(
+        &mut self,
+        store: &mut StakingContractStoreWrite,
+        validator_address: &Address,
+        receipt: UpdateValidatorCommissionReceipt,
+        tx_logger: &mut TransactionLog,
+    ) -> Result<(), AccountError> {
+        // Check that the validator exists.
+        store.expect_validator(validator_address)?;
+
+        let new_commission = store
+            .get_reward_pool(validator_address)
+            .and_then(|pool| pool.commission);
+        let old_commission = receipt.old_pool.as_ref().and_then(|pool| pool.commission);
+
+        match receipt.old_pool {
+            Some(pool) => store.put_reward_pool(validator_address, pool),
+            None => store.remove_reward_pool(validator_address),
+        }
+
+        tx_logger.push_log(Log::UpdateValidatorCommission {
+            validator_address: validator_address.clone(),
+            old_commission,
+            new_commission,
+        });
+
+        Ok(())
+    }
+
     /// Deactivates a validator. It is necessary to retire a validator before dropping it.
     pub fn deactivate_validator(
         &mut self,
@@ -593,6 +673,17 @@ impl StakingContract {
         // Remove the validator entry.
         store.remove_validator(validator_address);
 
+        // Stop distributing rewards. The reward pool is kept such that the stakers can still
+        // claim their pending rewards.
+        let commission = match store.get_reward_pool(validator_address) {
+            Some(mut pool) => {
+                let commission = pool.commission.take();
+                store.put_reward_pool(validator_address, pool);
+                commission
+            }
+            None => None,
+        };
+
         tx_logger.push_log(Log::DeleteValidator {
             validator_address: validator_address.clone(),
             reward_address: validator.reward_address.clone(),
@@ -604,6 +695,7 @@ impl StakingContract {
             voting_key: validator.voting_key,
             reward_address: validator.reward_address,
             signal_data: validator.signal_data,
+            commission,
             inactive_since: validator.inactive_since.unwrap(), // we checked above that this is Some
         })
     }
@@ -643,6 +735,15 @@ impl StakingContract {
             store.remove_tombstone(validator_address);
         }
 
+        // Restore the commission.
+        if let Some(commission) = receipt.commission {
+            let mut pool = store
+                .get_reward_pool(validator_address)
+                .expect("inconsistent contract state");
+            pool.commission = Some(commission);
+            store.put_reward_pool(validator_address, pool);
+        }
+
         tx_logger.push_log(Log::DeleteValidator {
             validator_address: validator_address.clone(),
             reward_address: validator.reward_address.clone(),
@@ -49,7 +49,11 @@ impl Accounts {
     }
 
     /// Initializes the Accounts struct with a given list of accounts.
-    pub fn init(&self, txn: &mut WriteTransactionProxy, genesis_accounts: Vec<TrieItem>) {
+    pub fn init<I: IntoIterator<Item = TrieItem>>(
+        &self,
+        txn: &mut WriteTransactionProxy,
+        genesis_accounts: I,
+    ) {
         self.tree.init(txn, genesis_accounts)
     }
 
@@ -1,3 +1,5 @@
+use std::{fmt, str::FromStr};
+
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
 use nimiq_primitives::{
@@ -9,6 +11,7 @@ use nimiq_transaction::{
     account::htlc_contract::{AnyHash, PreImage},
     Transaction,
 };
+use thiserror::Error;
 
 use crate::TransactionOperationReceipt;
 
@@ -78,6 +81,15 @@ pub enum Log {
         new_reward_address: Option<Address>,
     },
 
+    // Used when a validator changes the commission it charges on the rewards of its stakers. A
+    // commission of None means that the validator doesn't distribute its rewards to its stakers.
+    #[serde(rename_all = "camelCase")]
+    UpdateValidatorCommission {
+        validator_address: Address,
+        old_commission: Option<u16>,
+        new_commission: Option<u16>,
+    },
+
     #[serde(rename_all = "camelCase")]
     ValidatorFeeDeduction {
         validator_address: Address,
@@ -114,6 +126,25 @@ pub enum Log {
         new_validator_address: Option<Address>,
     },
 
+    // Used when a staker changes the part of its balance that is inactive, i.e. being unbonded.
+    #[serde(rename_all = "camelCase")]
+    SetInactiveStake {
+        staker_address: Address,
+        validator_address: Option<Address>,
+        value: Coin,
+        inactive_from: Option<u32>,
+    },
+
+    // Used when a staker changes the stake it delegates to a validator other than its main
+    // delegation. The stake is moved from or to the main delegation.
+    #[serde(rename_all = "camelCase")]
+    SetDelegationStake {
+        staker_address: Address,
+        validator_address: Address,
+        old_stake: Coin,
+        new_stake: Coin,
+    },
+
     #[serde(rename_all = "camelCase")]
     RetireValidator { validator_address: Address },
 
@@ -133,6 +164,15 @@ pub enum Log {
     #[serde(rename_all = "camelCase")]
     PayoutReward { to: Address, value: Coin },
 
+    // Used when a staker claims its share of the rewards distributed by a validator. The claimed
+    // rewards are added to the stake it delegates to that validator.
+    #[serde(rename_all = "camelCase")]
+    StakerReward {
+        staker_address: Address,
+        validator_address: Address,
+        value: Coin,
+    },
+
     #[serde(rename_all = "camelCase")]
     Slash {
         validator_address: Address,
@@ -153,7 +193,165 @@ pub enum Log {
     },
 }
 
+/// The type of a [`Log`], without any of its data. The discriminants are stable and used as keys
+/// in the log index of the history store, so existing values must not be changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[repr(u8)]
+pub enum LogType {
+    PayFee = 0,
+    Transfer = 1,
+    #[serde(rename = "htlc-create")]
+    HTLCCreate = 2,
+    #[serde(rename = "htlc-timeout-resolve")]
+    HTLCTimeoutResolve = 3,
+    #[serde(rename = "htlc-regular-transfer")]
+    HTLCRegularTransfer = 4,
+    #[serde(rename = "htlc-early-resolve")]
+    HTLCEarlyResolve = 5,
+    VestingCreate = 6,
+    CreateValidator = 7,
+    UpdateValidator = 8,
+    ValidatorFeeDeduction = 9,
+    DeactivateValidator = 10,
+    ReactivateValidator = 11,
+    CreateStaker = 12,
+    Stake = 13,
+    StakerFeeDeduction = 14,
+    UpdateStaker = 15,
+    RetireValidator = 16,
+    DeleteValidator = 17,
+    Unstake = 18,
+    PayoutReward = 19,
+    Slash = 20,
+    RevertContract = 21,
+    FailedTransaction = 22,
+    StakerReward = 23,
+    SetInactiveStake = 24,
+    SetDelegationStake = 25,
+    UpdateValidatorCommission = 26,
+}
+
+impl LogType {
+    /// All log types, ordered by their discriminant.
+    pub const ALL: [LogType; 27] = [
+        LogType::PayFee,
+        LogType::Transfer,
+        LogType::HTLCCreate,
+        LogType::HTLCTimeoutResolve,
+        LogType::HTLCRegularTransfer,
+        LogType::HTLCEarlyResolve,
+        LogType::VestingCreate,
+        LogType::CreateValidator,
+        LogType::UpdateValidator,
+        LogType::ValidatorFeeDeduction,
+        LogType::DeactivateValidator,
+        LogType::ReactivateValidator,
+        LogType::CreateStaker,
+        LogType::Stake,
+        LogType::StakerFeeDeduction,
+        LogType::UpdateStaker,
+        LogType::RetireValidator,
+        LogType::DeleteValidator,
+        LogType::Unstake,
+        LogType::PayoutReward,
+        LogType::Slash,
+        LogType::RevertContract,
+        LogType::FailedTransaction,
+        LogType::StakerReward,
+        LogType::SetInactiveStake,
+        LogType::SetDelegationStake,
+        LogType::UpdateValidatorCommission,
+    ];
+
+    /// Returns the name of the log type, as it is serialized.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogType::PayFee => "pay-fee",
+            LogType::Transfer => "transfer",
+            LogType::HTLCCreate => "htlc-create",
+            LogType::HTLCTimeoutResolve => "htlc-timeout-resolve",
+            LogType::HTLCRegularTransfer => "htlc-regular-transfer",
+            LogType::HTLCEarlyResolve => "htlc-early-resolve",
+            LogType::VestingCreate => "vesting-create",
+            LogType::CreateValidator => "create-validator",
+            LogType::UpdateValidator => "update-validator",
+            LogType::ValidatorFeeDeduction => "validator-fee-deduction",
+            LogType::DeactivateValidator => "deactivate-validator",
+            LogType::ReactivateValidator => "reactivate-validator",
+            LogType::CreateStaker => "create-staker",
+            LogType::Stake => "stake",
+            LogType::StakerFeeDeduction => "staker-fee-deduction",
+            LogType::UpdateStaker => "update-staker",
+            LogType::RetireValidator => "retire-validator",
+            LogType::DeleteValidator => "delete-validator",
+            LogType::Unstake => "unstake",
+            LogType::PayoutReward => "payout-reward",
+            LogType::Slash => "slash",
+            LogType::RevertContract => "revert-contract",
+            LogType::FailedTransaction => "failed-transaction",
+            LogType::StakerReward => "staker-reward",
+            LogType::SetInactiveStake => "set-inactive-stake",
+            LogType::SetDelegationStake => "set-delegation-stake",
+            LogType::UpdateValidatorCommission => "update-validator-commission",
+        }
+    }
+}
+
+impl fmt::Display for LogType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Unknown log type: {0}")]
+pub struct UnknownLogType(pub String);
+
+impl FromStr for LogType {
+    type Err = UnknownLogType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LogType::ALL
+            .into_iter()
+            .find(|log_type| log_type.name() == s)
+            .ok_or_else(|| UnknownLogType(s.to_string()))
+    }
+}
+
 impl Log {
+    pub fn log_type(&self) -> LogType {
+        match self {
+            Log::PayFee { .. } => LogType::PayFee,
+            Log::Transfer { .. } => LogType::Transfer,
+            Log::HTLCCreate { .. } => LogType::HTLCCreate,
+            Log::HTLCTimeoutResolve { .. } => LogType::HTLCTimeoutResolve,
+            Log::HTLCRegularTransfer { .. } => LogType::HTLCRegularTransfer,
+            Log::HTLCEarlyResolve { .. } => LogType::HTLCEarlyResolve,
+            Log::VestingCreate { .. } => LogType::VestingCreate,
+            Log::CreateValidator { .. } => LogType::CreateValidator,
+            Log::UpdateValidator { .. } => LogType::UpdateValidator,
+            Log::UpdateValidatorCommission { .. } => LogType::UpdateValidatorCommission,
+            Log::ValidatorFeeDeduction { .. } => LogType::ValidatorFeeDeduction,
+            Log::DeactivateValidator { .. } => LogType::DeactivateValidator,
+            Log::ReactivateValidator { .. } => LogType::ReactivateValidator,
+            Log::CreateStaker { .. } => LogType::CreateStaker,
+            Log::Stake { .. } => LogType::Stake,
+            Log::StakerFeeDeduction { .. } => LogType::StakerFeeDeduction,
+            Log::UpdateStaker { .. } => LogType::UpdateStaker,
+            Log::SetInactiveStake { .. } => LogType::SetInactiveStake,
+            Log::SetDelegationStake { .. } => LogType::SetDelegationStake,
+            Log::RetireValidator { .. } => LogType::RetireValidator,
+            Log::DeleteValidator { .. } => LogType::DeleteValidator,
+            Log::Unstake { .. } => LogType::Unstake,
+            Log::PayoutReward { .. } => LogType::PayoutReward,
+            Log::StakerReward { .. } => LogType::StakerReward,
+            Log::Slash { .. } => LogType::Slash,
+            Log::RevertContract { .. } => LogType::RevertContract,
+            Log::FailedTransaction { .. } => LogType::FailedTransaction,
+        }
+    }
+
     pub fn transfer_log(transaction: &Transaction) -> Self {
         Log::Transfer {
             from: transaction.sender.clone(),
@@ -212,6 +410,9 @@ impl Log {
                         .map(|new_reward_address| new_reward_address == address)
                         .unwrap_or(false)
             }
+            Log::UpdateValidatorCommission {
+                validator_address, ..
+            } => validator_address == address,
             Log::DeactivateValidator { validator_address } => validator_address == address,
             Log::ReactivateValidator { validator_address } => validator_address == address,
             Log::CreateStaker {
@@ -251,6 +452,22 @@ impl Log {
                         .map(|new_address| new_address == address)
                         .unwrap_or(false)
             }
+            Log::SetInactiveStake {
+                staker_address,
+                validator_address,
+                ..
+            } => {
+                staker_address == address
+                    || validator_address
+                        .as_ref()
+                        .map(|validator_address| validator_address == address)
+                        .unwrap_or(false)
+            }
+            Log::SetDelegationStake {
+                staker_address,
+                validator_address,
+                ..
+            } => staker_address == address || validator_address == address,
             Log::RetireValidator { validator_address } => validator_address == address,
             Log::DeleteValidator {
                 validator_address,
@@ -268,6 +485,11 @@ impl Log {
                         .unwrap_or(false)
             }
             Log::PayoutReward { to, .. } => to == address,
+            Log::StakerReward {
+                staker_address,
+                validator_address,
+                ..
+            } => staker_address == address || validator_address == address,
             Log::Slash {
                 validator_address, ..
             } => validator_address == address,
@@ -448,6 +670,10 @@ impl BlockLogger {
         }
     }
 
+    pub fn block_log(&self) -> &BlockLog {
+        &self.block_log
+    }
+
     pub fn build(self, size: u64) -> BlockLog {
         let mut block_log = self.block_log;
         match block_log {
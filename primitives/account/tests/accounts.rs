@@ -886,8 +886,9 @@ fn can_revert_transactions() {
         test_rng(false),
     );
 
+    // Stake that is deactivated at genesis is released after the second epoch.
     let block_state = BlockState::new(
-        Policy::blocks_per_epoch() + Policy::blocks_per_batch() + 1,
+        2 * Policy::blocks_per_epoch() + Policy::blocks_per_batch() + 1,
         10,
     );
 
@@ -912,6 +913,8 @@ fn can_revert_transactions() {
             IncomingType::CreateStaker,
             IncomingType::AddStake,
             IncomingType::UpdateStaker,
+            IncomingType::SetInactiveStake,
+            IncomingType::SetDelegationStake,
         ] {
             // Don't send from the staking contract to the staking contract.
             if matches!(
@@ -927,6 +930,8 @@ fn can_revert_transactions() {
                     | IncomingType::CreateStaker
                     | IncomingType::AddStake
                     | IncomingType::UpdateStaker
+                    | IncomingType::SetInactiveStake
+                    | IncomingType::SetDelegationStake
             ) {
                 continue;
             }
@@ -947,6 +952,8 @@ fn can_revert_transactions() {
                             | IncomingType::DeactivateValidator
                             | IncomingType::ReactivateValidator
                             | IncomingType::UpdateStaker
+                            | IncomingType::SetInactiveStake
+                            | IncomingType::SetDelegationStake
                     )
                 {
                     continue;
@@ -1142,6 +1142,7 @@ fn delete_validator_works() {
         voting_key: voting_key.clone(),
         reward_address: reward_address.clone(),
         signal_data: None,
+        commission: None,
         inactive_since: 2,
     };
     assert_eq!(receipt, Some(expected_receipt.into()));
@@ -1190,10 +1191,35 @@ fn delete_validator_works() {
         Coin::from_u64_unchecked(150_000_000)
     );
 
+    // Deactivate the stake of the staker.
+    let mut data_store_write = data_store.write(&mut db_txn);
+    let mut store = StakingContractStoreWrite::new(&mut data_store_write);
+
+    let deactivate_receipt = staking_contract
+        .set_inactive_stake(
+            &mut store,
+            &staker_address,
+            Coin::from_u64_unchecked(150_000_000),
+            block_state.number,
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to deactivate stake");
+
+    assert_eq!(
+        staking_contract.get_tombstone(&data_store.read(&db_txn), &validator_address),
+        Some(Tombstone {
+            remaining_stake: Coin::ZERO,
+            num_remaining_stakers: 1,
+        })
+    );
+
     // Remove the staker.
     let unstake_tx = make_unstake_transaction(150_000_000);
 
-    let unstake_block_state = BlockState::new(block_state.number + 1, block_state.time + 1);
+    let unstake_block_state = BlockState::new(
+        Policy::election_block_after(block_state.number) + Policy::blocks_per_epoch() + 1,
+        block_state.time + 1,
+    );
 
     let unstake_receipt = staking_contract
         .commit_outgoing_transaction(
@@ -1204,10 +1230,14 @@ fn delete_validator_works() {
         )
         .expect("Failed to commit transaction");
 
-    let expected_receipt = StakerReceipt {
+    let expected_receipt = RemoveStakeReceipt {
         delegation: Some(validator_address.clone()),
+        inactive_from: Some(block_state.number),
     };
-    assert_eq!(unstake_receipt, Some(expected_receipt.into()));
+    assert_eq!(
+        unstake_receipt,
+        staker_receipt(Some(expected_receipt.into()))
+    );
 
     assert_eq!(
         staking_contract.get_staker(&data_store.read(&db_txn), &staker_address),
@@ -1232,6 +1262,28 @@ fn delete_validator_works() {
         )
         .expect("Failed to revert transaction");
 
+    assert_eq!(
+        staking_contract.get_tombstone(&data_store.read(&db_txn), &validator_address),
+        Some(Tombstone {
+            remaining_stake: Coin::ZERO,
+            num_remaining_stakers: 1,
+        })
+    );
+
+    // Revert the deactivation of the stake.
+    let mut data_store_write = data_store.write(&mut db_txn);
+    let mut store = StakingContractStoreWrite::new(&mut data_store_write);
+
+    staking_contract
+        .revert_set_inactive_stake(
+            &mut store,
+            &staker_address,
+            Coin::from_u64_unchecked(150_000_000),
+            deactivate_receipt,
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to revert deactivating stake");
+
     assert_eq!(
         staking_contract.get_tombstone(&data_store.read(&db_txn), &validator_address),
         Some(Tombstone {
@@ -1346,7 +1398,7 @@ fn create_staker_works() {
         )
         .expect("Failed to commit transaction");
 
-    assert_eq!(receipt, None);
+    assert_eq!(receipt, staker_receipt(None));
     assert_eq!(
         tx_logger.logs,
         vec![Log::CreateStaker {
@@ -1405,7 +1457,7 @@ fn create_staker_works() {
         .revert_incoming_transaction(
             &tx,
             &block_state,
-            None,
+            receipt,
             data_store.write(&mut db_txn),
             &mut tx_logger,
         )
@@ -1480,7 +1532,7 @@ fn stake_works() {
         )
         .expect("Failed to commit transaction");
 
-    assert_eq!(receipt, None);
+    assert_eq!(receipt, staker_receipt(None));
     assert_eq!(
         tx_logger.logs,
         vec![Log::Stake {
@@ -1525,7 +1577,7 @@ fn stake_works() {
         .revert_incoming_transaction(
             &tx,
             &block_state,
-            None,
+            receipt,
             data_store.write(&mut db_txn),
             &mut tx_logger,
         )
@@ -1609,6 +1661,7 @@ fn update_staker_works() {
     let tx = make_signed_incoming_transaction(
         IncomingStakingTransactionData::UpdateStaker {
             new_delegation: Some(other_validator_address.clone()),
+            delegation_stake: None,
             proof: SignatureProof::default(),
         },
         0,
@@ -1628,7 +1681,7 @@ fn update_staker_works() {
     let expected_receipt = StakerReceipt {
         delegation: Some(validator_address.clone()),
     };
-    assert_eq!(receipt, Some(expected_receipt.into()));
+    assert_eq!(receipt, staker_receipt(Some(expected_receipt.into())));
 
     assert_eq!(
         tx_logger.logs,
@@ -1685,6 +1738,7 @@ fn update_staker_works() {
     let tx = make_signed_incoming_transaction(
         IncomingStakingTransactionData::UpdateStaker {
             new_delegation: Some(staker_address.clone()),
+            delegation_stake: None,
             proof: SignatureProof::default(),
         },
         0,
@@ -1707,6 +1761,7 @@ fn update_staker_works() {
     let tx = make_signed_incoming_transaction(
         IncomingStakingTransactionData::UpdateStaker {
             new_delegation: None,
+            delegation_stake: None,
             proof: SignatureProof::default(),
         },
         0,
@@ -1726,7 +1781,7 @@ fn update_staker_works() {
     let expected_receipt = StakerReceipt {
         delegation: Some(other_validator_address.clone()),
     };
-    assert_eq!(receipt, Some(expected_receipt.into()));
+    assert_eq!(receipt, staker_receipt(Some(expected_receipt.into())));
 
     assert_eq!(
         tx_logger.logs,
@@ -1808,6 +1863,7 @@ fn update_staker_works() {
     let tx = make_signed_incoming_transaction(
         IncomingStakingTransactionData::UpdateStaker {
             new_delegation: None,
+            delegation_stake: None,
             proof: SignatureProof::default(),
         },
         0,
@@ -1828,7 +1884,7 @@ fn update_staker_works() {
 }
 
 #[test]
-fn unstake_works() {
+fn set_delegation_stake_works() {
     let env = VolatileDatabase::new(20).unwrap();
     let accounts = Accounts::new(env.clone());
     let data_store = accounts.data_store(&Policy::STAKING_CONTRACT_ADDRESS);
@@ -1838,14 +1894,64 @@ fn unstake_works() {
 
     let mut staking_contract = make_sample_contract(data_store.write(&mut db_txn), true);
 
+    let staker_keypair = ed25519_key_pair(STAKER_PRIVATE_KEY);
     let staker_address = staker_address();
     let validator_address = validator_address();
+    let other_validator_address = Address::from([69u8; 20]);
+    let signing_key = ed25519_public_key(VALIDATOR_SIGNING_KEY);
+    let voting_key = bls_public_key(VALIDATOR_VOTING_KEY);
 
-    // Doesn't work if the value is greater than the balance.
-    let tx = make_unstake_transaction(200_000_000);
+    // To begin with, add another validator.
+    let mut data_store_write = data_store.write(&mut db_txn);
+    let mut store = StakingContractStoreWrite::new(&mut data_store_write);
+
+    staking_contract
+        .create_validator(
+            &mut store,
+            &other_validator_address,
+            signing_key,
+            voting_key,
+            other_validator_address.clone(),
+            None,
+            Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT),
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to create validator");
+
+    // Doesn't work for the validator of the main delegation.
+    let tx = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::UpdateStaker {
+            new_delegation: Some(validator_address.clone()),
+            delegation_stake: Some(Coin::from_u64_unchecked(50_000_000)),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &staker_keypair,
+    );
 
     assert_eq!(
-        staking_contract.commit_outgoing_transaction(
+        staking_contract.commit_incoming_transaction(
+            &tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty()
+        ),
+        Err(AccountError::InvalidForRecipient)
+    );
+
+    // Doesn't work if the stake is greater than the balance.
+    let tx = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::UpdateStaker {
+            new_delegation: Some(other_validator_address.clone()),
+            delegation_stake: Some(Coin::from_u64_unchecked(200_000_000)),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &staker_keypair,
+    );
+
+    assert_eq!(
+        staking_contract.commit_incoming_transaction(
             &tx,
             &block_state,
             data_store.write(&mut db_txn),
@@ -1858,11 +1964,19 @@ fn unstake_works() {
     );
 
     // Works in the valid case.
-    let tx = make_unstake_transaction(100_000_000);
+    let tx = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::UpdateStaker {
+            new_delegation: Some(other_validator_address.clone()),
+            delegation_stake: Some(Coin::from_u64_unchecked(50_000_000)),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &staker_keypair,
+    );
 
     let mut tx_logger = TransactionLog::empty();
     let receipt = staking_contract
-        .commit_outgoing_transaction(
+        .commit_incoming_transaction(
             &tx,
             &block_state,
             data_store.write(&mut db_txn),
@@ -1870,128 +1984,188 @@ fn unstake_works() {
         )
         .expect("Failed to commit transaction");
 
-    assert_eq!(receipt, None);
+    let expected_receipt = DelegationReceipt {
+        old_stake: Coin::ZERO,
+    };
+    assert_eq!(receipt, staker_receipt(Some(expected_receipt.into())));
+
     assert_eq!(
         tx_logger.logs,
-        vec![
-            Log::PayFee {
-                from: tx.sender.clone(),
-                fee: tx.fee,
-            },
-            Log::Transfer {
-                from: tx.sender.clone(),
-                to: tx.recipient.clone(),
-                amount: tx.value,
-                data: None,
-            },
-            Log::Unstake {
-                staker_address: staker_address.clone(),
-                validator_address: Some(validator_address.clone()),
-                value: Coin::from_u64_unchecked(100_000_000),
-            }
-        ]
+        vec![Log::SetDelegationStake {
+            staker_address: staker_address.clone(),
+            validator_address: other_validator_address.clone(),
+            old_stake: Coin::ZERO,
+            new_stake: Coin::from_u64_unchecked(50_000_000),
+        }]
     );
 
     let staker = staking_contract
         .get_staker(&data_store.read(&db_txn), &staker_address)
         .expect("Staker should exist");
 
-    assert_eq!(staker.address, staker_address);
-    assert_eq!(staker.balance, Coin::from_u64_unchecked(50_000_000));
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(150_000_000));
+    assert_eq!(staker.split_balance, Coin::from_u64_unchecked(50_000_000));
     assert_eq!(staker.delegation, Some(validator_address.clone()));
 
+    assert_eq!(
+        staking_contract.get_staker_delegations(&data_store.read(&db_txn), &staker_address),
+        vec![StakerDelegation {
+            staker_address: staker_address.clone(),
+            validator_address: other_validator_address.clone(),
+            stake: Coin::from_u64_unchecked(50_000_000),
+        }]
+    );
+
     let validator = staking_contract
         .get_validator(&data_store.read(&db_txn), &validator_address)
         .expect("Validator should exist");
 
     assert_eq!(
         validator.total_stake,
-        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 50_000_000)
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 100_000_000)
     );
     assert_eq!(validator.num_stakers, 1);
 
+    let other_validator = staking_contract
+        .get_validator(&data_store.read(&db_txn), &other_validator_address)
+        .expect("Validator should exist");
+
     assert_eq!(
-        staking_contract.balance,
+        other_validator.total_stake,
         Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 50_000_000)
     );
+    assert_eq!(other_validator.num_stakers, 1);
 
     assert_eq!(
-        staking_contract.active_validators.get(&validator_address),
+        staking_contract
+            .active_validators
+            .get(&other_validator_address),
         Some(&Coin::from_u64_unchecked(
             Policy::VALIDATOR_DEPOSIT + 50_000_000
         ))
     );
 
-    // Works when removing the entire balance.
-    let tx = make_unstake_transaction(50_000_000);
+    assert!(staking_contract.is_delegating_to(
+        &data_store.read(&db_txn),
+        &staker_address,
+        &other_validator_address
+    ));
+    assert_eq!(
+        staking_contract
+            .get_stakers_for_validator(&data_store.read(&db_txn), &other_validator_address),
+        vec![staker]
+    );
 
-    let block_state = BlockState::new(3, 3);
+    // The main delegation can't be changed to the additional validator.
+    let tx_update = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::UpdateStaker {
+            new_delegation: Some(other_validator_address.clone()),
+            delegation_stake: None,
+            proof: SignatureProof::default(),
+        },
+        0,
+        &staker_keypair,
+    );
 
-    let mut tx_logger = TransactionLog::empty();
-    let receipt = staking_contract
-        .commit_outgoing_transaction(
-            &tx,
+    assert_eq!(
+        staking_contract.commit_incoming_transaction(
+            &tx_update,
             &block_state,
             data_store.write(&mut db_txn),
-            &mut tx_logger,
-        )
-        .expect("Failed to commit transaction");
+            &mut TransactionLog::empty()
+        ),
+        Err(AccountError::InvalidForRecipient)
+    );
 
-    let expected_receipt = StakerReceipt {
-        delegation: Some(validator_address.clone()),
-    };
-    assert_eq!(receipt, Some(expected_receipt.into()));
+    // Only the stake of the main delegation can be deactivated.
+    let tx_inactive = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::SetInactiveStake {
+            new_inactive_balance: Coin::from_u64_unchecked(120_000_000),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &staker_keypair,
+    );
 
     assert_eq!(
-        tx_logger.logs,
-        vec![
-            Log::PayFee {
-                from: tx.sender.clone(),
-                fee: tx.fee,
-            },
-            Log::Transfer {
-                from: tx.sender.clone(),
-                to: tx.recipient.clone(),
-                amount: tx.value,
-                data: None,
-            },
-            Log::Unstake {
-                staker_address: staker_address.clone(),
-                validator_address: Some(validator_address.clone()),
-                value: Coin::from_u64_unchecked(50_000_000),
-            }
-        ]
+        staking_contract.commit_incoming_transaction(
+            &tx_inactive,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty()
+        ),
+        Err(AccountError::InsufficientFunds {
+            needed: Coin::from_u64_unchecked(120_000_000),
+            balance: Coin::from_u64_unchecked(100_000_000)
+        })
     );
 
-    assert_eq!(
-        staking_contract.get_staker(&data_store.read(&db_txn), &staker_address),
-        None
+    // Setting the stake to zero removes the delegation.
+    let tx2 = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::UpdateStaker {
+            new_delegation: Some(other_validator_address.clone()),
+            delegation_stake: Some(Coin::ZERO),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &staker_keypair,
     );
 
-    let validator = staking_contract
-        .get_validator(&data_store.read(&db_txn), &validator_address)
+    let receipt2 = staking_contract
+        .commit_incoming_transaction(
+            &tx2,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to commit transaction");
+
+    let expected_receipt = DelegationReceipt {
+        old_stake: Coin::from_u64_unchecked(50_000_000),
+    };
+    assert_eq!(receipt2, staker_receipt(Some(expected_receipt.into())));
+
+    let staker = staking_contract
+        .get_staker(&data_store.read(&db_txn), &staker_address)
+        .expect("Staker should exist");
+
+    assert_eq!(staker.split_balance, Coin::ZERO);
+    assert!(staking_contract
+        .get_staker_delegations(&data_store.read(&db_txn), &staker_address)
+        .is_empty());
+
+    let other_validator = staking_contract
+        .get_validator(&data_store.read(&db_txn), &other_validator_address)
         .expect("Validator should exist");
 
     assert_eq!(
-        validator.total_stake,
+        other_validator.total_stake,
         Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT)
     );
-    assert_eq!(validator.num_stakers, 0);
+    assert_eq!(other_validator.num_stakers, 0);
 
-    assert_eq!(
-        staking_contract.balance,
-        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT)
-    );
+    // Revert both transactions.
+    staking_contract
+        .revert_incoming_transaction(
+            &tx2,
+            &block_state,
+            receipt2,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to revert transaction");
 
     assert_eq!(
-        staking_contract.active_validators.get(&validator_address),
-        Some(&Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT))
+        staking_contract
+            .get_staker(&data_store.read(&db_txn), &staker_address)
+            .expect("Staker should exist")
+            .split_balance,
+        Coin::from_u64_unchecked(50_000_000)
     );
 
-    // Revert the transaction.
     let mut tx_logger = TransactionLog::empty();
     staking_contract
-        .revert_outgoing_transaction(
+        .revert_incoming_transaction(
             &tx,
             &block_state,
             receipt,
@@ -2002,82 +2176,1376 @@ fn unstake_works() {
 
     assert_eq!(
         tx_logger.logs,
-        vec![
-            Log::Unstake {
-                staker_address: staker_address.clone(),
-                validator_address: Some(validator_address.clone()),
-                value: Coin::from_u64_unchecked(50_000_000),
-            },
-            Log::Transfer {
+        vec![Log::SetDelegationStake {
+            staker_address: staker_address.clone(),
+            validator_address: other_validator_address.clone(),
+            old_stake: Coin::ZERO,
+            new_stake: Coin::from_u64_unchecked(50_000_000),
+        }]
+    );
+
+    let staker = staking_contract
+        .get_staker(&data_store.read(&db_txn), &staker_address)
+        .expect("Staker should exist");
+
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(150_000_000));
+    assert_eq!(staker.split_balance, Coin::ZERO);
+    assert!(staking_contract
+        .get_staker_delegations(&data_store.read(&db_txn), &staker_address)
+        .is_empty());
+
+    let validator = staking_contract
+        .get_validator(&data_store.read(&db_txn), &validator_address)
+        .expect("Validator should exist");
+
+    assert_eq!(
+        validator.total_stake,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 150_000_000)
+    );
+
+    let other_validator = staking_contract
+        .get_validator(&data_store.read(&db_txn), &other_validator_address)
+        .expect("Validator should exist");
+
+    assert_eq!(
+        other_validator.total_stake,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT)
+    );
+    assert_eq!(other_validator.num_stakers, 0);
+}
+
+#[test]
+fn set_inactive_stake_works() {
+    let env = VolatileDatabase::new(20).unwrap();
+    let accounts = Accounts::new(env.clone());
+    let data_store = accounts.data_store(&Policy::STAKING_CONTRACT_ADDRESS);
+    let block_state = BlockState::new(2, 2);
+    let mut db_txn = env.write_transaction();
+    let mut db_txn = (&mut db_txn).into();
+
+    let mut staking_contract = make_sample_contract(data_store.write(&mut db_txn), true);
+
+    let staker_keypair = ed25519_key_pair(STAKER_PRIVATE_KEY);
+    let staker_address = staker_address();
+    let validator_address = validator_address();
+
+    // Doesn't work if the value is greater than the balance.
+    let tx = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::SetInactiveStake {
+            new_inactive_balance: Coin::from_u64_unchecked(200_000_000),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &staker_keypair,
+    );
+
+    assert_eq!(
+        staking_contract.commit_incoming_transaction(
+            &tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty()
+        ),
+        Err(AccountError::InsufficientFunds {
+            needed: Coin::from_u64_unchecked(200_000_000),
+            balance: Coin::from_u64_unchecked(150_000_000)
+        })
+    );
+
+    // Works in the valid case.
+    let tx = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::SetInactiveStake {
+            new_inactive_balance: Coin::from_u64_unchecked(100_000_000),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &staker_keypair,
+    );
+
+    let mut tx_logger = TransactionLog::empty();
+    let receipt = staking_contract
+        .commit_incoming_transaction(
+            &tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut tx_logger,
+        )
+        .expect("Failed to commit transaction");
+
+    let expected_receipt = SetInactiveStakeReceipt {
+        old_inactive_balance: Coin::ZERO,
+        old_inactive_from: None,
+    };
+    assert_eq!(receipt, staker_receipt(Some(expected_receipt.into())));
+
+    assert_eq!(
+        tx_logger.logs,
+        vec![Log::SetInactiveStake {
+            staker_address: staker_address.clone(),
+            validator_address: Some(validator_address.clone()),
+            value: Coin::from_u64_unchecked(100_000_000),
+            inactive_from: Some(2),
+        }]
+    );
+
+    let staker = staking_contract
+        .get_staker(&data_store.read(&db_txn), &staker_address)
+        .expect("Staker should exist");
+
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(50_000_000));
+    assert_eq!(
+        staker.inactive_balance,
+        Coin::from_u64_unchecked(100_000_000)
+    );
+    assert_eq!(staker.inactive_from, Some(2));
+    assert_eq!(
+        staker.inactive_release(),
+        Some(2 * Policy::blocks_per_epoch())
+    );
+
+    let validator = staking_contract
+        .get_validator(&data_store.read(&db_txn), &validator_address)
+        .expect("Validator should exist");
+
+    assert_eq!(
+        validator.total_stake,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 50_000_000)
+    );
+    assert_eq!(validator.num_stakers, 1);
+
+    assert_eq!(
+        staking_contract.active_validators.get(&validator_address),
+        Some(&Coin::from_u64_unchecked(
+            Policy::VALIDATOR_DEPOSIT + 50_000_000
+        ))
+    );
+
+    // The inactive stake is still held by the contract.
+    assert_eq!(
+        staking_contract.balance,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 150_000_000)
+    );
+
+    // Reactivating part of the inactive stake doesn't restart the unbonding period.
+    let tx2 = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::SetInactiveStake {
+            new_inactive_balance: Coin::from_u64_unchecked(30_000_000),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &staker_keypair,
+    );
+
+    let block_state2 = BlockState::new(3, 3);
+    let receipt2 = staking_contract
+        .commit_incoming_transaction(
+            &tx2,
+            &block_state2,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to commit transaction");
+
+    let staker = staking_contract
+        .get_staker(&data_store.read(&db_txn), &staker_address)
+        .expect("Staker should exist");
+
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(120_000_000));
+    assert_eq!(
+        staker.inactive_balance,
+        Coin::from_u64_unchecked(30_000_000)
+    );
+    assert_eq!(staker.inactive_from, Some(2));
+
+    let validator = staking_contract
+        .get_validator(&data_store.read(&db_txn), &validator_address)
+        .expect("Validator should exist");
+
+    assert_eq!(
+        validator.total_stake,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 120_000_000)
+    );
+
+    // Revert both transactions.
+    staking_contract
+        .revert_incoming_transaction(
+            &tx2,
+            &block_state2,
+            receipt2,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to revert transaction");
+
+    let mut tx_logger = TransactionLog::empty();
+    staking_contract
+        .revert_incoming_transaction(
+            &tx,
+            &block_state,
+            receipt,
+            data_store.write(&mut db_txn),
+            &mut tx_logger,
+        )
+        .expect("Failed to revert transaction");
+
+    assert_eq!(
+        tx_logger.logs,
+        vec![Log::SetInactiveStake {
+            staker_address: staker_address.clone(),
+            validator_address: Some(validator_address.clone()),
+            value: Coin::from_u64_unchecked(100_000_000),
+            inactive_from: Some(2),
+        }]
+    );
+
+    let staker = staking_contract
+        .get_staker(&data_store.read(&db_txn), &staker_address)
+        .expect("Staker should exist");
+
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(150_000_000));
+    assert_eq!(staker.inactive_balance, Coin::ZERO);
+    assert_eq!(staker.inactive_from, None);
+
+    let validator = staking_contract
+        .get_validator(&data_store.read(&db_txn), &validator_address)
+        .expect("Validator should exist");
+
+    assert_eq!(
+        validator.total_stake,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 150_000_000)
+    );
+
+    assert_eq!(
+        staking_contract.active_validators.get(&validator_address),
+        Some(&Coin::from_u64_unchecked(
+            Policy::VALIDATOR_DEPOSIT + 150_000_000
+        ))
+    );
+}
+
+#[test]
+fn unstake_works() {
+    let env = VolatileDatabase::new(20).unwrap();
+    let accounts = Accounts::new(env.clone());
+    let data_store = accounts.data_store(&Policy::STAKING_CONTRACT_ADDRESS);
+    let block_state = BlockState::new(2, 2);
+    let mut db_txn = env.write_transaction();
+    let mut db_txn = (&mut db_txn).into();
+
+    let mut staking_contract = make_sample_contract(data_store.write(&mut db_txn), true);
+
+    let staker_address = staker_address();
+    let validator_address = validator_address();
+
+    // Doesn't work without inactive balance.
+    let tx = make_unstake_transaction(50_000_000);
+
+    assert_eq!(
+        staking_contract.commit_outgoing_transaction(
+            &tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty()
+        ),
+        Err(AccountError::InvalidForSender)
+    );
+
+    // Deactivate part of the stake.
+    let mut data_store_write = data_store.write(&mut db_txn);
+    let mut store = StakingContractStoreWrite::new(&mut data_store_write);
+
+    staking_contract
+        .set_inactive_stake(
+            &mut store,
+            &staker_address,
+            Coin::from_u64_unchecked(100_000_000),
+            block_state.number,
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to deactivate stake");
+
+    // Doesn't work before the inactive balance is released.
+    assert_eq!(
+        staking_contract.commit_outgoing_transaction(
+            &tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty()
+        ),
+        Err(AccountError::InvalidForSender)
+    );
+
+    let block_state = BlockState::new(2 * Policy::blocks_per_epoch(), 3);
+
+    assert_eq!(
+        staking_contract.commit_outgoing_transaction(
+            &tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty()
+        ),
+        Err(AccountError::InvalidForSender)
+    );
+
+    // Doesn't work if the value is greater than the inactive balance.
+    let block_state = BlockState::new(2 * Policy::blocks_per_epoch() + 1, 4);
+    let tx = make_unstake_transaction(150_000_000);
+
+    assert_eq!(
+        staking_contract.commit_outgoing_transaction(
+            &tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty()
+        ),
+        Err(AccountError::InsufficientFunds {
+            needed: Coin::from_u64_unchecked(150_000_000),
+            balance: Coin::from_u64_unchecked(100_000_000)
+        })
+    );
+
+    // Works in the valid case.
+    let tx = make_unstake_transaction(60_000_000);
+
+    let mut tx_logger = TransactionLog::empty();
+    let receipt = staking_contract
+        .commit_outgoing_transaction(
+            &tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut tx_logger,
+        )
+        .expect("Failed to commit transaction");
+
+    assert_eq!(receipt, staker_receipt(None));
+    assert_eq!(
+        tx_logger.logs,
+        vec![
+            Log::PayFee {
+                from: tx.sender.clone(),
+                fee: tx.fee,
+            },
+            Log::Transfer {
+                from: tx.sender.clone(),
+                to: tx.recipient.clone(),
+                amount: tx.value,
+                data: None,
+            },
+            Log::Unstake {
+                staker_address: staker_address.clone(),
+                validator_address: Some(validator_address.clone()),
+                value: Coin::from_u64_unchecked(60_000_000),
+            }
+        ]
+    );
+
+    let staker = staking_contract
+        .get_staker(&data_store.read(&db_txn), &staker_address)
+        .expect("Staker should exist");
+
+    assert_eq!(staker.address, staker_address);
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(50_000_000));
+    assert_eq!(
+        staker.inactive_balance,
+        Coin::from_u64_unchecked(40_000_000)
+    );
+    assert_eq!(staker.inactive_from, Some(2));
+    assert_eq!(staker.delegation, Some(validator_address.clone()));
+
+    // The inactive balance doesn't count towards the stake of the validator.
+    let validator = staking_contract
+        .get_validator(&data_store.read(&db_txn), &validator_address)
+        .expect("Validator should exist");
+
+    assert_eq!(
+        validator.total_stake,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 50_000_000)
+    );
+    assert_eq!(validator.num_stakers, 1);
+
+    assert_eq!(
+        staking_contract.balance,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 90_000_000)
+    );
+
+    assert_eq!(
+        staking_contract.active_validators.get(&validator_address),
+        Some(&Coin::from_u64_unchecked(
+            Policy::VALIDATOR_DEPOSIT + 50_000_000
+        ))
+    );
+
+    // Works when removing the entire inactive balance.
+    let tx = make_unstake_transaction(40_000_000);
+
+    let receipt = staking_contract
+        .commit_outgoing_transaction(
+            &tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to commit transaction");
+
+    let expected_receipt = RemoveStakeReceipt {
+        delegation: Some(validator_address.clone()),
+        inactive_from: Some(2),
+    };
+    assert_eq!(receipt, staker_receipt(Some(expected_receipt.into())));
+
+    let staker = staking_contract
+        .get_staker(&data_store.read(&db_txn), &staker_address)
+        .expect("Staker should exist");
+
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(50_000_000));
+    assert_eq!(staker.inactive_balance, Coin::ZERO);
+    assert_eq!(staker.inactive_from, None);
+
+    // Revert the transaction.
+    staking_contract
+        .revert_outgoing_transaction(
+            &tx,
+            &block_state,
+            receipt,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to revert transaction");
+
+    let staker = staking_contract
+        .get_staker(&data_store.read(&db_txn), &staker_address)
+        .expect("Staker should exist");
+
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(50_000_000));
+    assert_eq!(
+        staker.inactive_balance,
+        Coin::from_u64_unchecked(40_000_000)
+    );
+    assert_eq!(staker.inactive_from, Some(2));
+
+    // Deactivate the rest of the stake. This restarts the unbonding period.
+    let mut data_store_write = data_store.write(&mut db_txn);
+    let mut store = StakingContractStoreWrite::new(&mut data_store_write);
+
+    staking_contract
+        .set_inactive_stake(
+            &mut store,
+            &staker_address,
+            Coin::from_u64_unchecked(90_000_000),
+            block_state.number,
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to deactivate stake");
+
+    let tx = make_unstake_transaction(90_000_000);
+
+    assert_eq!(
+        staking_contract.commit_outgoing_transaction(
+            &tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty()
+        ),
+        Err(AccountError::InvalidForSender)
+    );
+
+    // Works when removing the entire balance.
+    let block_state = BlockState::new(4 * Policy::blocks_per_epoch() + 1, 5);
+
+    let mut tx_logger = TransactionLog::empty();
+    let receipt = staking_contract
+        .commit_outgoing_transaction(
+            &tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut tx_logger,
+        )
+        .expect("Failed to commit transaction");
+
+    let expected_receipt = RemoveStakeReceipt {
+        delegation: Some(validator_address.clone()),
+        inactive_from: Some(2 * Policy::blocks_per_epoch() + 1),
+    };
+    assert_eq!(receipt, staker_receipt(Some(expected_receipt.into())));
+
+    assert_eq!(
+        tx_logger.logs,
+        vec![
+            Log::PayFee {
+                from: tx.sender.clone(),
+                fee: tx.fee,
+            },
+            Log::Transfer {
+                from: tx.sender.clone(),
+                to: tx.recipient.clone(),
+                amount: tx.value,
+                data: None,
+            },
+            Log::Unstake {
+                staker_address: staker_address.clone(),
+                validator_address: Some(validator_address.clone()),
+                value: Coin::from_u64_unchecked(90_000_000),
+            }
+        ]
+    );
+
+    assert_eq!(
+        staking_contract.get_staker(&data_store.read(&db_txn), &staker_address),
+        None
+    );
+
+    let validator = staking_contract
+        .get_validator(&data_store.read(&db_txn), &validator_address)
+        .expect("Validator should exist");
+
+    assert_eq!(
+        validator.total_stake,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT)
+    );
+    assert_eq!(validator.num_stakers, 0);
+
+    assert_eq!(
+        staking_contract.balance,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT)
+    );
+
+    assert_eq!(
+        staking_contract.active_validators.get(&validator_address),
+        Some(&Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT))
+    );
+
+    // Revert the transaction.
+    let mut tx_logger = TransactionLog::empty();
+    staking_contract
+        .revert_outgoing_transaction(
+            &tx,
+            &block_state,
+            receipt,
+            data_store.write(&mut db_txn),
+            &mut tx_logger,
+        )
+        .expect("Failed to revert transaction");
+
+    assert_eq!(
+        tx_logger.logs,
+        vec![
+            Log::Unstake {
+                staker_address: staker_address.clone(),
+                validator_address: Some(validator_address.clone()),
+                value: Coin::from_u64_unchecked(90_000_000),
+            },
+            Log::Transfer {
+                from: tx.sender.clone(),
+                to: tx.recipient.clone(),
+                amount: tx.value,
+                data: None,
+            },
+            Log::PayFee {
+                from: tx.sender.clone(),
+                fee: tx.fee,
+            },
+        ]
+    );
+
+    let staker = staking_contract
+        .get_staker(&data_store.read(&db_txn), &staker_address)
+        .expect("Staker should exist");
+
+    assert_eq!(staker.address, staker_address);
+    assert_eq!(staker.balance, Coin::ZERO);
+    assert_eq!(
+        staker.inactive_balance,
+        Coin::from_u64_unchecked(90_000_000)
+    );
+    assert_eq!(
+        staker.inactive_from,
+        Some(2 * Policy::blocks_per_epoch() + 1)
+    );
+    assert_eq!(staker.delegation, Some(validator_address.clone()));
+
+    let validator = staking_contract
+        .get_validator(&data_store.read(&db_txn), &validator_address)
+        .expect("Validator should exist");
+
+    assert_eq!(
+        validator.total_stake,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT)
+    );
+    assert_eq!(validator.num_stakers, 1);
+
+    assert_eq!(
+        staking_contract.balance,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 90_000_000)
+    );
+}
+
+#[test]
+fn signaling_fees_are_paid_from_active_stake() {
+    let env = VolatileDatabase::new(20).unwrap();
+    let accounts = Accounts::new(env.clone());
+    let data_store = accounts.data_store(&Policy::STAKING_CONTRACT_ADDRESS);
+    let block_state = BlockState::new(2, 2);
+    let mut db_txn = env.write_transaction();
+    let mut db_txn = (&mut db_txn).into();
+
+    let mut staking_contract = make_sample_contract(data_store.write(&mut db_txn), true);
+
+    let staker_address = staker_address();
+    let validator_address = validator_address();
+
+    // The staker has no inactive balance, the fee is paid from its active stake.
+    let tx = make_signaling_fee_transaction(IncomingStakingTransactionData::UpdateStaker {
+        new_delegation: Some(validator_address.clone()),
+        delegation_stake: None,
+        proof: SignatureProof::default(),
+    });
+
+    let mut reserved_balance = ReservedBalance::new(Policy::STAKING_CONTRACT_ADDRESS);
+    assert_eq!(
+        staking_contract.reserve_balance(
+            &tx,
+            &mut reserved_balance,
+            &block_state,
+            data_store.read(&db_txn)
+        ),
+        Ok(())
+    );
+
+    let mut tx_logger = TransactionLog::empty();
+    let receipt = staking_contract
+        .commit_outgoing_transaction(
+            &tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut tx_logger,
+        )
+        .expect("Failed to commit transaction");
+
+    assert_eq!(receipt, staker_receipt(None));
+
+    assert_eq!(
+        tx_logger.logs,
+        vec![
+            Log::PayFee {
+                from: tx.sender.clone(),
+                fee: tx.fee,
+            },
+            Log::Transfer {
                 from: tx.sender.clone(),
                 to: tx.recipient.clone(),
                 amount: tx.value,
                 data: None,
             },
+            Log::StakerFeeDeduction {
+                staker_address: staker_address.clone(),
+                fee: tx.fee,
+            },
+        ]
+    );
+
+    let staker = staking_contract
+        .get_staker(&data_store.read(&db_txn), &staker_address)
+        .expect("Staker should exist");
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(150_000_000 - 100));
+    assert_eq!(staker.inactive_balance, Coin::ZERO);
+
+    let validator = staking_contract
+        .get_validator(&data_store.read(&db_txn), &validator_address)
+        .expect("Validator should exist");
+    assert_eq!(
+        validator.total_stake,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 150_000_000 - 100)
+    );
+    assert_eq!(
+        staking_contract.balance,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 150_000_000 - 100)
+    );
+
+    // Revert the transaction.
+    staking_contract
+        .revert_outgoing_transaction(
+            &tx,
+            &block_state,
+            receipt,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to revert transaction");
+
+    let staker = staking_contract
+        .get_staker(&data_store.read(&db_txn), &staker_address)
+        .expect("Staker should exist");
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(150_000_000));
+
+    let validator = staking_contract
+        .get_validator(&data_store.read(&db_txn), &validator_address)
+        .expect("Validator should exist");
+    assert_eq!(
+        validator.total_stake,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 150_000_000)
+    );
+    assert_eq!(
+        staking_contract.balance,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 150_000_000)
+    );
+
+    // Failed signaling transactions pay their fee the same way.
+    let mut tx_logger = TransactionLog::empty();
+    let receipt = staking_contract
+        .commit_failed_transaction(
+            &tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut tx_logger,
+        )
+        .expect("Failed to commit failed transaction");
+
+    assert_eq!(receipt, staker_receipt(None));
+
+    assert_eq!(
+        tx_logger.logs,
+        vec![
             Log::PayFee {
                 from: tx.sender.clone(),
                 fee: tx.fee,
             },
+            Log::StakerFeeDeduction {
+                staker_address: staker_address.clone(),
+                fee: tx.fee,
+            },
+        ]
+    );
+
+    let staker = staking_contract
+        .get_staker(&data_store.read(&db_txn), &staker_address)
+        .expect("Staker should exist");
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(150_000_000 - 100));
+
+    staking_contract
+        .revert_failed_transaction(
+            &tx,
+            &block_state,
+            receipt,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to revert failed transaction");
+
+    let staker = staking_contract
+        .get_staker(&data_store.read(&db_txn), &staker_address)
+        .expect("Staker should exist");
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(150_000_000));
+    assert_eq!(
+        staking_contract.balance,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 150_000_000)
+    );
+
+    // Stake delegated to additional validators can't pay fees.
+    let mut data_store_write = data_store.write(&mut db_txn);
+    let mut store = StakingContractStoreWrite::new(&mut data_store_write);
+    let mut staker = store.get_staker(&staker_address).unwrap();
+    staker.split_balance = staker.balance;
+    store.put_staker(&staker_address, staker);
+
+    assert_eq!(
+        staking_contract.commit_outgoing_transaction(
+            &tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty(),
+        ),
+        Err(AccountError::InsufficientFunds {
+            needed: tx.fee,
+            balance: Coin::ZERO
+        })
+    );
+}
+
+#[test]
+fn reward_inherents_not_allowed() {
+    let env = VolatileDatabase::new(20).unwrap();
+    let accounts = Accounts::new(env.clone());
+    let data_store = accounts.data_store(&Policy::STAKING_CONTRACT_ADDRESS);
+    let block_state = BlockState::new(2, 2);
+    let mut db_txn = env.write_transaction();
+    let mut db_txn = (&mut db_txn).into();
+
+    let mut staking_contract = make_sample_contract(data_store.write(&mut db_txn), true);
+
+    let validator_address = validator_address();
+
+    let inherent = Inherent::Reward {
+        target: validator_address,
+        value: Coin::ZERO,
+    };
+
+    assert_eq!(
+        staking_contract.commit_inherent(
+            &inherent,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut InherentLogger::empty()
+        ),
+        Err(AccountError::InvalidForTarget)
+    );
+}
+
+#[test]
+fn reward_pools_work() {
+    let env = VolatileDatabase::new(20).unwrap();
+    let accounts = Accounts::new(env.clone());
+    let data_store = accounts.data_store(&Policy::STAKING_CONTRACT_ADDRESS);
+    let block_state = BlockState::new(2, 2);
+    let mut db_txn = env.write_transaction();
+    let mut db_txn = (&mut db_txn).into();
+
+    let mut staking_contract = make_sample_contract(data_store.write(&mut db_txn), true);
+
+    let validator_address = validator_address();
+    let staker_address = staker_address();
+    let cold_keypair = ed25519_key_pair(VALIDATOR_PRIVATE_KEY);
+    let staker_keypair = ed25519_key_pair(STAKER_PRIVATE_KEY);
+
+    // Rewards can't be distributed before the validator opted in.
+    let inherent = Inherent::DistributeReward {
+        validator_address: validator_address.clone(),
+        value: Coin::from_u64_unchecked(1_500),
+    };
+
+    assert_eq!(
+        staking_contract.commit_inherent(
+            &inherent,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut InherentLogger::empty()
+        ),
+        Err(AccountError::InvalidForTarget)
+    );
+
+    // Opt in to distributing the rewards.
+    let commission_tx = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::UpdateValidatorCommission {
+            new_commission: Some(1_000),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &cold_keypair,
+    );
+
+    let mut tx_logger = TransactionLog::empty();
+    let commission_receipt = staking_contract
+        .commit_incoming_transaction(
+            &commission_tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut tx_logger,
+        )
+        .expect("Failed to commit transaction");
+
+    let commission_log = Log::UpdateValidatorCommission {
+        validator_address: validator_address.clone(),
+        old_commission: None,
+        new_commission: Some(1_000),
+    };
+    assert_eq!(
+        commission_receipt,
+        Some(UpdateValidatorCommissionReceipt { old_pool: None }.into())
+    );
+    assert_eq!(tx_logger.logs, vec![commission_log.clone()]);
+    assert_eq!(
+        staking_contract.get_reward_pool(&data_store.read(&db_txn), &validator_address),
+        Some(RewardPool {
+            commission: Some(1_000),
+            reward_per_stake: 0,
+            eligible_stake: Coin::from_u64_unchecked(150_000_000),
+            pending_stake: vec![],
+        })
+    );
+
+    // The reward is added to the pool of the validator.
+    let mut inherent_logs = vec![];
+    let inherent_receipt = staking_contract
+        .commit_inherent(
+            &inherent,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut InherentLogger::new(&mut inherent_logs),
+        )
+        .expect("Failed to commit inherent");
+
+    assert_eq!(
+        inherent_receipt,
+        Some(DistributeRewardReceipt { activated: vec![] }.into())
+    );
+
+    assert_eq!(
+        inherent_logs,
+        vec![Log::PayoutReward {
+            to: Policy::STAKING_CONTRACT_ADDRESS,
+            value: Coin::from_u64_unchecked(1_500),
+        }]
+    );
+    assert_eq!(
+        staking_contract.balance,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 150_001_500)
+    );
+    assert_eq!(
+        staking_contract.get_pending_staker_reward(
+            &data_store.read(&db_txn),
+            &staker_address,
+            &validator_address
+        ),
+        Coin::from_u64_unchecked(1_500)
+    );
+
+    // A failing staker transaction doesn't claim the rewards.
+    let tx = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::UpdateStaker {
+            new_delegation: Some(Address::from([1u8; 20])),
+            delegation_stake: None,
+            proof: SignatureProof::default(),
+        },
+        0,
+        &staker_keypair,
+    );
+
+    assert!(staking_contract
+        .commit_incoming_transaction(
+            &tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty(),
+        )
+        .is_err());
+    assert_eq!(
+        staking_contract.get_pending_staker_reward(
+            &data_store.read(&db_txn),
+            &staker_address,
+            &validator_address
+        ),
+        Coin::from_u64_unchecked(1_500)
+    );
+
+    // The next staker transaction claims the rewards.
+    let tx = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::AddStake {
+            staker_address: staker_address.clone(),
+        },
+        1_000,
+        &staker_keypair,
+    );
+
+    let mut tx_logger = TransactionLog::empty();
+    let receipt = staking_contract
+        .commit_incoming_transaction(
+            &tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut tx_logger,
+        )
+        .expect("Failed to commit transaction");
+
+    let expected_receipt = StakerRewardsReceipt {
+        claimed: vec![ClaimedReward {
+            validator_address: validator_address.clone(),
+            old_checkpoint: None,
+            activated: vec![],
+            value: Coin::from_u64_unchecked(1_500),
+        }],
+        receipt: None,
+    };
+    assert_eq!(receipt, Some(expected_receipt.into()));
+
+    assert_eq!(
+        tx_logger.logs,
+        vec![
+            Log::StakerReward {
+                staker_address: staker_address.clone(),
+                validator_address: validator_address.clone(),
+                value: Coin::from_u64_unchecked(1_500),
+            },
+            Log::Stake {
+                staker_address: staker_address.clone(),
+                validator_address: Some(validator_address.clone()),
+                value: Coin::from_u64_unchecked(1_000),
+            }
         ]
     );
 
     let staker = staking_contract
         .get_staker(&data_store.read(&db_txn), &staker_address)
-        .expect("Staker should exist");
-
-    assert_eq!(staker.address, staker_address);
-    assert_eq!(staker.balance, Coin::from_u64_unchecked(50_000_000));
-    assert_eq!(staker.delegation, Some(validator_address.clone()));
+        .unwrap();
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(150_002_500));
 
     let validator = staking_contract
         .get_validator(&data_store.read(&db_txn), &validator_address)
-        .expect("Validator should exist");
-
+        .unwrap();
     assert_eq!(
         validator.total_stake,
-        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 50_000_000)
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 150_002_500)
+    );
+    assert_eq!(
+        staking_contract.get_pending_staker_reward(
+            &data_store.read(&db_txn),
+            &staker_address,
+            &validator_address
+        ),
+        Coin::ZERO
     );
-    assert_eq!(validator.num_stakers, 1);
 
+    // The claimed reward and the added stake only earn rewards from the next epoch on.
     assert_eq!(
-        staking_contract.balance,
-        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 50_000_000)
+        staking_contract.get_reward_pool(&data_store.read(&db_txn), &validator_address),
+        Some(RewardPool {
+            commission: Some(1_000),
+            reward_per_stake: 1_500 * REWARD_PER_STAKE_PRECISION / 150_000_000,
+            eligible_stake: Coin::from_u64_unchecked(150_000_000),
+            pending_stake: vec![PendingStake {
+                election_block: Policy::blocks_per_epoch(),
+                stake: Coin::from_u64_unchecked(2_500),
+            }],
+        })
     );
 
+    // Revert everything.
+    staking_contract
+        .revert_incoming_transaction(
+            &tx,
+            &block_state,
+            receipt,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to revert transaction");
+
     assert_eq!(
-        staking_contract.active_validators.get(&validator_address),
-        Some(&Coin::from_u64_unchecked(
-            Policy::VALIDATOR_DEPOSIT + 50_000_000
-        ))
+        staking_contract.get_pending_staker_reward(
+            &data_store.read(&db_txn),
+            &staker_address,
+            &validator_address
+        ),
+        Coin::from_u64_unchecked(1_500)
+    );
+
+    staking_contract
+        .revert_inherent(
+            &inherent,
+            &block_state,
+            inherent_receipt,
+            data_store.write(&mut db_txn),
+            &mut InherentLogger::empty(),
+        )
+        .expect("Failed to revert inherent");
+
+    let mut tx_logger = TransactionLog::empty();
+    staking_contract
+        .revert_incoming_transaction(
+            &commission_tx,
+            &block_state,
+            commission_receipt,
+            data_store.write(&mut db_txn),
+            &mut tx_logger,
+        )
+        .expect("Failed to revert transaction");
+
+    assert_eq!(tx_logger.logs, vec![commission_log]);
+
+    let staker = staking_contract
+        .get_staker(&data_store.read(&db_txn), &staker_address)
+        .unwrap();
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(150_000_000));
+
+    let validator = staking_contract
+        .get_validator(&data_store.read(&db_txn), &validator_address)
+        .unwrap();
+    assert_eq!(
+        validator.total_stake,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 150_000_000)
+    );
+    assert_eq!(
+        staking_contract.balance,
+        Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 150_000_000)
+    );
+    assert_eq!(
+        staking_contract.get_reward_pool(&data_store.read(&db_txn), &validator_address),
+        None
     );
 }
 
 #[test]
-fn reward_inherents_not_allowed() {
+fn reward_pools_only_reward_stake_delegated_at_the_election_block() {
     let env = VolatileDatabase::new(20).unwrap();
     let accounts = Accounts::new(env.clone());
     let data_store = accounts.data_store(&Policy::STAKING_CONTRACT_ADDRESS);
-    let block_state = BlockState::new(2, 2);
     let mut db_txn = env.write_transaction();
     let mut db_txn = (&mut db_txn).into();
 
     let mut staking_contract = make_sample_contract(data_store.write(&mut db_txn), true);
 
     let validator_address = validator_address();
+    let staker_address = staker_address();
+    let cold_keypair = ed25519_key_pair(VALIDATOR_PRIVATE_KEY);
+    let late_staker_keypair = KeyPair::generate(&mut test_rng(false));
+    let late_staker_address = Address::from(&late_staker_keypair.public);
+    let election_block = Policy::blocks_per_epoch();
 
-    let inherent = Inherent::Reward {
-        target: validator_address,
-        value: Coin::ZERO,
+    // Opt in to distributing the rewards.
+    let block_state = BlockState::new(2, 2);
+    let commission_tx = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::UpdateValidatorCommission {
+            new_commission: Some(1_000),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &cold_keypair,
+    );
+    staking_contract
+        .commit_incoming_transaction(
+            &commission_tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to commit transaction");
+
+    // Another staker delegates the same stake after the genesis block.
+    let tx = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::CreateStaker {
+            delegation: Some(validator_address.clone()),
+            proof: SignatureProof::default(),
+        },
+        150_000_000,
+        &late_staker_keypair,
+    );
+    staking_contract
+        .commit_incoming_transaction(
+            &tx,
+            &block_state,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to commit transaction");
+
+    let pool = staking_contract
+        .get_reward_pool(&data_store.read(&db_txn), &validator_address)
+        .unwrap();
+    assert_eq!(pool.eligible_stake, Coin::from_u64_unchecked(150_000_000));
+    assert_eq!(
+        pool.pending_stake,
+        vec![PendingStake {
+            election_block,
+            stake: Coin::from_u64_unchecked(150_000_000),
+        }]
+    );
+
+    // The slots of the last batch of the first epoch were assigned at the genesis block, the new
+    // stake doesn't earn a share of its rewards.
+    let deposit = Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT);
+    let reward = Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 150_000_000);
+    assert_eq!(
+        pool.stakers_share(reward, deposit, election_block),
+        Coin::from_u64_unchecked(135_000_000)
+    );
+
+    let inherent = Inherent::DistributeReward {
+        validator_address: validator_address.clone(),
+        value: Coin::from_u64_unchecked(3_000),
     };
 
+    let first_block_state = BlockState::new(election_block, 2);
+    let first_receipt = staking_contract
+        .commit_inherent(
+            &inherent,
+            &first_block_state,
+            data_store.write(&mut db_txn),
+            &mut InherentLogger::empty(),
+        )
+        .expect("Failed to commit inherent");
+
     assert_eq!(
-        staking_contract.commit_inherent(
+        first_receipt,
+        Some(DistributeRewardReceipt { activated: vec![] }.into())
+    );
+    assert_eq!(
+        staking_contract.get_pending_staker_reward(
+            &data_store.read(&db_txn),
+            &staker_address,
+            &validator_address
+        ),
+        Coin::from_u64_unchecked(3_000)
+    );
+    assert_eq!(
+        staking_contract.get_pending_staker_reward(
+            &data_store.read(&db_txn),
+            &late_staker_address,
+            &validator_address
+        ),
+        Coin::ZERO
+    );
+
+    // The slots of the second batch of the second epoch were assigned at the election block, the
+    // new stake earns its share of their rewards.
+    let pool = staking_contract
+        .get_reward_pool(&data_store.read(&db_txn), &validator_address)
+        .unwrap();
+    let activation_reward_per_stake = pool.reward_per_stake;
+    let second_block = election_block + 2 * Policy::blocks_per_batch();
+    let reward = Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT + 300_000_000);
+    assert_eq!(
+        pool.stakers_share(reward, deposit, second_block),
+        Coin::from_u64_unchecked(270_000_000)
+    );
+
+    let second_block_state = BlockState::new(second_block, 2);
+    let second_receipt = staking_contract
+        .commit_inherent(
             &inherent,
-            &block_state,
+            &second_block_state,
             data_store.write(&mut db_txn),
-            &mut InherentLogger::empty()
+            &mut InherentLogger::empty(),
+        )
+        .expect("Failed to commit inherent");
+
+    assert_eq!(
+        second_receipt,
+        Some(
+            DistributeRewardReceipt {
+                activated: vec![PendingStake {
+                    election_block,
+                    stake: Coin::from_u64_unchecked(150_000_000),
+                }],
+            }
+            .into()
+        )
+    );
+    assert_eq!(
+        staking_contract.get_pending_staker_reward(
+            &data_store.read(&db_txn),
+            &staker_address,
+            &validator_address
         ),
-        Err(AccountError::InvalidForTarget)
+        Coin::from_u64_unchecked(4_500)
+    );
+    assert_eq!(
+        staking_contract.get_pending_staker_reward(
+            &data_store.read(&db_txn),
+            &late_staker_address,
+            &validator_address
+        ),
+        Coin::from_u64_unchecked(1_500)
+    );
+
+    // Claiming the rewards makes the activated stake eligible in the checkpoint of the staker.
+    let claim_block_state = BlockState::new(second_block + 1, 2);
+    let tx = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::AddStake {
+            staker_address: late_staker_address.clone(),
+        },
+        1_000,
+        &late_staker_keypair,
+    );
+    let receipt = staking_contract
+        .commit_incoming_transaction(
+            &tx,
+            &claim_block_state,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to commit transaction");
+
+    let expected_receipt = StakerRewardsReceipt {
+        claimed: vec![ClaimedReward {
+            validator_address: validator_address.clone(),
+            old_checkpoint: Some(RewardCheckpoint {
+                staker_address: late_staker_address.clone(),
+                validator_address: validator_address.clone(),
+                reward_per_stake: 0,
+                eligible_stake: Coin::ZERO,
+                pending_stake: vec![PendingStake {
+                    election_block,
+                    stake: Coin::from_u64_unchecked(150_000_000),
+                }],
+            }),
+            activated: vec![ActivatedStake {
+                election_block,
+                reward_per_stake: activation_reward_per_stake,
+            }],
+            value: Coin::from_u64_unchecked(1_500),
+        }],
+        receipt: None,
+    };
+    assert_eq!(receipt, Some(expected_receipt.into()));
+
+    let pool = staking_contract
+        .get_reward_pool(&data_store.read(&db_txn), &validator_address)
+        .unwrap();
+    assert_eq!(pool.eligible_stake, Coin::from_u64_unchecked(300_000_000));
+    assert_eq!(
+        pool.pending_stake,
+        vec![PendingStake {
+            election_block: 2 * election_block,
+            stake: Coin::from_u64_unchecked(2_500),
+        }]
+    );
+    assert_eq!(
+        staking_contract.get_pending_staker_reward(
+            &data_store.read(&db_txn),
+            &late_staker_address,
+            &validator_address
+        ),
+        Coin::ZERO
+    );
+
+    // Reverting restores the pending stake and its activation.
+    staking_contract
+        .revert_incoming_transaction(
+            &tx,
+            &claim_block_state,
+            receipt,
+            data_store.write(&mut db_txn),
+            &mut TransactionLog::empty(),
+        )
+        .expect("Failed to revert transaction");
+
+    assert_eq!(
+        staking_contract.get_pending_staker_reward(
+            &data_store.read(&db_txn),
+            &late_staker_address,
+            &validator_address
+        ),
+        Coin::from_u64_unchecked(1_500)
+    );
+
+    staking_contract
+        .revert_inherent(
+            &inherent,
+            &second_block_state,
+            second_receipt,
+            data_store.write(&mut db_txn),
+            &mut InherentLogger::empty(),
+        )
+        .expect("Failed to revert inherent");
+    staking_contract
+        .revert_inherent(
+            &inherent,
+            &first_block_state,
+            first_receipt,
+            data_store.write(&mut db_txn),
+            &mut InherentLogger::empty(),
+        )
+        .expect("Failed to revert inherent");
+
+    let pool = staking_contract
+        .get_reward_pool(&data_store.read(&db_txn), &validator_address)
+        .unwrap();
+    assert_eq!(pool.reward_per_stake, 0);
+    assert_eq!(pool.eligible_stake, Coin::from_u64_unchecked(150_000_000));
+    assert_eq!(
+        pool.pending_stake,
+        vec![PendingStake {
+            election_block,
+            stake: Coin::from_u64_unchecked(150_000_000),
+        }]
     );
 }
 
@@ -2707,3 +4175,36 @@ fn validator_address() -> Address {
 fn staker_address() -> Address {
     Address::from_hex(STAKER_ADDRESS).unwrap()
 }
+
+fn staker_receipt(receipt: Option<AccountReceipt>) -> Option<AccountReceipt> {
+    Some(
+        StakerRewardsReceipt {
+            claimed: vec![],
+            receipt,
+        }
+        .into(),
+    )
+}
+
+fn make_signaling_fee_transaction(data: IncomingStakingTransactionData) -> Transaction {
+    let mut tx = Transaction::new_signaling(
+        Policy::STAKING_CONTRACT_ADDRESS,
+        AccountType::Staking,
+        Policy::STAKING_CONTRACT_ADDRESS,
+        AccountType::Staking,
+        100.try_into().unwrap(),
+        data.serialize_to_vec(),
+        1,
+        NetworkId::Dummy,
+    );
+
+    let key_pair = ed25519_key_pair(STAKER_PRIVATE_KEY);
+
+    let in_proof = SignatureProof::from(key_pair.public, key_pair.sign(&tx.serialize_content()));
+    tx.data = IncomingStakingTransactionData::set_signature_on_data(&tx.data, in_proof).unwrap();
+
+    let out_proof = SignatureProof::from(key_pair.public, key_pair.sign(&tx.serialize_content()));
+    tx.proof = OutgoingStakingTransactionProof::RemoveStake { proof: out_proof }.serialize_to_vec();
+
+    tx
+}
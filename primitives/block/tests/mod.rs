@@ -4,16 +4,42 @@ use nimiq_block::{MacroBlock, MacroBody, MacroHeader, MultiSignature};
 use nimiq_bls::{AggregateSignature, CompressedPublicKey, KeyPair};
 use nimiq_collections::bitset::BitSet;
 use nimiq_handel::update::LevelUpdate;
-use nimiq_hash::{Blake2bHash, Blake2bHasher, Blake2sHash, Hasher};
+use nimiq_hash::{Blake2bHash, Blake2bHasher, Blake2sHash, Hash, Hasher};
 use nimiq_keys::{Address, PublicKey};
-use nimiq_primitives::slots::ValidatorsBuilder;
+use nimiq_primitives::{coin::Coin, slots::ValidatorsBuilder};
 use nimiq_serde::{Deserialize, Serialize};
 use nimiq_test_log::test;
+use nimiq_transaction::reward::RewardTransaction;
 use nimiq_vrf::VrfSeed;
 
 mod block_proof;
 mod macro_block;
 
+#[test]
+fn macro_body_commits_to_reward_delegations() {
+    let body = MacroBody {
+        validators: None,
+        lost_reward_set: BitSet::new(),
+        disabled_set: BitSet::new(),
+        transactions: vec![RewardTransaction::new_staker_rewards(
+            Address::from([1u8; Address::SIZE]),
+            Coin::from_u64_unchecked(1_000),
+        )],
+    };
+
+    let bytes = body.serialize_to_vec();
+    assert_eq!(MacroBody::deserialize_from_vec(&bytes).unwrap(), body);
+
+    // The delegation of the reward transactions is part of the body root.
+    let mut plain_body = body.clone();
+    plain_body.transactions[0].delegation = None;
+    assert_ne!(plain_body.serialize_to_vec(), bytes);
+    assert_ne!(
+        Hash::hash::<Blake2bHash>(&plain_body),
+        Hash::hash::<Blake2bHash>(&body)
+    );
+}
+
 #[test]
 fn it_can_convert_macro_block_into_slots() {
     let hash = Blake2bHasher::default().digest(&[]);
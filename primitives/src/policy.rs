@@ -92,6 +92,14 @@ impl Policy {
     /// in proof-of-work.
     pub const VALIDATOR_DEPOSIT: u64 = 1_000_000_000;
 
+    /// The maximum commission a validator can charge on the rewards of its stakers, in basis points
+    /// (i.e. 10,000 means that the validator keeps all rewards).
+    pub const MAX_VALIDATOR_COMMISSION: u16 = 10_000;
+
+    /// The number of full epochs a staker has to wait after deactivating stake before it can be
+    /// withdrawn. The count starts with the epoch after the one in which the stake was deactivated.
+    pub const STAKER_UNBONDING_EPOCHS: u32 = 1;
+
     /// Total supply in units.
     pub const TOTAL_SUPPLY: u64 = 2_100_000_000_000_000;
 
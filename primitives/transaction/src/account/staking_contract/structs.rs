@@ -15,10 +15,12 @@ use crate::{SignatureProof, Transaction, TransactionError};
 ///         * Deactivate
 ///         * Reactivate
 ///         * Retire
+///         * UpdateCommission
 ///     - Staker
 ///         * Create
 ///         * Update
 ///         * AddStake
+///         * SetInactiveStake
 ///     The type of transaction, parameters and proof are given in the data field of the transaction.
 /// 2. Outgoing transactions, which include:
 ///     - Validator
@@ -74,6 +76,25 @@ pub enum IncomingStakingTransactionData {
     },
     UpdateStaker {
         new_delegation: Option<Address>,
+        // If this is set, the stake delegated to `new_delegation` is set to the given value instead
+        // of changing the main delegation. The difference is moved from or to the main delegation,
+        // a value of zero removes the delegation to this validator again.
+        delegation_stake: Option<Coin>,
+        proof: SignatureProof,
+    },
+    SetInactiveStake {
+        // The new inactive balance of the staker. The difference to the current inactive balance
+        // is moved from or to the active balance.
+        new_inactive_balance: Coin,
+        proof: SignatureProof,
+    },
+    // Added as a new variant instead of a field of `UpdateValidator`, such that the encoding of
+    // the existing variants doesn't change.
+    UpdateValidatorCommission {
+        // The commission in basis points. Setting a commission opts in to distributing the rewards
+        // to the stakers, setting it to None opts out again.
+        new_commission: Option<u16>,
+        // This proof is signed with the validator cold key.
         proof: SignatureProof,
     },
 }
@@ -87,6 +108,8 @@ impl IncomingStakingTransactionData {
                 | IncomingStakingTransactionData::ReactivateValidator { .. }
                 | IncomingStakingTransactionData::RetireValidator { .. }
                 | IncomingStakingTransactionData::UpdateStaker { .. }
+                | IncomingStakingTransactionData::SetInactiveStake { .. }
+                | IncomingStakingTransactionData::UpdateValidatorCommission { .. }
         )
     }
 
@@ -167,7 +190,36 @@ impl IncomingStakingTransactionData {
             IncomingStakingTransactionData::AddStake { .. } => {
                 // No checks needed.
             }
-            IncomingStakingTransactionData::UpdateStaker { proof, .. } => {
+            IncomingStakingTransactionData::UpdateStaker {
+                new_delegation,
+                delegation_stake,
+                proof,
+            } => {
+                // Stake can only be delegated to a given validator.
+                if delegation_stake.is_some() && new_delegation.is_none() {
+                    error!("Delegation stake can only be set for a validator. The offending transaction is the following:\n{:?}", transaction);
+                    return Err(TransactionError::InvalidData);
+                }
+
+                // Check that the signature is correct.
+                verify_transaction_signature(transaction, proof, true)?
+            }
+            IncomingStakingTransactionData::SetInactiveStake { proof, .. } => {
+                // Check that the signature is correct.
+                verify_transaction_signature(transaction, proof, true)?
+            }
+            IncomingStakingTransactionData::UpdateValidatorCommission {
+                new_commission,
+                proof,
+            } => {
+                // Check that the commission is within bounds.
+                if let Some(commission) = new_commission {
+                    if *commission > Policy::MAX_VALIDATOR_COMMISSION {
+                        error!("Validator commission exceeds the maximum commission. The offending transaction is the following:\n{:?}", transaction);
+                        return Err(TransactionError::InvalidData);
+                    }
+                }
+
                 // Check that the signature is correct.
                 verify_transaction_signature(transaction, proof, true)?
             }
@@ -199,6 +251,12 @@ impl IncomingStakingTransactionData {
             IncomingStakingTransactionData::UpdateStaker { proof, .. } => {
                 *proof = signature_proof;
             }
+            IncomingStakingTransactionData::SetInactiveStake { proof, .. } => {
+                *proof = signature_proof;
+            }
+            IncomingStakingTransactionData::UpdateValidatorCommission { proof, .. } => {
+                *proof = signature_proof;
+            }
             _ => {}
         }
     }
@@ -3,7 +3,7 @@ use std::{error, fmt, io};
 use nimiq_database_value::{FromDatabaseValue, IntoDatabaseValue};
 use nimiq_hash::{Blake2bHash, Hash};
 use nimiq_mmr::hash::Hash as MMRHash;
-use nimiq_primitives::{coin::Coin, networks::NetworkId, policy::Policy};
+use nimiq_primitives::{account::AccountType, coin::Coin, networks::NetworkId, policy::Policy};
 use nimiq_serde::{Deserialize, Serialize};
 
 use crate::{inherent::Inherent, ExecutedTransaction, Transaction as BlockchainTransaction};
@@ -64,14 +64,14 @@ impl ExtendedTransaction {
 
         for inherent in inherents {
             match inherent {
-                Inherent::Slash { .. } | Inherent::Reward { .. } => {
-                    ext_txs.push(ExtendedTransaction {
-                        network_id,
-                        block_number,
-                        block_time,
-                        data: ExtTxData::Inherent(inherent),
-                    })
-                }
+                Inherent::Slash { .. }
+                | Inherent::Reward { .. }
+                | Inherent::DistributeReward { .. } => ext_txs.push(ExtendedTransaction {
+                    network_id,
+                    block_number,
+                    block_time,
+                    data: ExtTxData::Inherent(inherent),
+                }),
                 _ => {}
             }
         }
@@ -129,7 +129,9 @@ impl ExtendedTransaction {
         match &self.data {
             ExtTxData::Basic(tx) => tx.hash(),
             ExtTxData::Inherent(v) => match v {
-                Inherent::Reward { .. } => self.clone().into_transaction().unwrap().hash(),
+                Inherent::Reward { .. } | Inherent::DistributeReward { .. } => {
+                    self.clone().into_transaction().unwrap().hash()
+                }
                 _ => v.hash(),
             },
         }
@@ -137,24 +139,40 @@ impl ExtendedTransaction {
 
     /// Tries to convert an extended transaction into a regular transaction. This will work for all
     /// extended transactions that wrap over regular transactions and reward inherents.
+    /// Distributed rewards are mapped to a transaction to the staking contract, whose data is the
+    /// address of the validator.
     pub fn into_transaction(self) -> Result<ExecutedTransaction, IntoTransactionError> {
         match self.data {
             ExtTxData::Basic(tx) => Ok(tx),
-            ExtTxData::Inherent(inherent) => {
-                if let Inherent::Reward { target, value } = inherent {
-                    let txn = BlockchainTransaction::new_basic(
-                        Policy::COINBASE_ADDRESS,
-                        target,
-                        value,
-                        Coin::ZERO,
-                        self.block_number,
-                        self.network_id,
-                    );
-                    Ok(ExecutedTransaction::Ok(txn))
-                } else {
-                    Err(IntoTransactionError::NoBasicTransactionMapping)
-                }
+            ExtTxData::Inherent(Inherent::Reward { target, value }) => {
+                let txn = BlockchainTransaction::new_basic(
+                    Policy::COINBASE_ADDRESS,
+                    target,
+                    value,
+                    Coin::ZERO,
+                    self.block_number,
+                    self.network_id,
+                );
+                Ok(ExecutedTransaction::Ok(txn))
+            }
+            ExtTxData::Inherent(Inherent::DistributeReward {
+                validator_address,
+                value,
+            }) => {
+                let txn = BlockchainTransaction::new_extended(
+                    Policy::COINBASE_ADDRESS,
+                    AccountType::Basic,
+                    Policy::STAKING_CONTRACT_ADDRESS,
+                    AccountType::Staking,
+                    value,
+                    Coin::ZERO,
+                    validator_address.serialize_to_vec(),
+                    self.block_number,
+                    self.network_id,
+                );
+                Ok(ExecutedTransaction::Ok(txn))
             }
+            ExtTxData::Inherent(_) => Err(IntoTransactionError::NoBasicTransactionMapping),
         }
     }
 }
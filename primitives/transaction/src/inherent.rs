@@ -8,28 +8,46 @@ use crate::reward::RewardTransaction;
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, SerializeContent, Deserialize)]
 #[repr(u8)]
 pub enum Inherent {
-    Reward { target: Address, value: Coin },
-    Slash { slot: SlashedSlot },
+    Reward {
+        target: Address,
+        value: Coin,
+    },
+    Slash {
+        slot: SlashedSlot,
+    },
     FinalizeBatch,
     FinalizeEpoch,
+    /// The part of a reward that a validator distributes to its stakers. It is paid to the staking
+    /// contract, where the stakers can claim it.
+    DistributeReward {
+        validator_address: Address,
+        value: Coin,
+    },
 }
 
 impl Inherent {
     pub fn target(&self) -> &Address {
         match self {
             Inherent::Reward { target, .. } => target,
-            Inherent::Slash { .. } | Inherent::FinalizeBatch | Inherent::FinalizeEpoch => {
-                &Policy::STAKING_CONTRACT_ADDRESS
-            }
+            Inherent::Slash { .. }
+            | Inherent::FinalizeBatch
+            | Inherent::FinalizeEpoch
+            | Inherent::DistributeReward { .. } => &Policy::STAKING_CONTRACT_ADDRESS,
         }
     }
 }
 
 impl From<&RewardTransaction> for Inherent {
     fn from(tx: &RewardTransaction) -> Self {
-        Self::Reward {
-            target: tx.recipient.clone(),
-            value: tx.value,
+        match &tx.delegation {
+            Some(validator_address) => Self::DistributeReward {
+                validator_address: validator_address.clone(),
+                value: tx.value,
+            },
+            None => Self::Reward {
+                target: tx.recipient.clone(),
+                value: tx.value,
+            },
         }
     }
 }
@@ -9,6 +9,7 @@ use std::{
 };
 
 use bitflags::bitflags;
+use nimiq_database_value::{FromDatabaseValue, IntoDatabaseValue};
 use nimiq_hash::{Blake2bHash, Hash, SerializeContent};
 use nimiq_keys::{Address, PublicKey, Signature};
 use nimiq_network_interface::network::Topic;
@@ -508,6 +509,26 @@ impl Ord for Transaction {
     }
 }
 
+impl IntoDatabaseValue for Transaction {
+    fn database_byte_size(&self) -> usize {
+        self.serialized_size()
+    }
+
+    fn copy_into_database(&self, mut bytes: &mut [u8]) {
+        Serialize::serialize_to_writer(&self, &mut bytes).unwrap();
+    }
+}
+
+impl FromDatabaseValue for Transaction {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        Deserialize::deserialize_from_vec(bytes)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+    }
+}
+
 mod serde_derive {
     use std::fmt;
 
@@ -1,5 +1,5 @@
 use nimiq_keys::Address;
-use nimiq_primitives::coin::Coin;
+use nimiq_primitives::{coin::Coin, policy::Policy};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
@@ -7,10 +7,25 @@ use serde::{Deserialize, Serialize};
 pub struct RewardTransaction {
     pub recipient: Address,
     pub value: Coin,
+    /// If set, the reward is distributed to the stakers delegating to this validator and the
+    /// recipient is the staking contract.
+    pub delegation: Option<Address>,
 }
 
 impl RewardTransaction {
     pub fn new(recipient: Address, value: Coin) -> Self {
-        Self { recipient, value }
+        Self {
+            recipient,
+            value,
+            delegation: None,
+        }
+    }
+
+    pub fn new_staker_rewards(validator_address: Address, value: Coin) -> Self {
+        Self {
+            recipient: Policy::STAKING_CONTRACT_ADDRESS,
+            value,
+            delegation: Some(validator_address),
+        }
     }
 }
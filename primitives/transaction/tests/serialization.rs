@@ -1,10 +1,10 @@
 use std::convert::{TryFrom, TryInto};
 
 use nimiq_keys::Address;
-use nimiq_primitives::{account::AccountType, coin::Coin, networks::NetworkId};
+use nimiq_primitives::{account::AccountType, coin::Coin, networks::NetworkId, policy::Policy};
 use nimiq_serde::{Deserialize, DeserializeError, Serialize};
 use nimiq_test_log::test;
-use nimiq_transaction::*;
+use nimiq_transaction::{reward::RewardTransaction, *};
 
 const EXTENDED_TRANSACTION: &str = "01004a88aaad038f9b8248865c4b9249efc554960e1600ad25610feb43d75307763d3f010822a7570274290000000746a52880000000000000000000000136c32a00e2010e4712ea5b1703873529dd195b2b8f014c295ab352a12e3332d8f30cfc2db9680480c77af04feb0d89bdb5d5d9432d4ca17866abf3b4d6c1a05fa0fbdaed056181eaff68db063c759a0964bceb5f262f7335ed97c5471e773429926c106eae50881b998c516581e6d93933bb92feb2edcdbdb1b118fc000f8f1df8715538840b79e74721c631efe0f9977ccd88773b022a07b3935f2e8546e20ed7f7e1a0c77da7a7e1737bf0625170610846792ea16bc0f6d8cf9ded8a9da1d467f4191a3a97d5fc17d08d699dfa486787f70eb09e2cdbd5b63fd1a8357e1cd24cd37aa2f3408400";
 const BASIC_TRANSACTION: &str = "000222666efadc937148a6d61589ce6d4aeecca97fda4c32348d294eab582f14a0754d1260f15bea0e8fb07ab18f45301483599e34000000000000c350000000000000008a00019640023fecb82d3aef4be76853d5c5b263754b7d495d9838f6ae5df60cf3addd3512a82988db0056059c7a52ae15285983ef0db8229ae446c004559147686d28f0a30a";
@@ -82,3 +82,31 @@ fn it_can_serialize_basic_transaction() {
     assert_eq!(size, t.serialized_size());
     assert_eq!(hex::encode(v2), BASIC_TRANSACTION);
 }
+
+#[test]
+fn it_can_serialize_reward_transactions() {
+    let recipient = Address::from([2u8; Address::SIZE]);
+    let value = Coin::from_u64_unchecked(1_000);
+
+    // The delegation is encoded after the value, rewards without one end with a zero byte.
+    let tx = RewardTransaction::new(recipient, value);
+    let bytes = tx.serialize_to_vec();
+    assert_eq!(
+        hex::encode(&bytes),
+        format!("{}00000000000003e800", "02".repeat(Address::SIZE))
+    );
+    assert_eq!(RewardTransaction::deserialize_from_vec(&bytes).unwrap(), tx);
+
+    let validator_address = Address::from([1u8; Address::SIZE]);
+    let tx = RewardTransaction::new_staker_rewards(validator_address, value);
+    let bytes = tx.serialize_to_vec();
+    assert_eq!(
+        hex::encode(&bytes),
+        format!(
+            "{}00000000000003e801{}",
+            hex::encode(Policy::STAKING_CONTRACT_ADDRESS.0),
+            "01".repeat(Address::SIZE)
+        )
+    );
+    assert_eq!(RewardTransaction::deserialize_from_vec(&bytes).unwrap(), tx);
+}
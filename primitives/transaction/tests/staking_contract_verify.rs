@@ -293,6 +293,82 @@ fn update_validator() {
     );
 }
 
+#[test]
+fn update_validator_commission() {
+    let mut rng = test_rng(false);
+    let cold_keypair = ed25519_key_pair(VALIDATOR_PRIVATE_KEY);
+
+    let mut tx = make_signed_incoming_tx(
+        IncomingStakingTransactionData::UpdateValidatorCommission {
+            new_commission: Some(500),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &cold_keypair,
+        None,
+    );
+
+    // Works in the valid case.
+    assert_eq!(AccountType::verify_incoming_transaction(&tx), Ok(()));
+
+    // Opting out again is valid as well.
+    let tx_opt_out = make_signed_incoming_tx(
+        IncomingStakingTransactionData::UpdateValidatorCommission {
+            new_commission: None,
+            proof: SignatureProof::default(),
+        },
+        0,
+        &cold_keypair,
+        None,
+    );
+    assert_eq!(
+        AccountType::verify_incoming_transaction(&tx_opt_out),
+        Ok(())
+    );
+
+    // Signaling transaction with a non-zero value.
+    tx.value = Coin::from_u64_unchecked(1);
+
+    assert_eq!(
+        AccountType::verify_incoming_transaction(&tx),
+        Err(TransactionError::InvalidValue)
+    );
+
+    // Commission above the maximum.
+    let tx = make_signed_incoming_tx(
+        IncomingStakingTransactionData::UpdateValidatorCommission {
+            new_commission: Some(Policy::MAX_VALIDATOR_COMMISSION + 1),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &cold_keypair,
+        None,
+    );
+
+    assert_eq!(
+        AccountType::verify_incoming_transaction(&tx),
+        Err(TransactionError::InvalidData)
+    );
+
+    // Invalid signature.
+    let other_pair = KeyPair::generate(&mut rng);
+
+    let tx = make_signed_incoming_tx(
+        IncomingStakingTransactionData::UpdateValidatorCommission {
+            new_commission: Some(500),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &cold_keypair,
+        Some(other_pair.public),
+    );
+
+    assert_eq!(
+        AccountType::verify_incoming_transaction(&tx),
+        Err(TransactionError::InvalidProof)
+    );
+}
+
 #[test]
 fn deactivate_validator() {
     let mut rng = test_rng(false);
@@ -557,6 +633,7 @@ fn update_staker() {
     let mut tx = make_signed_incoming_tx(
         IncomingStakingTransactionData::UpdateStaker {
             new_delegation: Some(VALIDATOR_ADDRESS.parse().unwrap()),
+            delegation_stake: None,
             proof: SignatureProof::default(),
         },
         0,
@@ -564,8 +641,8 @@ fn update_staker() {
         None,
     );
 
-    let tx_hex = "0179070183fa05dbe31f85e719f4c4fd67ebdba2e444d9f8b3adb13fe6887f6cdcb8c82c429f718fcdbbb27b2a19df7c1ea9814f19cd9105000000b5ba97691f51803d57a58a35ef7a755d045a611d835abcab02e2960ccf0f40b639869282da13610655af32fbd2316c916821c4582c6c448cb96ee706cb2eae088c551fabc6e6e00c609c3f0313257ad7e835643c000000000000000000000000000000000000000001030000000000000000000000000000006400000001040263b3adb13fe6887f6cdcb8c82c429f718fcdbbb27b2a19df7c1ea9814f19cd9105000000ffb3e60d86b516f5aef19d8196c9183cf2403555b5d30a9bb51b0c3df9a43b1a1ff2e8d53805bb2b3218d7fc13368f9b5d66e000e5a521cd2f0e218fc1698901";
-    let tx_size = 287;
+    let tx_hex = "017a070183fa05dbe31f85e719f4c4fd67ebdba2e444d9f800b3adb13fe6887f6cdcb8c82c429f718fcdbbb27b2a19df7c1ea9814f19cd9105000000506f4bc34954a0a9fff3fb2ace94b1ad10795b670af4b4493b96e01b4e9df17a33ecf2f566b227593129a575dba6aae73c8efd73228ee86d64b55060d8eca9058c551fabc6e6e00c609c3f0313257ad7e835643c000000000000000000000000000000000000000001030000000000000000000000000000006400000001040263b3adb13fe6887f6cdcb8c82c429f718fcdbbb27b2a19df7c1ea9814f19cd91050000004d5e29e274dc6d94c3fb727f6a24f71d695c9e93c55f44f04fd9771e4515b60ad2bd655eef273223f8623667e97363c1760b3c862073c6f0ec28ac2e0be6d804";
+    let tx_size = 288;
 
     let mut ser_tx: Vec<u8> = Vec::with_capacity(tx_size);
     assert_eq!(tx_size, tx.serialized_size());
@@ -592,6 +669,140 @@ fn update_staker() {
     let tx = make_signed_incoming_tx(
         IncomingStakingTransactionData::UpdateStaker {
             new_delegation: None,
+            delegation_stake: None,
+            proof: SignatureProof::default(),
+        },
+        0,
+        &keypair,
+        Some(other_pair.public),
+    );
+
+    assert_eq!(
+        AccountType::verify_incoming_transaction(&tx),
+        Err(TransactionError::InvalidProof)
+    );
+}
+
+#[test]
+fn set_delegation_stake() {
+    let mut rng = test_rng(false);
+    let keypair = ed25519_key_pair(STAKER_PRIVATE_KEY);
+
+    // Test serialization and deserialization.
+    let mut tx = make_signed_incoming_tx(
+        IncomingStakingTransactionData::UpdateStaker {
+            new_delegation: Some(VALIDATOR_ADDRESS.parse().unwrap()),
+            delegation_stake: Some(Coin::from_u64_unchecked(200_000_000)),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &keypair,
+        None,
+    );
+
+    let tx_hex = "018201070183fa05dbe31f85e719f4c4fd67ebdba2e444d9f801000000000bebc200b3adb13fe6887f6cdcb8c82c429f718fcdbbb27b2a19df7c1ea9814f19cd9105000000064ecad23c6b65669b047e7334e91a1c6d71c13e8aa12904dbb89c6938e4bdddb81dc68970eb8ac80df2f4357bccf44c0d93f85c82be5c0a914b68644efbdb0f8c551fabc6e6e00c609c3f0313257ad7e835643c000000000000000000000000000000000000000001030000000000000000000000000000006400000001040263b3adb13fe6887f6cdcb8c82c429f718fcdbbb27b2a19df7c1ea9814f19cd910500000005483a8891e1832f8b9071136d4014f47488e8a7b5a02d1cc26021372b094ed7e3fec4f058c1591042bf452746640c4837033f873c3de29ac2e68695a8214f08";
+    let tx_size = 297;
+
+    let mut ser_tx: Vec<u8> = Vec::with_capacity(tx_size);
+    assert_eq!(tx_size, tx.serialized_size());
+    assert_eq!(tx_size, tx.serialize_to_writer(&mut ser_tx).unwrap());
+    assert_eq!(tx_hex, hex::encode(ser_tx));
+
+    let deser_tx = Deserialize::deserialize_from_vec(&hex::decode(tx_hex).unwrap()[..]).unwrap();
+    assert_eq!(tx, deser_tx);
+
+    // Works in the valid case.
+    assert_eq!(AccountType::verify_incoming_transaction(&tx), Ok(()));
+
+    // Signaling transaction with a non-zero value.
+    tx.value = Coin::from_u64_unchecked(1);
+
+    assert_eq!(
+        AccountType::verify_incoming_transaction(&tx),
+        Err(TransactionError::InvalidValue)
+    );
+
+    // Delegation stake without a validator.
+    let tx = make_signed_incoming_tx(
+        IncomingStakingTransactionData::UpdateStaker {
+            new_delegation: None,
+            delegation_stake: Some(Coin::from_u64_unchecked(200_000_000)),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &keypair,
+        None,
+    );
+
+    assert_eq!(
+        AccountType::verify_incoming_transaction(&tx),
+        Err(TransactionError::InvalidData)
+    );
+
+    // Invalid signature.
+    let other_pair = KeyPair::generate(&mut rng);
+
+    let tx = make_signed_incoming_tx(
+        IncomingStakingTransactionData::UpdateStaker {
+            new_delegation: Some(VALIDATOR_ADDRESS.parse().unwrap()),
+            delegation_stake: Some(Coin::from_u64_unchecked(200_000_000)),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &keypair,
+        Some(other_pair.public),
+    );
+
+    assert_eq!(
+        AccountType::verify_incoming_transaction(&tx),
+        Err(TransactionError::InvalidProof)
+    );
+}
+
+#[test]
+fn set_inactive_stake() {
+    let mut rng = test_rng(false);
+    let keypair = ed25519_key_pair(STAKER_PRIVATE_KEY);
+
+    // Test serialization and deserialization.
+    let mut tx = make_signed_incoming_tx(
+        IncomingStakingTransactionData::SetInactiveStake {
+            new_inactive_balance: Coin::from_u64_unchecked(150_000_000),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &keypair,
+        None,
+    );
+
+    let tx_hex = "016c080000000008f0d180b3adb13fe6887f6cdcb8c82c429f718fcdbbb27b2a19df7c1ea9814f19cd910500000089e66822795c8e973df9f6dd177dac8407ac36fa7f08d1412e96152598779d8eb694f0c9f7ad4065da72146fd8136f41092f419f6fd89ee4bca8184c065482078c551fabc6e6e00c609c3f0313257ad7e835643c000000000000000000000000000000000000000001030000000000000000000000000000006400000001040263b3adb13fe6887f6cdcb8c82c429f718fcdbbb27b2a19df7c1ea9814f19cd91050000009bacf4907b61c50b5e9b6e4891e2b24a54ecc275da3c35961add603fcb4179c3245d619e8c48e3fa77c9584940314867d53614d10bbec84ef6053b38c80e990a";
+    let tx_size = 274;
+
+    let mut ser_tx: Vec<u8> = Vec::with_capacity(tx_size);
+    assert_eq!(tx_size, tx.serialized_size());
+    assert_eq!(tx_size, tx.serialize_to_writer(&mut ser_tx).unwrap());
+    assert_eq!(tx_hex, hex::encode(ser_tx));
+
+    let deser_tx = Deserialize::deserialize_from_vec(&hex::decode(tx_hex).unwrap()[..]).unwrap();
+    assert_eq!(tx, deser_tx);
+
+    // Works in the valid case.
+    assert_eq!(AccountType::verify_incoming_transaction(&tx), Ok(()));
+
+    // Signaling transaction with a non-zero value.
+    tx.value = Coin::from_u64_unchecked(1);
+
+    assert_eq!(
+        AccountType::verify_incoming_transaction(&tx),
+        Err(TransactionError::InvalidValue)
+    );
+
+    // Invalid signature.
+    let other_pair = KeyPair::generate(&mut rng);
+
+    let tx = make_signed_incoming_tx(
+        IncomingStakingTransactionData::SetInactiveStake {
+            new_inactive_balance: Coin::ZERO,
             proof: SignatureProof::default(),
         },
         0,
@@ -121,9 +121,14 @@ impl MerkleRadixTrie {
 
         let tree = MerkleRadixTrie { db: table };
 
-        let mut txn = db.write_transaction();
-        tree.init_root(&mut (&mut txn).into(), incomplete);
-        txn.commit();
+        // Only open a write transaction if the root is missing, such that an existing trie can
+        // also be opened from a read-only database.
+        let has_root = tree.get_root(&db.read_transaction()).is_some();
+        if !has_root {
+            let mut txn = db.write_transaction();
+            tree.init_root(&mut (&mut txn).into(), incomplete);
+            txn.commit();
+        }
 
         tree
     }
@@ -139,7 +144,11 @@ impl MerkleRadixTrie {
         }
     }
 
-    pub fn init(&self, txn: &mut WriteTransactionProxy, values: Vec<TrieItem>) {
+    pub fn init<I: IntoIterator<Item = TrieItem>>(
+        &self,
+        txn: &mut WriteTransactionProxy,
+        values: I,
+    ) {
         assert!(self.is_complete(txn));
         assert_eq!(self.num_leaves(txn), 0);
         assert_eq!(self.num_hybrids(txn), 0);
@@ -1467,23 +1476,53 @@ impl MerkleRadixTrie {
         start_key: &KeyNibbles,
         end_key: &KeyNibbles,
     ) -> TrieNodeIter<'txn, T> {
+        TrieNodeIter(self.iter_nodes_with_keys(txn, start_key, end_key))
+    }
+
+    /// Same as `iter_nodes`, but also returns the key of each node.
+    pub fn iter_nodes_with_keys<'txn, T: Deserialize>(
+        &self,
+        txn: &'txn TransactionProxy,
+        start_key: &KeyNibbles,
+        end_key: &KeyNibbles,
+    ) -> TrieNodeKeyIter<'txn, T> {
         assert_eq!(
             start_key.len(),
             end_key.len(),
             "Start and end keys should have the same length"
         );
-        TrieNodeIter::new(&self.db, txn, start_key, end_key.clone())
+        TrieNodeKeyIter::new(&self.db, txn, start_key, end_key.clone())
+    }
+
+    /// Iterates over all items of the trie, i.e. all nodes that have a value, returning their keys
+    /// together with their raw, serialized values.
+    pub fn iter_items<'txn>(&self, txn: &'txn TransactionProxy) -> TrieItemIter<'txn> {
+        TrieItemIter {
+            iter: txn.cursor(&self.db).into_iter_start(),
+        }
+    }
+}
+
+/// This iterator is meant to start at `start_key` and finish at `end_key`, both of these are inclusive.
+pub struct TrieNodeIter<'txn, T>(TrieNodeKeyIter<'txn, T>);
+
+impl<'txn, T: Deserialize> Iterator for TrieNodeIter<'txn, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(_, value)| value)
     }
 }
 
 /// This iterator is meant to start at `start_key` and finish at `end_key`, both of these are inclusive.
-pub struct TrieNodeIter<'txn, T> {
+/// It returns the values together with their keys.
+pub struct TrieNodeKeyIter<'txn, T> {
     iter: IntoIterProxy<'txn, KeyNibbles, TrieNode>,
     end_key: KeyNibbles,
     _type: PhantomData<T>,
 }
 
-impl<'txn, T> TrieNodeIter<'txn, T> {
+impl<'txn, T> TrieNodeKeyIter<'txn, T> {
     /// This iterator is meant to start at `start_key` and finish at `end_key`, both of these are inclusive.
     fn new(
         db: &TableProxy,
@@ -1501,19 +1540,38 @@ impl<'txn, T> TrieNodeIter<'txn, T> {
     }
 }
 
-impl<'txn, T: Deserialize> Iterator for TrieNodeIter<'txn, T> {
-    type Item = T;
+impl<'txn, T: Deserialize> Iterator for TrieNodeKeyIter<'txn, T> {
+    type Item = (KeyNibbles, T);
 
     fn next(&mut self) -> Option<Self::Item> {
         let (k, v) = self.iter.next()?;
 
         if k <= self.end_key {
-            return T::deserialize_from_vec(&v.value?).ok();
+            let value = T::deserialize_from_vec(&v.value?).ok()?;
+            return Some((k, value));
         }
         None
     }
 }
 
+/// This iterator returns all items of the trie in the order in which they are stored.
+pub struct TrieItemIter<'txn> {
+    iter: IntoIterProxy<'txn, KeyNibbles, TrieNode>,
+}
+
+impl<'txn> Iterator for TrieItemIter<'txn> {
+    type Item = TrieItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (key, node) = self.iter.next()?;
+            if let Some(value) = node.value {
+                return Some(TrieItem::new(key, value));
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use nimiq_primitives::trie::trie_diff::{TrieDiffBuilder, ValueChange};
@@ -2520,4 +2578,67 @@ mod tests {
         assert_eq!(original.count_nodes(&txn), (0, 2, 2));
         assert!(original.is_complete(&txn));
     }
+
+    #[test]
+    fn iter_nodes_with_keys_works() {
+        let key_1: KeyNibbles = "413f22".parse().unwrap();
+        let key_2: KeyNibbles = "413b39".parse().unwrap();
+        let key_3: KeyNibbles = "cfb986".parse().unwrap();
+        let key_4: KeyNibbles = "cfb9".parse().unwrap();
+
+        let env = nimiq_database::volatile::VolatileDatabase::new(20).unwrap();
+        let trie = MerkleRadixTrie::new(env.clone(), "database");
+        let mut raw_txn = env.write_transaction();
+        let mut txn: WriteTransactionProxy = (&mut raw_txn).into();
+
+        trie.put(&mut txn, &key_1, 1).expect("complete trie");
+        trie.put(&mut txn, &key_2, 2).expect("complete trie");
+        trie.put(&mut txn, &key_3, 3).expect("complete trie");
+        trie.put(&mut txn, &key_4, 4).expect("complete trie");
+
+        let start = "000000".parse().unwrap();
+        let end = "ffffff".parse().unwrap();
+        let nodes: Vec<(KeyNibbles, i32)> = trie.iter_nodes_with_keys(&txn, &start, &end).collect();
+        assert_eq!(nodes, vec![(key_2, 2), (key_1, 1), (key_3, 3)]);
+
+        let values: Vec<i32> = trie.iter_nodes(&txn, &start, &end).collect();
+        assert_eq!(values, vec![2, 1, 3]);
+    }
+
+    #[test]
+    fn iter_items_works() {
+        let key_1: KeyNibbles = "413f22".parse().unwrap();
+        let key_2: KeyNibbles = "413b39".parse().unwrap();
+        let key_3: KeyNibbles = "cfb986".parse().unwrap();
+        let key_4: KeyNibbles = "cfb9".parse().unwrap();
+
+        let env = nimiq_database::volatile::VolatileDatabase::new(20).unwrap();
+        let trie = MerkleRadixTrie::new(env.clone(), "database");
+        let mut raw_txn = env.write_transaction();
+        let mut txn: WriteTransactionProxy = (&mut raw_txn).into();
+
+        trie.put(&mut txn, &key_1, 1).expect("complete trie");
+        trie.put(&mut txn, &key_2, 2).expect("complete trie");
+        trie.put(&mut txn, &key_3, 3).expect("complete trie");
+        trie.put(&mut txn, &key_4, 4).expect("complete trie");
+
+        // All items are returned, including the hybrid node, but none of the branch nodes.
+        let mut items: Vec<(KeyNibbles, i32)> = trie
+            .iter_items(&txn)
+            .map(|item| (item.key, i32::deserialize_from_vec(&item.value).unwrap()))
+            .collect();
+        items.sort();
+        assert_eq!(items, vec![(key_2, 2), (key_1, 1), (key_4, 4), (key_3, 3)]);
+
+        // The items can be used to initialize a copy of the trie.
+        let copy_env = nimiq_database::volatile::VolatileDatabase::new(20).unwrap();
+        let copy = MerkleRadixTrie::new(copy_env.clone(), "database");
+        let mut raw_copy_txn = copy_env.write_transaction();
+        let mut copy_txn: WriteTransactionProxy = (&mut raw_copy_txn).into();
+        copy.init(&mut copy_txn, trie.iter_items(&txn));
+        assert_eq!(
+            copy.root_hash_assert(&copy_txn),
+            trie.root_hash_assert(&txn)
+        );
+    }
 }
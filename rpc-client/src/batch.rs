@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Error};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use futures::{SinkExt, StreamExt};
+use nimiq_jsonrpc_core::Credentials;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, http::HeaderValue, Message},
+};
+use url::Url;
+
+/// The maximum number of requests sent in a single batch, the default of the RPC server.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 100;
+
+/// A list of calls of the same method, sent to the RPC server in batches.
+pub struct Batch {
+    method: String,
+    params: Vec<Value>,
+}
+
+impl Batch {
+    pub fn new(method: &str) -> Self {
+        Self {
+            method: method.to_string(),
+            params: vec![],
+        }
+    }
+
+    /// Adds a call with the given parameters to the batch.
+    pub fn add<P: Serialize>(&mut self, params: P) -> Result<&mut Self, Error> {
+        self.params.push(serde_json::to_value(params)?);
+        Ok(self)
+    }
+
+    pub fn len(&self) -> usize {
+        self.params.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+}
+
+/// Sends JSON-RPC batches to the RPC server over its WebSocket transport. Batches larger than
+/// the maximum batch size are split into several batches, which are sent one after the other.
+pub struct BatchClient {
+    url: Url,
+    credentials: Option<Credentials>,
+    max_batch_size: usize,
+}
+
+impl BatchClient {
+    pub fn new(url: Url, credentials: Option<Credentials>) -> Self {
+        Self {
+            url,
+            credentials,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+        }
+    }
+
+    /// Sets the maximum number of requests sent in a single batch. It must not exceed the
+    /// maximum batch size of the RPC server.
+    pub fn set_max_batch_size(&mut self, max_batch_size: usize) {
+        self.max_batch_size = max_batch_size.max(1);
+    }
+
+    /// Sends all calls of the batch and returns their results, in the order in which the calls
+    /// were added. A call fails individually if the RPC server answers it with an error.
+    pub async fn send<T: DeserializeOwned>(
+        &self,
+        batch: Batch,
+    ) -> Result<Vec<Result<T, Error>>, Error> {
+        if batch.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut request = self.url.as_str().into_client_request()?;
+        if let Some(credentials) = &self.credentials {
+            let encoded =
+                BASE64.encode(format!("{}:{}", credentials.username, credentials.password));
+            request.headers_mut().insert(
+                "Authorization",
+                HeaderValue::from_str(&format!("Basic {encoded}"))?,
+            );
+        }
+        let (mut socket, _) = connect_async(request).await?;
+
+        let mut results = Vec::with_capacity(batch.len());
+        for (chunk_index, chunk) in batch.params.chunks(self.max_batch_size).enumerate() {
+            let first_id = chunk_index * self.max_batch_size;
+            let requests: Vec<Value> = chunk
+                .iter()
+                .enumerate()
+                .map(|(i, params)| {
+                    json!({
+                        "jsonrpc": "2.0",
+                        "id": first_id + i,
+                        "method": batch.method,
+                        "params": params,
+                    })
+                })
+                .collect();
+            socket
+                .send(Message::Text(Value::Array(requests).to_string()))
+                .await?;
+
+            let mut responses = loop {
+                match socket.next().await {
+                    Some(Ok(Message::Text(text))) => match serde_json::from_str::<Value>(&text)? {
+                        Value::Array(responses) => break responses_by_id(responses),
+                        // The whole batch was rejected.
+                        response => bail!("Batch failed: {}", response["error"]),
+                    },
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(e.into()),
+                    None => bail!("Connection closed"),
+                }
+            };
+
+            for id in first_id..first_id + chunk.len() {
+                results.push(match responses.remove(&id) {
+                    Some(response) => parse_result(response),
+                    None => Err(anyhow!("No response for request {id}")),
+                });
+            }
+        }
+
+        socket.close(None).await?;
+        Ok(results)
+    }
+}
+
+fn responses_by_id(responses: Vec<Value>) -> HashMap<usize, Value> {
+    responses
+        .into_iter()
+        .filter_map(|response| {
+            let id = response.get("id")?.as_u64()? as usize;
+            Some((id, response))
+        })
+        .collect()
+}
+
+fn parse_result<T: DeserializeOwned>(mut response: Value) -> Result<T, Error> {
+    if let Some(error) = response.get("error") {
+        bail!("RPC error {}: {}", error["code"], error["message"]);
+    }
+    match response.get_mut("result") {
+        Some(result) => Ok(serde_json::from_value(result.take())?),
+        None => bail!("Invalid response: {response}"),
+    }
+}
@@ -8,9 +8,10 @@ use nimiq_rpc_interface::{
     zkp_component::ZKPComponentProxy,
 };
 use url::Url;
+pub mod batch;
 pub mod subcommands;
 
-use crate::subcommands::*;
+use crate::{batch::BatchClient, subcommands::*};
 
 #[derive(Debug, Parser)]
 struct Opt {
@@ -88,10 +89,16 @@ pub struct Client {
     pub validator: ValidatorProxy<ArcClient<WebsocketClient>>,
     pub network: NetworkProxy<ArcClient<WebsocketClient>>,
     pub zkp_component: ZKPComponentProxy<ArcClient<WebsocketClient>>,
+    pub batch: BatchClient,
 }
 
 impl Client {
     pub async fn new(url: Url, credentials: Option<Credentials>) -> Result<Self, Error> {
+        let batch_credentials = credentials.as_ref().map(|credentials| Credentials {
+            username: credentials.username.clone(),
+            password: credentials.password.clone(),
+        });
+        let batch = BatchClient::new(url.clone(), batch_credentials);
         let client = ArcClient::new(WebsocketClient::new(url, credentials).await?);
 
         Ok(Self {
@@ -104,6 +111,7 @@ impl Client {
             network: NetworkProxy::new(client.clone()),
             zkp_component: ZKPComponentProxy::new(client.clone()),
             ws_client: client,
+            batch,
         })
     }
 
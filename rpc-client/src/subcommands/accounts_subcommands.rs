@@ -99,6 +99,11 @@ pub enum AccountCommand {
     Get {
         /// The account's address.
         address: Address,
+
+        /// Queries the account as it was right after the given block instead. This is only
+        /// available on history nodes.
+        #[clap(long)]
+        at_block: Option<u32>,
     },
 }
 
@@ -172,11 +177,21 @@ impl HandleSubcommand for AccountCommand {
                         .await?
                 );
             }
-            AccountCommand::Get { address } => {
-                println!(
-                    "{:#?}",
-                    client.blockchain.get_account_by_address(address).await?
-                );
+            AccountCommand::Get { address, at_block } => {
+                if let Some(block_number) = at_block {
+                    println!(
+                        "{:#?}",
+                        client
+                            .blockchain
+                            .get_account_by_address_at(address, block_number)
+                            .await?
+                    );
+                } else {
+                    println!(
+                        "{:#?}",
+                        client.blockchain.get_account_by_address(address).await?
+                    );
+                }
             }
 
             AccountCommand::GetAll {} => {
@@ -4,10 +4,16 @@ use clap::{ArgGroup, Parser};
 use futures::StreamExt;
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
-use nimiq_rpc_interface::{blockchain::BlockchainInterface, types::LogType};
+use nimiq_rpc_interface::{
+    blockchain::BlockchainInterface,
+    types::{Block, ExecutedTransaction, HistoryDirection, LogPosition, LogType, RPCData},
+};
 
 use super::accounts_subcommands::HandleSubcommand;
-use crate::Client;
+use crate::{
+    batch::{Batch, DEFAULT_MAX_BATCH_SIZE},
+    Client,
+};
 
 #[derive(Debug, Parser)]
 pub enum BlockchainCommand {
@@ -41,6 +47,23 @@ pub enum BlockchainCommand {
         include_body: bool,
     },
 
+    /// Query all blocks in a range of block numbers. The blocks are fetched in batches.
+    Blocks {
+        /// The first block of the range.
+        from_block: u32,
+
+        /// The last block of the range (inclusive).
+        to_block: u32,
+
+        /// Whether to include the block bodies
+        #[clap(short = 'b', long)]
+        include_body: bool,
+
+        /// The maximum number of blocks fetched in a single batch.
+        #[clap(long, default_value_t = DEFAULT_MAX_BATCH_SIZE)]
+        batch_size: usize,
+    },
+
     /// Query a transaction from the blockchain.
     Transaction {
         /// The transaction hash.
@@ -63,6 +86,20 @@ pub enum BlockchainCommand {
         batch_number: Option<u32>,
     },
 
+    /// Query for all transactions present within the blocks in a range of block numbers.
+    /// The transactions are fetched in batches.
+    TransactionsOfBlocks {
+        /// The first block of the range.
+        from_block: u32,
+
+        /// The last block of the range (inclusive).
+        to_block: u32,
+
+        /// The maximum number of blocks whose transactions are fetched in a single batch.
+        #[clap(long, default_value_t = DEFAULT_MAX_BATCH_SIZE)]
+        batch_size: usize,
+    },
+
     /// Query for all inherents present within a block or batch.
     /// Block or batch number arguments are mutually exclusive, only exactly one of them can be provided.
     #[clap(group(
@@ -92,11 +129,26 @@ pub enum BlockchainCommand {
         #[clap(long)]
         max: Option<u16>,
 
+        /// Hash of the last transaction of the previous page. If absent it starts at the beginning
+        /// of the history.
+        #[clap(long)]
+        start_after: Option<Blake2bHash>,
+
+        /// The order in which the transactions are fetched. If absent it defaults to newest-first.
+        #[clap(long, value_enum)]
+        direction: Option<HistoryDirection>,
+
         /// If set true only the hash of the transactions will be fetched. Otherwise the full transactions will be retrieved.
         #[clap(short = 'h')]
         just_hash: bool,
     },
 
+    /// Returns the total number of transactions for a given address, including reward transactions.
+    TransactionCountByAddress {
+        /// The address to query by.
+        address: Address,
+    },
+
     /// Returns the information for the slot owner at the given block height and offset. The
     /// offset is optional, it will default to the block number for micro blocks and to the round number for macro blocks.
     /// We only have this information available for the last 2 batches at most.
@@ -123,6 +175,19 @@ pub enum BlockchainCommand {
         address: Address,
     },
 
+    /// Reports the produced and missed micro blocks, fork proofs, lost rewards and deactivations
+    /// of a validator for every batch of the last epochs.
+    /// IMPORTANT: This is a very expensive operation, iterating over all blocks of the requested epochs.
+    ValidatorPerformance {
+        /// The validator address to query by.
+        address: Address,
+
+        /// The number of epochs to report, counting back from the current one. At most 4 epochs
+        /// can be requested, batches whose blocks have been pruned are omitted.
+        #[clap(short, long)]
+        epochs: Option<u32>,
+    },
+
     /// Tries to fetch all validators in the staking contract.
     /// IMPORTANT: This is a very expensive operation, iterating over all existing validators in the contract.
     Validators {},
@@ -156,6 +221,38 @@ pub enum BlockchainCommand {
         address: Address,
     },
 
+    /// Returns the logs produced by the blocks in the given range that are associated with the
+    /// specified addresses and of any of the log types given.
+    /// If no addresses or no log types are provided it does not filter by them.
+    Logs {
+        /// The first block of the range.
+        from_block: u32,
+
+        /// The last block of the range (inclusive).
+        to_block: u32,
+
+        /// List of all addresses to select. If empty it does not filter by address.
+        #[clap(short = 'a', long)]
+        addresses: Vec<Address>,
+
+        /// List of all log types to select. If empty it does not filter by log type.
+        #[clap(short = 'l', long)]
+        log_types: Vec<LogType>,
+
+        /// Max number of logs to fetch. If absent it defaults to 500.
+        #[clap(long)]
+        max: Option<u16>,
+
+        /// Block number of the last log of the previous page. If absent it starts at the
+        /// beginning of the range.
+        #[clap(long, requires = "start_after_index")]
+        start_after_block: Option<u32>,
+
+        /// Position within its block of the last log of the previous page.
+        #[clap(long, requires = "start_after_block")]
+        start_after_index: Option<u32>,
+    },
+
     /// Follow the logs associated with the specified addresses and of any of the log types given.
     /// If no addresses or no log types are provided it fetches all logs.
     FollowLogsOfAddressesAndTypes {
@@ -164,7 +261,7 @@ pub enum BlockchainCommand {
         addresses: Vec<Address>,
 
         /// List of all log types to select. If empty it does not filter by log type.
-        #[clap(short = 'l', long, value_enum)]
+        #[clap(short = 'l', long)]
         log_types: Vec<LogType>,
     },
 }
@@ -193,6 +290,21 @@ impl HandleSubcommand for BlockchainCommand {
                 }?;
                 println!("{block:#?}")
             }
+            BlockchainCommand::Blocks {
+                from_block,
+                to_block,
+                include_body,
+                batch_size,
+            } => {
+                let mut batch = Batch::new("getBlockByNumber");
+                for block_number in from_block..=to_block {
+                    batch.add((block_number, Some(include_body)))?;
+                }
+                client.batch.set_max_batch_size(batch_size);
+                for block in client.batch.send::<RPCData<Block, ()>>(batch).await? {
+                    println!("{:#?}", block?)
+                }
+            }
             BlockchainCommand::BlockNumber {} => {
                 println!("{:#?}", client.blockchain.get_block_number().await?)
             }
@@ -239,6 +351,24 @@ impl HandleSubcommand for BlockchainCommand {
                     )
                 }
             }
+            BlockchainCommand::TransactionsOfBlocks {
+                from_block,
+                to_block,
+                batch_size,
+            } => {
+                let mut batch = Batch::new("getTransactionsByBlockNumber");
+                for block_number in from_block..=to_block {
+                    batch.add((block_number,))?;
+                }
+                client.batch.set_max_batch_size(batch_size);
+                let results = client
+                    .batch
+                    .send::<RPCData<Vec<ExecutedTransaction>, ()>>(batch)
+                    .await?;
+                for (block_number, transactions) in (from_block..=to_block).zip(results) {
+                    println!("Block #{block_number}: {:#?}", transactions?)
+                }
+            }
             BlockchainCommand::Inherents {
                 block_number,
                 batch_number,
@@ -265,6 +395,8 @@ impl HandleSubcommand for BlockchainCommand {
             BlockchainCommand::TransactionsByAddress {
                 address,
                 max,
+                start_after,
+                direction,
                 just_hash,
             } => {
                 if just_hash {
@@ -272,7 +404,7 @@ impl HandleSubcommand for BlockchainCommand {
                         "{:#?}",
                         client
                             .blockchain
-                            .get_transaction_hashes_by_address(address, max)
+                            .get_transaction_hashes_by_address(address, max, start_after, direction)
                             .await?
                     )
                 } else {
@@ -280,11 +412,20 @@ impl HandleSubcommand for BlockchainCommand {
                         "{:#?}",
                         client
                             .blockchain
-                            .get_transactions_by_address(address, max)
+                            .get_transactions_by_address(address, max, start_after, direction)
                             .await?
                     )
                 }
             }
+            BlockchainCommand::TransactionCountByAddress { address } => {
+                println!(
+                    "{:#?}",
+                    client
+                        .blockchain
+                        .get_transaction_count_by_address(address)
+                        .await?
+                )
+            }
             BlockchainCommand::SlashedSlots { previous_slashed } => {
                 if previous_slashed {
                     println!(
@@ -302,6 +443,13 @@ impl HandleSubcommand for BlockchainCommand {
                 "{:#?}",
                 client.blockchain.get_validator_by_address(address).await?
             ),
+            BlockchainCommand::ValidatorPerformance { address, epochs } => println!(
+                "{:#?}",
+                client
+                    .blockchain
+                    .get_validator_performance(address, epochs)
+                    .await?
+            ),
 
             BlockchainCommand::Validators {} => {
                 println!("{:#?}", client.blockchain.get_validators().await?)
@@ -351,6 +499,30 @@ impl HandleSubcommand for BlockchainCommand {
                     println!("{validator:#?}");
                 }
             }
+            BlockchainCommand::Logs {
+                from_block,
+                to_block,
+                addresses,
+                log_types,
+                max,
+                start_after_block,
+                start_after_index,
+            } => {
+                let start_after =
+                    start_after_block
+                        .zip(start_after_index)
+                        .map(|(block_number, index)| LogPosition {
+                            block_number,
+                            index,
+                        });
+                println!(
+                    "{:#?}",
+                    client
+                        .blockchain
+                        .get_logs(from_block, to_block, addresses, log_types, max, start_after)
+                        .await?
+                );
+            }
             BlockchainCommand::FollowLogsOfAddressesAndTypes {
                 addresses,
                 log_types,
@@ -1,6 +1,8 @@
 use anyhow::Error;
 use async_trait::async_trait;
 use clap::Parser;
+use futures::StreamExt;
+use nimiq_keys::Address;
 use nimiq_rpc_interface::mempool::MempoolInterface;
 
 use super::accounts_subcommands::HandleSubcommand;
@@ -30,6 +32,24 @@ pub enum MempoolCommand {
 
     /// Returns the minimum fee per byte of the local mempool.
     MinFeePerByte {},
+
+    /// Returns the statistics of the local mempool for the most recent blocks.
+    MempoolHistory {},
+
+    /// Returns the estimated fee per byte needed to be included within the given number of blocks.
+    EstimateFee {
+        /// The number of blocks within which the transaction should be included.
+        #[clap(short, long, default_value_t = 1)]
+        target_blocks: u32,
+    },
+
+    /// Follow the transactions being added to, removed from or evicted from the local mempool.
+    /// If no addresses are provided it follows all transactions.
+    FollowTransactions {
+        /// List of all addresses to follow. If empty it does not filter by address.
+        #[clap(short = 'a', long)]
+        addresses: Vec<Address>,
+    },
 }
 
 #[async_trait]
@@ -66,6 +86,27 @@ impl HandleSubcommand for MempoolCommand {
             MempoolCommand::MinFeePerByte {} => {
                 println!("{:#?}", client.mempool.get_min_fee_per_byte().await?);
             }
+            MempoolCommand::MempoolHistory {} => {
+                println!("{:#?}", client.mempool.get_mempool_history().await?);
+            }
+            MempoolCommand::EstimateFee { target_blocks } => {
+                println!("{:#?}", client.mempool.estimate_fee(target_blocks).await?);
+            }
+            MempoolCommand::FollowTransactions { addresses } => {
+                let addresses = if addresses.is_empty() {
+                    None
+                } else {
+                    Some(addresses)
+                };
+                let mut stream = client
+                    .mempool
+                    .subscribe_for_mempool_transactions(addresses)
+                    .await?;
+
+                while let Some(event) = stream.next().await {
+                    println!("{event:#?}");
+                }
+            }
         }
         Ok(client)
     }
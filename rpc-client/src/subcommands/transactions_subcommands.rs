@@ -105,8 +105,28 @@ pub enum TransactionCommand {
         tx_commons: TxCommon,
     },
 
-    /// Sends a `unstake` transaction to the network. The transaction fee will be paid from the funds
-    /// being unstaked.
+    /// Sends a `set_inactive_stake` transaction to the network. Increasing the inactive balance
+    /// deactivates stake, which can be unstaked once it has been released. Decreasing it reactivates
+    /// stake. You can pay the transaction fee from a basic account (by providing the sender wallet)
+    /// or from the staker account's balance (by not providing a sender wallet).
+    SetInactiveStake {
+        /// The fee will be payed by this wallet if any is provided. In such case the sender wallet must be unlocked prior to this action.
+        /// If absent the fee is payed by the stakers account.
+        #[clap(long)]
+        sender_wallet: Option<Address>,
+
+        /// The staker whose inactive balance is set. This wallet must be already unlocked.
+        staker_wallet: Address,
+
+        /// The new inactive balance of the staker.
+        new_inactive_balance: Coin,
+
+        #[clap(flatten)]
+        tx_commons: TxCommon,
+    },
+
+    /// Sends a `unstake` transaction to the network. Only the released inactive balance of the
+    /// staker can be unstaked. The transaction fee will be paid from the funds being unstaked.
     Unstake {
         /// The stake will be sent from this wallet. The sender wallet must be unlocked prior to this action.
         sender_wallet: Address,
@@ -422,6 +442,38 @@ impl HandleSubcommand for TransactionCommand {
                     println!("{txid:#?}");
                 }
             }
+            TransactionCommand::SetInactiveStake {
+                sender_wallet,
+                staker_wallet,
+                new_inactive_balance,
+                tx_commons,
+            } => {
+                if tx_commons.dry {
+                    let tx = client
+                        .consensus
+                        .create_set_inactive_stake_transaction(
+                            sender_wallet,
+                            staker_wallet,
+                            new_inactive_balance,
+                            tx_commons.fee,
+                            tx_commons.validity_start_height,
+                        )
+                        .await?;
+                    println!("{tx:#?}");
+                } else {
+                    let txid = client
+                        .consensus
+                        .send_set_inactive_stake_transaction(
+                            sender_wallet,
+                            staker_wallet,
+                            new_inactive_balance,
+                            tx_commons.fee,
+                            tx_commons.validity_start_height,
+                        )
+                        .await?;
+                    println!("{txid:#?}");
+                }
+            }
             TransactionCommand::Unstake {
                 sender_wallet,
                 recipient,
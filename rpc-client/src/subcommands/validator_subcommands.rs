@@ -28,6 +28,16 @@ pub enum ValidatorCommand {
     /// Returns the voting key of the local validator.
     ValidatorVotingKey {},
 
+    /// Exports the signing history of the local validator. Import it on the new machine before
+    /// starting the validator there to avoid being slashed for conflicting signatures.
+    ExportSlashingProtection {},
+
+    /// Imports a signing history exported with export-slashing-protection into the local validator.
+    ImportSlashingProtection {
+        /// The hex encoded signing history.
+        data: String,
+    },
+
     /// Sends a `new_validator` transaction to the network. You need to provide the address of a basic
     /// account (the sender wallet) to pay the transaction fee and the validator deposit. The sender wallet must be unlocked
     /// prior to this command.
@@ -92,6 +102,21 @@ pub enum ValidatorCommand {
         tx_commons: TxCommon,
     },
 
+    /// Sends a transaction to the network to set the commission this validator charges on the
+    /// rewards of its stakers. Setting a commission distributes the rewards to the stakers,
+    /// omitting it opts out again. The sender wallet must be unlocked prior to this command.
+    UpdateValidatorCommission {
+        /// The fee will be payed from this address. This wallet must be already unlocked.
+        sender_wallet: Address,
+
+        /// The new commission in basis points charged on the rewards of the stakers.
+        #[clap(long)]
+        new_commission: Option<u16>,
+
+        #[clap(flatten)]
+        tx_commons: TxCommon,
+    },
+
     /// Sends a transaction to deactivate this validator. In order to avoid having the validator reactivated soon after
     /// this transaction takes effect, use the command set-auto-reactivate-validator to make sure the automatic reactivation
     /// configuration is turned off.
@@ -142,6 +167,20 @@ impl HandleSubcommand for ValidatorCommand {
                 println!("{:#?}", client.validator.get_voting_key().await?);
             }
 
+            ValidatorCommand::ExportSlashingProtection {} => {
+                println!(
+                    "{:#?}",
+                    client.validator.export_slashing_protection().await?
+                );
+            }
+
+            ValidatorCommand::ImportSlashingProtection { data } => {
+                println!(
+                    "{:#?}",
+                    client.validator.import_slashing_protection(data).await?
+                );
+            }
+
             ValidatorCommand::SetAutoReactivateValidator {
                 automatic_reactivate,
             } => {
@@ -236,6 +275,39 @@ impl HandleSubcommand for ValidatorCommand {
                 }
             }
 
+            ValidatorCommand::UpdateValidatorCommission {
+                sender_wallet,
+                new_commission,
+                tx_commons,
+            } => {
+                let validator_address = client.validator.get_address().await?.data;
+                if tx_commons.dry {
+                    let tx = client
+                        .consensus
+                        .create_update_validator_commission_transaction(
+                            sender_wallet,
+                            validator_address,
+                            new_commission,
+                            tx_commons.fee,
+                            tx_commons.validity_start_height,
+                        )
+                        .await?;
+                    println!("{tx:#?}");
+                } else {
+                    let txid = client
+                        .consensus
+                        .send_update_validator_commission_transaction(
+                            sender_wallet,
+                            validator_address,
+                            new_commission,
+                            tx_commons.fee,
+                            tx_commons.validity_start_height,
+                        )
+                        .await?;
+                    println!("{txid:#?}");
+                }
+            }
+
             ValidatorCommand::DeactivateValidator {
                 sender_wallet,
                 tx_commons,
@@ -10,6 +10,12 @@ use crate::Client;
 pub enum ZKPComponentCommand {
     /// Returns the current zkp state.
     ZkpState {},
+
+    /// Lists the block numbers of the election blocks for which a proof is stored.
+    StoredZkps {},
+
+    /// Returns the stored proof for the election block with the given block number.
+    StoredZkp { block_number: u32 },
 }
 
 #[async_trait]
@@ -19,6 +25,15 @@ impl HandleSubcommand for ZKPComponentCommand {
             ZKPComponentCommand::ZkpState {} => {
                 println!("{:?}", client.zkp_component.get_zkp_state().await?);
             }
+            ZKPComponentCommand::StoredZkps {} => {
+                println!("{:?}", client.zkp_component.list_stored_zkps().await?);
+            }
+            ZKPComponentCommand::StoredZkp { block_number } => {
+                println!(
+                    "{:?}",
+                    client.zkp_component.get_stored_zkp(block_number).await?
+                );
+            }
         }
         Ok(client)
     }
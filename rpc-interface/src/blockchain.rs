@@ -4,8 +4,9 @@ use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
 
 use crate::types::{
-    Account, Block, BlockLog, BlockchainState, ExecutedTransaction, Inherent, LogType, RPCData,
-    RPCResult, SlashedSlots, Slot, Staker, Validator,
+    Account, Block, BlockLog, BlockchainState, ExecutedTransaction, HistoryDirection, IndexedLog,
+    Inherent, LogPosition, LogType, RPCData, RPCResult, SlashedSlots, Slot, Staker, Validator,
+    ValidatorBatchPerformance,
 };
 
 #[nimiq_jsonrpc_derive::proxy(name = "BlockchainProxy", rename_all = "camelCase")]
@@ -72,19 +73,52 @@ pub trait BlockchainInterface {
         &mut self,
         address: Address,
         max: Option<u16>,
+        start_after: Option<Blake2bHash>,
+        direction: Option<HistoryDirection>,
     ) -> RPCResult<Vec<Blake2bHash>, (), Self::Error>;
 
     async fn get_transactions_by_address(
         &mut self,
         address: Address,
         max: Option<u16>,
+        start_after: Option<Blake2bHash>,
+        direction: Option<HistoryDirection>,
     ) -> RPCResult<Vec<ExecutedTransaction>, (), Self::Error>;
 
+    async fn get_transaction_count_by_address(
+        &mut self,
+        address: Address,
+    ) -> RPCResult<usize, (), Self::Error>;
+
+    /// Fetches the logs produced by the blocks between `from_block` and `to_block` (both inclusive)
+    /// that are related to any of the given addresses and of any of the given log types.
+    /// The logs are filtered the same way as in `subscribe_for_logs_by_addresses_and_types`.
+    /// The range may span at most 1000 blocks and at most `max` logs (500 by default, 1000 at
+    /// most) are returned. To fetch the remaining logs of the range, pass the position of the last
+    /// log returned as `start_after`.
+    async fn get_logs(
+        &mut self,
+        from_block: u32,
+        to_block: u32,
+        addresses: Vec<Address>,
+        log_types: Vec<LogType>,
+        max: Option<u16>,
+        start_after: Option<LogPosition>,
+    ) -> RPCResult<Vec<IndexedLog>, (), Self::Error>;
+
     async fn get_account_by_address(
         &mut self,
         address: Address,
     ) -> RPCResult<Account, BlockchainState, Self::Error>;
 
+    /// Reconstructs the account at the given address as it was right after the given block.
+    /// Only available on history nodes and for blocks whose logs are stored.
+    async fn get_account_by_address_at(
+        &mut self,
+        address: Address,
+        block_number: u32,
+    ) -> RPCResult<Account, (), Self::Error>;
+
     /// Fetches all accounts in the accounts tree.
     /// IMPORTANT: This operation iterates over all accounts in the accounts tree
     /// and thus is extremely computationally expensive.
@@ -107,6 +141,17 @@ pub trait BlockchainInterface {
         address: Address,
     ) -> RPCResult<Validator, BlockchainState, Self::Error>;
 
+    /// Reports the performance of a validator for every batch of the last `epochs` epochs
+    /// (defaults to the current epoch only, at most 4). Batches whose micro blocks have been
+    /// pruned already are omitted.
+    /// IMPORTANT: This operation iterates over all blocks of the requested epochs
+    /// and thus is extremely computationally expensive.
+    async fn get_validator_performance(
+        &mut self,
+        address: Address,
+        epochs: Option<u32>,
+    ) -> RPCResult<Vec<ValidatorBatchPerformance>, BlockchainState, Self::Error>;
+
     /// Fetches all validators in the staking contract.
     /// IMPORTANT: This operation iterates over all validators in the staking contract
     /// and thus is extremely computationally expensive.
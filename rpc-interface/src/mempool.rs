@@ -1,8 +1,12 @@
 use async_trait::async_trait;
+use futures::stream::BoxStream;
 use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
 use nimiq_transaction::Transaction;
 
-use crate::types::{HashOrTx, MempoolInfo, RPCResult};
+use crate::types::{
+    HashOrTx, MempoolHistoryEntry, MempoolInfo, MempoolTransactionEvent, RPCData, RPCResult,
+};
 
 #[nimiq_jsonrpc_derive::proxy(name = "MempoolProxy", rename_all = "camelCase")]
 #[async_trait]
@@ -31,9 +35,25 @@ pub trait MempoolInterface {
     /// Obtains the minimum fee per byte as per mempool configuration
     async fn get_min_fee_per_byte(&mut self) -> RPCResult<f64, (), Self::Error>;
 
+    /// Estimates the fee per byte needed for a transaction to be included within the given number
+    /// of blocks, based on the mempool content and the fill of recent blocks
+    async fn estimate_fee(&mut self, target_blocks: u32) -> RPCResult<f64, (), Self::Error>;
+
+    /// Obtains the mempool statistics of the most recent blocks, from oldest to newest
+    async fn get_mempool_history(&mut self)
+        -> RPCResult<Vec<MempoolHistoryEntry>, (), Self::Error>;
+
     /// Tries to obtain the given transaction (using its hash) from the mempool
     async fn get_transaction_from_mempool(
         &mut self,
         hash: Blake2bHash,
     ) -> RPCResult<Transaction, (), Self::Error>;
+
+    /// Subscribes to transactions being added to, removed from or evicted from the mempool.
+    /// If addresses are given, only transactions sent from or to one of them are streamed.
+    #[stream]
+    async fn subscribe_for_mempool_transactions(
+        &mut self,
+        addresses: Option<Vec<Address>>,
+    ) -> Result<BoxStream<'static, RPCData<MempoolTransactionEvent, ()>>, Self::Error>;
 }
@@ -9,7 +9,7 @@ use std::{
 use clap::ValueEnum;
 use nimiq_account::{BlockLog as BBlockLog, Log, TransactionLog};
 use nimiq_block::{MicroJustification, MultiSignature};
-use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainError};
+use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainError, Direction};
 use nimiq_blockchain_proxy::BlockchainReadProxy;
 use nimiq_bls::CompressedPublicKey;
 use nimiq_collections::BitSet;
@@ -24,6 +24,9 @@ use nimiq_vrf::VrfSeed;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DeserializeFromStr, SerializeDisplay};
 
+pub use nimiq_account::LogType;
+pub use nimiq_blockchain::LogPosition;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum HashOrTx {
@@ -381,6 +384,31 @@ pub struct SlashedSlots {
     pub disabled: BitSet,
 }
 
+/// Performance of a validator during a single batch.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorBatchPerformance {
+    pub batch_number: u32,
+    pub epoch_number: u32,
+    /// Number of slots the validator owns in the epoch of this batch.
+    pub num_slots: u16,
+    /// Number of micro blocks the validator was selected for and produced.
+    pub produced_micro_blocks: u32,
+    /// Number of micro blocks the validator was selected for but missed, i.e. the skip blocks
+    /// it caused.
+    pub missed_micro_blocks: u32,
+    /// Number of fork proofs against the validator that were included in this batch.
+    pub fork_proofs: u32,
+    /// Number of the validator's slots that do not receive rewards for this batch.
+    pub lost_reward_slots: u16,
+    /// The rewards lost by those slots, including their share of the transaction fees. This is
+    /// only present once the rewards of the batch have been distributed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lost_rewards: Option<Coin>,
+    /// The logs of this batch that deactivated, reactivated or retired the validator.
+    pub deactivation_events: Vec<IndexedLog>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ForkProof {
@@ -498,12 +526,20 @@ impl Transaction {
     }
 
     fn from_reward_tx(reward_tx: &RewardTransaction, block_number: u32) -> Self {
+        // Rewards distributed to the stakers of a validator are paid to the staking contract,
+        // the data holds the address of the validator.
+        let data = match &reward_tx.delegation {
+            Some(validator_address) => validator_address.serialize_to_vec(),
+            None => vec![],
+        };
+
         Self {
             block_number: Some(block_number),
             from: Policy::COINBASE_ADDRESS,
             to: reward_tx.recipient.clone(),
             value: reward_tx.value,
             fee: Coin::ZERO,
+            data,
             validity_start_height: block_number,
             ..Default::default()
         }
@@ -520,6 +556,13 @@ pub enum Inherent {
         value: Coin,
         hash: Blake2bHash,
     },
+    DistributeReward {
+        block_number: u32,
+        block_time: u64,
+        validator_address: Address,
+        value: Coin,
+        hash: Blake2bHash,
+    },
     Slash {
         block_number: u32,
         block_time: u64,
@@ -548,6 +591,16 @@ impl Inherent {
                 value,
                 hash,
             },
+            BaseInherent::DistributeReward {
+                validator_address,
+                value,
+            } => Inherent::DistributeReward {
+                block_number,
+                block_time,
+                validator_address,
+                value,
+                hash,
+            },
             BaseInherent::Slash { slot } => Inherent::Slash {
                 block_number,
                 block_time,
@@ -688,14 +741,49 @@ pub struct Staker {
     pub balance: Coin,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub delegation: Option<Address>,
+    pub split_balance: Coin,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub delegations: Vec<StakerDelegation>,
+    pub inactive_balance: Coin,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inactive_from: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inactive_release: Option<u32>,
 }
 
 impl Staker {
-    pub fn from_staker(staker: &nimiq_account::Staker) -> Self {
+    pub fn from_staker(
+        staker: &nimiq_account::Staker,
+        delegations: &[nimiq_account::StakerDelegation],
+    ) -> Self {
         Staker {
             address: staker.address.clone(),
             balance: staker.balance,
             delegation: staker.delegation.clone(),
+            split_balance: staker.split_balance,
+            delegations: delegations
+                .iter()
+                .map(StakerDelegation::from_delegation)
+                .collect(),
+            inactive_balance: staker.inactive_balance,
+            inactive_from: staker.inactive_from,
+            inactive_release: staker.inactive_release(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StakerDelegation {
+    pub validator_address: Address,
+    pub stake: Coin,
+}
+
+impl StakerDelegation {
+    pub fn from_delegation(delegation: &nimiq_account::StakerDelegation) -> Self {
+        StakerDelegation {
+            validator_address: delegation.validator_address.clone(),
+            stake: delegation.stake,
         }
     }
 }
@@ -709,6 +797,8 @@ pub struct Validator {
     pub reward_address: Address,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub signal_data: Option<Blake2bHash>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commission: Option<u16>,
     pub balance: Coin,
     pub num_stakers: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -716,13 +806,14 @@ pub struct Validator {
 }
 
 impl Validator {
-    pub fn from_validator(validator: &nimiq_account::Validator) -> Self {
+    pub fn from_validator(validator: &nimiq_account::Validator, commission: Option<u16>) -> Self {
         Validator {
             address: validator.address.clone(),
             signing_key: validator.signing_key,
             voting_key: validator.voting_key.clone(),
             reward_address: validator.reward_address.clone(),
             signal_data: validator.signal_data.clone(),
+            commission,
             balance: validator.total_stake,
             num_stakers: validator.num_stakers,
             inactivity_flag: validator.inactive_since,
@@ -825,32 +916,21 @@ impl BlockchainState {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, ValueEnum)]
+/// The order in which the transactions of an address are returned.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, ValueEnum)]
 #[serde(rename_all = "kebab-case")]
-pub enum LogType {
-    PayFee,
-    Transfer,
-    HtlcCreate,
-    HtlcTimeoutResolve,
-    HtlcRegularTransfer,
-    HtlcEarlyResolve,
-    VestingCreate,
-    CreateValidator,
-    UpdateValidator,
-    ValidatorFeeDeduction,
-    DeactivateValidator,
-    ReactivateValidator,
-    CreateStaker,
-    Stake,
-    UpdateStaker,
-    StakerFeeDeduction,
-    RetireValidator,
-    DeleteValidator,
-    Unstake,
-    PayoutReward,
-    Slash,
-    RevertContract,
-    FailedTransaction,
+pub enum HistoryDirection {
+    NewestFirst,
+    OldestFirst,
+}
+
+impl From<HistoryDirection> for Direction {
+    fn from(direction: HistoryDirection) -> Self {
+        match direction {
+            HistoryDirection::NewestFirst => Direction::Backward,
+            HistoryDirection::OldestFirst => Direction::Forward,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -874,32 +954,23 @@ pub enum BlockLog {
     },
 }
 
-impl LogType {
-    pub fn with_log(log: &Log) -> Self {
-        match log {
-            Log::PayFee { .. } => Self::PayFee,
-            Log::Transfer { .. } => Self::Transfer,
-            Log::HTLCCreate { .. } => Self::HtlcCreate,
-            Log::HTLCTimeoutResolve { .. } => Self::HtlcTimeoutResolve,
-            Log::HTLCRegularTransfer { .. } => Self::HtlcRegularTransfer,
-            Log::HTLCEarlyResolve { .. } => Self::HtlcEarlyResolve,
-            Log::VestingCreate { .. } => Self::VestingCreate,
-            Log::CreateValidator { .. } => Self::CreateValidator,
-            Log::UpdateValidator { .. } => Self::UpdateValidator,
-            Log::DeactivateValidator { .. } => Self::DeactivateValidator,
-            Log::ReactivateValidator { .. } => Self::ReactivateValidator,
-            Log::CreateStaker { .. } => Self::CreateStaker,
-            Log::Stake { .. } => Self::Stake,
-            Log::UpdateStaker { .. } => Self::UpdateStaker,
-            Log::RetireValidator { .. } => Self::RetireValidator,
-            Log::DeleteValidator { .. } => Self::DeleteValidator,
-            Log::Unstake { .. } => Self::Unstake,
-            Log::PayoutReward { .. } => Self::PayoutReward,
-            Log::Slash { .. } => Self::Slash,
-            Log::RevertContract { .. } => Self::RevertContract,
-            Log::FailedTransaction { .. } => Self::FailedTransaction,
-            Log::ValidatorFeeDeduction { .. } => Self::ValidatorFeeDeduction,
-            Log::StakerFeeDeduction { .. } => Self::StakerFeeDeduction,
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedLog {
+    pub block_number: u32,
+    pub index: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<Blake2bHash>,
+    pub log: Log,
+}
+
+impl From<nimiq_blockchain::IndexedLog> for IndexedLog {
+    fn from(indexed_log: nimiq_blockchain::IndexedLog) -> Self {
+        IndexedLog {
+            block_number: indexed_log.block_number,
+            index: indexed_log.index,
+            transaction_hash: indexed_log.tx_hash,
+            log: indexed_log.log,
         }
     }
 }
@@ -916,7 +987,7 @@ pub fn is_of_log_type_and_related_to_addresses(
     // If addresses are empty, it tries to find a matching log_types and early return. Otherwise, it will finish and return false.
     if addresses.is_empty() {
         for log_type in log_types {
-            if log_type.eq(&LogType::with_log(log)) {
+            if *log_type == log.log_type() {
                 return true;
             }
         }
@@ -929,7 +1000,7 @@ pub fn is_of_log_type_and_related_to_addresses(
                 }
                 // If there are log_types to compare with, it tries to find a match and early return.
                 for log_type in log_types {
-                    if log_type.eq(&LogType::with_log(log)) {
+                    if *log_type == log.log_type() {
                         return true;
                     }
                 }
@@ -962,6 +1033,60 @@ impl ZKPState {
     }
 }
 
+/// A ZK proof kept in the proof history of the node.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredZKProof {
+    /// The block number of the election block the proof proves.
+    pub block_number: u32,
+    /// The serialized proof, hex encoded.
+    #[serde(with = "crate::serde_helpers::hex")]
+    pub proof: Vec<u8>,
+}
+
+impl StoredZKProof {
+    pub fn from_zk_proof(zk_proof: &nimiq_zkp_component::types::ZKProof) -> Self {
+        Self {
+            block_number: zk_proof.block_number,
+            proof: zk_proof.serialize_to_vec(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MempoolEvictions {
+    pub expired: u32,
+    pub filtered: u32,
+    pub too_full: u32,
+    pub invalidated: u32,
+    pub replaced: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MempoolHistoryEntry {
+    pub block_number: u32,
+    pub regular_transactions: usize,
+    pub control_transactions: usize,
+    pub avg_fee_per_byte: f64,
+    pub evictions: MempoolEvictions,
+}
+
+/// A transaction entering or leaving the mempool.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum MempoolTransactionEvent {
+    /// The transaction was added to the mempool.
+    Added { transaction: Transaction },
+    /// The transaction was removed from the mempool because it was included in a block.
+    Removed { transaction: Transaction },
+    /// The transaction was evicted from the mempool without being included in a block.
+    Evicted { transaction: Transaction },
+    /// The subscriber fell behind and missed the given number of events.
+    Lagged { missed: u64 },
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MempoolInfo {
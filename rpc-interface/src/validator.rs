@@ -18,4 +18,15 @@ pub trait ValidatorInterface {
         &mut self,
         automatic_reactivate: bool,
     ) -> RPCResult<(), (), Self::Error>;
+
+    /// Exports the signing history of our validator, to be imported on another machine
+    /// before the validator is started there.
+    async fn export_slashing_protection(&mut self) -> RPCResult<String, (), Self::Error>;
+
+    /// Imports a signing history exported by `export_slashing_protection`. Returns the number
+    /// of imported messages.
+    async fn import_slashing_protection(
+        &mut self,
+        data: String,
+    ) -> RPCResult<usize, (), Self::Error>;
 }
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 
-use crate::types::{RPCResult, ZKPState};
+use crate::types::{RPCResult, StoredZKProof, ZKPState};
 
 #[nimiq_jsonrpc_derive::proxy(name = "ZKPComponentProxy", rename_all = "camelCase")]
 #[async_trait]
@@ -8,4 +8,11 @@ pub trait ZKPComponentInterface {
     type Error;
 
     async fn get_zkp_state(&mut self) -> RPCResult<ZKPState, (), Self::Error>;
+
+    async fn list_stored_zkps(&mut self) -> RPCResult<Vec<u32>, (), Self::Error>;
+
+    async fn get_stored_zkp(
+        &mut self,
+        block_number: u32,
+    ) -> RPCResult<StoredZKProof, (), Self::Error>;
 }
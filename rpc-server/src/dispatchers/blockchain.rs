@@ -1,8 +1,10 @@
 use async_trait::async_trait;
 use futures::{future, stream::BoxStream, StreamExt};
-use nimiq_account::{BlockLog as BBlockLog, TransactionLog};
-use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainEvent};
+use nimiq_account::{BlockLog as BBlockLog, Log, TransactionLog};
+use nimiq_blockchain::{reward::reward_pot_for_batch, Blockchain, LogPosition};
+use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainError, BlockchainEvent};
 use nimiq_blockchain_proxy::{BlockchainProxy, BlockchainReadProxy};
+use nimiq_database::TransactionProxy;
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
 use nimiq_primitives::{key_nibbles::KeyNibbles, policy::Policy};
@@ -10,14 +12,23 @@ use nimiq_rpc_interface::{
     blockchain::BlockchainInterface,
     types::{
         is_of_log_type_and_related_to_addresses, Account, Block, BlockLog, BlockchainState,
-        ExecutedTransaction, Inherent, LogType, RPCData, RPCResult, SlashedSlots, Slot, Staker,
-        Validator,
+        ExecutedTransaction, HistoryDirection, IndexedLog, Inherent, LogType, RPCData, RPCResult,
+        SlashedSlots, Slot, Staker, Validator, ValidatorBatchPerformance,
     },
 };
 use tokio_stream::wrappers::BroadcastStream;
 
 use crate::error::Error;
 
+/// The maximum number of blocks `get_logs` searches in a single request.
+const MAX_LOGS_BLOCK_RANGE: u32 = 1_000;
+
+/// The maximum number of logs `get_logs` returns in a single request.
+const MAX_LOGS: u16 = 1_000;
+
+/// The maximum number of epochs `get_validator_performance` reports on in a single request.
+const MAX_PERFORMANCE_EPOCHS: u32 = 4;
+
 pub struct BlockchainDispatcher {
     blockchain: BlockchainProxy,
 }
@@ -60,9 +71,12 @@ fn get_validator_by_address(
         let validator = staking_contract
             .get_validator(&data_store.read(&db_txn), address)
             .ok_or_else(|| Error::ValidatorNotFound(address.clone()))?;
+        let commission = staking_contract
+            .get_reward_pool(&data_store.read(&db_txn), address)
+            .and_then(|pool| pool.commission);
 
         Ok(RPCData::with_blockchain(
-            Validator::from_validator(&validator),
+            Validator::from_validator(&validator, commission),
             blockchain_proxy,
         ))
     } else {
@@ -70,6 +84,171 @@ fn get_validator_by_address(
     }
 }
 
+/// Computes the performance of a validator during the given batch, which must have started
+/// already. The lost rewards and disabled slots of finished batches are taken from their macro
+/// block, those of the current batch from the staking contract. Returns None if the micro blocks
+/// of the batch have been pruned already.
+/// This function requires the read lock acquisition prior to its execution.
+fn get_validator_batch_performance(
+    blockchain: &Blockchain,
+    address: &Address,
+    batch_number: u32,
+    txn: &TransactionProxy,
+) -> Result<Option<ValidatorBatchPerformance>, Error> {
+    let head_number = blockchain.block_number();
+    let first_block = Policy::first_block_of_batch(batch_number)
+        .ok_or_else(|| Error::InvalidArgument("Batch number out of bounds".to_string()))?;
+    let macro_number = Policy::macro_block_of(batch_number)
+        .ok_or_else(|| Error::InvalidArgument("Batch number out of bounds".to_string()))?;
+    let epoch_number = Policy::epoch_at(first_block);
+
+    // The micro blocks of old epochs are pruned, skip batches that aren't available anymore.
+    match blockchain.get_block_at(first_block, false, Some(txn)) {
+        Ok(_) => {}
+        Err(BlockchainError::BlockNotFound) => return Ok(None),
+        Err(error) => return Err(error.into()),
+    }
+
+    let validators = blockchain.get_validators_for_epoch(epoch_number, Some(txn))?;
+    let slot_range = validators
+        .get_validator_by_address(address.clone())
+        .map(|validator| validator.slot_range);
+    let num_slots = slot_range.map_or(0, |(start, end)| end - start);
+
+    // The proposers of the batch are selected among the slots that weren't disabled in the
+    // preceding macro block.
+    let prev_macro_block = blockchain
+        .get_block_at(first_block - 1, true, Some(txn))?
+        .unwrap_macro();
+    let mut vrf_entropy = prev_macro_block.header.seed.entropy();
+    let disabled_slots = prev_macro_block
+        .body
+        .ok_or(Error::BlockNotFound(first_block - 1))?
+        .disabled_set;
+
+    // Go through the micro blocks of the batch and check which of them the validator was
+    // supposed to produce. A missed micro block is replaced by a skip block. Only the headers
+    // and justifications are needed for this.
+    let mut produced_micro_blocks = 0;
+    let mut missed_micro_blocks = 0;
+    for block_number in first_block..macro_number.min(head_number + 1) {
+        let block = blockchain
+            .get_block_at(block_number, false, Some(txn))?
+            .unwrap_micro();
+
+        if slot_range.is_some() {
+            let slot_number =
+                Blockchain::compute_slot_number(block_number, vrf_entropy, &disabled_slots);
+            let proposer = validators.get_validator_by_slot_number(slot_number);
+            if proposer.address == *address {
+                if block.is_skip_block() {
+                    missed_micro_blocks += 1;
+                } else {
+                    produced_micro_blocks += 1;
+                }
+            }
+        }
+
+        vrf_entropy = block.header.seed.entropy();
+    }
+
+    let (lost_reward_set, disabled_set) = if macro_number <= head_number {
+        let body = blockchain
+            .get_block_at(macro_number, true, Some(txn))?
+            .unwrap_macro()
+            .body
+            .ok_or(Error::BlockNotFound(macro_number))?;
+        (body.lost_reward_set, body.disabled_set)
+    } else {
+        let staking_contract = blockchain
+            .get_staking_contract_if_complete(None)
+            .ok_or(Error::NoConsensus)?;
+        (
+            staking_contract.current_batch_lost_rewards(),
+            staking_contract.current_epoch_disabled_slots(),
+        )
+    };
+    let lost_reward_slots = slot_range.map_or(0, |(start, end)| {
+        (lost_reward_set | disabled_set)
+            .iter()
+            .filter(|slot| (start as usize..end as usize).contains(slot))
+            .count() as u16
+    });
+
+    // The rewards of a batch are distributed in the macro block of the following batch.
+    let reward_number = macro_number + Policy::blocks_per_batch();
+    let lost_rewards = if reward_number <= head_number {
+        let macro_block = blockchain.get_block_at(macro_number, false, Some(txn))?;
+        let macro_info = blockchain.get_chain_info(&macro_block.hash(), false, Some(txn))?;
+        let reward_block = blockchain
+            .get_block_at(reward_number, false, Some(txn))?
+            .unwrap_macro();
+        let (genesis_supply, genesis_timestamp) = blockchain.get_genesis_parameters();
+        let reward_pot = reward_pot_for_batch(
+            &reward_block.header,
+            &macro_info,
+            genesis_supply,
+            genesis_timestamp,
+        );
+        let slot_reward = reward_pot / Policy::SLOTS as u64;
+        Some(
+            slot_reward
+                .checked_mul(lost_reward_slots as u64)
+                .expect("Overflow in reward"),
+        )
+    } else {
+        None
+    };
+
+    // Fork proofs and skip blocks both slash the validator. Slashes caused by a fork proof refer
+    // to an earlier block than the one that included them.
+    let logs = blockchain.history_store.get_logs(
+        first_block,
+        macro_number,
+        &[address.clone()],
+        &[
+            LogType::DeactivateValidator,
+            LogType::ReactivateValidator,
+            LogType::RetireValidator,
+            LogType::Slash,
+        ],
+        None,
+        usize::MAX,
+        Some(txn),
+    );
+
+    let fork_proofs = logs
+        .iter()
+        .filter(|indexed_log| match &indexed_log.log {
+            Log::Slash { event_block, .. } => *event_block < indexed_log.block_number,
+            _ => false,
+        })
+        .count() as u32;
+
+    let deactivation_events = logs
+        .into_iter()
+        .filter(|indexed_log| match &indexed_log.log {
+            Log::Slash {
+                newly_deactivated, ..
+            } => *newly_deactivated,
+            _ => true,
+        })
+        .map(IndexedLog::from)
+        .collect();
+
+    Ok(Some(ValidatorBatchPerformance {
+        batch_number,
+        epoch_number,
+        num_slots,
+        produced_micro_blocks,
+        missed_micro_blocks,
+        fork_proofs,
+        lost_reward_slots,
+        lost_rewards,
+        deactivation_events,
+    }))
+}
+
 #[nimiq_jsonrpc_derive::service(rename_all = "camelCase")]
 #[async_trait]
 impl BlockchainInterface for BlockchainDispatcher {
@@ -398,16 +577,26 @@ impl BlockchainInterface for BlockchainDispatcher {
     /// where the given address is listed as a recipient or as a sender are considered. Reward
     /// transactions are also returned. It has an option to specify the maximum number of hashes to
     /// fetch, it defaults to 500.
+    /// The history can be paged through by passing the last hash of the previous page as
+    /// `start_after`. By default, the newest transactions are returned first.
     async fn get_transaction_hashes_by_address(
         &mut self,
         address: Address,
         max: Option<u16>,
+        start_after: Option<Blake2bHash>,
+        direction: Option<HistoryDirection>,
     ) -> RPCResult<Vec<Blake2bHash>, (), Self::Error> {
         if let BlockchainProxy::Full(blockchain) = &self.blockchain {
             Ok(blockchain
                 .read()
                 .history_store
-                .get_tx_hashes_by_address(&address, max.unwrap_or(500), None)
+                .get_tx_hashes_by_address(
+                    &address,
+                    max.unwrap_or(500),
+                    start_after.as_ref(),
+                    direction.unwrap_or(HistoryDirection::NewestFirst).into(),
+                    None,
+                )
                 .into())
         } else {
             Err(Error::NotSupportedForLightBlockchain)
@@ -418,16 +607,22 @@ impl BlockchainInterface for BlockchainDispatcher {
     /// where the given address is listed as a recipient or as a sender are considered. Reward
     /// transactions are also returned. It has an option to specify the maximum number of transactions
     /// to fetch, it defaults to 500.
+    /// The history can be paged through by passing the hash of the last transaction of the previous
+    /// page as `start_after`. By default, the newest transactions are returned first.
     async fn get_transactions_by_address(
         &mut self,
         address: Address,
         max: Option<u16>,
+        start_after: Option<Blake2bHash>,
+        direction: Option<HistoryDirection>,
     ) -> RPCResult<Vec<ExecutedTransaction>, (), Self::Error> {
         if let BlockchainReadProxy::Full(blockchain) = self.blockchain.read() {
             // Get the transaction hashes for this address.
             let tx_hashes = blockchain.history_store.get_tx_hashes_by_address(
                 &address,
                 max.unwrap_or(500),
+                start_after.as_ref(),
+                direction.unwrap_or(HistoryDirection::NewestFirst).into(),
                 None,
             );
 
@@ -469,6 +664,65 @@ impl BlockchainInterface for BlockchainDispatcher {
         }
     }
 
+    /// Returns the total number of transactions for a given address, including reward
+    /// transactions.
+    async fn get_transaction_count_by_address(
+        &mut self,
+        address: Address,
+    ) -> RPCResult<usize, (), Self::Error> {
+        if let BlockchainProxy::Full(blockchain) = &self.blockchain {
+            Ok(blockchain
+                .read()
+                .history_store
+                .num_tx_hashes_by_address(&address, None)
+                .into())
+        } else {
+            Err(Error::NotSupportedForLightBlockchain)
+        }
+    }
+
+    async fn get_logs(
+        &mut self,
+        from_block: u32,
+        to_block: u32,
+        addresses: Vec<Address>,
+        log_types: Vec<LogType>,
+        max: Option<u16>,
+        start_after: Option<LogPosition>,
+    ) -> RPCResult<Vec<IndexedLog>, (), Self::Error> {
+        if from_block > to_block {
+            return Err(Error::InvalidArgument(format!(
+                "from_block {from_block} is greater than to_block {to_block}"
+            )));
+        }
+        if to_block - from_block >= MAX_LOGS_BLOCK_RANGE {
+            return Err(Error::InvalidArgument(format!(
+                "Block range too large, at most {MAX_LOGS_BLOCK_RANGE} blocks are allowed"
+            )));
+        }
+
+        if let BlockchainReadProxy::Full(blockchain) = self.blockchain.read() {
+            let logs = blockchain
+                .history_store
+                .get_logs(
+                    from_block,
+                    to_block,
+                    &addresses,
+                    &log_types,
+                    start_after,
+                    max.unwrap_or(500).min(MAX_LOGS) as usize,
+                    None,
+                )
+                .into_iter()
+                .map(IndexedLog::from)
+                .collect::<Vec<_>>();
+
+            Ok(logs.into())
+        } else {
+            Err(Error::NotSupportedForLightBlockchain)
+        }
+    }
+
     /// Tries to fetch the account at the given address.
     async fn get_account_by_address(
         &mut self,
@@ -489,6 +743,40 @@ impl BlockchainInterface for BlockchainDispatcher {
         }
     }
 
+    /// Reconstructs the account at the given address as it was right after the given block, by
+    /// reverting the changes recorded in the history store since that block. The changes are
+    /// reverted in chunks, releasing the blockchain lock in between, so that block processing
+    /// isn't stalled by queries far into the past.
+    async fn get_account_by_address_at(
+        &mut self,
+        address: Address,
+        block_number: u32,
+    ) -> RPCResult<Account, (), Self::Error> {
+        let map_error = |e: BlockchainError| match e {
+            BlockchainError::AccountsIncomplete => Error::NoConsensus,
+            e => Error::Blockchain(e),
+        };
+
+        let mut account_at = match self.blockchain.read() {
+            BlockchainReadProxy::Full(blockchain) => blockchain
+                .start_account_at(&address, block_number)
+                .map_err(map_error)?,
+            _ => return Err(Error::NotSupportedForLightBlockchain),
+        };
+
+        loop {
+            let account = match self.blockchain.read() {
+                BlockchainReadProxy::Full(blockchain) => blockchain
+                    .revert_account_at_chunk(&mut account_at)
+                    .map_err(map_error)?,
+                _ => return Err(Error::NotSupportedForLightBlockchain),
+            };
+            if let Some(account) = account {
+                return Ok(Account::from_account(address, account).into());
+            }
+        }
+    }
+
     /// Fetches all accounts in the accounts tree.
     /// IMPORTANT: This operation iterates over all accounts in the accounts tree
     /// and thus is extremely computationally expensive.
@@ -596,6 +884,67 @@ impl BlockchainInterface for BlockchainDispatcher {
         get_validator_by_address(&self.blockchain.read(), &address)
     }
 
+    /// Reports the performance of a validator for every batch of the last `epochs` epochs
+    /// (defaults to the current epoch only, at most `MAX_PERFORMANCE_EPOCHS`). This includes the
+    /// produced and missed micro blocks, fork proofs, the slots and rewards lost and the events
+    /// that deactivated the validator. Batches whose micro blocks have been pruned already are
+    /// omitted.
+    /// IMPORTANT: This operation iterates over all blocks of the requested epochs
+    /// and thus is extremely computationally expensive.
+    async fn get_validator_performance(
+        &mut self,
+        address: Address,
+        epochs: Option<u32>,
+    ) -> RPCResult<Vec<ValidatorBatchPerformance>, BlockchainState, Self::Error> {
+        let epochs = epochs.unwrap_or(1);
+        if epochs == 0 || epochs > MAX_PERFORMANCE_EPOCHS {
+            return Err(Error::InvalidArgument(format!(
+                "The number of epochs must be between 1 and {MAX_PERFORMANCE_EPOCHS}"
+            )));
+        }
+
+        let (first_batch, last_batch) = {
+            let blockchain_proxy = self.blockchain.read();
+            if let BlockchainReadProxy::Full(ref blockchain) = blockchain_proxy {
+                blockchain
+                    .get_staking_contract_if_complete(None)
+                    .ok_or(Error::NoConsensus)?;
+
+                let head_number = blockchain.block_number();
+                let first_epoch = Policy::epoch_at(head_number)
+                    .saturating_sub(epochs - 1)
+                    .max(1);
+                let first_block = Policy::first_block_of(first_epoch)
+                    .ok_or_else(|| Error::InvalidArgument("Epoch out of bounds".to_string()))?;
+
+                (Policy::batch_at(first_block), Policy::batch_at(head_number))
+            } else {
+                return Err(Error::NotSupportedForLightBlockchain);
+            }
+        };
+
+        // Take the read lock for one batch at a time, such that new blocks can be pushed in
+        // between.
+        let mut performance = vec![];
+        for batch_number in first_batch..=last_batch {
+            let blockchain_proxy = self.blockchain.read();
+            if let BlockchainReadProxy::Full(ref blockchain) = blockchain_proxy {
+                let txn = blockchain.read_transaction();
+                performance.extend(get_validator_batch_performance(
+                    blockchain,
+                    &address,
+                    batch_number,
+                    &txn,
+                )?);
+            }
+        }
+
+        Ok(RPCData::with_blockchain(
+            performance,
+            &self.blockchain.read(),
+        ))
+    }
+
     /// Fetches all validators in the staking contract.
     /// IMPORTANT: This operation iterates over all validators in the staking contract
     /// and thus is extremely computationally expensive.
@@ -611,10 +960,17 @@ impl BlockchainInterface for BlockchainDispatcher {
             let db_txn = blockchain.read_transaction();
             let validators = staking_contract.get_validators(&data_store.read(&db_txn));
 
-            Ok(RPCData::with_blockchain(
-                validators.iter().map(Validator::from_validator).collect(),
-                &blockchain_proxy,
-            ))
+            let validators = validators
+                .iter()
+                .map(|validator| {
+                    let commission = staking_contract
+                        .get_reward_pool(&data_store.read(&db_txn), &validator.address)
+                        .and_then(|pool| pool.commission);
+                    Validator::from_validator(validator, commission)
+                })
+                .collect();
+
+            Ok(RPCData::with_blockchain(validators, &blockchain_proxy))
         } else {
             Err(Error::NotSupportedForLightBlockchain)
         }
@@ -636,11 +992,18 @@ impl BlockchainInterface for BlockchainDispatcher {
                 .ok_or(Error::NoConsensus)?;
             let data_store = blockchain.get_staking_contract_store();
             let db_txn = blockchain.read_transaction();
-            let staker =
-                staking_contract.get_stakers_for_validator(&data_store.read(&db_txn), &address);
+            let data_store_read = data_store.read(&db_txn);
+            let stakers = staking_contract.get_stakers_for_validator(&data_store_read, &address);
 
             Ok(RPCData::with_blockchain(
-                staker.iter().map(Staker::from_staker).collect(),
+                stakers
+                    .iter()
+                    .map(|staker| {
+                        let delegations = staking_contract
+                            .get_staker_delegations(&data_store_read, &staker.address);
+                        Staker::from_staker(staker, &delegations)
+                    })
+                    .collect(),
                 &blockchain_proxy,
             ))
         } else {
@@ -660,12 +1023,14 @@ impl BlockchainInterface for BlockchainDispatcher {
                 .ok_or(Error::NoConsensus)?;
             let data_store = blockchain.get_staking_contract_store();
             let db_txn = blockchain.read_transaction();
+            let data_store_read = data_store.read(&db_txn);
             let staker = staking_contract
-                .get_staker(&data_store.read(&db_txn), &address)
-                .ok_or(Error::StakerNotFound(address))?;
+                .get_staker(&data_store_read, &address)
+                .ok_or(Error::StakerNotFound(address.clone()))?;
+            let delegations = staking_contract.get_staker_delegations(&data_store_read, &address);
 
             Ok(RPCData::with_blockchain(
-                Staker::from_staker(&staker),
+                Staker::from_staker(&staker, &delegations),
                 &blockchain_proxy,
             ))
         } else {
@@ -687,8 +687,61 @@ impl ConsensusInterface for ConsensusDispatcher {
         self.send_raw_transaction(raw_tx).await
     }
 
-    /// Returns a serialized `unstake` transaction. The transaction fee will be paid from the funds
-    /// being unstaked.
+    /// Returns a serialized `set_inactive_stake` transaction. Increasing the inactive balance of a
+    /// staker deactivates stake, which can be unstaked once it has been released. Decreasing it
+    /// reactivates stake. You can pay the transaction fee from a basic account (by providing the
+    /// sender wallet) or from the staker account's balance (by not providing a sender wallet).
+    async fn create_set_inactive_stake_transaction(
+        &mut self,
+        sender_wallet: Option<Address>,
+        staker_wallet: Address,
+        new_inactive_balance: Coin,
+        fee: Coin,
+        validity_start_height: ValidityStartHeight,
+    ) -> RPCResult<String, (), Self::Error> {
+        let sender_key = match sender_wallet {
+            None => None,
+            Some(address) => Some(self.get_wallet_keypair(&address)?),
+        };
+
+        let transaction = TransactionBuilder::new_set_inactive_stake(
+            sender_key.as_ref(),
+            &self.get_wallet_keypair(&staker_wallet)?,
+            new_inactive_balance,
+            fee,
+            self.validity_start_height(validity_start_height),
+            self.get_network_id(),
+        )?;
+
+        Ok(transaction_to_hex_string(&transaction).into())
+    }
+
+    /// Sends a `set_inactive_stake` transaction to the network. You can pay the transaction fee
+    /// from a basic account (by providing the sender wallet) or from the staker account's released
+    /// inactive balance (by not providing a sender wallet).
+    async fn send_set_inactive_stake_transaction(
+        &mut self,
+        sender_wallet: Option<Address>,
+        staker_wallet: Address,
+        new_inactive_balance: Coin,
+        fee: Coin,
+        validity_start_height: ValidityStartHeight,
+    ) -> RPCResult<Blake2bHash, (), Self::Error> {
+        let raw_tx = self
+            .create_set_inactive_stake_transaction(
+                sender_wallet,
+                staker_wallet,
+                new_inactive_balance,
+                fee,
+                validity_start_height,
+            )
+            .await?
+            .data;
+        self.send_raw_transaction(raw_tx).await
+    }
+
+    /// Returns a serialized `unstake` transaction. Only the released inactive balance of the staker
+    /// can be unstaked. The transaction fee will be paid from the funds being unstaked.
     async fn create_unstake_transaction(
         &mut self,
         staker_wallet: Address,
@@ -709,8 +762,8 @@ impl ConsensusInterface for ConsensusDispatcher {
         Ok(transaction_to_hex_string(&transaction).into())
     }
 
-    /// Sends a `unstake` transaction to the network. The transaction fee will be paid from the funds
-    /// being unstaked.
+    /// Sends a `unstake` transaction to the network. Only the released inactive balance of the
+    /// staker can be unstaked. The transaction fee will be paid from the funds being unstaked.
     async fn send_unstake_transaction(
         &mut self,
         staker_wallet: Address,
@@ -921,6 +974,53 @@ impl ConsensusInterface for ConsensusDispatcher {
         self.send_raw_transaction(raw_tx).await
     }
 
+    /// Returns a serialized `update_validator_commission` transaction. You need to provide the
+    /// address of a basic account (the sender wallet) to pay the transaction fee.
+    /// The commission is given in basis points, null opts out of distributing the rewards to the
+    /// stakers.
+    async fn create_update_validator_commission_transaction(
+        &mut self,
+        sender_wallet: Address,
+        validator_wallet: Address,
+        new_commission: Option<u16>,
+        fee: Coin,
+        validity_start_height: ValidityStartHeight,
+    ) -> RPCResult<String, (), Self::Error> {
+        let transaction = TransactionBuilder::new_update_validator_commission(
+            &self.get_wallet_keypair(&sender_wallet)?,
+            &self.get_wallet_keypair(&validator_wallet)?,
+            new_commission,
+            fee,
+            self.validity_start_height(validity_start_height),
+            self.get_network_id(),
+        )?;
+
+        Ok(transaction_to_hex_string(&transaction).into())
+    }
+
+    /// Sends a `update_validator_commission` transaction to the network. You need to provide the
+    /// address of a basic account (the sender wallet) to pay the transaction fee.
+    async fn send_update_validator_commission_transaction(
+        &mut self,
+        sender_wallet: Address,
+        validator_wallet: Address,
+        new_commission: Option<u16>,
+        fee: Coin,
+        validity_start_height: ValidityStartHeight,
+    ) -> RPCResult<Blake2bHash, (), Self::Error> {
+        let raw_tx = self
+            .create_update_validator_commission_transaction(
+                sender_wallet,
+                validator_wallet,
+                new_commission,
+                fee,
+                validity_start_height,
+            )
+            .await?
+            .data;
+        self.send_raw_transaction(raw_tx).await
+    }
+
     /// Returns a serialized `deactivate_validator` transaction. You need to provide the address of a basic
     /// account (the sender wallet) to pay the transaction fee.
     async fn create_deactivate_validator_transaction(
@@ -1,14 +1,22 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use async_trait::async_trait;
+use futures::{future, stream::BoxStream, StreamExt};
 use nimiq_hash::{Blake2bHash, Hash};
-use nimiq_mempool::{mempool::Mempool, mempool_transactions::TxPriority};
+use nimiq_keys::Address;
+use nimiq_mempool::{
+    mempool::Mempool, mempool_events::MempoolEvent, mempool_transactions::TxPriority,
+};
 use nimiq_rpc_interface::{
     mempool::MempoolInterface,
-    types::{HashOrTx, MempoolInfo, RPCResult},
+    types::{
+        HashOrTx, MempoolEvictions, MempoolHistoryEntry, MempoolInfo, MempoolTransactionEvent,
+        RPCData, RPCResult, Transaction as RPCTransaction,
+    },
 };
 use nimiq_serde::Deserialize;
 use nimiq_transaction::Transaction;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
 
 use crate::error::Error;
 
@@ -92,6 +100,34 @@ impl MempoolInterface for MempoolDispatcher {
         Ok(self.mempool.get_rules().tx_fee_per_byte.into())
     }
 
+    async fn estimate_fee(&mut self, target_blocks: u32) -> RPCResult<f64, (), Self::Error> {
+        Ok(self.mempool.estimate_fee_per_byte(target_blocks).into())
+    }
+
+    async fn get_mempool_history(
+        &mut self,
+    ) -> RPCResult<Vec<MempoolHistoryEntry>, (), Self::Error> {
+        Ok(self
+            .mempool
+            .get_history()
+            .into_iter()
+            .map(|entry| MempoolHistoryEntry {
+                block_number: entry.block_number,
+                regular_transactions: entry.num_regular_transactions,
+                control_transactions: entry.num_control_transactions,
+                avg_fee_per_byte: entry.avg_fee_per_byte,
+                evictions: MempoolEvictions {
+                    expired: entry.evictions.expired,
+                    filtered: entry.evictions.filtered,
+                    too_full: entry.evictions.too_full,
+                    invalidated: entry.evictions.invalidated,
+                    replaced: entry.evictions.replaced,
+                },
+            })
+            .collect::<Vec<_>>()
+            .into())
+    }
+
     async fn get_transaction_from_mempool(
         &mut self,
         hash: Blake2bHash,
@@ -102,4 +138,48 @@ impl MempoolInterface for MempoolDispatcher {
             return Err(Error::TransactionNotFound(hash));
         }
     }
+
+    /// Subscribes to transactions being added to, removed from or evicted from the mempool,
+    /// optionally only those sent from or to one of the given addresses.
+    #[stream]
+    async fn subscribe_for_mempool_transactions(
+        &mut self,
+        addresses: Option<Vec<Address>>,
+    ) -> Result<BoxStream<'static, RPCData<MempoolTransactionEvent, ()>>, Self::Error> {
+        let addresses: Option<HashSet<Address>> = addresses.map(HashSet::from_iter);
+        Ok(self
+            .mempool
+            .subscribe_events()
+            .filter_map(move |event| {
+                let event = match event {
+                    Ok(event) => event,
+                    // Let the subscriber know that it missed events, so it can resynchronize.
+                    Err(BroadcastStreamRecvError::Lagged(missed)) => {
+                        return future::ready(Some(
+                            MempoolTransactionEvent::Lagged { missed }.into(),
+                        ))
+                    }
+                };
+                let tx = event.transaction();
+                let is_relevant = addresses.as_ref().map_or(true, |addresses| {
+                    addresses.contains(&tx.sender) || addresses.contains(&tx.recipient)
+                });
+                let result = is_relevant.then(|| {
+                    match event {
+                        MempoolEvent::Added(tx) => MempoolTransactionEvent::Added {
+                            transaction: RPCTransaction::from_transaction(tx),
+                        },
+                        MempoolEvent::Removed(tx) => MempoolTransactionEvent::Removed {
+                            transaction: RPCTransaction::from_transaction(tx),
+                        },
+                        MempoolEvent::Evicted(tx) => MempoolTransactionEvent::Evicted {
+                            transaction: RPCTransaction::from_transaction(tx),
+                        },
+                    }
+                    .into()
+                });
+                future::ready(result)
+            })
+            .boxed())
+    }
 }
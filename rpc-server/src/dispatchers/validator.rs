@@ -3,8 +3,8 @@ use std::sync::atomic::Ordering;
 use async_trait::async_trait;
 use nimiq_keys::Address;
 use nimiq_rpc_interface::{types::RPCResult, validator::ValidatorInterface};
-use nimiq_serde::Serialize;
-use nimiq_validator::validator::ValidatorProxy;
+use nimiq_serde::{Deserialize, Serialize};
+use nimiq_validator::{slashing_protection::SlashingProtectionExport, validator::ValidatorProxy};
 
 use crate::error::Error;
 
@@ -30,19 +30,22 @@ impl ValidatorInterface for ValidatorDispatcher {
 
     /// Returns our validator signing key.
     async fn get_signing_key(&mut self) -> RPCResult<String, (), Self::Error> {
-        Ok(hex::encode(self.validator.signing_key.read().private.serialize_to_vec()).into())
+        let signer = self
+            .validator
+            .signer
+            .as_local()
+            .ok_or(Error::RemoteSigner)?;
+        Ok(hex::encode(signer.signing_key.private.serialize_to_vec()).into())
     }
 
     /// Returns our validator voting key.
     async fn get_voting_key(&mut self) -> RPCResult<String, (), Self::Error> {
-        Ok(hex::encode(
-            self.validator
-                .voting_key
-                .read()
-                .secret_key
-                .serialize_to_vec(),
-        )
-        .into())
+        let signer = self
+            .validator
+            .signer
+            .as_local()
+            .ok_or(Error::RemoteSigner)?;
+        Ok(hex::encode(signer.voting_key.secret_key.serialize_to_vec()).into())
     }
 
     /// Updates the configuration setting to automatically reactivate our validator.
@@ -57,4 +60,33 @@ impl ValidatorInterface for ValidatorDispatcher {
         log::debug!("Automatic reactivation set to {}.", automatic_reactivate);
         Ok(().into())
     }
+
+    /// Exports the signing history of our validator.
+    async fn export_slashing_protection(&mut self) -> RPCResult<String, (), Self::Error> {
+        Ok(hex::encode(
+            self.validator
+                .slashing_protection
+                .export()
+                .serialize_to_vec(),
+        )
+        .into())
+    }
+
+    /// Imports a signing history into the slashing protection database of our validator.
+    async fn import_slashing_protection(
+        &mut self,
+        data: String,
+    ) -> RPCResult<usize, (), Self::Error> {
+        let data = SlashingProtectionExport::deserialize_from_vec(&hex::decode(data)?)?;
+        if data.version != SlashingProtectionExport::VERSION {
+            return Err(Error::InvalidArgument(format!(
+                "Unsupported slashing protection version: {}",
+                data.version
+            )));
+        }
+
+        let num_imported = self.validator.slashing_protection.import(data);
+        log::info!(num_imported, "Imported slashing protection data");
+        Ok(num_imported.into())
+    }
 }
@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use nimiq_network_libp2p::Network;
 use nimiq_rpc_interface::{
-    types::{RPCResult, ZKPState},
+    types::{RPCResult, StoredZKProof, ZKPState},
     zkp_component::ZKPComponentInterface,
 };
 use nimiq_zkp_component::zkp_component::ZKPComponentProxy;
@@ -27,4 +27,20 @@ impl ZKPComponentInterface for ZKPComponentDispatcher {
     async fn get_zkp_state(&mut self) -> RPCResult<ZKPState, (), Self::Error> {
         Ok(ZKPState::with_zkp_state(&self.zkp_component.get_zkp_state()).into())
     }
+
+    /// Returns the block numbers of the election blocks for which a proof is stored, in ascending order.
+    async fn list_stored_zkps(&mut self) -> RPCResult<Vec<u32>, (), Self::Error> {
+        Ok(self.zkp_component.list_stored_zkps().into())
+    }
+
+    /// Returns the stored proof for the election block with the given block number.
+    async fn get_stored_zkp(
+        &mut self,
+        block_number: u32,
+    ) -> RPCResult<StoredZKProof, (), Self::Error> {
+        self.zkp_component
+            .get_stored_zkp(block_number)
+            .map(|zk_proof| StoredZKProof::from_zk_proof(&zk_proof).into())
+            .ok_or(Error::ZKProofNotFound(block_number))
+    }
 }
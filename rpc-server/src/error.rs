@@ -87,9 +87,18 @@ pub enum Error {
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
 
+    #[error("{0}")]
+    Blockchain(#[from] nimiq_blockchain_interface::BlockchainError),
+
+    #[error("No stored zk proof for block: {0}")]
+    ZKProofNotFound(u32),
+
     #[error("No consensus")]
     NoConsensus,
 
+    #[error("The validator keys are held by a remote signer")]
+    RemoteSigner,
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use nimiq_blockchain_proxy::BlockchainProxy;
+use nimiq_keys::Address;
+use nimiq_primitives::policy::Policy;
+use nimiq_rpc_interface::blockchain::BlockchainInterface;
+use nimiq_rpc_server::{dispatchers::BlockchainDispatcher, Error};
+use nimiq_test_log::test;
+use nimiq_test_utils::{
+    block_production::TemporaryBlockProducer, blockchain::produce_macro_blocks,
+};
+
+fn validator_address() -> Address {
+    Address::from_user_friendly_address("NQ20 TSB0 DFSM UH9C 15GQ GAGJ TTE4 D3MA 859E").unwrap()
+}
+
+#[test(tokio::test)]
+async fn it_reports_produced_and_missed_micro_blocks() {
+    let temp_producer = TemporaryBlockProducer::new();
+    for skip_block in [false, false, false, true, false, false] {
+        temp_producer.next_block(vec![], skip_block);
+    }
+
+    let mut dispatcher =
+        BlockchainDispatcher::new(BlockchainProxy::from(Arc::clone(&temp_producer.blockchain)));
+
+    let performance = dispatcher
+        .get_validator_performance(validator_address(), None)
+        .await
+        .unwrap()
+        .data;
+
+    // The only validator owns all slots, so it is the proposer of every block.
+    assert_eq!(performance.len(), 1);
+    assert_eq!(performance[0].batch_number, 1);
+    assert_eq!(performance[0].epoch_number, 1);
+    assert_eq!(performance[0].num_slots, Policy::SLOTS);
+    assert_eq!(performance[0].produced_micro_blocks, 5);
+    assert_eq!(performance[0].missed_micro_blocks, 1);
+    assert_eq!(performance[0].fork_proofs, 0);
+    assert_eq!(performance[0].lost_rewards, None);
+
+    // Unknown validators didn't produce anything.
+    let performance = dispatcher
+        .get_validator_performance(Address::from([1u8; 20]), None)
+        .await
+        .unwrap()
+        .data;
+
+    assert_eq!(performance.len(), 1);
+    assert_eq!(performance[0].num_slots, 0);
+    assert_eq!(performance[0].produced_micro_blocks, 0);
+    assert_eq!(performance[0].missed_micro_blocks, 0);
+}
+
+#[test(tokio::test)]
+async fn it_limits_the_number_of_epochs() {
+    let temp_producer = TemporaryBlockProducer::new();
+    let mut dispatcher =
+        BlockchainDispatcher::new(BlockchainProxy::from(Arc::clone(&temp_producer.blockchain)));
+
+    assert!(matches!(
+        dispatcher
+            .get_validator_performance(validator_address(), Some(0))
+            .await,
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        dispatcher
+            .get_validator_performance(validator_address(), Some(5))
+            .await,
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+#[test(tokio::test)]
+async fn it_omits_pruned_batches() {
+    let temp_producer = TemporaryBlockProducer::new();
+
+    // The micro blocks of the first epoch are pruned with the election block of the second one.
+    produce_macro_blocks(
+        &temp_producer.producer,
+        &temp_producer.blockchain,
+        2 * Policy::batches_per_epoch() as usize + 1,
+    );
+
+    let mut dispatcher =
+        BlockchainDispatcher::new(BlockchainProxy::from(Arc::clone(&temp_producer.blockchain)));
+
+    let performance = dispatcher
+        .get_validator_performance(validator_address(), Some(3))
+        .await
+        .unwrap()
+        .data;
+
+    assert_eq!(performance.len(), Policy::batches_per_epoch() as usize + 1);
+    assert_eq!(performance[0].epoch_number, 2);
+    assert_eq!(
+        performance[0].batch_number,
+        Policy::batch_at(Policy::first_block_of(2).unwrap())
+    );
+    for batch in &performance {
+        assert_eq!(batch.produced_micro_blocks, Policy::blocks_per_batch() - 1);
+        assert_eq!(batch.missed_micro_blocks, 0);
+    }
+}
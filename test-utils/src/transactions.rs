@@ -80,6 +80,8 @@ pub enum IncomingType {
     CreateStaker,
     AddStake,
     UpdateStaker,
+    SetInactiveStake,
+    SetDelegationStake,
 }
 
 impl IncomingType {
@@ -97,7 +99,11 @@ impl IncomingType {
     pub fn is_staker_related(&self) -> bool {
         matches!(
             self,
-            IncomingType::CreateStaker | IncomingType::AddStake | IncomingType::UpdateStaker
+            IncomingType::CreateStaker
+                | IncomingType::AddStake
+                | IncomingType::UpdateStaker
+                | IncomingType::SetInactiveStake
+                | IncomingType::SetDelegationStake
         )
     }
 }
@@ -116,6 +122,8 @@ impl From<IncomingType> for AccountType {
             IncomingType::CreateStaker => AccountType::Staking,
             IncomingType::AddStake => AccountType::Staking,
             IncomingType::UpdateStaker => AccountType::Staking,
+            IncomingType::SetInactiveStake => AccountType::Staking,
+            IncomingType::SetDelegationStake => AccountType::Staking,
         }
     }
 }
@@ -482,6 +490,12 @@ impl<R: Rng + CryptoRng> TransactionsGenerator<R> {
             OutgoingType::DeleteValidator | OutgoingType::RemoveStake => {
                 let (validator_key_pair, _, staker_key_pair) =
                     self.create_validator_and_staker(balance, ValidatorState::Retired);
+
+                // Only the inactive balance of a staker can be removed.
+                if matches!(outgoing_type, OutgoingType::RemoveStake) {
+                    self.deactivate_stake(&staker_key_pair, balance, 0);
+                }
+
                 OutgoingAccountData::Staking {
                     validator_key_pair,
                     staker_key_pair,
@@ -692,6 +706,48 @@ impl<R: Rng + CryptoRng> TransactionsGenerator<R> {
                 IncomingAccountData::Staking {
                     parameters: IncomingStakingTransactionData::UpdateStaker {
                         new_delegation: Some(Address::from(&validator_key_pair)),
+                        delegation_stake: None,
+                        proof: SignatureProof::default(),
+                    },
+                    validator_key_pair,
+                    staker_key_pair,
+                }
+            }
+            IncomingType::SetInactiveStake => {
+                let (validator_key_pair, _, mut staker_key_pair) =
+                    self.create_validator_and_staker(balance, ValidatorState::Active);
+
+                // We can make the transaction fail by using a non-existing staker address.
+                if fail_recipient {
+                    staker_key_pair = KeyPair::generate(&mut self.rng);
+                }
+
+                IncomingAccountData::Staking {
+                    parameters: IncomingStakingTransactionData::SetInactiveStake {
+                        new_inactive_balance: balance,
+                        proof: SignatureProof::default(),
+                    },
+                    validator_key_pair,
+                    staker_key_pair,
+                }
+            }
+            IncomingType::SetDelegationStake => {
+                // Create a staker that stakes for a validator.
+                let (_, _, mut staker_key_pair) =
+                    self.create_validator_and_staker(balance, ValidatorState::Active);
+                // Then create another validator to delegate to.
+                let (validator_key_pair, _, _) =
+                    self.create_validator_and_staker(balance, ValidatorState::Active);
+
+                // We can make the transaction fail by using a non-existing staker address.
+                if fail_recipient {
+                    staker_key_pair = KeyPair::generate(&mut self.rng);
+                }
+
+                IncomingAccountData::Staking {
+                    parameters: IncomingStakingTransactionData::UpdateStaker {
+                        new_delegation: Some(Address::from(&validator_key_pair)),
+                        delegation_stake: Some(balance),
                         proof: SignatureProof::default(),
                     },
                     validator_key_pair,
@@ -803,4 +859,46 @@ impl<R: Rng + CryptoRng> TransactionsGenerator<R> {
             staker_key_pair,
         )
     }
+
+    /// Moves `value` of the active balance of the given staker to its inactive balance at the given
+    /// block number.
+    pub fn deactivate_stake(&mut self, staker_key_pair: &KeyPair, value: Coin, block_number: u32) {
+        let mut staking_contract = match self
+            .accounts
+            .get_complete(&Policy::STAKING_CONTRACT_ADDRESS, None)
+        {
+            Account::Staking(contract) => contract,
+            _ => panic!("Staking contract doesn't exist"),
+        };
+
+        let mut raw_txn = self.accounts.env.write_transaction();
+        let mut txn: WriteTransactionProxy = (&mut raw_txn).into();
+        let data_store = self.accounts.data_store(&Policy::STAKING_CONTRACT_ADDRESS);
+        let mut data_store_write = data_store.write(&mut txn);
+        let mut store = StakingContractStoreWrite::new(&mut data_store_write);
+
+        staking_contract
+            .set_inactive_stake(
+                &mut store,
+                &Address::from(staker_key_pair),
+                value,
+                block_number,
+                &mut TransactionLog::empty(),
+            )
+            .expect("Failed to deactivate stake");
+
+        self.accounts
+            .tree
+            .put(
+                &mut txn,
+                &KeyNibbles::from(&Policy::STAKING_CONTRACT_ADDRESS),
+                Account::Staking(staking_contract),
+            )
+            .expect("Failed to store staking contract");
+        self.accounts
+            .tree
+            .update_root(&mut txn)
+            .expect("Tree must be complete");
+        raw_txn.commit();
+    }
 }
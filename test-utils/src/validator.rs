@@ -11,7 +11,7 @@ use nimiq_mempool::config::MempoolConfig;
 use nimiq_network_interface::network::Network as NetworkInterface;
 use nimiq_network_mock::MockHub;
 use nimiq_serde::{Deserialize, Serialize};
-use nimiq_validator::validator::Validator;
+use nimiq_validator::{signer::LocalSigner, validator::Validator};
 use nimiq_validator_network::network_impl::ValidatorNetworkImpl;
 use rand::{rngs::StdRng, SeedableRng};
 use tokio_stream::wrappers::BroadcastStream;
@@ -49,9 +49,8 @@ where
             validator_network,
             validator_address,
             automatic_reactivate,
-            signing_key,
-            voting_key,
-            fee_key,
+            0,
+            Arc::new(LocalSigner::new(signing_key, voting_key, fee_key)),
             MempoolConfig::default(),
         ),
         consensus,
@@ -161,7 +160,7 @@ where
     validators
         .iter()
         .find(|validator| {
-            &validator.voting_key().public_key.compress() == slot.voting_key.compressed()
+            &validator.signer().voting_public_key().compress() == slot.voting_key.compressed()
         })
         .unwrap()
 }
@@ -186,7 +185,7 @@ where
     let index = validators
         .iter()
         .position(|validator| {
-            &validator.voting_key().public_key.compress() == slot.voting_key.compressed()
+            &validator.signer().voting_public_key().compress() == slot.voting_key.compressed()
         })
         .unwrap();
     validators.remove(index)
@@ -0,0 +1,713 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    process::exit,
+    str::FromStr,
+    sync::Arc,
+};
+
+use anyhow::Error;
+use clap::{crate_authors, crate_version, value_parser, Arg, ArgAction, Command};
+use nimiq_account::{Account, Accounts, RewardPool, Staker, StakingContractStore, Validator};
+use nimiq_blockchain::{Blockchain, BlockchainConfig, ChainStore};
+use nimiq_blockchain_interface::ChainInfo;
+use nimiq_database::{
+    mdbx::MdbxDatabase, traits::Database, volatile::VolatileDatabase, TransactionProxy,
+};
+use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
+use nimiq_primitives::{key_nibbles::KeyNibbles, networks::NetworkId, policy::Policy};
+use nimiq_serde::Serialize as _;
+use nimiq_utils::time::OffsetTime;
+use serde::Serialize;
+use thiserror::Error;
+
+/// The maximum number of tables the node opens in its database.
+const MAX_TABLES: u32 = 32;
+
+/// The point in the chain at which the state was exported.
+struct ExportInfo {
+    block_number: u32,
+    block_hash: Blake2bHash,
+    root_hash: Blake2bHash,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountRecord {
+    address: String,
+    #[serde(rename = "type")]
+    ty: &'static str,
+    balance: u64,
+}
+
+impl AccountRecord {
+    const CSV_HEADER: &'static str = "address,type,balance";
+
+    fn new(address: Address, account: &Account) -> Self {
+        let ty = match account {
+            Account::Basic(_) => "basic",
+            Account::Vesting(_) => "vesting",
+            Account::HTLC(_) => "htlc",
+            Account::Staking(_) => "staking",
+        };
+        AccountRecord {
+            address: address.to_user_friendly_address(),
+            ty,
+            balance: account.balance().into(),
+        }
+    }
+
+    fn to_csv(&self) -> String {
+        format!("{},{},{}", self.address, self.ty, self.balance)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StakerRecord {
+    address: String,
+    balance: u64,
+    delegation: Option<String>,
+    split_balance: u64,
+    inactive_balance: u64,
+    inactive_from: Option<u32>,
+}
+
+impl StakerRecord {
+    const CSV_HEADER: &'static str =
+        "address,balance,delegation,split_balance,inactive_balance,inactive_from";
+
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{},{}",
+            self.address,
+            self.balance,
+            self.delegation.as_deref().unwrap_or_default(),
+            self.split_balance,
+            self.inactive_balance,
+            self.inactive_from
+                .map(|inactive_from| inactive_from.to_string())
+                .unwrap_or_default()
+        )
+    }
+}
+
+impl From<Staker> for StakerRecord {
+    fn from(staker: Staker) -> Self {
+        StakerRecord {
+            address: staker.address.to_user_friendly_address(),
+            balance: staker.balance.into(),
+            delegation: staker
+                .delegation
+                .map(|address| address.to_user_friendly_address()),
+            split_balance: staker.split_balance.into(),
+            inactive_balance: staker.inactive_balance.into(),
+            inactive_from: staker.inactive_from,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ValidatorRecord {
+    address: String,
+    signing_key: String,
+    voting_key: String,
+    reward_address: String,
+    signal_data: Option<String>,
+    commission: Option<u16>,
+    total_stake: u64,
+    deposit: u64,
+    num_stakers: u64,
+    inactive_since: Option<u32>,
+    retired: bool,
+}
+
+impl ValidatorRecord {
+    const CSV_HEADER: &'static str = "address,signing_key,voting_key,reward_address,signal_data,\
+        commission,total_stake,deposit,num_stakers,inactive_since,retired";
+
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            self.address,
+            self.signing_key,
+            self.voting_key,
+            self.reward_address,
+            self.signal_data.as_deref().unwrap_or_default(),
+            self.commission
+                .map(|commission| commission.to_string())
+                .unwrap_or_default(),
+            self.total_stake,
+            self.deposit,
+            self.num_stakers,
+            self.inactive_since
+                .map(|block_number| block_number.to_string())
+                .unwrap_or_default(),
+            self.retired,
+        )
+    }
+
+    fn new(validator: Validator, reward_pool: Option<RewardPool>) -> Self {
+        ValidatorRecord {
+            address: validator.address.to_user_friendly_address(),
+            signing_key: validator.signing_key.to_hex(),
+            voting_key: validator.voting_key.to_hex(),
+            reward_address: validator.reward_address.to_user_friendly_address(),
+            signal_data: validator.signal_data.map(|hash| hash.to_hex()),
+            commission: reward_pool.and_then(|pool| pool.commission),
+            total_stake: validator.total_stake.into(),
+            deposit: validator.deposit.into(),
+            num_stakers: validator.num_stakers,
+            inactive_since: validator.inactive_since,
+            retired: validator.retired,
+        }
+    }
+}
+
+/// Writes records either as CSV rows, prefixed with the export info, or as a single JSON
+/// document. Records are written as they are read, so the whole state never needs to be held
+/// in memory.
+struct RecordWriter<W: Write> {
+    writer: W,
+    json: bool,
+    info: ExportInfo,
+    num_records: usize,
+}
+
+impl<W: Write> RecordWriter<W> {
+    fn new(mut writer: W, json: bool, info: ExportInfo, csv_header: &str) -> io::Result<Self> {
+        if json {
+            write!(
+                writer,
+                "{{\"blockNumber\":{},\"blockHash\":\"{}\",\"rootHash\":\"{}\",\"records\":[",
+                info.block_number, info.block_hash, info.root_hash
+            )?;
+        } else {
+            writeln!(writer, "block_number,block_hash,root_hash,{csv_header}")?;
+        }
+
+        Ok(RecordWriter {
+            writer,
+            json,
+            info,
+            num_records: 0,
+        })
+    }
+
+    fn write<F: FnOnce(&T) -> String, T: Serialize>(
+        &mut self,
+        record: &T,
+        to_csv: F,
+    ) -> Result<(), Error> {
+        if self.json {
+            if self.num_records > 0 {
+                write!(self.writer, ",")?;
+            }
+            serde_json::to_writer(&mut self.writer, record)?;
+        } else {
+            writeln!(
+                self.writer,
+                "{},{},{},{}",
+                self.info.block_number,
+                self.info.block_hash,
+                self.info.root_hash,
+                to_csv(record)
+            )?;
+        }
+        self.num_records += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> io::Result<usize> {
+        if self.json {
+            writeln!(self.writer, "]}}")?;
+        }
+        self.writer.flush()?;
+        Ok(self.num_records)
+    }
+}
+
+fn run_app() -> Result<(), Error> {
+    let matches = Command::new("nimiq-export")
+        .version(crate_version!())
+        .author(crate_authors!())
+        .about("Exports accounts, stakers or validators from the database of a node")
+        .arg(
+            Arg::new("database")
+                .short('d')
+                .long("database")
+                .value_name("PATH")
+                .required(true)
+                .help("Path to the node's MDBX database, which is opened read-only."),
+        )
+        .arg(
+            Arg::new("records")
+                .short('r')
+                .long("records")
+                .value_name("RECORDS")
+                .value_parser(["accounts", "stakers", "validators"])
+                .default_value("accounts")
+                .help("The kind of records to export."),
+        )
+        .arg(
+            Arg::new("format")
+                .short('f')
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["csv", "json", "trie"])
+                .default_value("csv")
+                .help("The output format. The trie format writes all items of the accounts trie \
+                    in binary, which can be imported by the genesis builder, and ignores --records."),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("FILE")
+                .help("Write the export to FILE instead of STDOUT."),
+        )
+        .arg(
+            Arg::new("block")
+                .short('b')
+                .long("block")
+                .value_name("NUMBER|HASH")
+                .help("Export the state right after the given block of the main chain instead of \
+                    the head. Only the last macro block and the blocks after it are available. \
+                    The state is reconstructed in a temporary copy of the accounts trie."),
+        )
+        .arg(
+            Arg::new("network")
+                .short('n')
+                .long("network")
+                .value_name("NETWORK")
+                .default_value("main")
+                .help("The network of the node. Only needed with --block."),
+        )
+        .arg(
+            Arg::new("allow_micro")
+                .long("allow-micro")
+                .help("Export the state even if the exported block is not a macro block.")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("max_tables")
+                .long("max-tables")
+                .value_name("NUM")
+                .value_parser(value_parser!(u32))
+                .help("Maximum number of tables, if the node uses a non-default value."),
+        )
+        .get_matches();
+
+    let path = matches
+        .get_one::<String>("database")
+        .ok_or(AppError::Database)?;
+    let max_tables = matches
+        .get_one::<u32>("max_tables")
+        .copied()
+        .unwrap_or(MAX_TABLES);
+    let env = MdbxDatabase::new_read_only(path, max_tables)?;
+
+    let chain_store = ChainStore::new(env.clone());
+    let accounts = Accounts::new(env.clone());
+
+    // Use a single transaction, such that all records are read at the same state, even if the
+    // node keeps running.
+    let txn = env.read_transaction();
+
+    let head_hash = chain_store.get_head(Some(&txn)).ok_or(AppError::NoHead)?;
+    let chain_info = match matches.get_one::<String>("block") {
+        Some(block) => get_main_chain_info(&chain_store, block, &txn)?,
+        None => chain_store.get_chain_info(&head_hash, false, Some(&txn))?,
+    };
+    let block_hash = chain_info.head.hash();
+    let block_number = chain_info.head.block_number();
+    if !chain_info.head.is_macro() && !matches.get_flag("allow_micro") {
+        return Err(AppError::NotMacro(block_number).into());
+    }
+
+    if !accounts.is_complete(Some(&txn)) {
+        return Err(AppError::Incomplete.into());
+    }
+
+    // To export the state at a past block, the accounts trie is copied to a temporary database
+    // and the blocks after the exported one are reverted in the copy.
+    let copy;
+    let copy_txn;
+    let (accounts, txn) = if block_hash == head_hash {
+        (&accounts, &txn)
+    } else {
+        let network_id = NetworkId::from_str(
+            matches
+                .get_one::<String>("network")
+                .ok_or(AppError::Network)?,
+        )?;
+        let blockchain = Blockchain::new(
+            env.clone(),
+            BlockchainConfig::default(),
+            network_id,
+            Arc::new(OffsetTime::new()),
+        )?;
+        copy = copy_accounts_at(&blockchain, &accounts, &txn, block_number)?;
+        copy_txn = copy.env.read_transaction();
+        (&copy, &copy_txn)
+    };
+
+    let root_hash = accounts
+        .get_root_hash(Some(txn))
+        .ok_or(AppError::Incomplete)?;
+    if &root_hash != chain_info.head.state_root() {
+        return Err(AppError::StateRootMismatch.into());
+    }
+
+    let info = ExportInfo {
+        block_number,
+        block_hash,
+        root_hash,
+    };
+    let format = matches.get_one::<String>("format").map(String::as_str);
+
+    let output: Box<dyn Write> = match matches.get_one::<String>("output") {
+        Some(file) => Box::new(File::create(file)?),
+        None => Box::new(io::stdout().lock()),
+    };
+    let mut output = BufWriter::new(output);
+
+    if format == Some("trie") {
+        let num_items = export_trie(accounts, txn, &mut output)?;
+        output.flush()?;
+        eprintln!(
+            "Exported {} trie items at block #{} ({}), root hash {}",
+            num_items, info.block_number, info.block_hash, info.root_hash
+        );
+        return Ok(());
+    }
+    let json = format == Some("json");
+    let records = matches.get_one::<String>("records").map(String::as_str);
+
+    let num_records = export_records(accounts, txn, records, output, json, info)?;
+
+    eprintln!("Exported {num_records} records");
+    Ok(())
+}
+
+/// Copies the accounts trie, as seen by the given read transaction of the node's database, to a
+/// temporary database and reverts the copy to its state right after the given block.
+fn copy_accounts_at(
+    blockchain: &Blockchain,
+    accounts: &Accounts,
+    txn: &TransactionProxy,
+    block_number: u32,
+) -> Result<Accounts, Error> {
+    let env = VolatileDatabase::new(1)?;
+    let copy = Accounts::new(env.clone());
+
+    let mut copy_txn = env.write_transaction();
+    copy.init(&mut (&mut copy_txn).into(), accounts.tree.iter_items(txn));
+    blockchain.revert_accounts_copy_to(txn, &copy, &mut (&mut copy_txn).into(), block_number)?;
+    copy_txn.commit();
+
+    Ok(copy)
+}
+
+/// Writes all items of the accounts trie as a serialized list of trie items, as imported by the
+/// genesis builder. The items are written as they are read, so the whole trie never needs to be
+/// held in memory. Returns the number of items written.
+fn export_trie<W: Write>(
+    accounts: &Accounts,
+    txn: &TransactionProxy,
+    output: &mut W,
+) -> Result<usize, Error> {
+    // A list is serialized as its length followed by its elements, so the number of items must
+    // be known upfront. Every leaf and every hybrid node holds an item.
+    let num_items = (accounts.tree.num_leaves(txn) + accounts.tree.num_hybrids(txn)) as usize;
+    num_items.serialize_to_writer(output)?;
+
+    let mut num_written = 0;
+    for item in accounts.tree.iter_items(txn) {
+        item.serialize_to_writer(output)?;
+        num_written += 1;
+    }
+    if num_written != num_items {
+        return Err(AppError::Incomplete.into());
+    }
+
+    Ok(num_items)
+}
+
+/// Writes the accounts, stakers or validators of the accounts trie. Returns the number of
+/// records written.
+fn export_records<W: Write>(
+    accounts: &Accounts,
+    txn: &TransactionProxy,
+    records: Option<&str>,
+    output: W,
+    json: bool,
+    info: ExportInfo,
+) -> Result<usize, Error> {
+    let num_records = match records {
+        Some("stakers") => {
+            let mut writer = RecordWriter::new(output, json, info, StakerRecord::CSV_HEADER)?;
+            let prefix = KeyNibbles::from(&Policy::STAKING_CONTRACT_ADDRESS);
+            for staker in accounts.tree.iter_nodes::<Staker>(
+                txn,
+                &(&prefix + &StakingContractStore::staker_key(&Address::START_ADDRESS)),
+                &(&prefix + &StakingContractStore::staker_key(&Address::END_ADDRESS)),
+            ) {
+                writer.write(&StakerRecord::from(staker), StakerRecord::to_csv)?;
+            }
+            writer.finish()?
+        }
+        Some("validators") => {
+            let mut writer = RecordWriter::new(output, json, info, ValidatorRecord::CSV_HEADER)?;
+            let prefix = KeyNibbles::from(&Policy::STAKING_CONTRACT_ADDRESS);
+            for validator in accounts.tree.iter_nodes::<Validator>(
+                txn,
+                &(&prefix + &StakingContractStore::validator_key(&Address::START_ADDRESS)),
+                &(&prefix + &StakingContractStore::validator_key(&Address::END_ADDRESS)),
+            ) {
+                let reward_pool = accounts
+                    .tree
+                    .get(
+                        txn,
+                        &(&prefix + &StakingContractStore::reward_pool_key(&validator.address)),
+                    )
+                    .map_err(|_| AppError::Incomplete)?;
+                writer.write(
+                    &ValidatorRecord::new(validator, reward_pool),
+                    ValidatorRecord::to_csv,
+                )?;
+            }
+            writer.finish()?
+        }
+        _ => {
+            let mut writer = RecordWriter::new(output, json, info, AccountRecord::CSV_HEADER)?;
+            for (key, account) in accounts.tree.iter_nodes_with_keys::<Account>(
+                txn,
+                &KeyNibbles::from(&Address::START_ADDRESS),
+                &KeyNibbles::from(&Address::END_ADDRESS),
+            ) {
+                let address = key.to_address().ok_or(AppError::InvalidKey)?;
+                writer.write(
+                    &AccountRecord::new(address, &account),
+                    AccountRecord::to_csv,
+                )?;
+            }
+            writer.finish()?
+        }
+    };
+
+    Ok(num_records)
+}
+
+/// Returns the chain info of the main chain block with the given number or hash.
+fn get_main_chain_info(
+    chain_store: &ChainStore,
+    block: &str,
+    txn: &TransactionProxy,
+) -> Result<ChainInfo, Error> {
+    let chain_info = match block.parse::<u32>() {
+        Ok(block_number) => chain_store.get_chain_info_at(block_number, false, Some(txn))?,
+        Err(_) => {
+            let block_hash = Blake2bHash::from_str(block).map_err(|_| AppError::Block)?;
+            chain_store.get_chain_info(&block_hash, false, Some(txn))?
+        }
+    };
+    if !chain_info.on_main_chain {
+        return Err(AppError::NotMainChain.into());
+    }
+    Ok(chain_info)
+}
+
+fn main() {
+    exit(match run_app() {
+        Ok(_) => 0,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            1
+        }
+    });
+}
+
+#[derive(Debug, Error)]
+enum AppError {
+    #[error("Database path is missing")]
+    Database,
+    #[error("Database does not contain a chain head")]
+    NoHead,
+    #[error("Network is missing")]
+    Network,
+    #[error("Block must be a block number or hash")]
+    Block,
+    #[error("Block is not on the main chain")]
+    NotMainChain,
+    #[error("Block #{0} is a micro block, use --allow-micro to export it anyway")]
+    NotMacro(u32),
+    #[error("Accounts trie is incomplete, the node has not finished syncing")]
+    Incomplete,
+    #[error("Accounts root hash does not match the state root of the head block")]
+    StateRootMismatch,
+    #[error("Accounts trie contains a key that is not an address")]
+    InvalidKey,
+}
+
+#[cfg(test)]
+mod tests {
+    use nimiq_blockchain_interface::AbstractBlockchain;
+    use nimiq_primitives::trie::trie_chunk::TrieItem;
+    use nimiq_serde::Deserialize;
+    use nimiq_test_log::test;
+    use serde_json::Value;
+
+    use super::*;
+
+    fn blockchain() -> Blockchain {
+        Blockchain::new(
+            VolatileDatabase::new(20).unwrap(),
+            BlockchainConfig::default(),
+            NetworkId::UnitAlbatross,
+            Arc::new(OffsetTime::new()),
+        )
+        .unwrap()
+    }
+
+    fn export_info(blockchain: &Blockchain) -> ExportInfo {
+        let head = blockchain.head();
+        ExportInfo {
+            block_number: head.block_number(),
+            block_hash: head.hash(),
+            root_hash: head.state_root().clone(),
+        }
+    }
+
+    fn export_json(blockchain: &Blockchain, records: &str) -> Vec<Value> {
+        let txn = blockchain.read_transaction();
+        let mut output = vec![];
+        let num_records = export_records(
+            &blockchain.state().accounts,
+            &txn,
+            Some(records),
+            &mut output,
+            true,
+            export_info(blockchain),
+        )
+        .unwrap();
+
+        let mut json: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(json["blockNumber"], 0);
+        assert_eq!(json["blockHash"], blockchain.head_hash().to_string());
+        assert_eq!(json["rootHash"], blockchain.head().state_root().to_string());
+
+        let records = json["records"].take().as_array().unwrap().clone();
+        assert_eq!(records.len(), num_records);
+        records
+    }
+
+    #[test]
+    fn it_exports_accounts() {
+        let blockchain = blockchain();
+        let records = export_json(&blockchain, "accounts");
+
+        // The basic account and the staking contract of the genesis block.
+        assert_eq!(records.len(), 2);
+        let staking_contract = blockchain
+            .get_account_if_complete(&Policy::STAKING_CONTRACT_ADDRESS)
+            .unwrap();
+        assert!(records.contains(&serde_json::json!({
+            "address": Policy::STAKING_CONTRACT_ADDRESS.to_user_friendly_address(),
+            "type": "staking",
+            "balance": u64::from(staking_contract.balance()),
+        })));
+        assert!(records.contains(&serde_json::json!({
+            "address": "NQ46 U66M JNLD 0DJ7 0E9P Q7XR V9KV H976 813A",
+            "type": "basic",
+            "balance": 10_000_000_00000u64,
+        })));
+
+        let txn = blockchain.read_transaction();
+        let mut output = vec![];
+        export_records(
+            &blockchain.state().accounts,
+            &txn,
+            None,
+            &mut output,
+            false,
+            export_info(&blockchain),
+        )
+        .unwrap();
+        let csv = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!(
+                "block_number,block_hash,root_hash,{}",
+                AccountRecord::CSV_HEADER
+            )
+        );
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1..].iter().all(|line| line.starts_with(&format!(
+            "0,{},{},",
+            blockchain.head_hash(),
+            blockchain.head().state_root()
+        ))));
+    }
+
+    #[test]
+    fn it_exports_stakers_and_validators() {
+        let blockchain = blockchain();
+
+        let stakers = export_json(&blockchain, "stakers");
+        assert_eq!(stakers.len(), 1);
+        assert_eq!(
+            stakers[0]["address"],
+            "NQ39 VBTN P2HX Q3MF KHF1 CCLA G6FS 9B8S VY28"
+        );
+        assert_eq!(stakers[0]["balance"], 100_000);
+        assert_eq!(
+            stakers[0]["delegation"],
+            "NQ20 TSB0 DFSM UH9C 15GQ GAGJ TTE4 D3MA 859E"
+        );
+
+        let validators = export_json(&blockchain, "validators");
+        assert_eq!(validators.len(), 1);
+        let validator = &validators[0];
+        assert_eq!(
+            validator["address"],
+            "NQ20 TSB0 DFSM UH9C 15GQ GAGJ TTE4 D3MA 859E"
+        );
+        assert_eq!(
+            validator["rewardAddress"],
+            "NQ46 U66M JNLD 0DJ7 0E9P Q7XR V9KV H976 813A"
+        );
+        assert_eq!(validator["numStakers"], 1);
+        assert_eq!(
+            validator["totalStake"].as_u64().unwrap(),
+            validator["deposit"].as_u64().unwrap() + 100_000
+        );
+        assert_eq!(validator["retired"], false);
+    }
+
+    #[test]
+    fn it_exports_the_trie_for_the_genesis_builder() {
+        let blockchain = blockchain();
+        let txn = blockchain.read_transaction();
+        let accounts = &blockchain.state().accounts;
+
+        let mut output = vec![];
+        let num_items = export_trie(accounts, &txn, &mut output).unwrap();
+
+        // The export can be read as a list of trie items, which initializes an identical trie.
+        let items = Vec::<TrieItem>::deserialize_from_vec(&output).unwrap();
+        assert_eq!(items.len(), num_items);
+
+        let env = VolatileDatabase::new(20).unwrap();
+        let imported = Accounts::new(env.clone());
+        let mut imported_txn = env.write_transaction();
+        imported.init(&mut (&mut imported_txn).into(), items);
+        assert_eq!(
+            imported.get_root_hash_assert(Some(&imported_txn)),
+            accounts.get_root_hash_assert(Some(&txn))
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use std::{fs, path::PathBuf, process::exit, sync::Arc, time::Duration};
+
+use anyhow::Error;
+use clap::{
+    crate_authors, crate_description, crate_version, value_parser, Arg, ArgAction, Command,
+};
+use nimiq_bls::KeyPair as BlsKeyPair;
+use nimiq_database::mdbx::MdbxDatabase;
+use nimiq_keys::{Address, KeyPair};
+use nimiq_serde::Deserialize;
+use nimiq_utils::file_store::FileStore;
+use nimiq_validator::{
+    signer::{
+        remote::{handle_request, RemoteSignerEndpoint},
+        LocalSigner, ProtectedSigner, ValidatorSigner,
+    },
+    slashing_protection::{SlashingProtection, SlashingProtectionExport},
+};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+    sync::Semaphore,
+    time,
+};
+
+/// Size of the database holding the signing history.
+const DB_SIZE: usize = 1024 * 1024 * 1024;
+
+/// The signing history is the only table in the database.
+const MAX_TABLES: u32 = 1;
+
+/// The largest request accepted, including the HTTP headers.
+const MAX_REQUEST_SIZE: u64 = 64 * 1024;
+
+/// The most connections served at the same time. Further connections wait to be accepted.
+const MAX_CONNECTIONS: usize = 16;
+
+/// Time a client has to send a request before its connection is closed.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time a client has to receive the response before its connection is closed.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+async fn run_app() -> Result<(), Error> {
+    let matches = Command::new("Remote signer")
+        .version(crate_version!())
+        .author(crate_authors!())
+        .about(crate_description!())
+        .arg(
+            Arg::new("signing_key_file")
+                .long("signing-key-file")
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .required(true)
+                .help("Path to the signing key file, as written by the node."),
+        )
+        .arg(
+            Arg::new("voting_key_file")
+                .long("voting-key-file")
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .required(true)
+                .help("Path to the voting key file, as written by the node."),
+        )
+        .arg(
+            Arg::new("fee_key_file")
+                .long("fee-key-file")
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .required(true)
+                .help("Path to the fee key file, as written by the node."),
+        )
+        .arg(
+            Arg::new("validator_address")
+                .long("validator-address")
+                .value_name("ADDRESS")
+                .required(true)
+                .help("Address of the validator, the only one transactions are signed for."),
+        )
+        .arg(
+            Arg::new("database")
+                .short('d')
+                .long("database")
+                .value_name("PATH")
+                .value_parser(value_parser!(PathBuf))
+                .required(true)
+                .help("Path to the directory holding the signing history."),
+        )
+        .arg(
+            Arg::new("listen")
+                .short('l')
+                .long("listen")
+                .value_name("ENDPOINT")
+                .required(true)
+                .help(
+                    "Where to listen for requests: unix:<path> or http://<token>@<host>:<port>. \
+                     HTTP requests must carry the token as bearer token.",
+                ),
+        )
+        .arg(
+            Arg::new("import")
+                .long("import")
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .action(ArgAction::Set)
+                .help("Import a signing history exported from the node before starting."),
+        )
+        .get_matches();
+
+    let signing_key: KeyPair =
+        FileStore::new(matches.get_one::<PathBuf>("signing_key_file").unwrap()).load()?;
+    let voting_key: BlsKeyPair =
+        FileStore::new(matches.get_one::<PathBuf>("voting_key_file").unwrap()).load()?;
+    let fee_key: KeyPair =
+        FileStore::new(matches.get_one::<PathBuf>("fee_key_file").unwrap()).load()?;
+    let validator_address =
+        Address::from_any_str(matches.get_one::<String>("validator_address").unwrap())?;
+
+    let path = matches.get_one::<PathBuf>("database").unwrap();
+    fs::create_dir_all(path)?;
+    let env = MdbxDatabase::new(path, DB_SIZE, MAX_TABLES)?;
+    let slashing_protection = SlashingProtection::new(env);
+
+    if let Some(file) = matches.get_one::<PathBuf>("import") {
+        let export = SlashingProtectionExport::deserialize_from_vec(&hex::decode(
+            fs::read_to_string(file)?.trim(),
+        )?)?;
+        if export.version != SlashingProtectionExport::VERSION {
+            return Err(AppError::ExportVersion(export.version).into());
+        }
+        let num_imported = slashing_protection.import(export);
+        println!("Imported {num_imported} signed messages");
+    }
+
+    let signer: Arc<dyn ValidatorSigner> = Arc::new(ProtectedSigner::new(
+        LocalSigner::new(signing_key, voting_key, fee_key),
+        slashing_protection,
+        validator_address,
+    ));
+
+    let endpoint: RemoteSignerEndpoint = matches
+        .get_one::<String>("listen")
+        .unwrap()
+        .parse()
+        .map_err(AppError::Endpoint)?;
+    println!("Listening on {endpoint}");
+
+    match endpoint {
+        RemoteSignerEndpoint::Socket(path) => serve_socket(path, signer).await,
+        RemoteSignerEndpoint::Http { address, token } => serve_http(&address, token, signer).await,
+    }
+}
+
+/// Answers every line received on a connection with a line holding the response.
+#[cfg(unix)]
+async fn serve_socket(path: PathBuf, signer: Arc<dyn ValidatorSigner>) -> Result<(), Error> {
+    use tokio::net::UnixListener;
+
+    // Remove the socket left over by a previous run.
+    let _ = fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)?;
+    let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+
+    loop {
+        let permit = Arc::clone(&connections).acquire_owned().await?;
+        let (stream, _) = listener.accept().await?;
+        let signer = Arc::clone(&signer);
+        tokio::spawn(async move {
+            let _permit = permit;
+            let (reader, mut writer) = stream.into_split();
+            let mut reader = BufReader::new(reader);
+            loop {
+                let mut request = Vec::new();
+                let read = (&mut reader)
+                    .take(MAX_REQUEST_SIZE)
+                    .read_until(b'\n', &mut request);
+                match time::timeout(READ_TIMEOUT, read).await {
+                    Ok(Ok(_)) if request.last() == Some(&b'\n') => {}
+                    // Closed, timed out or too large.
+                    _ => return,
+                }
+                request.pop();
+
+                let mut response = handle_request(signer.as_ref(), &request).await;
+                response.push(b'\n');
+                match time::timeout(WRITE_TIMEOUT, writer.write_all(&response)).await {
+                    Ok(Ok(())) => {}
+                    _ => return,
+                }
+            }
+        });
+    }
+}
+
+#[cfg(not(unix))]
+async fn serve_socket(_path: PathBuf, _signer: Arc<dyn ValidatorSigner>) -> Result<(), Error> {
+    Err(AppError::Endpoint("Local sockets are only supported on Unix".to_string()).into())
+}
+
+/// Answers `POST /sign` requests carrying the token, one request per connection.
+async fn serve_http(
+    address: &str,
+    token: String,
+    signer: Arc<dyn ValidatorSigner>,
+) -> Result<(), Error> {
+    let listener = TcpListener::bind(address).await?;
+    let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+    let token = Arc::new(token);
+
+    loop {
+        let permit = Arc::clone(&connections).acquire_owned().await?;
+        let (mut stream, _) = listener.accept().await?;
+        let signer = Arc::clone(&signer);
+        let token = Arc::clone(&token);
+        tokio::spawn(async move {
+            let _permit = permit;
+            let request = time::timeout(READ_TIMEOUT, read_http_request(&mut stream, &token));
+            let (status, body) = match request.await {
+                Ok(Ok(request)) => ("200 OK", handle_request(signer.as_ref(), &request).await),
+                Ok(Err(status)) => (status, vec![]),
+                Err(_) => ("408 Request Timeout", vec![]),
+            };
+            let response = format!(
+                "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            let _ = time::timeout(WRITE_TIMEOUT, async {
+                stream.write_all(response.as_bytes()).await?;
+                stream.write_all(&body).await
+            })
+            .await;
+        });
+    }
+}
+
+/// Reads a `POST /sign` request and returns its body, or the HTTP status to answer with.
+async fn read_http_request(
+    stream: &mut (impl AsyncRead + Unpin),
+    token: &str,
+) -> Result<Vec<u8>, &'static str> {
+    let mut reader = BufReader::new(stream.take(MAX_REQUEST_SIZE));
+
+    let mut request_line = String::new();
+    reader
+        .read_line(&mut request_line)
+        .await
+        .map_err(|_| "400 Bad Request")?;
+    let mut parts = request_line.split_whitespace();
+    if parts.next() != Some("POST") || parts.next() != Some("/sign") {
+        return Err("404 Not Found");
+    }
+
+    let mut content_length = 0;
+    let mut authorized = false;
+    loop {
+        let mut header = String::new();
+        if reader
+            .read_line(&mut header)
+            .await
+            .map_err(|_| "400 Bad Request")?
+            == 0
+        {
+            return Err("400 Bad Request");
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().map_err(|_| "400 Bad Request")?;
+            } else if name.eq_ignore_ascii_case("authorization") {
+                authorized = value.trim().strip_prefix("Bearer ").map_or(false, |value| {
+                    tokens_match(value.as_bytes(), token.as_bytes())
+                });
+            }
+        }
+    }
+    if !authorized {
+        return Err("401 Unauthorized");
+    }
+    if content_length as u64 > MAX_REQUEST_SIZE {
+        return Err("413 Payload Too Large");
+    }
+
+    let mut body = vec![0; content_length];
+    reader
+        .read_exact(&mut body)
+        .await
+        .map_err(|_| "400 Bad Request")?;
+    Ok(body)
+}
+
+/// Compares the tokens in constant time, so they can't be guessed byte by byte.
+fn tokens_match(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[tokio::main]
+async fn main() {
+    exit(match run_app().await {
+        Ok(_) => 0,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            1
+        }
+    });
+}
+
+#[derive(Debug, Error)]
+enum AppError {
+    #[error("{0}")]
+    Endpoint(String),
+    #[error("Unsupported signing history version {0}")]
+    ExportVersion(u8),
+}
@@ -1043,7 +1043,7 @@ impl TransactionBuilder {
     ///  - `key_pair`:              The optional key pair used to sign the outgoing transaction. If
     ///                             it is given, the fee will be paid from the basic account
     ///                             belonging to this key pair. Otherwise, it will be deducted from
-    ///                             the staker's balance.
+    ///                             the stake of the staker's main delegation.
     ///  - `staker_key_pair`:       The key pair used to sign the incoming transaction. The staker
     ///                             address will be derived from this key pair.
     ///  - `delegation`:            The new delegation.
@@ -1110,15 +1110,172 @@ impl TransactionBuilder {
         }
     }
 
+    /// Creates a transaction that sets the stake a given staker delegates to an additional
+    /// validator. The difference is moved from or to the staker's main delegation, a stake of zero
+    /// removes the delegation to the validator. It can pay fees from the staker's balance.
+    ///
+    /// # Arguments
+    ///
+    ///  - `key_pair`:              The optional key pair used to sign the outgoing transaction. If
+    ///                             it is given, the fee will be paid from the basic account
+    ///                             belonging to this key pair. Otherwise, it will be deducted from
+    ///                             the stake of the staker's main delegation.
+    ///  - `staker_key_pair`:       The key pair used to sign the incoming transaction. The staker
+    ///                             address will be derived from this key pair.
+    ///  - `validator_address`:     The address of the additional validator.
+    ///  - `stake`:                 The new stake delegated to the validator.
+    ///  - `fee`:                   Transaction fee.
+    ///  - `validity_start_height`: Block height from which this transaction is valid.
+    ///  - `network_id`:            ID of network for which the transaction is meant.
+    ///
+    /// # Returns
+    ///
+    /// The finalized transaction.
+    ///
+    /// # Note
+    ///
+    /// This is a *signaling transaction*.
+    ///
+    pub fn new_set_delegation_stake(
+        key_pair: Option<&KeyPair>,
+        staker_key_pair: &KeyPair,
+        validator_address: Address,
+        stake: Coin,
+        fee: Coin,
+        validity_start_height: u32,
+        network_id: NetworkId,
+    ) -> Result<Transaction, TransactionBuilderError> {
+        let mut recipient = Recipient::new_staking_builder();
+        recipient.set_delegation_stake(validator_address, stake);
+
+        let mut builder = Self::new();
+        builder
+            .with_recipient(recipient.generate().unwrap())
+            .with_value(Coin::ZERO)
+            .with_fee(fee)
+            .with_validity_start_height(validity_start_height)
+            .with_network_id(network_id);
+
+        match key_pair {
+            None => {
+                builder
+                    .with_sender(Policy::STAKING_CONTRACT_ADDRESS)
+                    .with_sender_type(AccountType::Staking);
+            }
+            Some(key) => {
+                builder.with_sender(Address::from(key));
+            }
+        }
+
+        let proof_builder = builder.generate()?;
+        match proof_builder {
+            TransactionProofBuilder::InStaking(mut builder) => {
+                builder.sign_with_key_pair(staker_key_pair);
+                match key_pair {
+                    None => {
+                        let mut builder = builder.generate().unwrap().unwrap_out_staking();
+                        builder.unstake(staker_key_pair);
+                        Ok(builder.generate().unwrap())
+                    }
+                    Some(key) => {
+                        let mut builder = builder.generate().unwrap().unwrap_basic();
+                        builder.sign_with_key_pair(key);
+                        Ok(builder.generate().unwrap())
+                    }
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Creates a transaction that sets the inactive balance of a given staker. Increasing the
+    /// inactive balance deactivates stake, which can be withdrawn using [`new_unstake`] after the
+    /// unbonding period. Decreasing it reactivates stake. It can pay fees from the staker's
+    /// balance.
+    ///
+    /// # Arguments
+    ///
+    ///  - `key_pair`:              The optional key pair used to sign the outgoing transaction. If
+    ///                             it is given, the fee will be paid from the basic account
+    ///                             belonging to this key pair. Otherwise, it will be deducted from
+    ///                             the stake of the staker's main delegation.
+    ///  - `staker_key_pair`:       The key pair used to sign the incoming transaction. The staker
+    ///                             address will be derived from this key pair.
+    ///  - `new_inactive_balance`:  The new inactive balance of the staker.
+    ///  - `fee`:                   Transaction fee.
+    ///  - `validity_start_height`: Block height from which this transaction is valid.
+    ///  - `network_id`:            ID of network for which the transaction is meant.
+    ///
+    /// # Returns
+    ///
+    /// The finalized transaction.
+    ///
+    /// # Note
+    ///
+    /// This is a *signaling transaction*.
+    ///
+    /// [`new_unstake`]: TransactionBuilder::new_unstake
+    pub fn new_set_inactive_stake(
+        key_pair: Option<&KeyPair>,
+        staker_key_pair: &KeyPair,
+        new_inactive_balance: Coin,
+        fee: Coin,
+        validity_start_height: u32,
+        network_id: NetworkId,
+    ) -> Result<Transaction, TransactionBuilderError> {
+        let mut recipient = Recipient::new_staking_builder();
+        recipient.set_inactive_stake(new_inactive_balance);
+
+        let mut builder = Self::new();
+        builder
+            .with_recipient(recipient.generate().unwrap())
+            .with_value(Coin::ZERO)
+            .with_fee(fee)
+            .with_validity_start_height(validity_start_height)
+            .with_network_id(network_id);
+
+        match key_pair {
+            None => {
+                builder
+                    .with_sender(Policy::STAKING_CONTRACT_ADDRESS)
+                    .with_sender_type(AccountType::Staking);
+            }
+            Some(key) => {
+                builder.with_sender(Address::from(key));
+            }
+        }
+
+        let proof_builder = builder.generate()?;
+        match proof_builder {
+            TransactionProofBuilder::InStaking(mut builder) => {
+                builder.sign_with_key_pair(staker_key_pair);
+                match key_pair {
+                    None => {
+                        let mut builder = builder.generate().unwrap().unwrap_out_staking();
+                        builder.unstake(staker_key_pair);
+                        Ok(builder.generate().unwrap())
+                    }
+                    Some(key) => {
+                        let mut builder = builder.generate().unwrap().unwrap_basic();
+                        builder.sign_with_key_pair(key);
+                        Ok(builder.generate().unwrap())
+                    }
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
     /// Creates a transaction to move stake of a given staker from the staking contract to a
-    /// basic `recipient` address.
+    /// basic `recipient` address. Only the inactive balance of the staker can be withdrawn and
+    /// only after it has been released, see [`new_set_inactive_stake`].
     ///
     /// # Arguments
     ///
     ///  - `key_pair`:              The key pair used to sign the outgoing transaction. The staker
     ///                             address will be derived from this key pair.
     ///  - `recipient`:             The basic address that will receive the unstaked funds.
-    ///  - `value`:                 The value to be moved from the staker.
+    ///  - `value`:                 The value to be moved from the staker's inactive balance.
     ///  - `fee`:                   Transaction fee.
     ///  - `validity_start_height`: Block height from which this transaction is valid.
     ///  - `network_id`:            ID of network for which the transaction is meant.
@@ -1127,6 +1284,7 @@ impl TransactionBuilder {
     ///
     /// The finalized transaction.
     ///
+    /// [`new_set_inactive_stake`]: TransactionBuilder::new_set_inactive_stake
     pub fn new_unstake(
         key_pair: &KeyPair,
         recipient: Address,
@@ -1276,6 +1434,61 @@ impl TransactionBuilder {
         }
     }
 
+    /// Creates a transaction that sets the commission a validator charges on the rewards of its
+    /// stakers.
+    ///
+    /// # Arguments
+    ///
+    ///  - `key_pair`:                 The key pair used to sign the transaction. The transaction
+    ///                                fee is taken from the account belonging to this key pair.
+    ///  - `cold_key_pair`:            The key pair that corresponds to the validator address. The
+    ///                                data is signed using this key pair.
+    ///  - `new_commission`:           The new commission in basis points. `None` opts out of
+    ///                                distributing the rewards to the stakers.
+    ///  - `fee`:                      Transaction fee.
+    ///  - `validity_start_height`:    Block height from which this transaction is valid.
+    ///  - `network_id`:               ID of network for which the transaction is valid.
+    ///
+    /// # Returns
+    ///
+    /// The finalized transaction.
+    ///
+    /// # Note
+    ///
+    /// This is a *signaling transaction*.
+    ///
+    pub fn new_update_validator_commission(
+        key_pair: &KeyPair,
+        cold_key_pair: &KeyPair,
+        new_commission: Option<u16>,
+        fee: Coin,
+        validity_start_height: u32,
+        network_id: NetworkId,
+    ) -> Result<Transaction, TransactionBuilderError> {
+        let mut recipient = Recipient::new_staking_builder();
+        recipient.update_validator_commission(new_commission);
+
+        let mut builder = Self::new();
+        builder
+            .with_sender(Address::from(key_pair))
+            .with_recipient(recipient.generate().unwrap())
+            .with_value(Coin::ZERO)
+            .with_fee(fee)
+            .with_validity_start_height(validity_start_height)
+            .with_network_id(network_id);
+
+        let proof_builder = builder.generate()?;
+        match proof_builder {
+            TransactionProofBuilder::InStaking(mut builder) => {
+                builder.sign_with_key_pair(cold_key_pair);
+                let mut builder = builder.generate().unwrap().unwrap_basic();
+                builder.sign_with_key_pair(key_pair);
+                Ok(builder.generate().unwrap())
+            }
+            _ => unreachable!(),
+        }
+    }
+
     /// Creates a transaction that deactivates a validator.
     ///
     /// # Arguments
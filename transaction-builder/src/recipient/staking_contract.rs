@@ -1,6 +1,7 @@
 use nimiq_bls::{CompressedSignature, KeyPair as BlsKeyPair};
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::{Address, PublicKey as SchnorrPublicKey};
+use nimiq_primitives::coin::Coin;
 use nimiq_transaction::account::staking_contract::IncomingStakingTransactionData;
 
 use crate::recipient::Recipient;
@@ -18,6 +19,8 @@ use crate::recipient::Recipient;
 ///         * Create
 ///         * Stake
 ///         * Update (signaling)
+///         * Set delegation stake (signaling)
+///         * Set inactive stake (signaling)
 ///
 /// Signaling transactions have a special status as they require a zero value
 /// as well as an additional step during the proof generation.
@@ -82,6 +85,17 @@ impl StakingRecipientBuilder {
         self
     }
 
+    /// This method allows to set the commission a validator charges on the rewards of its
+    /// stakers. Setting a commission distributes the rewards to the stakers, None opts out again.
+    /// It needs to be signed by the key pair corresponding to the validator address.
+    pub fn update_validator_commission(&mut self, new_commission: Option<u16>) -> &mut Self {
+        self.data = Some(IncomingStakingTransactionData::UpdateValidatorCommission {
+            new_commission,
+            proof: Default::default(),
+        });
+        self
+    }
+
     /// This method allows to deactivate a validator entry. Inactive validators will not be considered
     /// for the validator selection.
     /// It needs to be signed by the key pair corresponding to the signing key.
@@ -137,6 +151,31 @@ impl StakingRecipientBuilder {
     pub fn update_staker(&mut self, new_delegation: Option<Address>) -> &mut Self {
         self.data = Some(IncomingStakingTransactionData::UpdateStaker {
             new_delegation,
+            delegation_stake: None,
+            proof: Default::default(),
+        });
+        self
+    }
+
+    /// This method allows to set the stake a staker delegates to an additional validator. The
+    /// difference is moved from or to the staker's main delegation, a stake of zero removes the
+    /// delegation to the validator.
+    /// It needs to be signed by the key pair corresponding to the staker address.
+    pub fn set_delegation_stake(&mut self, validator_address: Address, stake: Coin) -> &mut Self {
+        self.data = Some(IncomingStakingTransactionData::UpdateStaker {
+            new_delegation: Some(validator_address),
+            delegation_stake: Some(stake),
+            proof: Default::default(),
+        });
+        self
+    }
+
+    /// This method allows to set the inactive balance of a staker. Increasing it deactivates
+    /// stake, which can be withdrawn after the unbonding period. Decreasing it reactivates stake.
+    /// It needs to be signed by the key pair corresponding to the staker address.
+    pub fn set_inactive_stake(&mut self, new_inactive_balance: Coin) -> &mut Self {
+        self.data = Some(IncomingStakingTransactionData::SetInactiveStake {
+            new_inactive_balance,
             proof: Default::default(),
         });
         self
@@ -71,6 +71,7 @@ fn it_can_create_staker_transactions() {
     let tx = make_signed_incoming_transaction(
         IncomingStakingTransactionData::UpdateStaker {
             new_delegation: None,
+            delegation_stake: None,
             proof: Default::default(),
         },
         0,
@@ -93,6 +94,7 @@ fn it_can_create_staker_transactions() {
     let tx = make_self_transaction(
         IncomingStakingTransactionData::UpdateStaker {
             new_delegation: None,
+            delegation_stake: None,
             proof: Default::default(),
         },
         &key_pair,
@@ -110,6 +112,73 @@ fn it_can_create_staker_transactions() {
 
     assert_eq!(tx, tx2);
 
+    // Set delegation stake (fees from basic account)
+    let tx = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::UpdateStaker {
+            new_delegation: Some(Address::from_any_str(ADDRESS).unwrap()),
+            delegation_stake: Some(50_000_000.try_into().unwrap()),
+            proof: Default::default(),
+        },
+        0,
+        &key_pair,
+    );
+
+    let tx2 = TransactionBuilder::new_set_delegation_stake(
+        Some(&key_pair),
+        &key_pair,
+        Address::from_any_str(ADDRESS).unwrap(),
+        50_000_000.try_into().unwrap(),
+        100.try_into().unwrap(),
+        1,
+        NetworkId::Dummy,
+    )
+    .unwrap();
+
+    assert_eq!(tx, tx2);
+
+    // Set inactive stake (fees from basic account)
+    let tx = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::SetInactiveStake {
+            new_inactive_balance: 150_000_000.try_into().unwrap(),
+            proof: Default::default(),
+        },
+        0,
+        &key_pair,
+    );
+
+    let tx2 = TransactionBuilder::new_set_inactive_stake(
+        Some(&key_pair),
+        &key_pair,
+        150_000_000.try_into().unwrap(),
+        100.try_into().unwrap(),
+        1,
+        NetworkId::Dummy,
+    )
+    .unwrap();
+
+    assert_eq!(tx, tx2);
+
+    // Set inactive stake (fees from staker account)
+    let tx = make_self_transaction(
+        IncomingStakingTransactionData::SetInactiveStake {
+            new_inactive_balance: 150_000_000.try_into().unwrap(),
+            proof: Default::default(),
+        },
+        &key_pair,
+    );
+
+    let tx2 = TransactionBuilder::new_set_inactive_stake(
+        None,
+        &key_pair,
+        150_000_000.try_into().unwrap(),
+        100.try_into().unwrap(),
+        1,
+        NetworkId::Dummy,
+    )
+    .unwrap();
+
+    assert_eq!(tx, tx2);
+
     // Unstake
     let tx = make_unstake_transaction(&key_pair, 150_000_000);
 
@@ -213,6 +282,28 @@ fn it_can_create_validator_transactions() {
 
     assert_eq!(tx, tx2);
 
+    // Update commission
+    let tx = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::UpdateValidatorCommission {
+            new_commission: Some(500),
+            proof: Default::default(),
+        },
+        0,
+        &key_pair,
+    );
+
+    let tx2 = TransactionBuilder::new_update_validator_commission(
+        &key_pair,
+        &key_pair,
+        Some(500),
+        100.try_into().unwrap(),
+        1,
+        NetworkId::Dummy,
+    )
+    .unwrap();
+
+    assert_eq!(tx, tx2);
+
     // Deactivate
     let tx = make_signed_incoming_transaction(
         IncomingStakingTransactionData::DeactivateValidator {
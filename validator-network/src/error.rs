@@ -29,4 +29,8 @@ where
 
     #[error("Request error: {0}")]
     Request(RequestError),
+
+    /// Our validator record could not be signed.
+    #[error("Validator record could not be signed")]
+    UnsignedRecord,
 }
@@ -6,8 +6,8 @@ pub mod validator_record;
 use std::{pin::Pin, time::Duration};
 
 use async_trait::async_trait;
-use futures::{stream::BoxStream, Stream};
-use nimiq_bls::{lazy::LazyPublicKey, CompressedPublicKey, SecretKey};
+use futures::{future::BoxFuture, stream::BoxStream, Stream};
+use nimiq_bls::{lazy::LazyPublicKey, CompressedPublicKey, Signature};
 use nimiq_network_interface::{
     network::{MsgAcceptance, Network, PubsubId, Topic},
     request::{Message, Request, RequestCommon},
@@ -68,11 +68,12 @@ pub trait ValidatorNetwork: Send + Sync {
     /// `lifetime` or `buffer_size` of 0 should disable the cache.
     fn cache<M: Message>(&self, buffer_size: usize, lifetime: Duration);
 
-    /// Sets this node peer ID using its secret key and public key.
+    /// Publishes this node peer ID under the given public key. The record is signed by
+    /// `sign_record`, which resolves to `None` if it can't sign.
     async fn set_public_key(
         &self,
         public_key: &CompressedPublicKey,
-        secret_key: &SecretKey,
+        sign_record: &(dyn Fn(Vec<u8>) -> BoxFuture<'static, Option<Signature>> + Send + Sync),
     ) -> Result<(), Self::Error>;
 
     /// Signals that a Gossipsup'd message with `id` was verified successfully and can be relayed.
@@ -1,8 +1,8 @@
 use std::{collections::BTreeMap, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
-use futures::{stream::BoxStream, StreamExt, TryFutureExt};
-use nimiq_bls::{lazy::LazyPublicKey, CompressedPublicKey, SecretKey};
+use futures::{future::BoxFuture, stream::BoxStream, StreamExt, TryFutureExt};
+use nimiq_bls::{lazy::LazyPublicKey, CompressedPublicKey, Signature};
 use nimiq_network_interface::{
     network::{MsgAcceptance, Network, NetworkEvent, Topic},
     request::{Message, Request, RequestCommon},
@@ -283,12 +283,15 @@ where
     async fn set_public_key(
         &self,
         public_key: &CompressedPublicKey,
-        secret_key: &SecretKey,
+        sign_record: &(dyn Fn(Vec<u8>) -> BoxFuture<'static, Option<Signature>> + Send + Sync),
     ) -> Result<(), Self::Error> {
         let peer_id = self.network.get_local_peer_id();
         let record = ValidatorRecord::new(peer_id);
+        let signature = sign_record(record.serialize_to_vec())
+            .await
+            .ok_or(NetworkError::UnsignedRecord)?;
         self.network
-            .dht_put(public_key, &record.sign(secret_key))
+            .dht_put(public_key, &record.with_signature(signature))
             .await?;
 
         Ok(())
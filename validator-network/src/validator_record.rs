@@ -32,6 +32,11 @@ where
         let data = self.serialize_to_vec();
         let signature = secret_key.sign(&data);
 
+        self.with_signature(signature)
+    }
+
+    /// Attaches a signature over the serialized record, created by someone holding the key.
+    pub fn with_signature(self, signature: Signature) -> SignedValidatorRecord<TPeerId> {
         SignedValidatorRecord {
             record: self,
             signature,
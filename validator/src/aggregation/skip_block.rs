@@ -11,8 +11,8 @@ use futures::{
     ready,
     stream::{select, BoxStream, Stream, StreamExt},
 };
-use nimiq_block::{Message, MultiSignature, SignedSkipBlockInfo, SkipBlockInfo, SkipBlockProof};
-use nimiq_bls::{AggregateSignature, KeyPair};
+use nimiq_block::{Message, MultiSignature, SkipBlockInfo, SkipBlockProof};
+use nimiq_bls::{AggregateSignature, Signature};
 use nimiq_collections::BitSet;
 use nimiq_handel::{
     aggregation::Aggregation,
@@ -140,7 +140,7 @@ impl AggregatableContribution for SignedSkipBlockMessage {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
-struct SkipBlockUpdate(pub LevelUpdate<SignedSkipBlockMessage>, pub SkipBlockInfo);
+pub(crate) struct SkipBlockUpdate(pub LevelUpdate<SignedSkipBlockMessage>, pub SkipBlockInfo);
 
 impl RequestCommon for SkipBlockUpdate {
     type Kind = MessageMarker;
@@ -244,7 +244,8 @@ pub struct SkipBlockAggregation {}
 impl SkipBlockAggregation {
     pub async fn start<N: ValidatorNetwork + 'static>(
         skip_block_info: SkipBlockInfo,
-        voting_key: KeyPair,
+        // Our signature over the skip block info.
+        signature: Signature,
         // TODO: This seems to be a SlotBand. Change this to a proper Validator ID.
         validator_id: u16,
         active_validators: Validators,
@@ -264,15 +265,8 @@ impl SkipBlockAggregation {
                 &skip_block_info,
                 message_hash
             );
-            let signed_skip_block_info = SignedSkipBlockInfo::from_message(
-                skip_block_info.clone(),
-                &voting_key.secret_key,
-                validator_id,
-            );
-
-            let signature = AggregateSignature::from_signatures(&[signed_skip_block_info
-                .signature
-                .multiply(slots.len() as u16)]);
+            let signature =
+                AggregateSignature::from_signatures(&[signature.multiply(slots.len() as u16)]);
 
             let mut signers = BitSet::new();
             for slot in &slots {
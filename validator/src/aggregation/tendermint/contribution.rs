@@ -1,7 +1,7 @@
 use std::collections::BTreeMap;
 
 use nimiq_block::{MultiSignature, TendermintVote};
-use nimiq_bls::{AggregateSignature, SecretKey};
+use nimiq_bls::{AggregateSignature, Signature};
 use nimiq_collections::bitset::BitSet;
 use nimiq_handel::{
     contribution::{AggregatableContribution, ContributionError},
@@ -27,14 +27,15 @@ impl std::fmt::Debug for TendermintContribution {
 impl TendermintContribution {
     pub(crate) fn from_vote(
         vote: TendermintVote,
-        secret_key: &SecretKey,
+        signature: Signature,
         validator_slots: Vec<u16>,
     ) -> Self {
         assert!(!validator_slots.is_empty());
-        // sign the hash
-        let signature = AggregateSignature::from_signatures(&[secret_key
-            .sign(&vote)
-            .multiply(validator_slots.len() as u16)]);
+        // weigh our signature of the vote by our number of slots
+        let signature =
+            AggregateSignature::from_signatures(
+                &[signature.multiply(validator_slots.len() as u16)],
+            );
 
         // get the slots of the validator ad insert them into the bitset
         let mut signers = BitSet::new();
@@ -0,0 +1,275 @@
+use std::task::{Context, Poll};
+
+use futures::stream::{self, BoxStream, StreamExt};
+use nimiq_block::{Block, MicroJustification};
+use nimiq_blockchain::Blockchain;
+use nimiq_collections::BitSet;
+use nimiq_handel::contribution::AggregatableContribution;
+use nimiq_keys::Address;
+use nimiq_primitives::policy::Policy;
+use nimiq_validator_network::ValidatorNetwork;
+
+use crate::aggregation::{
+    skip_block::SkipBlockUpdate, tendermint::update_message::TendermintUpdate,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+enum DoppelgangerState {
+    /// Waiting for the validator to be able to become active.
+    Pending,
+    /// Watching the blocks up to and including `until_block` for signatures of our validator.
+    Observing { start_block: u32, until_block: u32 },
+    /// No other instance was seen, the validator is allowed to sign.
+    Passed,
+    /// Another instance signing with our keys was seen, the validator must not sign.
+    Detected,
+}
+
+/// A skip block or tendermint aggregation message received from another validator.
+#[derive(Clone, Debug)]
+pub struct Contribution {
+    /// The block the aggregation is for.
+    pub block_number: u32,
+    /// The validator id (i.e. slot band) of the sender.
+    pub origin: usize,
+    /// The slots whose signatures are part of the aggregate.
+    pub contributors: BitSet,
+}
+
+/// The slots our validator holds in an epoch.
+#[derive(Clone, Debug)]
+pub struct ValidatorSlots {
+    pub epoch: u32,
+    pub slot_band: u16,
+    pub slot_range: (u16, u16),
+}
+
+/// Doppelganger protection keeps a freshly started validator from signing anything until it
+/// has watched the chain for a number of blocks. If any of these blocks was produced by our
+/// validator, or its aggregated skip block or tendermint signature contains a contribution of
+/// one of our slots, another instance is running with the same keys. The same is checked for
+/// the skip block and tendermint aggregation messages other validators send us in the meantime.
+/// We then refuse to sign, since both instances would be slashed otherwise.
+pub struct DoppelgangerProtection {
+    num_blocks: u32,
+    state: DoppelgangerState,
+    validator_slots: Option<ValidatorSlots>,
+    contributions: Option<BoxStream<'static, Contribution>>,
+}
+
+impl DoppelgangerProtection {
+    /// Creates the protection for the given number of blocks. Zero disables it.
+    pub fn new(num_blocks: u32) -> Self {
+        let state = if num_blocks == 0 {
+            DoppelgangerState::Passed
+        } else {
+            DoppelgangerState::Pending
+        };
+        Self {
+            num_blocks,
+            state,
+            validator_slots: None,
+            contributions: None,
+        }
+    }
+
+    /// Receives the skip block and tendermint aggregation messages sent to us.
+    ///
+    /// The network only keeps one receiver per message type. This is fine, since no aggregation
+    /// is running while we observe, and the aggregations replace these receivers once they start.
+    pub fn receive_contributions<N: ValidatorNetwork>(
+        network: &N,
+    ) -> BoxStream<'static, Contribution> {
+        let skip_blocks = network.receive::<SkipBlockUpdate>().map(|(update, _)| {
+            let SkipBlockUpdate(level_update, skip_block_info) = update;
+            Contribution {
+                block_number: skip_block_info.block_number,
+                origin: level_update.origin(),
+                contributors: level_update.aggregate.contributors(),
+            }
+        });
+        let votes = network.receive::<TendermintUpdate>().map(|(update, _)| {
+            let TendermintUpdate(message, block_number) = update;
+            Contribution {
+                block_number,
+                origin: message.aggregation.0.origin(),
+                contributors: message.aggregation.0.aggregate.contributors(),
+            }
+        });
+        stream::select(skip_blocks, votes).boxed()
+    }
+
+    /// Starts observing from the given head block number, watching the given contributions in
+    /// addition to the blocks.
+    pub fn start(
+        &mut self,
+        head_block_number: u32,
+        contributions: BoxStream<'static, Contribution>,
+    ) {
+        if self.state == DoppelgangerState::Pending {
+            let until_block = head_block_number + self.num_blocks;
+            info!(
+                until_block,
+                "Observing the network for another instance of this validator before signing"
+            );
+            self.state = DoppelgangerState::Observing {
+                start_block: head_block_number,
+                until_block,
+            };
+            self.contributions = Some(contributions);
+        }
+    }
+
+    /// Whether the observation hasn't started yet.
+    pub fn is_pending(&self) -> bool {
+        self.state == DoppelgangerState::Pending
+    }
+
+    /// Whether we are currently observing the network.
+    pub fn is_observing(&self) -> bool {
+        matches!(self.state, DoppelgangerState::Observing { .. })
+    }
+
+    /// Whether the validator is allowed to produce blocks and sign.
+    pub fn may_sign(&self) -> bool {
+        self.state == DoppelgangerState::Passed
+    }
+
+    /// Sets the slots of our validator in the current epoch, `None` if it isn't elected.
+    pub fn set_validator_slots(&mut self, validator_slots: Option<ValidatorSlots>) {
+        self.validator_slots = validator_slots;
+    }
+
+    /// Processes the contributions received so far.
+    pub fn poll_contributions(&mut self, cx: &mut Context<'_>) {
+        while self.is_observing() {
+            let contribution = match self.contributions.as_mut() {
+                Some(contributions) => match contributions.poll_next_unpin(cx) {
+                    Poll::Ready(Some(contribution)) => contribution,
+                    Poll::Ready(None) | Poll::Pending => return,
+                },
+                None => return,
+            };
+            self.on_contribution(&contribution);
+        }
+    }
+
+    /// Inspects a contribution that was received while observing.
+    pub fn on_contribution(&mut self, contribution: &Contribution) {
+        let start_block = match self.state {
+            DoppelgangerState::Observing { start_block, .. } => start_block,
+            _ => return,
+        };
+        let validator_slots = match &self.validator_slots {
+            Some(validator_slots) => validator_slots,
+            None => return,
+        };
+
+        // Same as for blocks, a previous run of this validator may still have contributed to
+        // the aggregations for the block following our start.
+        if contribution.block_number <= start_block + 1
+            || Policy::epoch_at(contribution.block_number) != validator_slots.epoch
+        {
+            return;
+        }
+
+        let (start, end) = validator_slots.slot_range;
+        if contribution.origin == validator_slots.slot_band as usize
+            || contribution
+                .contributors
+                .iter()
+                .any(|slot| (start as usize..end as usize).contains(&slot))
+        {
+            error!(
+                block_number = contribution.block_number,
+                "Detected a contribution of another instance of this validator, refusing to sign"
+            );
+            self.set_state(DoppelgangerState::Detected);
+        }
+    }
+
+    /// Inspects a block that was added to the chain while observing.
+    pub fn on_block(
+        &mut self,
+        blockchain: &Blockchain,
+        block: &Block,
+        validator_address: &Address,
+    ) {
+        let (start_block, until_block) = match self.state {
+            DoppelgangerState::Observing {
+                start_block,
+                until_block,
+            } => (start_block, until_block),
+            _ => return,
+        };
+
+        // A previous run of this validator may still have contributed signatures to the block
+        // following our start, so we only look at the blocks after that one.
+        let block_number = block.block_number();
+        if block_number > start_block + 1
+            && Self::is_signed_by(blockchain, block, validator_address)
+        {
+            error!(
+                block_number,
+                %validator_address,
+                "Detected another instance of this validator, refusing to sign"
+            );
+            self.set_state(DoppelgangerState::Detected);
+        } else if block_number >= until_block {
+            info!("No other instance of this validator detected, starting to sign");
+            self.set_state(DoppelgangerState::Passed);
+        }
+    }
+
+    /// Ends the observation, which also stops receiving contributions.
+    fn set_state(&mut self, state: DoppelgangerState) {
+        self.state = state;
+        self.contributions = None;
+    }
+
+    /// Checks whether the block was proposed by the given validator or contains a signature of
+    /// any of its slots.
+    fn is_signed_by(blockchain: &Blockchain, block: &Block, validator_address: &Address) -> bool {
+        let block_number = block.block_number();
+
+        let signers = match block {
+            Block::Micro(micro_block) => match &micro_block.justification {
+                Some(MicroJustification::Skip(proof)) => &proof.sig.signers,
+                Some(MicroJustification::Micro(_)) => {
+                    return blockchain
+                        .get_block(&micro_block.header.parent_hash, false, None)
+                        .and_then(|parent| {
+                            blockchain.get_proposer_at(
+                                block_number,
+                                block_number,
+                                parent.seed().entropy(),
+                                None,
+                            )
+                        })
+                        .map(|slot| slot.validator.address == *validator_address)
+                        .unwrap_or(false);
+                }
+                None => return false,
+            },
+            Block::Macro(macro_block) => match &macro_block.justification {
+                Some(proof) => &proof.sig.signers,
+                None => return false,
+            },
+        };
+
+        let validators =
+            match blockchain.get_validators_for_epoch(Policy::epoch_at(block_number), None) {
+                Ok(validators) => validators,
+                Err(_) => return false,
+            };
+
+        validators
+            .get_validator_by_address(validator_address.clone())
+            .map_or(false, |validator| {
+                let (start, end) = validator.slot_range;
+                signers
+                    .iter()
+                    .any(|slot| (start as usize..end as usize).contains(&slot))
+            })
+    }
+}
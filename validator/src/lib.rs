@@ -2,8 +2,11 @@
 extern crate log;
 
 pub mod aggregation;
+pub mod doppelganger;
 mod r#macro;
 mod micro;
+pub mod signer;
 mod slash;
+pub mod slashing_protection;
 mod tendermint;
 pub mod validator;
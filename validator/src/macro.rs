@@ -10,7 +10,6 @@ use futures::{
     stream::{BoxStream, Stream, StreamExt},
 };
 use nimiq_block::MacroBlock;
-use nimiq_block_production::BlockProducer;
 use nimiq_blockchain::Blockchain;
 use nimiq_keys::Signature as SchnorrSignature;
 use nimiq_network_interface::network::Topic;
@@ -25,6 +24,8 @@ use crate::{
         state::MacroState,
         update_message::TendermintUpdate,
     },
+    signer::ValidatorSigner,
+    slashing_protection::SlashingProtection,
     tendermint::TendermintProtocol,
 };
 
@@ -75,7 +76,7 @@ where
     pub fn new(
         blockchain: Arc<RwLock<Blockchain>>,
         network: Arc<TValidatorNetwork>,
-        block_producer: BlockProducer,
+        signer: Arc<dyn ValidatorSigner>,
         validator_slot_band: u16,
         current_validators: Validators,
         block_height: u32,
@@ -87,6 +88,7 @@ where
                 (SchnorrSignature, u16),
             >,
         >,
+        slashing_protection: Arc<SlashingProtection>,
     ) -> Self {
         let input = network
             .receive::<TendermintUpdate>()
@@ -99,22 +101,40 @@ where
             })
             .boxed();
 
-        let dependencies = TendermintProtocol::new(
-            blockchain,
-            network,
-            block_producer,
-            current_validators,
-            validator_slot_band,
-            block_height,
-        );
+        // Sign the VRF seed for our proposals before tendermint starts, as the proposals are
+        // created synchronously and a remote signer may take a while to answer.
+        let prev_seed = blockchain.read().head().seed().clone();
+        let tendermint = futures::stream::once(async move {
+            let proposal_seed = match signer.sign_vrf_seed(&prev_seed).await {
+                Ok(seed) => seed,
+                Err(error) => {
+                    // A proposal with an invalid seed is rejected by everyone, which has the same
+                    // effect as not proposing at all.
+                    log::error!(%error, "Failed to sign the VRF seed of the macro block proposal");
+                    prev_seed
+                }
+            };
 
-        // create the Tendermint instance, which implements Stream
-        let tendermint = Tendermint::new(
-            dependencies,
-            state_opt.and_then(|s| s.into_tendermint_state(block_height)),
-            proposal_stream,
-            input,
-        )
+            let dependencies = TendermintProtocol::new(
+                blockchain,
+                network,
+                signer,
+                current_validators,
+                validator_slot_band,
+                block_height,
+                slashing_protection,
+                proposal_seed,
+            );
+
+            // create the Tendermint instance, which implements Stream
+            Tendermint::new(
+                dependencies,
+                state_opt.and_then(|s| s.into_tendermint_state(block_height)),
+                proposal_stream,
+                input,
+            )
+        })
+        .flatten()
         // and map the return value such that a state update can be persisted.
         .map(move |item| match item {
             TendermintReturn::Decision(decision) => MappedReturn::Decision(decision),
@@ -7,7 +7,7 @@ use std::{
 };
 
 use futures::{future::BoxFuture, ready, FutureExt, Stream};
-use nimiq_block::{Block, ForkProof, MicroBlock, SkipBlockInfo};
+use nimiq_block::{Block, ForkProof, MicroBlock, MicroJustification, SkipBlockInfo};
 use nimiq_block_production::BlockProducer;
 use nimiq_blockchain::Blockchain;
 use nimiq_blockchain_interface::{AbstractBlockchain, PushResult};
@@ -18,7 +18,11 @@ use nimiq_vrf::VrfSeed;
 use parking_lot::RwLock;
 use tokio::time;
 
-use crate::aggregation::skip_block::SkipBlockAggregation;
+use crate::{
+    aggregation::skip_block::SkipBlockAggregation,
+    signer::ValidatorSigner,
+    slashing_protection::{SignedMessage, SlashingProtection},
+};
 
 // Ignoring this clippy warning since size difference is not that much (320
 // bytes) and we probably don't want the performance penalty of the allocation.
@@ -32,13 +36,14 @@ struct NextProduceMicroBlockEvent<TValidatorNetwork> {
     blockchain: Arc<RwLock<Blockchain>>,
     mempool: Arc<Mempool>,
     network: Arc<TValidatorNetwork>,
-    block_producer: BlockProducer,
+    signer: Arc<dyn ValidatorSigner>,
     validator_slot_band: u16,
     fork_proofs: Vec<ForkProof>,
     prev_seed: VrfSeed,
     block_number: u32,
     producer_timeout: Duration,
     block_separation_time: Duration,
+    slashing_protection: Arc<SlashingProtection>,
 }
 
 impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<TValidatorNetwork> {
@@ -49,25 +54,27 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<T
         blockchain: Arc<RwLock<Blockchain>>,
         mempool: Arc<Mempool>,
         network: Arc<TValidatorNetwork>,
-        block_producer: BlockProducer,
+        signer: Arc<dyn ValidatorSigner>,
         validator_slot_band: u16,
         fork_proofs: Vec<ForkProof>,
         prev_seed: VrfSeed,
         block_number: u32,
         producer_timeout: Duration,
         block_separation_time: Duration,
+        slashing_protection: Arc<SlashingProtection>,
     ) -> Self {
         Self {
             blockchain,
             mempool,
             network,
-            block_producer,
+            signer,
             validator_slot_band,
             fork_proofs,
             prev_seed,
             block_number,
             producer_timeout,
             block_separation_time,
+            slashing_protection,
         }
     }
 
@@ -85,72 +92,139 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<T
         let mut expected_next_ts;
 
         let return_value = loop {
-            // Acquire blockchain.upgradable_read() to prevent further changes to the blockchain while
-            // we're constructing the block. Check if we're still in the correct state, abort otherwise.
-            {
-                let blockchain = self.blockchain.upgradable_read();
+            // Check whether it is our turn under a blockchain read lock. The block is signed only
+            // after dropping the lock, since a remote signer may take a while to answer and the
+            // slashing protection database must be written before.
+            let produce = {
+                let blockchain = self.blockchain.read();
 
                 // Calculate the expected block time as expected by the reward function.
                 expected_next_ts = self.expected_next_timestamp(&blockchain);
 
                 if !in_current_state(&blockchain.head()) {
                     break Some(None);
-                } else if self.is_our_turn(&blockchain) {
-                    // We want to produce a block at the expected timestamp for this block in this batch
-                    // as it is calculated by the reward function and set the producer timeout accordingly
-                    let now = systemtime_to_timestamp(SystemTime::now());
-
-                    // If the expected timestamp is already in the past, produce a block immediately.
-                    // If the timestamp hasn't passed, wait until the expected block timestamp
-                    // to produce the block.
-                    if expected_next_ts <= now {
-                        info!(
+                } else if !self.is_our_turn(&blockchain) {
+                    break None;
+                }
+
+                // We want to produce a block at the expected timestamp for this block in this batch
+                // as it is calculated by the reward function and set the producer timeout accordingly
+                let now = systemtime_to_timestamp(SystemTime::now());
+
+                // If the expected timestamp is already in the past, produce a block immediately.
+                // If the timestamp hasn't passed, wait until the expected block timestamp
+                // to produce the block.
+                if expected_next_ts <= now {
+                    true
+                } else {
+                    delay = Duration::from_millis(expected_next_ts - now);
+                    false
+                }
+            };
+
+            if produce {
+                info!(
+                    block_number = self.block_number,
+                    slot_band = self.validator_slot_band,
+                    "Our turn, producing micro block #{}",
+                    self.block_number,
+                );
+
+                let seed = match self.signer.sign_vrf_seed(&self.prev_seed).await {
+                    Ok(seed) => seed,
+                    Err(error) => {
+                        error!(
                             block_number = self.block_number,
-                            slot_band = self.validator_slot_band,
-                            "Our turn, producing micro block #{}",
-                            self.block_number,
+                            %error,
+                            "Failed to sign the VRF seed of the micro block"
                         );
+                        break Some(None);
+                    }
+                };
 
-                        let block = self.produce_micro_block(&blockchain);
-                        let num_transactions = block
-                            .body
-                            .as_ref()
-                            .map(|body| body.transactions.len())
-                            .unwrap_or(0);
-
-                        debug!(
-                            block_number = block.header.block_number,
-                            num_transactions,
-                            ?delay,
-                            "Produced micro block {} with {} transactions",
-                            block,
-                            num_transactions
+                let mut block = {
+                    let blockchain = self.blockchain.read();
+                    if !in_current_state(&blockchain.head()) {
+                        break Some(None);
+                    }
+                    self.produce_micro_block(&blockchain, seed)
+                };
+
+                // Never publish a micro block that conflicts with anything we signed for
+                // this slot before, it would get us slashed.
+                let message = SignedMessage::MicroBlock {
+                    block_number: self.block_number,
+                    vrf_entropy: self.prev_seed.entropy(),
+                    header_hash: block.hash(),
+                };
+                if let Err(signed) = self.slashing_protection.try_sign(message) {
+                    error!(
+                        block_number = self.block_number,
+                        ?signed,
+                        "Refusing to produce a conflicting micro block"
+                    );
+                    break Some(None);
+                }
+
+                match self
+                    .signer
+                    .sign_micro_header(&block.header, &self.prev_seed)
+                    .await
+                {
+                    Ok(signature) => {
+                        block.justification = Some(MicroJustification::Micro(signature))
+                    }
+                    Err(error) => {
+                        error!(
+                            block_number = self.block_number,
+                            %error,
+                            "Failed to sign micro block"
                         );
+                        break Some(None);
+                    }
+                }
+
+                let num_transactions = block
+                    .body
+                    .as_ref()
+                    .map(|body| body.transactions.len())
+                    .unwrap_or(0);
+
+                debug!(
+                    block_number = block.header.block_number,
+                    num_transactions,
+                    ?delay,
+                    "Produced micro block {} with {} transactions",
+                    block,
+                    num_transactions
+                );
 
-                        let block1 = block.clone();
-
-                        // Use a trusted push since these blocks were generated by this validator
-                        let result = if cfg!(feature = "trusted_push") {
-                            Blockchain::trusted_push(blockchain, Block::Micro(block))
-                        } else {
-                            Blockchain::push(blockchain, Block::Micro(block))
-                        };
-
-                        if let Err(e) = &result {
-                            error!("Failed to push our own block onto the chain: {:?}", e);
-                        }
-
-                        let event = result
-                            .map(move |result| ProduceMicroBlockEvent::MicroBlock(block1, result))
-                            .ok();
-                        break Some(event);
-                    } else {
-                        delay = Duration::from_millis(expected_next_ts - now);
-                    };
+                // Acquire blockchain.upgradable_read() to prevent further changes to the blockchain
+                // while we're pushing the block. The chain might have moved on since we produced it.
+                let blockchain = self.blockchain.upgradable_read();
+                if !in_current_state(&blockchain.head()) {
+                    break Some(None);
+                }
+
+                let block1 = block.clone();
+
+                // Use a trusted push since these blocks were generated by this validator
+                let result = if cfg!(feature = "trusted_push") {
+                    Blockchain::trusted_push(blockchain, Block::Micro(block))
                 } else {
-                    break None;
+                    Blockchain::push(blockchain, Block::Micro(block))
+                };
+
+                if let Err(e) = &result {
+                    error!("Failed to push our own block onto the chain: {:?}", e);
                 }
+
+                let event = result
+                    .map(move |result| ProduceMicroBlockEvent::MicroBlock(block1, result))
+                    .ok();
+                break Some(event);
             }
+
             // We have dropped the blockchain lock.
             // Wait for the expected timestamp to arrive before actually producing the block
             time::sleep(delay).await;
@@ -199,9 +273,33 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<T
             vrf_entropy: self.prev_seed.entropy(),
         };
 
+        if let Err(signed) = self.slashing_protection.try_sign(SignedMessage::SkipBlock {
+            block_number: skip_block_info.block_number,
+            vrf_entropy: skip_block_info.vrf_entropy,
+        }) {
+            error!(
+                block_number = self.block_number,
+                ?signed,
+                "Refusing to contribute to a conflicting skip block"
+            );
+            return (None, self);
+        }
+
+        let signature = match self.signer.sign_skip_block(&skip_block_info).await {
+            Ok(signature) => signature,
+            Err(error) => {
+                error!(
+                    block_number = self.block_number,
+                    %error,
+                    "Failed to sign skip block"
+                );
+                return (None, self);
+            }
+        };
+
         let (_, skip_block_proof) = SkipBlockAggregation::start(
             skip_block_info.clone(),
-            self.block_producer.voting_key.clone(),
+            signature,
             self.validator_slot_band,
             active_validators.unwrap(),
             Arc::clone(&self.network),
@@ -219,9 +317,10 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<T
             } else {
                 let timestamp = head.timestamp() + self.producer_timeout.as_millis() as u64;
 
-                let block = self.block_producer.next_micro_block(
+                let block = BlockProducer::next_unsigned_micro_block(
                     &blockchain,
                     timestamp,
+                    head.seed().clone(),
                     vec![],
                     vec![],
                     vec![], // TODO: Allow validators to set extra data field.
@@ -275,7 +374,8 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<T
         }
     }
 
-    fn produce_micro_block(&self, blockchain: &Blockchain) -> MicroBlock {
+    /// Produces the next micro block with the given VRF seed. Its header still needs to be signed.
+    fn produce_micro_block(&self, blockchain: &Blockchain, seed: VrfSeed) -> MicroBlock {
         let timestamp = u64::max(
             blockchain.timestamp(),
             systemtime_to_timestamp(SystemTime::now()),
@@ -296,9 +396,10 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<T
 
         transactions.append(&mut regular_transactions);
 
-        self.block_producer.next_micro_block(
+        BlockProducer::next_unsigned_micro_block(
             blockchain,
             timestamp,
+            seed,
             self.fork_proofs.clone(),
             transactions,
             vec![], // TODO: Allow validators to set extra data field.
@@ -335,25 +436,27 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> ProduceMicroBlock<TValidator
         blockchain: Arc<RwLock<Blockchain>>,
         mempool: Arc<Mempool>,
         network: Arc<TValidatorNetwork>,
-        block_producer: BlockProducer,
+        signer: Arc<dyn ValidatorSigner>,
         validator_slot_band: u16,
         fork_proofs: Vec<ForkProof>,
         prev_seed: VrfSeed,
         block_number: u32,
         producer_timeout: Duration,
         block_separation_time: Duration,
+        slashing_protection: Arc<SlashingProtection>,
     ) -> Self {
         let next_event = NextProduceMicroBlockEvent::new(
             blockchain,
             mempool,
             network,
-            block_producer,
+            signer,
             validator_slot_band,
             fork_proofs,
             prev_seed,
             block_number,
             producer_timeout,
             block_separation_time,
+            slashing_protection,
         )
         .next()
         .boxed();
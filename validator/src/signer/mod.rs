@@ -0,0 +1,485 @@
+use std::io;
+
+use async_trait::async_trait;
+use byteorder::WriteBytesExt;
+use nimiq_block::{
+    MacroHeader, Message, MicroHeader, SkipBlockInfo, TendermintIdentifier, TendermintStep,
+    TendermintVote,
+};
+use nimiq_bls::{
+    CompressedPublicKey as BlsCompressedPublicKey, KeyPair as BlsKeyPair,
+    PublicKey as BlsPublicKey, Signature as BlsSignature,
+};
+use nimiq_hash::{Blake2bHash, Blake2sHash, Blake2sHasher, Hash, Hasher, SerializeContent};
+use nimiq_keys::{
+    Address, KeyPair as SchnorrKeyPair, PublicKey as SchnorrPublicKey,
+    Signature as SchnorrSignature,
+};
+use nimiq_primitives::{coin::Coin, networks::NetworkId};
+use nimiq_serde::{Deserialize, Serialize};
+use nimiq_transaction::Transaction;
+use nimiq_transaction_builder::TransactionBuilder;
+use nimiq_vrf::VrfSeed;
+use thiserror::Error;
+
+use crate::slashing_protection::{SignedMessage, SlashingProtection};
+
+pub mod remote;
+
+#[derive(Debug, Error)]
+pub enum SignerError {
+    #[error("Signer refused to sign: {0}")]
+    Refused(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Malformed signer message: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Signer responded with HTTP status {0}")]
+    Http(String),
+    #[error("Signer didn't respond in time")]
+    Timeout,
+    #[error("Unexpected response from signer")]
+    UnexpectedResponse,
+}
+
+/// A request to sign something with one of the validator keys. The Schnorr signing key is used
+/// for VRF seeds, micro blocks, macro block proposals and the data of control transactions, the
+/// BLS voting key for skip blocks, tendermint votes and the validator record. The fee key pays for
+/// the control transactions.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SigningRequest {
+    PublicKeys,
+    VrfSeed {
+        prev_seed: VrfSeed,
+    },
+    MicroBlock {
+        header: MicroHeader,
+        prev_seed: VrfSeed,
+    },
+    MacroProposal {
+        header: MacroHeader,
+        round: u32,
+        valid_round: Option<u32>,
+    },
+    SkipBlock {
+        info: SkipBlockInfo,
+    },
+    TendermintVote {
+        block_number: u32,
+        round: u32,
+        step: TendermintStep,
+        proposal_hash: Option<Blake2sHash>,
+    },
+    /// The DHT record announcing the peer id of our validator, given as serialized in the record.
+    ValidatorRecord {
+        peer_id: Vec<u8>,
+    },
+    /// A transaction without fee reactivating our validator.
+    ReactivateValidator {
+        validator_address: Address,
+        validity_start_height: u32,
+        network_id: NetworkId,
+    },
+}
+
+impl SigningRequest {
+    /// The message to record in the signing history before this request may be signed, if
+    /// signing it twice with different contents could get the validator slashed.
+    pub fn signed_message(&self) -> Option<SignedMessage> {
+        match self {
+            SigningRequest::MicroBlock { header, prev_seed } => Some(SignedMessage::MicroBlock {
+                block_number: header.block_number,
+                vrf_entropy: prev_seed.entropy(),
+                header_hash: header.hash(),
+            }),
+            SigningRequest::SkipBlock { info } => Some(SignedMessage::SkipBlock {
+                block_number: info.block_number,
+                vrf_entropy: info.vrf_entropy,
+            }),
+            SigningRequest::TendermintVote {
+                block_number,
+                round,
+                step,
+                proposal_hash,
+            } => Some(SignedMessage::TendermintVote {
+                block_number: *block_number,
+                round: *round,
+                step: *step,
+                proposal_hash: proposal_hash.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SigningResponse {
+    PublicKeys {
+        signing_key: SchnorrPublicKey,
+        voting_key: BlsCompressedPublicKey,
+        fee_key: SchnorrPublicKey,
+    },
+    VrfSeed {
+        seed: VrfSeed,
+    },
+    Schnorr {
+        signature: SchnorrSignature,
+    },
+    Bls {
+        signature: BlsSignature,
+    },
+    Transaction {
+        transaction: Transaction,
+    },
+}
+
+/// Computes the data signed by the proposer of a macro block. It doesn't include the
+/// pk_tree_root, thus it must not be used for the aggregated tendermint signatures.
+pub fn macro_proposal_signing_data(
+    header: &MacroHeader,
+    round: u32,
+    valid_round: Option<u32>,
+) -> Vec<u8> {
+    let mut h = Blake2sHasher::new();
+
+    h.write_u8(TendermintStep::Propose as u8)
+        .expect("Must be able to write Prefix to hasher");
+    header
+        .serialize_content::<_, Blake2sHash>(&mut h)
+        .expect("Must be able to serialize content of the proposal to hasher");
+    round
+        .serialize_to_writer(&mut h)
+        .expect("Must be able to serialize content of the round to hasher ");
+    valid_round
+        .serialize_to_writer(&mut h)
+        .expect("Must be able to serialize content of the valid_round to hasher ");
+
+    let mut v = vec![];
+    h.finish()
+        .serialize_to_writer(&mut v)
+        .expect("Must be able to serialize the hash.");
+
+    v
+}
+
+/// Holds the signing, voting and fee keys of a validator, either in this process or somewhere
+/// else.
+///
+/// Signing may take a while when the keys are held by another process, and it may fail if that
+/// process is unavailable or refuses to sign. It must thus never be awaited while holding a lock.
+#[async_trait]
+pub trait ValidatorSigner: Send + Sync {
+    fn signing_public_key(&self) -> SchnorrPublicKey;
+
+    fn voting_public_key(&self) -> BlsPublicKey;
+
+    fn fee_public_key(&self) -> SchnorrPublicKey;
+
+    async fn sign(&self, request: SigningRequest) -> Result<SigningResponse, SignerError>;
+
+    /// Returns the signer holding the keys in this process, if any.
+    fn as_local(&self) -> Option<&LocalSigner> {
+        None
+    }
+
+    async fn sign_vrf_seed(&self, prev_seed: &VrfSeed) -> Result<VrfSeed, SignerError> {
+        match self
+            .sign(SigningRequest::VrfSeed {
+                prev_seed: prev_seed.clone(),
+            })
+            .await?
+        {
+            SigningResponse::VrfSeed { seed } => Ok(seed),
+            _ => Err(SignerError::UnexpectedResponse),
+        }
+    }
+
+    async fn sign_micro_header(
+        &self,
+        header: &MicroHeader,
+        prev_seed: &VrfSeed,
+    ) -> Result<SchnorrSignature, SignerError> {
+        schnorr_signature(
+            self.sign(SigningRequest::MicroBlock {
+                header: header.clone(),
+                prev_seed: prev_seed.clone(),
+            })
+            .await?,
+        )
+    }
+
+    async fn sign_macro_proposal(
+        &self,
+        header: &MacroHeader,
+        round: u32,
+        valid_round: Option<u32>,
+    ) -> Result<SchnorrSignature, SignerError> {
+        schnorr_signature(
+            self.sign(SigningRequest::MacroProposal {
+                header: header.clone(),
+                round,
+                valid_round,
+            })
+            .await?,
+        )
+    }
+
+    async fn sign_skip_block(&self, info: &SkipBlockInfo) -> Result<BlsSignature, SignerError> {
+        bls_signature(
+            self.sign(SigningRequest::SkipBlock { info: info.clone() })
+                .await?,
+        )
+    }
+
+    async fn sign_tendermint_vote(
+        &self,
+        vote: &TendermintVote,
+    ) -> Result<BlsSignature, SignerError> {
+        bls_signature(
+            self.sign(SigningRequest::TendermintVote {
+                block_number: vote.id.block_number,
+                round: vote.id.round_number,
+                step: vote.id.step,
+                proposal_hash: vote.proposal_hash.clone(),
+            })
+            .await?,
+        )
+    }
+
+    async fn sign_validator_record(&self, peer_id: Vec<u8>) -> Result<BlsSignature, SignerError> {
+        bls_signature(
+            self.sign(SigningRequest::ValidatorRecord { peer_id })
+                .await?,
+        )
+    }
+
+    async fn sign_reactivate_transaction(
+        &self,
+        validator_address: &Address,
+        validity_start_height: u32,
+        network_id: NetworkId,
+    ) -> Result<Transaction, SignerError> {
+        match self
+            .sign(SigningRequest::ReactivateValidator {
+                validator_address: validator_address.clone(),
+                validity_start_height,
+                network_id,
+            })
+            .await?
+        {
+            SigningResponse::Transaction { transaction } => Ok(transaction),
+            _ => Err(SignerError::UnexpectedResponse),
+        }
+    }
+}
+
+fn schnorr_signature(response: SigningResponse) -> Result<SchnorrSignature, SignerError> {
+    match response {
+        SigningResponse::Schnorr { signature } => Ok(signature),
+        _ => Err(SignerError::UnexpectedResponse),
+    }
+}
+
+fn bls_signature(response: SigningResponse) -> Result<BlsSignature, SignerError> {
+    match response {
+        SigningResponse::Bls { signature } => Ok(signature),
+        _ => Err(SignerError::UnexpectedResponse),
+    }
+}
+
+/// Signs with keys loaded into this process.
+pub struct LocalSigner {
+    pub signing_key: SchnorrKeyPair,
+    pub voting_key: BlsKeyPair,
+    pub fee_key: SchnorrKeyPair,
+}
+
+impl LocalSigner {
+    pub fn new(
+        signing_key: SchnorrKeyPair,
+        voting_key: BlsKeyPair,
+        fee_key: SchnorrKeyPair,
+    ) -> Self {
+        Self {
+            signing_key,
+            voting_key,
+            fee_key,
+        }
+    }
+}
+
+#[async_trait]
+impl ValidatorSigner for LocalSigner {
+    fn signing_public_key(&self) -> SchnorrPublicKey {
+        self.signing_key.public
+    }
+
+    fn voting_public_key(&self) -> BlsPublicKey {
+        self.voting_key.public_key
+    }
+
+    fn fee_public_key(&self) -> SchnorrPublicKey {
+        self.fee_key.public
+    }
+
+    async fn sign(&self, request: SigningRequest) -> Result<SigningResponse, SignerError> {
+        let response = match request {
+            SigningRequest::PublicKeys => SigningResponse::PublicKeys {
+                signing_key: self.signing_key.public,
+                voting_key: self.voting_key.public_key.compress(),
+                fee_key: self.fee_key.public,
+            },
+            SigningRequest::VrfSeed { prev_seed } => SigningResponse::VrfSeed {
+                seed: prev_seed.sign_next(&self.signing_key),
+            },
+            SigningRequest::MicroBlock { header, .. } => SigningResponse::Schnorr {
+                signature: self
+                    .signing_key
+                    .sign(header.hash::<Blake2bHash>().as_slice()),
+            },
+            SigningRequest::MacroProposal {
+                header,
+                round,
+                valid_round,
+            } => SigningResponse::Schnorr {
+                signature: self.signing_key.sign(&macro_proposal_signing_data(
+                    &header,
+                    round,
+                    valid_round,
+                )),
+            },
+            SigningRequest::SkipBlock { info } => SigningResponse::Bls {
+                signature: info.sign(&self.voting_key.secret_key),
+            },
+            SigningRequest::TendermintVote {
+                block_number,
+                round,
+                step,
+                proposal_hash,
+            } => {
+                let vote = TendermintVote {
+                    proposal_hash,
+                    id: TendermintIdentifier {
+                        block_number,
+                        round_number: round,
+                        step,
+                    },
+                };
+                SigningResponse::Bls {
+                    signature: self.voting_key.secret_key.sign(&vote),
+                }
+            }
+            // The record only consists of the peer id, so its serialization is the one of the
+            // peer id.
+            SigningRequest::ValidatorRecord { peer_id } => SigningResponse::Bls {
+                signature: self.voting_key.secret_key.sign(&peer_id),
+            },
+            SigningRequest::ReactivateValidator {
+                validator_address,
+                validity_start_height,
+                network_id,
+            } => SigningResponse::Transaction {
+                transaction: TransactionBuilder::new_reactivate_validator(
+                    &self.fee_key,
+                    validator_address,
+                    &self.signing_key,
+                    Coin::ZERO,
+                    validity_start_height,
+                    network_id,
+                )
+                .map_err(|error| SignerError::Refused(error.to_string()))?,
+            },
+        };
+        Ok(response)
+    }
+
+    fn as_local(&self) -> Option<&LocalSigner> {
+        Some(self)
+    }
+}
+
+/// Signs with another signer after checking every request against a signing history. Requests
+/// that could get the validator slashed are refused, as are transactions for other validators
+/// and validator records that can't hold a peer id.
+pub struct ProtectedSigner<S> {
+    signer: S,
+    slashing_protection: SlashingProtection,
+    validator_address: Address,
+}
+
+impl<S: ValidatorSigner> ProtectedSigner<S> {
+    /// The largest serialized peer id signed in a validator record.
+    const MAX_PEER_ID_SIZE: usize = 64;
+
+    pub fn new(
+        signer: S,
+        slashing_protection: SlashingProtection,
+        validator_address: Address,
+    ) -> Self {
+        Self {
+            signer,
+            slashing_protection,
+            validator_address,
+        }
+    }
+
+    pub fn slashing_protection(&self) -> &SlashingProtection {
+        &self.slashing_protection
+    }
+}
+
+#[async_trait]
+impl<S: ValidatorSigner> ValidatorSigner for ProtectedSigner<S> {
+    fn signing_public_key(&self) -> SchnorrPublicKey {
+        self.signer.signing_public_key()
+    }
+
+    fn voting_public_key(&self) -> BlsPublicKey {
+        self.signer.voting_public_key()
+    }
+
+    fn fee_public_key(&self) -> SchnorrPublicKey {
+        self.signer.fee_public_key()
+    }
+
+    async fn sign(&self, request: SigningRequest) -> Result<SigningResponse, SignerError> {
+        match &request {
+            // Make sure the VRF entropy we record is the one the block is actually built on.
+            SigningRequest::MicroBlock { header, prev_seed } => {
+                header
+                    .seed
+                    .verify(prev_seed, &self.signer.signing_public_key())
+                    .map_err(|_| {
+                        SignerError::Refused("VRF seed doesn't follow the previous seed".into())
+                    })?;
+            }
+            SigningRequest::ValidatorRecord { peer_id }
+                if peer_id.len() > Self::MAX_PEER_ID_SIZE =>
+            {
+                return Err(SignerError::Refused(
+                    "Validator record is too large to hold a peer id".into(),
+                ));
+            }
+            SigningRequest::ReactivateValidator {
+                validator_address, ..
+            } if *validator_address != self.validator_address => {
+                return Err(SignerError::Refused(
+                    "Only our own validator is reactivated".into(),
+                ));
+            }
+            _ => {}
+        }
+
+        if let Some(message) = request.signed_message() {
+            self.slashing_protection
+                .try_sign(message)
+                .map_err(|signed| {
+                    SignerError::Refused(format!("Conflicts with signed message {signed:?}"))
+                })?;
+        }
+
+        self.signer.sign(request).await
+    }
+}
@@ -0,0 +1,206 @@
+use std::{fmt, path::PathBuf, str::FromStr, time::Duration};
+
+use async_trait::async_trait;
+use nimiq_bls::PublicKey as BlsPublicKey;
+use nimiq_keys::PublicKey as SchnorrPublicKey;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+    time,
+};
+
+use super::{SignerError, SigningRequest, SigningResponse, ValidatorSigner};
+
+/// The response sent by a remote signer: either the signature or the reason it refused to sign.
+pub type SignerResult = Result<SigningResponse, String>;
+
+/// Where a remote signer can be reached.
+///
+/// On a local socket, every request is a single line of JSON answered with a single line of JSON.
+/// Over HTTP, every request is the JSON body of a `POST /sign` authorized with the bearer token,
+/// answered with a JSON body.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RemoteSignerEndpoint {
+    /// A Unix domain socket, given as `unix:<path>`.
+    Socket(PathBuf),
+    /// An HTTP server, given as `http://<token>@<host>:<port>`.
+    Http { address: String, token: String },
+}
+
+impl FromStr for RemoteSignerEndpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = s.strip_prefix("unix:") {
+            Ok(RemoteSignerEndpoint::Socket(PathBuf::from(path)))
+        } else if let Some((token, address)) = s
+            .strip_prefix("http://")
+            .and_then(|rest| rest.split_once('@'))
+            .filter(|(token, _)| !token.is_empty())
+        {
+            Ok(RemoteSignerEndpoint::Http {
+                address: address.trim_end_matches('/').to_string(),
+                token: token.to_string(),
+            })
+        } else {
+            Err(format!(
+                "Invalid remote signer endpoint {s}, expected unix:<path> or http://<token>@<host>:<port>"
+            ))
+        }
+    }
+}
+
+impl fmt::Display for RemoteSignerEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RemoteSignerEndpoint::Socket(path) => write!(f, "unix:{}", path.display()),
+            // Never print the token.
+            RemoteSignerEndpoint::Http { address, .. } => write!(f, "http://{address}"),
+        }
+    }
+}
+
+/// Signs by sending the requests to a signer running in another process.
+pub struct RemoteSigner {
+    endpoint: RemoteSignerEndpoint,
+    signing_public_key: SchnorrPublicKey,
+    voting_public_key: BlsPublicKey,
+    fee_public_key: SchnorrPublicKey,
+}
+
+impl RemoteSigner {
+    /// Time after which an unanswered request fails.
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// The largest response accepted from the signer.
+    const MAX_RESPONSE_SIZE: u64 = 64 * 1024;
+
+    /// Connects to the remote signer and fetches the public keys of the validator.
+    pub async fn connect(endpoint: RemoteSignerEndpoint) -> Result<Self, SignerError> {
+        match Self::request(&endpoint, &SigningRequest::PublicKeys).await? {
+            SigningResponse::PublicKeys {
+                signing_key,
+                voting_key,
+                fee_key,
+            } => {
+                let voting_public_key = voting_key
+                    .uncompress()
+                    .map_err(|_| SignerError::UnexpectedResponse)?;
+                Ok(Self {
+                    endpoint,
+                    signing_public_key: signing_key,
+                    voting_public_key,
+                    fee_public_key: fee_key,
+                })
+            }
+            _ => Err(SignerError::UnexpectedResponse),
+        }
+    }
+
+    async fn request(
+        endpoint: &RemoteSignerEndpoint,
+        request: &SigningRequest,
+    ) -> Result<SigningResponse, SignerError> {
+        let body = serde_json::to_vec(request)?;
+        let response = time::timeout(Self::REQUEST_TIMEOUT, async {
+            match endpoint {
+                RemoteSignerEndpoint::Socket(path) => Self::socket_request(path, &body).await,
+                RemoteSignerEndpoint::Http { address, token } => {
+                    Self::http_request(address, token, &body).await
+                }
+            }
+        })
+        .await
+        .map_err(|_| SignerError::Timeout)??;
+
+        serde_json::from_slice::<SignerResult>(&response)?.map_err(SignerError::Refused)
+    }
+
+    #[cfg(unix)]
+    async fn socket_request(path: &std::path::Path, body: &[u8]) -> Result<Vec<u8>, SignerError> {
+        let mut stream = tokio::net::UnixStream::connect(path).await?;
+
+        stream.write_all(body).await?;
+        stream.write_all(b"\n").await?;
+
+        let mut response = Vec::new();
+        BufReader::new(stream.take(Self::MAX_RESPONSE_SIZE))
+            .read_until(b'\n', &mut response)
+            .await?;
+        Ok(response)
+    }
+
+    #[cfg(not(unix))]
+    async fn socket_request(_path: &std::path::Path, _body: &[u8]) -> Result<Vec<u8>, SignerError> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "Local sockets are only supported on Unix",
+        )
+        .into())
+    }
+
+    async fn http_request(address: &str, token: &str, body: &[u8]) -> Result<Vec<u8>, SignerError> {
+        let mut stream = TcpStream::connect(address).await?;
+
+        let header = format!(
+            "POST /sign HTTP/1.1\r\nHost: {address}\r\nAuthorization: Bearer {token}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        );
+        stream.write_all(header.as_bytes()).await?;
+        stream.write_all(body).await?;
+
+        let mut response = Vec::new();
+        stream
+            .take(Self::MAX_RESPONSE_SIZE)
+            .read_to_end(&mut response)
+            .await?;
+
+        let header_end = response
+            .windows(4)
+            .position(|window| window == b"\r\n\r\n")
+            .ok_or(SignerError::UnexpectedResponse)?;
+        let status = String::from_utf8_lossy(&response[..header_end])
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .map(str::to_string)
+            .ok_or(SignerError::UnexpectedResponse)?;
+        if status != "200" {
+            return Err(SignerError::Http(status));
+        }
+
+        Ok(response.split_off(header_end + 4))
+    }
+}
+
+#[async_trait]
+impl ValidatorSigner for RemoteSigner {
+    fn signing_public_key(&self) -> SchnorrPublicKey {
+        self.signing_public_key
+    }
+
+    fn voting_public_key(&self) -> BlsPublicKey {
+        self.voting_public_key
+    }
+
+    fn fee_public_key(&self) -> SchnorrPublicKey {
+        self.fee_public_key
+    }
+
+    async fn sign(&self, request: SigningRequest) -> Result<SigningResponse, SignerError> {
+        Self::request(&self.endpoint, &request).await
+    }
+}
+
+/// Handles a single JSON encoded request on the signer side and returns the JSON encoded
+/// response. Used by signers serving other processes.
+pub async fn handle_request(signer: &dyn ValidatorSigner, request: &[u8]) -> Vec<u8> {
+    let result: SignerResult = match serde_json::from_slice::<SigningRequest>(request) {
+        Ok(request) => signer.sign(request).await.map_err(|error| match error {
+            SignerError::Refused(reason) => reason,
+            error => error.to_string(),
+        }),
+        Err(error) => Err(format!("Malformed request: {error}")),
+    };
+    serde_json::to_vec(&result).expect("Signer responses can always be serialized")
+}
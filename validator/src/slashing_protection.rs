@@ -0,0 +1,221 @@
+use std::io;
+
+use nimiq_block::TendermintStep;
+use nimiq_database::{
+    traits::{Database, ReadCursor, ReadTransaction, WriteTransaction},
+    DatabaseProxy, TableProxy,
+};
+use nimiq_database_value::{FromDatabaseValue, IntoDatabaseValue};
+use nimiq_hash::{Blake2bHash, Blake2sHash};
+use nimiq_primitives::policy::Policy;
+use nimiq_serde::{Deserialize, Serialize};
+use nimiq_vrf::VrfEntropy;
+
+/// A message signed by our validator that could be used to slash it if we ever signed a
+/// conflicting one.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignedMessage {
+    /// A micro block header, produced on top of a block with the given VRF entropy.
+    MicroBlock {
+        block_number: u32,
+        vrf_entropy: VrfEntropy,
+        header_hash: Blake2bHash,
+    },
+    /// A contribution to the skip block for the given block number and VRF entropy.
+    SkipBlock {
+        block_number: u32,
+        vrf_entropy: VrfEntropy,
+    },
+    /// A tendermint prevote or precommit.
+    TendermintVote {
+        block_number: u32,
+        round: u32,
+        step: TendermintStep,
+        proposal_hash: Option<Blake2sHash>,
+    },
+}
+
+impl SignedMessage {
+    const PREFIX_BLOCK: u8 = 0;
+    const PREFIX_TENDERMINT_VOTE: u8 = 1;
+
+    /// The block number this message was signed for.
+    pub fn block_number(&self) -> u32 {
+        match self {
+            SignedMessage::MicroBlock { block_number, .. }
+            | SignedMessage::SkipBlock { block_number, .. }
+            | SignedMessage::TendermintVote { block_number, .. } => *block_number,
+        }
+    }
+
+    /// Messages with the same key conflict with each other unless they are equal. A micro block
+    /// and a skip block for the same slot share the same key, so we never contribute to a skip
+    /// block for a micro block we produced and vice versa.
+    fn key(&self) -> Vec<u8> {
+        let mut key = Vec::with_capacity(1 + 4 + VrfEntropy::SIZE);
+        match self {
+            SignedMessage::MicroBlock {
+                block_number,
+                vrf_entropy,
+                ..
+            }
+            | SignedMessage::SkipBlock {
+                block_number,
+                vrf_entropy,
+            } => {
+                key.push(Self::PREFIX_BLOCK);
+                key.extend_from_slice(&block_number.to_be_bytes());
+                key.extend_from_slice(&vrf_entropy.0);
+            }
+            SignedMessage::TendermintVote {
+                block_number,
+                round,
+                step,
+                ..
+            } => {
+                key.push(Self::PREFIX_TENDERMINT_VOTE);
+                key.extend_from_slice(&block_number.to_be_bytes());
+                key.extend_from_slice(&round.to_be_bytes());
+                key.push(*step as u8);
+            }
+        }
+        key
+    }
+}
+
+impl IntoDatabaseValue for SignedMessage {
+    fn database_byte_size(&self) -> usize {
+        self.serialized_size()
+    }
+
+    fn copy_into_database(&self, mut bytes: &mut [u8]) {
+        Serialize::serialize_to_writer(&self, &mut bytes).unwrap();
+    }
+}
+
+impl FromDatabaseValue for SignedMessage {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        Deserialize::deserialize_from_vec(bytes)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+}
+
+/// The format used to move the signing history of a validator to another machine.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SlashingProtectionExport {
+    pub version: u8,
+    pub messages: Vec<SignedMessage>,
+}
+
+impl SlashingProtectionExport {
+    pub const VERSION: u8 = 1;
+}
+
+/// Persistent history of the messages signed by our validator.
+///
+/// Before signing a micro block, a skip block contribution or a tendermint vote, the validator
+/// records it here. A message that conflicts with one signed earlier, e.g. before a crash or a
+/// database restore, is refused, since it would create a fork proof or an equivocating vote.
+#[derive(Debug)]
+pub struct SlashingProtection {
+    env: DatabaseProxy,
+    signed_messages_db: TableProxy,
+}
+
+impl SlashingProtection {
+    const SIGNED_MESSAGES_DB_NAME: &'static str = "ValidatorSignedMessages";
+
+    pub fn new(env: DatabaseProxy) -> Self {
+        let signed_messages_db = env.open_table(Self::SIGNED_MESSAGES_DB_NAME.to_string());
+
+        Self {
+            env,
+            signed_messages_db,
+        }
+    }
+
+    /// Records the given message as signed, unless it conflicts with a message signed before.
+    /// In that case the message must not be signed and the conflicting message is returned.
+    pub fn try_sign(&self, message: SignedMessage) -> Result<(), SignedMessage> {
+        let key = message.key();
+
+        let mut txn = self.env.write_transaction();
+        if let Some(signed) = txn.get::<_, SignedMessage>(&self.signed_messages_db, &key) {
+            return if signed == message {
+                Ok(())
+            } else {
+                Err(signed)
+            };
+        }
+
+        txn.put_reserve(&self.signed_messages_db, &key, &message);
+        txn.commit();
+        Ok(())
+    }
+
+    /// Removes all messages signed for blocks that can't be used to slash us anymore once the
+    /// macro block with the given block number has been applied. Fork proofs are valid during
+    /// the batch of the fork and the batch after it.
+    pub fn prune(&self, macro_block_number: u32) {
+        let first_block = macro_block_number.saturating_sub(Policy::blocks_per_batch()) + 1;
+
+        let txn = self.env.write_transaction();
+        {
+            // Keys start with the message type followed by the big endian block number, so the
+            // expired messages of each type are the first entries with that prefix.
+            let mut cursor = WriteTransaction::cursor(&txn, &self.signed_messages_db);
+            for prefix in [
+                SignedMessage::PREFIX_BLOCK,
+                SignedMessage::PREFIX_TENDERMINT_VOTE,
+            ] {
+                let mut entry = cursor.seek_range_key::<Vec<u8>, SignedMessage>(&vec![prefix]);
+                while let Some((key, message)) = entry {
+                    if key[0] != prefix || message.block_number() >= first_block {
+                        break;
+                    }
+                    cursor.remove();
+                    entry = cursor.next();
+                }
+            }
+        }
+        txn.commit();
+    }
+
+    /// Returns the complete signing history.
+    pub fn export(&self) -> SlashingProtectionExport {
+        let txn = self.env.read_transaction();
+        let messages = txn
+            .cursor(&self.signed_messages_db)
+            .into_iter_start::<Vec<u8>, SignedMessage>()
+            .map(|(_, message)| message)
+            .collect();
+
+        SlashingProtectionExport {
+            version: SlashingProtectionExport::VERSION,
+            messages,
+        }
+    }
+
+    /// Adds the messages of an exported signing history. Messages for which we already have a
+    /// record are skipped, as we may not sign anything conflicting with either of them.
+    /// Returns the number of imported messages.
+    pub fn import(&self, data: SlashingProtectionExport) -> usize {
+        let mut txn = self.env.write_transaction();
+        let mut num_imported = 0;
+        for message in data.messages {
+            let key = message.key();
+            if txn
+                .get::<_, SignedMessage>(&self.signed_messages_db, &key)
+                .is_none()
+            {
+                txn.put_reserve(&self.signed_messages_db, &key, &message);
+                num_imported += 1;
+            }
+        }
+        txn.commit();
+        num_imported
+    }
+}
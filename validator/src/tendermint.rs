@@ -1,6 +1,5 @@
-use std::sync::Arc;
+use std::{future::Future, io, sync::Arc};
 
-use byteorder::WriteBytesExt;
 use futures::{
     future::{self, FutureExt},
     stream::{BoxStream, StreamExt},
@@ -14,10 +13,9 @@ use nimiq_blockchain::Blockchain;
 use nimiq_blockchain_interface::AbstractBlockchain;
 use nimiq_collections::BitSet;
 use nimiq_handel::{aggregation::Aggregation, identity::IdentityRegistry};
-use nimiq_hash::{Blake2sHash, Blake2sHasher, Hash, Hasher, SerializeContent};
+use nimiq_hash::{Blake2sHash, Hash};
 use nimiq_keys::Signature as SchnorrSignature;
 use nimiq_primitives::{policy::Policy, slots::Validators};
-use nimiq_serde::Serialize;
 use nimiq_tendermint::{
     Proposal, ProposalError, ProposalMessage, Protocol, SignedProposalMessage, Step,
     TaggedAggregationMessage,
@@ -25,7 +23,9 @@ use nimiq_tendermint::{
 use nimiq_validator_network::{
     single_response_requester::SingleResponseRequester, ValidatorNetwork,
 };
+use nimiq_vrf::VrfSeed;
 use parking_lot::RwLock;
+use tokio::runtime::{Handle, RuntimeFlavor};
 
 use crate::{
     aggregation::{
@@ -38,6 +38,8 @@ use crate::{
         },
     },
     r#macro::ProposalTopic,
+    signer::{macro_proposal_signing_data, SignerError, ValidatorSigner},
+    slashing_protection::{SignedMessage, SlashingProtection},
 };
 
 // A note for the signing of the proposal:
@@ -47,6 +49,24 @@ use crate::{
 // The other one is a BLS signature over the zkp_hash, which is defined as Blake2S(Blake2b(header_hash).append(pk_tree_root))
 //     This one is being aggregated, and contains the pk_tree_root, so the body is necessary for the verification of the signature.
 
+/// Tendermint needs the proposal signature right away. Signing is immediate for keys held by this
+/// process, while a remote signer answers or fails within its request timeout, so we block the
+/// current worker thread on it. That isn't possible on a single threaded runtime, where only
+/// signatures that are available immediately can be used.
+fn sign_blocking<T, F>(future: F) -> Result<T, SignerError>
+where
+    F: Future<Output = Result<T, SignerError>>,
+{
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(|| handle.block_on(future))
+        }
+        _ => future
+            .now_or_never()
+            .unwrap_or_else(|| Err(io::Error::from(io::ErrorKind::WouldBlock).into())),
+    }
+}
+
 struct NetworkWrapper<TValidatorNetwork: ValidatorNetwork> {
     network: Arc<TValidatorNetwork>,
     tag: (u32, Step),
@@ -101,14 +121,18 @@ pub struct TendermintProtocol<TValidatorNetwork: ValidatorNetwork> {
     pub validator_slot_band: u16,
     // The block number of the macro block to produce.
     pub block_height: u32,
-    // Information relative to our validator that is necessary to produce blocks.
-    pub block_producer: BlockProducer,
+    // The keys of our validator, used to sign proposals and votes.
+    pub signer: Arc<dyn ValidatorSigner>,
     // The validators for the current epoch.
     pub current_validators: Validators,
     // The main blockchain struct. Contains all of this validator information about the current chain.
     pub blockchain: Arc<RwLock<Blockchain>>,
     // Validator registry on the heap for easy cloning into handel protocol.
     validator_registry: Arc<ValidatorRegistry>,
+    // The signing history of our validator, used to never cast equivocating votes.
+    slashing_protection: Arc<SlashingProtection>,
+    // The VRF seed of our proposals, signed before tendermint starts.
+    proposal_seed: VrfSeed,
 }
 
 impl<TValidatorNetwork: ValidatorNetwork> Clone for TendermintProtocol<TValidatorNetwork> {
@@ -117,10 +141,12 @@ impl<TValidatorNetwork: ValidatorNetwork> Clone for TendermintProtocol<TValidato
             network: Arc::clone(&self.network),
             validator_slot_band: self.validator_slot_band,
             block_height: self.block_height,
-            block_producer: self.block_producer.clone(),
+            signer: Arc::clone(&self.signer),
             current_validators: self.current_validators.clone(),
             blockchain: Arc::clone(&self.blockchain),
             validator_registry: Arc::clone(&self.validator_registry),
+            slashing_protection: Arc::clone(&self.slashing_protection),
+            proposal_seed: self.proposal_seed.clone(),
         }
     }
 }
@@ -129,24 +155,26 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> TendermintProtocol<TValidato
 where
     <TValidatorNetwork as ValidatorNetwork>::PubsubId: std::fmt::Debug + Unpin,
 {
-    const PROPOSAL_PREFIX: u8 = TendermintStep::Propose as u8;
-
     pub fn new(
         blockchain: Arc<RwLock<Blockchain>>,
         network: Arc<TValidatorNetwork>,
-        block_producer: BlockProducer,
+        signer: Arc<dyn ValidatorSigner>,
         current_validators: Validators,
         validator_slot_band: u16,
         block_height: u32,
+        slashing_protection: Arc<SlashingProtection>,
+        proposal_seed: VrfSeed,
     ) -> Self {
         Self {
-            block_producer,
+            signer,
             blockchain,
             block_height,
             validator_slot_band,
             validator_registry: Arc::new(ValidatorRegistry::new(current_validators.clone())),
             current_validators,
             network,
+            slashing_protection,
+            proposal_seed,
         }
     }
 
@@ -155,30 +183,11 @@ where
     /// This hash is NOT suited to be signed for BLS Aggregated signatures for the macro blocks, as those need to include the pk_tree_root.
     /// See MacroBlock::zkp_hash for more details.
     fn hash_proposal(proposal_msg: &ProposalMessage<<Self as Protocol>::Proposal>) -> Vec<u8> {
-        let mut h = Blake2sHasher::new();
-
-        h.write_u8(Self::PROPOSAL_PREFIX)
-            .expect("Must be able to write Prefix to hasher");
-        proposal_msg
-            .proposal
-            .0
-            .serialize_content::<_, Blake2sHash>(&mut h)
-            .expect("Must be able to serialize content of the proposal to hasher");
-        proposal_msg
-            .round
-            .serialize_to_writer(&mut h)
-            .expect("Must be able to serialize content of the round to hasher ");
-        proposal_msg
-            .valid_round
-            .serialize_to_writer(&mut h)
-            .expect("Must be able to serialize content of the valid_round to hasher ");
-
-        let mut v = vec![];
-        h.finish()
-            .serialize_to_writer(&mut v)
-            .expect("Must be able to serialize the hash.");
-
-        v
+        macro_proposal_signing_data(
+            &proposal_msg.proposal.0,
+            proposal_msg.round,
+            proposal_msg.valid_round,
+        )
     }
 }
 
@@ -226,9 +235,13 @@ where
         let blockchain = self.blockchain.read();
         let time = blockchain.time.now();
 
-        let block = self
-            .block_producer
-            .next_macro_block_proposal(&blockchain, time, round, vec![]);
+        let block = BlockProducer::next_macro_block_proposal_with_seed(
+            &blockchain,
+            time,
+            round,
+            self.proposal_seed.clone(),
+            vec![],
+        );
 
         // Always `Some(…)` because the above function always sets it to `Some(…)`.
         let body = block.body.expect("produced blocks always have a body");
@@ -464,11 +477,17 @@ where
         &self,
         proposal_message: &ProposalMessage<Self::Proposal>,
     ) -> Self::ProposalSignature {
-        let data = Self::hash_proposal(proposal_message);
-        (
-            self.block_producer.signing_key.sign(data.as_slice()),
-            self.validator_slot_band,
-        )
+        let signature = sign_blocking(self.signer.sign_macro_proposal(
+            &proposal_message.proposal.0,
+            proposal_message.round,
+            proposal_message.valid_round,
+        ))
+        .unwrap_or_else(|error| {
+            // An invalid signature gets the proposal rejected, just like not proposing.
+            log::error!(%error, "Failed to sign the macro block proposal");
+            SchnorrSignature::default()
+        });
+        (signature, self.validator_slot_band)
     }
 
     fn create_aggregation(
@@ -494,31 +513,69 @@ where
             step,
         };
 
+        // If we already voted in this round and step, e.g. before a restart, we must cast the
+        // very same vote again. Anything else would be an equivocation.
+        let proposal_hash = match self
+            .slashing_protection
+            .try_sign(SignedMessage::TendermintVote {
+                block_number: self.block_height,
+                round,
+                step,
+                proposal_hash: proposal_hash.clone(),
+            }) {
+            Ok(()) => proposal_hash,
+            Err(SignedMessage::TendermintVote {
+                proposal_hash: signed_hash,
+                ..
+            }) => {
+                log::warn!(
+                    block_number = self.block_height,
+                    round,
+                    ?step,
+                    "Repeating previous tendermint vote instead of casting a conflicting one"
+                );
+                signed_hash
+            }
+            Err(signed) => unreachable!("Tendermint votes only conflict with votes: {:?}", signed),
+        };
+
         let tendermint_vote = TendermintVote {
             proposal_hash,
             id: id.clone(),
         };
 
-        let own_contribution = TendermintContribution::from_vote(
-            tendermint_vote,
-            &self.block_producer.voting_key.secret_key,
-            self.validator_registry.get_slots(self.validator_slot_band),
-        );
-
+        let slots = self.validator_registry.get_slots(self.validator_slot_band);
         let protocol = TendermintAggregationProtocol::new(
             Arc::clone(&self.validator_registry),
             self.validator_slot_band as usize,
             1, // to be removed
-            id,
+            id.clone(),
         );
 
-        Aggregation::new(
-            protocol,
-            nimiq_handel::config::Config::default(),
-            own_contribution,
-            update_stream.map(|item| item.0).boxed(),
-            network,
-        )
+        // The aggregation starts once the vote is signed, which may take a while for remote signers.
+        let signer = Arc::clone(&self.signer);
+        futures::stream::once(async move {
+            let signature = match signer.sign_tendermint_vote(&tendermint_vote).await {
+                Ok(signature) => signature,
+                Err(error) => {
+                    log::error!(%error, ?id, "Failed to sign tendermint vote");
+                    return futures::stream::empty().boxed();
+                }
+            };
+
+            let own_contribution =
+                TendermintContribution::from_vote(tendermint_vote, signature, slots);
+
+            Aggregation::new(
+                protocol,
+                nimiq_handel::config::Config::default(),
+                own_contribution,
+                update_stream.map(|item| item.0).boxed(),
+                network,
+            )
+            .boxed()
+        })
+        .flatten()
         .boxed()
     }
 
@@ -10,29 +10,30 @@ use std::{
     time::Duration,
 };
 
-use futures::stream::{BoxStream, Stream, StreamExt};
+use futures::{
+    future::{BoxFuture, FutureExt},
+    stream::{BoxStream, Stream, StreamExt},
+};
 use linked_hash_map::LinkedHashMap;
 use nimiq_block::{Block, BlockHeaderTopic, BlockTopic, BlockType};
-use nimiq_block_production::BlockProducer;
 use nimiq_blockchain::Blockchain;
 use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainEvent, ForkEvent, PushResult};
-use nimiq_bls::{lazy::LazyPublicKey, KeyPair as BlsKeyPair};
+use nimiq_bls::lazy::LazyPublicKey;
 use nimiq_consensus::{Consensus, ConsensusEvent, ConsensusProxy};
 use nimiq_database::{
     traits::{Database, ReadTransaction, WriteTransaction},
     DatabaseProxy, TableProxy,
 };
 use nimiq_hash::{Blake2bHash, Hash};
-use nimiq_keys::{Address, KeyPair as SchnorrKeyPair, Signature as SchnorrSignature};
+use nimiq_keys::{Address, Signature as SchnorrSignature};
 use nimiq_macros::store_waker;
 use nimiq_mempool::{config::MempoolConfig, mempool::Mempool};
 use nimiq_network_interface::{
     network::{MsgAcceptance, Network, PubsubId, Topic},
     request::request_handler,
 };
-use nimiq_primitives::{coin::Coin, policy::Policy};
+use nimiq_primitives::policy::Policy;
 use nimiq_tendermint::SignedProposalMessage;
-use nimiq_transaction_builder::TransactionBuilder;
 use nimiq_validator_network::ValidatorNetwork;
 use parking_lot::RwLock;
 #[cfg(feature = "metrics")]
@@ -44,9 +45,12 @@ use crate::{
         proposal::{Header, RequestProposal},
         state::MacroState,
     },
+    doppelganger::{DoppelgangerProtection, ValidatorSlots},
     micro::{ProduceMicroBlock, ProduceMicroBlockEvent},
     r#macro::{MappedReturn, ProduceMacroBlock, ProposalTopic},
+    signer::ValidatorSigner,
     slash::ForkProofPool,
+    slashing_protection::SlashingProtection,
 };
 
 #[derive(PartialEq)]
@@ -78,20 +82,18 @@ enum MempoolState {
 
 pub struct ValidatorProxy {
     pub validator_address: Arc<RwLock<Address>>,
-    pub signing_key: Arc<RwLock<SchnorrKeyPair>>,
-    pub voting_key: Arc<RwLock<BlsKeyPair>>,
-    pub fee_key: Arc<RwLock<SchnorrKeyPair>>,
+    pub signer: Arc<dyn ValidatorSigner>,
     pub automatic_reactivate: Arc<AtomicBool>,
+    pub slashing_protection: Arc<SlashingProtection>,
 }
 
 impl Clone for ValidatorProxy {
     fn clone(&self) -> Self {
         Self {
             validator_address: Arc::clone(&self.validator_address),
-            signing_key: Arc::clone(&self.signing_key),
-            voting_key: Arc::clone(&self.voting_key),
-            fee_key: Arc::clone(&self.fee_key),
+            signer: Arc::clone(&self.signer),
             automatic_reactivate: Arc::clone(&self.automatic_reactivate),
+            slashing_protection: Arc::clone(&self.slashing_protection),
         }
     }
 }
@@ -108,9 +110,7 @@ where
     env: DatabaseProxy,
 
     validator_address: Arc<RwLock<Address>>,
-    signing_key: Arc<RwLock<SchnorrKeyPair>>,
-    voting_key: Arc<RwLock<BlsKeyPair>>,
-    fee_key: Arc<RwLock<SchnorrKeyPair>>,
+    signer: Arc<dyn ValidatorSigner>,
 
     proposal_receiver: ProposalReceiver<TValidatorNetwork>,
 
@@ -121,7 +121,10 @@ where
     epoch_state: Option<ActiveEpochState>,
     blockchain_state: BlockchainState,
     validator_state: Option<InactivityState>,
+    reactivate_future: Option<BoxFuture<'static, Option<InactivityState>>>,
     automatic_reactivate: Arc<AtomicBool>,
+    doppelganger: DoppelgangerProtection,
+    slashing_protection: Arc<SlashingProtection>,
 
     macro_producer: Option<ProduceMacroBlock<TValidatorNetwork>>,
     macro_state: Arc<RwLock<Option<MacroState>>>,
@@ -153,9 +156,8 @@ where
         network: Arc<TValidatorNetwork>,
         validator_address: Address,
         automatic_reactivate: bool,
-        signing_key: SchnorrKeyPair,
-        voting_key: BlsKeyPair,
-        fee_key: SchnorrKeyPair,
+        doppelganger_protection_blocks: u32,
+        signer: Arc<dyn ValidatorSigner>,
         mempool_config: MempoolConfig,
     ) -> Self {
         let consensus_event_rx = consensus.subscribe_events();
@@ -182,10 +184,16 @@ where
         let network1 = Arc::clone(&network);
         let (proposal_sender, proposal_receiver) = ProposalBuffer::new();
 
-        let mempool = Arc::new(Mempool::new(Arc::clone(&blockchain), mempool_config));
+        let mempool = if mempool_config.journal {
+            Mempool::new_with_journal(Arc::clone(&blockchain), mempool_config, env.clone())
+        } else {
+            Mempool::new(Arc::clone(&blockchain), mempool_config)
+        };
+        let mempool = Arc::new(mempool);
         let mempool_state = MempoolState::Inactive;
 
         let automatic_reactivate = Arc::new(AtomicBool::new(automatic_reactivate));
+        let slashing_protection = Arc::new(SlashingProtection::new(env.clone()));
 
         let mut this = Self {
             consensus: consensus.proxy(),
@@ -196,9 +204,7 @@ where
             env,
 
             validator_address: Arc::new(RwLock::new(validator_address)),
-            signing_key: Arc::new(RwLock::new(signing_key)),
-            voting_key: Arc::new(RwLock::new(voting_key)),
-            fee_key: Arc::new(RwLock::new(fee_key)),
+            signer,
 
             proposal_receiver,
 
@@ -209,7 +215,10 @@ where
             epoch_state: None,
             blockchain_state,
             validator_state: None,
+            reactivate_future: None,
             automatic_reactivate,
+            doppelganger: DoppelgangerProtection::new(doppelganger_protection_blocks),
+            slashing_protection,
 
             macro_producer: None,
             macro_state: Arc::clone(&macro_state),
@@ -296,6 +305,7 @@ where
         let validators = blockchain.current_validators().unwrap();
 
         self.epoch_state = None;
+        let mut validator_slots = None;
         log::trace!(
             "This is our validator address: {}",
             self.validator_address()
@@ -311,23 +321,41 @@ where
                 self.epoch_state = Some(ActiveEpochState {
                     validator_slot_band: i as u16,
                 });
+                validator_slots = Some(ValidatorSlots {
+                    epoch: Policy::epoch_at(blockchain.block_number() + 1),
+                    slot_band: i as u16,
+                    slot_range: validator.slot_range,
+                });
                 break;
             }
         }
+        self.doppelganger.set_validator_slots(validator_slots);
 
         let voting_keys: Vec<LazyPublicKey> = validators
             .iter()
             .map(|validator| validator.voting_key.clone())
             .collect();
-        let key = self.voting_key();
+        let signer = Arc::clone(&self.signer);
         let network = Arc::clone(&self.network);
 
         // TODO might better be done without the task.
         // However we have an entire batch to execute the task so it should not be extremely bad.
         // Also the setting up of our own public key record should probably not be done here but in `init` instead.
         tokio::spawn(async move {
+            let record_signer = Arc::clone(&signer);
+            let sign_record = move |peer_id| {
+                let signer = Arc::clone(&record_signer);
+                async move {
+                    signer
+                        .sign_validator_record(peer_id)
+                        .await
+                        .map_err(|error| error!(%error, "could not sign DHT record"))
+                        .ok()
+                }
+                .boxed()
+            };
             if let Err(err) = network
-                .set_public_key(&key.public_key.compress(), &key.secret_key)
+                .set_public_key(&signer.voting_public_key().compress(), &sign_record)
                 .await
             {
                 error!("could not set up DHT record: {:?}", err);
@@ -337,7 +365,7 @@ where
     }
 
     fn init_block_producer(&mut self, event: Option<Blake2bHash>) {
-        if !self.is_active() {
+        if !self.is_active() || !self.doppelganger.may_sign() {
             return;
         }
 
@@ -354,7 +382,6 @@ where
 
         let head = blockchain.head();
         let next_block_number = head.block_number() + 1;
-        let block_producer = BlockProducer::new(self.signing_key(), self.voting_key());
 
         debug!(
             next_block_number = next_block_number,
@@ -374,12 +401,13 @@ where
                 self.macro_producer = Some(ProduceMacroBlock::new(
                     Arc::clone(&self.blockchain),
                     Arc::clone(&self.network),
-                    block_producer,
+                    Arc::clone(&self.signer),
                     self.validator_slot_band(),
                     active_validators,
                     next_block_number,
                     self.macro_state.read().clone(),
                     proposal_stream,
+                    Arc::clone(&self.slashing_protection),
                 ));
             }
             BlockType::Micro => {
@@ -395,13 +423,14 @@ where
                     Arc::clone(&self.blockchain),
                     Arc::clone(&self.mempool),
                     Arc::clone(&self.network),
-                    block_producer,
+                    Arc::clone(&self.signer),
                     self.validator_slot_band(),
                     fork_proofs,
                     prev_seed,
                     next_block_number,
                     Self::PRODUCER_TIMEOUT,
                     Self::BLOCK_SEPARATION_TIME,
+                    Arc::clone(&self.slashing_protection),
                 ));
             }
         }
@@ -417,6 +446,7 @@ where
                     // The mempool is not updated while consensus is lost.
                     // Thus, we need to check all transactions if they are still valid.
                     mempool.mempool_update_full();
+                    mempool.restore_journal().await;
                     mempool.start_executors(network, None, None).await;
                 }
             });
@@ -428,6 +458,7 @@ where
                     // The mempool is not updated while consensus is lost.
                     // Thus, we need to check all transactions if they are still valid.
                     mempool.mempool_update_full();
+                    mempool.restore_journal().await;
 
                     mempool
                         .start_executors(network, Some(mempool_monitor), Some(ctrl_mempool_monitor))
@@ -491,10 +522,26 @@ where
 
         // Update mempool and blockchain state
         self.blockchain_state.fork_proofs.apply_block(&block);
+        if self.doppelganger.is_observing() {
+            let validator_address = self.validator_address();
+            self.doppelganger
+                .on_block(&self.blockchain.read(), &block, &validator_address);
+        }
+        // Signatures for blocks before the previous batch can't be used to slash us anymore.
+        if block.is_macro() {
+            self.slashing_protection.prune(block.block_number());
+        }
         // Mempool updates are only done once we can be active.
         if self.can_be_active() {
+            let is_macro = block.is_macro();
             self.mempool
                 .mempool_update(&vec![(hash.clone(), block)], [].as_ref());
+
+            // Persist the mempool content once per batch.
+            if is_macro {
+                let mempool = Arc::clone(&self.mempool);
+                tokio::task::spawn_blocking(move || mempool.save_journal());
+            }
         }
     }
 
@@ -510,6 +557,14 @@ where
         for (_hash, block) in new_chain.iter() {
             self.blockchain_state.fork_proofs.apply_block(block);
         }
+        if self.doppelganger.is_observing() {
+            let blockchain = self.blockchain.read();
+            let validator_address = self.validator_address();
+            for (_hash, block) in new_chain.iter() {
+                self.doppelganger
+                    .on_block(&blockchain, block, &validator_address);
+            }
+        }
         // Mempool updates are only done once we can be active.
         if self.can_be_active() {
             self.mempool.mempool_update(new_chain, old_chain);
@@ -696,36 +751,42 @@ where
             )
     }
 
-    fn reactivate(&self, blockchain: &Blockchain) -> InactivityState {
+    /// Signs a reactivate transaction for our validator and sends it to the network. The
+    /// returned future resolves once the transaction was signed, which may take a while if the
+    /// keys are held by a remote signer.
+    fn reactivate(&self, blockchain: &Blockchain) -> BoxFuture<'static, Option<InactivityState>> {
         let validity_start_height = blockchain.block_number();
-
-        let reactivate_transaction = TransactionBuilder::new_reactivate_validator(
-            &self.fee_key(),
-            self.validator_address(),
-            &self.signing_key(),
-            Coin::ZERO,
-            validity_start_height,
-            blockchain.network_id(),
-        )
-        .unwrap(); // TODO: Handle transaction creation error
-        let tx_hash = reactivate_transaction.hash();
-
+        let network_id = blockchain.network_id();
+        let validator_address = self.validator_address();
+        let signer = Arc::clone(&self.signer);
         let cn = self.consensus.clone();
-        tokio::spawn(async move {
-            debug!("Sending reactivate transaction to the network");
-            if cn
-                .send_transaction(reactivate_transaction.clone())
+
+        async move {
+            let reactivate_transaction = match signer
+                .sign_reactivate_transaction(&validator_address, validity_start_height, network_id)
                 .await
-                .is_err()
             {
-                error!("Failed to send reactivate transaction");
-            }
-        });
+                Ok(transaction) => transaction,
+                Err(error) => {
+                    error!(%error, "Failed to sign reactivate transaction");
+                    return None;
+                }
+            };
+            let tx_hash = reactivate_transaction.hash();
 
-        InactivityState {
-            inactive_tx_hash: tx_hash,
-            inactive_tx_validity_window_start: validity_start_height,
+            tokio::spawn(async move {
+                debug!("Sending reactivate transaction to the network");
+                if cn.send_transaction(reactivate_transaction).await.is_err() {
+                    error!("Failed to send reactivate transaction");
+                }
+            });
+
+            Some(InactivityState {
+                inactive_tx_hash: tx_hash,
+                inactive_tx_validity_window_start: validity_start_height,
+            })
         }
+        .boxed()
     }
 
     pub fn validator_slot_band(&self) -> u16 {
@@ -739,25 +800,16 @@ where
         self.validator_address.read().clone()
     }
 
-    pub fn voting_key(&self) -> BlsKeyPair {
-        self.voting_key.read().clone()
-    }
-
-    pub fn signing_key(&self) -> SchnorrKeyPair {
-        self.signing_key.read().clone()
-    }
-
-    pub fn fee_key(&self) -> SchnorrKeyPair {
-        self.fee_key.read().clone()
+    pub fn signer(&self) -> Arc<dyn ValidatorSigner> {
+        Arc::clone(&self.signer)
     }
 
     pub fn proxy(&self) -> ValidatorProxy {
         ValidatorProxy {
             validator_address: Arc::clone(&self.validator_address),
-            signing_key: Arc::clone(&self.signing_key),
-            voting_key: Arc::clone(&self.voting_key),
-            fee_key: Arc::clone(&self.fee_key),
+            signer: Arc::clone(&self.signer),
             automatic_reactivate: Arc::clone(&self.automatic_reactivate),
+            slashing_protection: Arc::clone(&self.slashing_protection),
         }
     }
 }
@@ -792,6 +844,14 @@ where
             }
         }
 
+        // Start looking for other instances of this validator once we could become active.
+        if self.doppelganger.is_pending() && self.can_be_active() {
+            let head_block_number = self.blockchain.read().block_number();
+            let contributions = DoppelgangerProtection::receive_contributions(&*self.network);
+            self.doppelganger.start(head_block_number, contributions);
+        }
+        self.doppelganger.poll_contributions(cx);
+
         // Process blockchain updates.
         let mut received_event: Option<Blake2bHash> = None;
         while let Poll::Ready(Some(event)) = self.blockchain_event_rx.poll_next_unpin(cx) {
@@ -842,17 +902,26 @@ where
                 }
                 ValidatorStakingState::Inactive => {
                     if self.validator_state.is_none()
+                        && self.reactivate_future.is_none()
                         && self.automatic_reactivate.load(Ordering::Acquire)
                     {
-                        let inactivity_state = self.reactivate(&blockchain);
+                        let reactivate_future = self.reactivate(&blockchain);
                         drop(blockchain);
-                        self.validator_state = Some(inactivity_state);
+                        self.reactivate_future = Some(reactivate_future);
                     }
                 }
                 ValidatorStakingState::NoStake => {}
             }
         }
 
+        // The reactivate transaction is signed outside of the blockchain lock.
+        if let Some(reactivate_future) = self.reactivate_future.as_mut() {
+            if let Poll::Ready(inactivity_state) = reactivate_future.poll_unpin(cx) {
+                self.reactivate_future = None;
+                self.validator_state = inactivity_state;
+            }
+        }
+
         Poll::Pending
     }
 }
@@ -0,0 +1,155 @@
+use std::task::Context;
+
+use futures::{
+    stream::{self, StreamExt},
+    task::noop_waker_ref,
+};
+use nimiq_collections::BitSet;
+use nimiq_keys::Address;
+use nimiq_primitives::policy::Policy;
+use nimiq_test_log::test;
+use nimiq_test_utils::block_production::TemporaryBlockProducer;
+use nimiq_validator::doppelganger::{Contribution, DoppelgangerProtection, ValidatorSlots};
+
+fn validator_address() -> Address {
+    Address::from_user_friendly_address("NQ20 TSB0 DFSM UH9C 15GQ GAGJ TTE4 D3MA 859E").unwrap()
+}
+
+fn validator_slots() -> ValidatorSlots {
+    ValidatorSlots {
+        epoch: 1,
+        slot_band: 3,
+        slot_range: (10, 20),
+    }
+}
+
+fn contribution(block_number: u32, origin: usize, slots: &[usize]) -> Contribution {
+    let mut contributors = BitSet::new();
+    for slot in slots {
+        contributors.insert(*slot);
+    }
+    Contribution {
+        block_number,
+        origin,
+        contributors,
+    }
+}
+
+#[test]
+fn it_detects_contributions_of_our_slots() {
+    let mut doppelganger = DoppelgangerProtection::new(10);
+    doppelganger.set_validator_slots(Some(validator_slots()));
+    doppelganger.start(1, stream::pending().boxed());
+    assert!(doppelganger.is_observing());
+
+    // Contributions of other slots are fine.
+    doppelganger.on_contribution(&contribution(3, 1, &[0, 5, 20]));
+    assert!(doppelganger.is_observing());
+
+    // A previous run of ours may still have contributed to the block after our start.
+    doppelganger.on_contribution(&contribution(2, 3, &[10]));
+    assert!(doppelganger.is_observing());
+
+    // Slots are only ours in the epoch they were set for.
+    doppelganger.on_contribution(&contribution(Policy::blocks_per_epoch() + 1, 1, &[15]));
+    assert!(doppelganger.is_observing());
+
+    doppelganger.on_contribution(&contribution(3, 1, &[0, 15]));
+    assert!(!doppelganger.is_observing());
+    assert!(!doppelganger.may_sign());
+}
+
+#[test]
+fn it_detects_messages_sent_by_our_validator() {
+    let mut doppelganger = DoppelgangerProtection::new(10);
+    doppelganger.set_validator_slots(Some(validator_slots()));
+    doppelganger.start(1, stream::pending().boxed());
+
+    doppelganger.on_contribution(&contribution(3, 3, &[0]));
+    assert!(!doppelganger.is_observing());
+    assert!(!doppelganger.may_sign());
+}
+
+#[test]
+fn it_polls_received_contributions() {
+    let mut doppelganger = DoppelgangerProtection::new(10);
+    doppelganger.set_validator_slots(Some(validator_slots()));
+    doppelganger.start(
+        1,
+        stream::iter(vec![contribution(3, 1, &[0]), contribution(4, 2, &[11])]).boxed(),
+    );
+
+    doppelganger.poll_contributions(&mut Context::from_waker(noop_waker_ref()));
+    assert!(!doppelganger.is_observing());
+    assert!(!doppelganger.may_sign());
+}
+
+#[test]
+fn it_passes_if_our_validator_did_not_produce_blocks() {
+    let temp_producer = TemporaryBlockProducer::new();
+    let mut doppelganger = DoppelgangerProtection::new(3);
+    assert!(doppelganger.is_pending());
+    doppelganger.start(0, stream::pending().boxed());
+
+    for block_number in 1..=3 {
+        assert!(!doppelganger.may_sign());
+        let block = temp_producer.next_block(vec![], false);
+        doppelganger.on_block(
+            &temp_producer.blockchain.read(),
+            &block,
+            &Address::from([1u8; 20]),
+        );
+        assert_eq!(doppelganger.is_observing(), block_number < 3);
+    }
+    assert!(doppelganger.may_sign());
+}
+
+#[test]
+fn it_detects_blocks_produced_by_our_validator() {
+    let temp_producer = TemporaryBlockProducer::new();
+    let mut doppelganger = DoppelgangerProtection::new(5);
+    doppelganger.start(0, stream::pending().boxed());
+
+    // The only validator produces all blocks, but the block after our start is ignored.
+    let block = temp_producer.next_block(vec![], false);
+    doppelganger.on_block(
+        &temp_producer.blockchain.read(),
+        &block,
+        &validator_address(),
+    );
+    assert!(doppelganger.is_observing());
+
+    let block = temp_producer.next_block(vec![], false);
+    doppelganger.on_block(
+        &temp_producer.blockchain.read(),
+        &block,
+        &validator_address(),
+    );
+    assert!(!doppelganger.is_observing());
+    assert!(!doppelganger.may_sign());
+}
+
+#[test]
+fn it_detects_skip_blocks_signed_by_our_validator() {
+    let temp_producer = TemporaryBlockProducer::new();
+    let mut doppelganger = DoppelgangerProtection::new(5);
+    doppelganger.start(1, stream::pending().boxed());
+
+    temp_producer.next_block(vec![], false);
+    temp_producer.next_block(vec![], false);
+    let block = temp_producer.next_block(vec![], true);
+    doppelganger.on_block(
+        &temp_producer.blockchain.read(),
+        &block,
+        &validator_address(),
+    );
+    assert!(!doppelganger.may_sign());
+    assert!(!doppelganger.is_observing());
+}
+
+#[test]
+fn it_is_disabled_with_zero_blocks() {
+    let doppelganger = DoppelgangerProtection::new(0);
+    assert!(doppelganger.may_sign());
+    assert!(!doppelganger.is_pending());
+}
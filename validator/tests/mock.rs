@@ -265,7 +265,7 @@ async fn validator_can_catch_up() {
     // Manually construct a skip block for the validator
     let vc = create_skip_block_update(
         skip_block_info,
-        validator.voting_key(),
+        validator.signer().as_local().unwrap().voting_key.clone(),
         validator.validator_slot_band(),
         &slots,
     );
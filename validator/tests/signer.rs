@@ -0,0 +1,161 @@
+use nimiq_block::{SkipBlockInfo, TendermintIdentifier, TendermintStep, TendermintVote};
+use nimiq_bls::KeyPair as BlsKeyPair;
+use nimiq_database::volatile::VolatileDatabase;
+use nimiq_hash::Blake2sHash;
+use nimiq_keys::{Address, KeyPair as SchnorrKeyPair};
+use nimiq_primitives::{networks::NetworkId, policy::Policy};
+use nimiq_test_log::test;
+use nimiq_test_utils::validator::seeded_rng;
+use nimiq_validator::{
+    signer::{
+        remote::{handle_request, RemoteSignerEndpoint, SignerResult},
+        LocalSigner, ProtectedSigner, SignerError, SigningRequest, SigningResponse,
+        ValidatorSigner,
+    },
+    slashing_protection::SlashingProtection,
+};
+use nimiq_vrf::VrfEntropy;
+
+fn validator_address() -> Address {
+    Address::from([1; 20])
+}
+
+fn protected_signer() -> ProtectedSigner<LocalSigner> {
+    let mut rng = seeded_rng(0);
+    ProtectedSigner::new(
+        LocalSigner::new(
+            SchnorrKeyPair::generate(&mut rng),
+            BlsKeyPair::generate(&mut rng),
+            SchnorrKeyPair::generate(&mut rng),
+        ),
+        SlashingProtection::new(VolatileDatabase::new(20).unwrap()),
+        validator_address(),
+    )
+}
+
+#[test(tokio::test)]
+async fn it_refuses_equivocating_requests() {
+    let signer = protected_signer();
+
+    let vote = |proposal_hash: Option<Blake2sHash>| TendermintVote {
+        proposal_hash,
+        id: TendermintIdentifier {
+            block_number: Policy::blocks_per_batch(),
+            round_number: 0,
+            step: TendermintStep::PreVote,
+        },
+    };
+
+    let signature = signer
+        .sign_tendermint_vote(&vote(Some(Blake2sHash::from([1; 32]))))
+        .await
+        .unwrap();
+    assert!(signer
+        .voting_public_key()
+        .verify(&vote(Some(Blake2sHash::from([1; 32]))), &signature));
+    assert!(matches!(
+        signer.sign_tendermint_vote(&vote(None)).await,
+        Err(SignerError::Refused(_))
+    ));
+}
+
+#[test(tokio::test)]
+async fn it_only_signs_the_expected_transactions_and_records() {
+    let signer = protected_signer();
+
+    // Our own validator is reactivated, paid for by the fee key.
+    let transaction = signer
+        .sign_reactivate_transaction(&validator_address(), 1, NetworkId::UnitAlbatross)
+        .await
+        .unwrap();
+    assert_eq!(transaction.sender, Address::from(&signer.fee_public_key()));
+    assert_eq!(transaction.validity_start_height, 1);
+    assert!(transaction.fee.is_zero());
+
+    // Other validators are not.
+    assert!(matches!(
+        signer
+            .sign_reactivate_transaction(&Address::from([2; 20]), 1, NetworkId::UnitAlbatross)
+            .await,
+        Err(SignerError::Refused(_))
+    ));
+
+    // Validator records must be small enough to only hold a peer id.
+    assert!(signer.sign_validator_record(vec![1; 38]).await.is_ok());
+    assert!(matches!(
+        signer.sign_validator_record(vec![1; 1024]).await,
+        Err(SignerError::Refused(_))
+    ));
+}
+
+#[test(tokio::test)]
+async fn it_handles_serialized_requests() {
+    let signer = protected_signer();
+
+    let info = SkipBlockInfo {
+        block_number: 1,
+        vrf_entropy: VrfEntropy::from([1; VrfEntropy::SIZE]),
+    };
+    let request = serde_json::to_vec(&SigningRequest::SkipBlock { info: info.clone() }).unwrap();
+
+    let response: SignerResult =
+        serde_json::from_slice(&handle_request(&signer, &request).await).unwrap();
+    match response {
+        Ok(SigningResponse::Bls { signature }) => {
+            assert_eq!(signature, signer.sign_skip_block(&info).await.unwrap())
+        }
+        response => panic!("Unexpected response {response:?}"),
+    }
+
+    let response: SignerResult =
+        serde_json::from_slice(&handle_request(&signer, b"not a request").await).unwrap();
+    assert!(response.is_err());
+
+    // The public keys are sent compressed.
+    let response: SignerResult = serde_json::from_slice(
+        &handle_request(
+            &signer,
+            &serde_json::to_vec(&SigningRequest::PublicKeys).unwrap(),
+        )
+        .await,
+    )
+    .unwrap();
+    match response {
+        Ok(SigningResponse::PublicKeys {
+            voting_key,
+            fee_key,
+            ..
+        }) => {
+            assert_eq!(voting_key, signer.voting_public_key().compress());
+            assert_eq!(fee_key, signer.fee_public_key());
+        }
+        response => panic!("Unexpected response {response:?}"),
+    }
+}
+
+#[test]
+fn it_parses_endpoints() {
+    assert_eq!(
+        "unix:/run/signer.sock".parse(),
+        Ok(RemoteSignerEndpoint::Socket("/run/signer.sock".into()))
+    );
+    let endpoint: RemoteSignerEndpoint = "http://secret@127.0.0.1:8650/".parse().unwrap();
+    assert_eq!(
+        endpoint,
+        RemoteSignerEndpoint::Http {
+            address: "127.0.0.1:8650".into(),
+            token: "secret".into(),
+        }
+    );
+    // The token is never printed.
+    assert_eq!(endpoint.to_string(), "http://127.0.0.1:8650");
+
+    // HTTP signers require a token.
+    assert!("http://127.0.0.1:8650"
+        .parse::<RemoteSignerEndpoint>()
+        .is_err());
+    assert!("http://@127.0.0.1:8650"
+        .parse::<RemoteSignerEndpoint>()
+        .is_err());
+    assert!("127.0.0.1:8650".parse::<RemoteSignerEndpoint>().is_err());
+}
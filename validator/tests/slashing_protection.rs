@@ -0,0 +1,116 @@
+use nimiq_block::TendermintStep;
+use nimiq_database::volatile::VolatileDatabase;
+use nimiq_hash::{Blake2bHash, Blake2sHash};
+use nimiq_primitives::policy::Policy;
+use nimiq_test_log::test;
+use nimiq_validator::slashing_protection::{SignedMessage, SlashingProtection};
+use nimiq_vrf::VrfEntropy;
+
+fn micro_block(block_number: u32, entropy: u8, hash: u8) -> SignedMessage {
+    SignedMessage::MicroBlock {
+        block_number,
+        vrf_entropy: VrfEntropy::from([entropy; VrfEntropy::SIZE]),
+        header_hash: Blake2bHash::from([hash; 32]),
+    }
+}
+
+#[test]
+fn it_refuses_conflicting_block_signatures() {
+    let slashing_protection = SlashingProtection::new(VolatileDatabase::new(20).unwrap());
+
+    let block = micro_block(10, 1, 1);
+    assert_eq!(slashing_protection.try_sign(block.clone()), Ok(()));
+    // Signing the very same block again is fine.
+    assert_eq!(slashing_protection.try_sign(block.clone()), Ok(()));
+    // A different block for the same slot would create a fork proof.
+    assert_eq!(
+        slashing_protection.try_sign(micro_block(10, 1, 2)),
+        Err(block.clone())
+    );
+    // So would a skip block contribution for it.
+    assert_eq!(
+        slashing_protection.try_sign(SignedMessage::SkipBlock {
+            block_number: 10,
+            vrf_entropy: VrfEntropy::from([1; VrfEntropy::SIZE]),
+        }),
+        Err(block)
+    );
+    // A block on top of a different predecessor doesn't conflict.
+    assert_eq!(slashing_protection.try_sign(micro_block(10, 2, 2)), Ok(()));
+}
+
+#[test]
+fn it_refuses_equivocating_votes() {
+    let slashing_protection = SlashingProtection::new(VolatileDatabase::new(20).unwrap());
+
+    let vote = |round, step, hash: Option<u8>| SignedMessage::TendermintVote {
+        block_number: Policy::blocks_per_batch(),
+        round,
+        step,
+        proposal_hash: hash.map(|hash| Blake2sHash::from([hash; 32])),
+    };
+
+    assert_eq!(
+        slashing_protection.try_sign(vote(0, TendermintStep::PreVote, Some(1))),
+        Ok(())
+    );
+    assert_eq!(
+        slashing_protection.try_sign(vote(0, TendermintStep::PreVote, None)),
+        Err(vote(0, TendermintStep::PreVote, Some(1)))
+    );
+    assert_eq!(
+        slashing_protection.try_sign(vote(0, TendermintStep::PreCommit, None)),
+        Ok(())
+    );
+    assert_eq!(
+        slashing_protection.try_sign(vote(1, TendermintStep::PreVote, Some(2))),
+        Ok(())
+    );
+}
+
+#[test]
+fn it_prunes_and_migrates_the_signing_history() {
+    let slashing_protection = SlashingProtection::new(VolatileDatabase::new(20).unwrap());
+    let batch = Policy::blocks_per_batch();
+
+    assert_eq!(slashing_protection.try_sign(micro_block(1, 1, 1)), Ok(()));
+    assert_eq!(
+        slashing_protection.try_sign(micro_block(batch + 1, 1, 1)),
+        Ok(())
+    );
+    let vote = |block_number| SignedMessage::TendermintVote {
+        block_number,
+        round: 0,
+        step: TendermintStep::PreCommit,
+        proposal_hash: None,
+    };
+    assert_eq!(slashing_protection.try_sign(vote(batch)), Ok(()));
+    assert_eq!(slashing_protection.try_sign(vote(2 * batch)), Ok(()));
+
+    // The signatures of the first batch can still be used to slash us during the second one.
+    slashing_protection.prune(batch);
+    assert_eq!(slashing_protection.export().messages.len(), 4);
+    slashing_protection.prune(2 * batch);
+    assert_eq!(
+        slashing_protection.export().messages,
+        vec![micro_block(batch + 1, 1, 1), vote(2 * batch)]
+    );
+    slashing_protection.prune(3 * batch);
+    assert_eq!(slashing_protection.export().messages, vec![vote(2 * batch)]);
+
+    let slashing_protection = SlashingProtection::new(VolatileDatabase::new(20).unwrap());
+    assert_eq!(
+        slashing_protection.try_sign(micro_block(batch + 1, 1, 1)),
+        Ok(())
+    );
+    let export = slashing_protection.export();
+
+    let new_machine = SlashingProtection::new(VolatileDatabase::new(20).unwrap());
+    assert_eq!(new_machine.try_sign(micro_block(2 * batch, 1, 1)), Ok(()));
+    assert_eq!(new_machine.import(export.clone()), 1);
+    assert_eq!(new_machine.import(export), 0);
+    assert_eq!(
+        new_machine.try_sign(micro_block(batch + 1, 1, 2)),
+        Err(micro_block(batch + 1, 1, 1))
+    );
+}
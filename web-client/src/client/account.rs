@@ -1,3 +1,5 @@
+use nimiq_consensus::VerifiedTrieProof;
+use nimiq_serde::Serialize;
 use tsify::Tsify;
 use wasm_bindgen::prelude::*;
 
@@ -139,11 +141,18 @@ impl PlainAccount {
 #[derive(serde::Serialize, Tsify)]
 #[serde(rename_all = "camelCase")]
 pub struct PlainStaker {
-    /// The staker's balance.
+    /// The staker's active balance.
     balance: u64,
     /// The address of the validator for which the staker is delegating its stake for. If it is not
     /// delegating to any validator, this will be set to None.
     delegation: Option<String>,
+    /// The part of the active balance that is delegated to additional validators.
+    split_balance: u64,
+    /// The staker's inactive balance, which is being unbonded.
+    inactive_balance: u64,
+    /// The block number after which the inactive balance can be unstaked. If the staker has no
+    /// inactive balance, this will be set to None.
+    inactive_release: Option<u32>,
 }
 
 impl PlainStaker {
@@ -154,6 +163,9 @@ impl PlainStaker {
                 .as_ref()
                 .map(|address| address.to_user_friendly_address()),
             balance: staker.balance.into(),
+            split_balance: staker.split_balance.into(),
+            inactive_balance: staker.inactive_balance.into(),
+            inactive_release: staker.inactive_release(),
         }
     }
 }
@@ -203,6 +215,102 @@ impl PlainValidator {
     }
 }
 
+/// An account together with the proof it was verified with, so that it can be verified again
+/// against the state root of the block.
+#[derive(serde::Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct PlainAccountWithProof {
+    /// The account. Accounts that do not exist are returned as empty basic accounts.
+    pub account: PlainAccount,
+    /// The serialized trie proof of the account, hex-encoded.
+    pub proof: String,
+    /// The hash of the block whose state the proof was created for.
+    pub block_hash: String,
+    /// The number of the block whose state the proof was created for.
+    pub block_number: u32,
+    /// The state root the proof was verified against.
+    pub state_root: String,
+}
+
+impl PlainAccountWithProof {
+    pub fn from_native(
+        account: &nimiq_account::Account,
+        proof: &VerifiedTrieProof,
+    ) -> PlainAccountWithProof {
+        PlainAccountWithProof {
+            account: PlainAccount::from_native(account),
+            proof: hex::encode(proof.proof.serialize_to_vec()),
+            block_hash: proof.block_hash.to_hex(),
+            block_number: proof.block_number,
+            state_root: proof.state_root.to_hex(),
+        }
+    }
+}
+
+/// A staker together with the proof it was verified with, so that it can be verified again
+/// against the state root of the block.
+#[derive(serde::Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct PlainStakerWithProof {
+    /// The staker, `undefined` if the proof shows that it does not exist.
+    pub staker: Option<PlainStaker>,
+    /// The serialized trie proof of the staker, hex-encoded.
+    pub proof: String,
+    /// The hash of the block whose state the proof was created for.
+    pub block_hash: String,
+    /// The number of the block whose state the proof was created for.
+    pub block_number: u32,
+    /// The state root the proof was verified against.
+    pub state_root: String,
+}
+
+impl PlainStakerWithProof {
+    pub fn from_native(
+        staker: Option<&nimiq_account::Staker>,
+        proof: &VerifiedTrieProof,
+    ) -> PlainStakerWithProof {
+        PlainStakerWithProof {
+            staker: staker.map(PlainStaker::from_native),
+            proof: hex::encode(proof.proof.serialize_to_vec()),
+            block_hash: proof.block_hash.to_hex(),
+            block_number: proof.block_number,
+            state_root: proof.state_root.to_hex(),
+        }
+    }
+}
+
+/// A validator together with the proof it was verified with, so that it can be verified again
+/// against the state root of the block.
+#[derive(serde::Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct PlainValidatorWithProof {
+    /// The validator, `undefined` if the proof shows that it does not exist.
+    pub validator: Option<PlainValidator>,
+    /// The serialized trie proof of the validator, hex-encoded.
+    pub proof: String,
+    /// The hash of the block whose state the proof was created for.
+    pub block_hash: String,
+    /// The number of the block whose state the proof was created for.
+    pub block_number: u32,
+    /// The state root the proof was verified against.
+    pub state_root: String,
+}
+
+impl PlainValidatorWithProof {
+    pub fn from_native(
+        validator: Option<&nimiq_account::Validator>,
+        proof: &VerifiedTrieProof,
+    ) -> PlainValidatorWithProof {
+        PlainValidatorWithProof {
+            validator: validator.map(PlainValidator::from_native),
+            proof: hex::encode(proof.proof.serialize_to_vec()),
+            block_hash: proof.block_hash.to_hex(),
+            block_number: proof.block_number,
+            state_root: proof.state_root.to_hex(),
+        }
+    }
+}
+
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(typescript_type = "PlainAccount")]
@@ -211,15 +319,24 @@ extern "C" {
     #[wasm_bindgen(typescript_type = "PlainAccount[]")]
     pub type PlainAccountArrayType;
 
+    #[wasm_bindgen(typescript_type = "PlainAccountWithProof")]
+    pub type PlainAccountWithProofType;
+
     #[wasm_bindgen(typescript_type = "PlainStaker | undefined")]
     pub type PlainStakerType;
 
     #[wasm_bindgen(typescript_type = "(PlainStaker | undefined)[]")]
     pub type PlainStakerArrayType;
 
+    #[wasm_bindgen(typescript_type = "PlainStakerWithProof")]
+    pub type PlainStakerWithProofType;
+
     #[wasm_bindgen(typescript_type = "PlainValidator | undefined")]
     pub type PlainValidatorType;
 
     #[wasm_bindgen(typescript_type = "(PlainValidator | undefined)[]")]
     pub type PlainValidatorArrayType;
+
+    #[wasm_bindgen(typescript_type = "PlainValidatorWithProof")]
+    pub type PlainValidatorWithProofType;
 }
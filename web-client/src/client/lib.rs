@@ -6,6 +6,7 @@ use std::{
     },
     rc::Rc,
     str::FromStr,
+    sync::Arc,
     time::Duration,
 };
 
@@ -24,7 +25,7 @@ pub use nimiq::{
     extras::{panic::initialize_panic_reporting, web_logging::initialize_web_logging},
 };
 use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainEvent};
-use nimiq_consensus::ConsensusEvent;
+use nimiq_consensus::{ConsensusEvent, TrieItemKind, VerifiedTrieProof};
 use nimiq_hash::Blake2bHash;
 use nimiq_network_interface::{
     network::{CloseReason, Network, NetworkEvent},
@@ -43,12 +44,15 @@ use crate::{
     address::{Address, AddressAnyArrayType, AddressAnyType},
     client::{
         account::{
-            PlainAccount, PlainAccountArrayType, PlainAccountType, PlainStaker,
-            PlainStakerArrayType, PlainStakerType, PlainValidator, PlainValidatorArrayType,
-            PlainValidatorType,
+            PlainAccount, PlainAccountArrayType, PlainAccountType, PlainAccountWithProof,
+            PlainAccountWithProofType, PlainStaker, PlainStakerArrayType, PlainStakerType,
+            PlainStakerWithProof, PlainStakerWithProofType, PlainValidator,
+            PlainValidatorArrayType, PlainValidatorType, PlainValidatorWithProof,
+            PlainValidatorWithProofType,
         },
         block::{PlainBlock, PlainBlockType},
         peer_info::PlainPeerInfo,
+        storage::IndexedDbLightChainStorage,
     },
     client_configuration::{
         ClientConfiguration, PlainClientConfiguration, PlainClientConfigurationType,
@@ -162,15 +166,46 @@ impl Client {
 
         log::info!(?config, "Final configuration");
 
+        // Open the persistent storage of the blockchain, if enabled.
+        let light_chain_storage = if web_config.persistent_storage {
+            let name = format!("nimiq-light-blockchain-{}", web_config.network_id);
+            match IndexedDbLightChainStorage::open(&name).await {
+                Ok(storage) => Some(Arc::new(storage)),
+                Err(error) => {
+                    log::warn!(
+                        ?error,
+                        "Failed to open persistent storage, using volatile storage"
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
         // Create client from config.
         log::info!("Initializing light client");
-        let mut client = nimiq::client::Client::from_config(
-            config,
-            Box::new(|fut| {
-                spawn_local(fut);
-            }),
-        )
-        .await
+        let mut client = match light_chain_storage {
+            Some(storage) => {
+                nimiq::client::Client::from_config_with_light_chain_storage(
+                    config,
+                    Box::new(|fut| {
+                        spawn_local(fut);
+                    }),
+                    storage,
+                )
+                .await
+            }
+            None => {
+                nimiq::client::Client::from_config(
+                    config,
+                    Box::new(|fut| {
+                        spawn_local(fut);
+                    }),
+                )
+                .await
+            }
+        }
         .expect("Client initialization failed");
         log::info!("Web client initialized");
 
@@ -439,6 +474,154 @@ impl Client {
         Ok(serde_wasm_bindgen::to_value(account)?.into())
     }
 
+    /// Fetches the account for the provided address from the network, together with the trie
+    /// proof it was verified with and the block whose state root the proof was verified against.
+    ///
+    /// Throws if the address cannot be parsed and on network errors.
+    #[wasm_bindgen(js_name = getAccountWithProof)]
+    pub async fn get_account_with_proof(
+        &self,
+        address: &AddressAnyType,
+    ) -> Result<PlainAccountWithProofType, JsError> {
+        let address = Address::from_any(address)?.take_native();
+        let (accounts, proof) = self
+            .inner
+            .consensus_proxy()
+            .request_accounts_with_proof(vec![address.clone()], 1)
+            .await?;
+
+        let account = accounts
+            .get(&address)
+            .ok_or(JsError::new(&format!(
+                "Missing trie proof node for {}",
+                address
+            )))?
+            .clone()
+            .unwrap_or_default();
+        let plain_account = PlainAccountWithProof::from_native(&account, &proof);
+        Ok(serde_wasm_bindgen::to_value(&plain_account)?.into())
+    }
+
+    /// Fetches the staker for the provided address from the network, together with the trie
+    /// proof it was verified with and the block whose state root the proof was verified against.
+    ///
+    /// Throws if the address cannot be parsed and on network errors.
+    #[wasm_bindgen(js_name = getStakerWithProof)]
+    pub async fn get_staker_with_proof(
+        &self,
+        address: &AddressAnyType,
+    ) -> Result<PlainStakerWithProofType, JsError> {
+        let address = Address::from_any(address)?.take_native();
+        let (stakers, proof) = self
+            .inner
+            .consensus_proxy()
+            .request_stakers_with_proof(vec![address.clone()], 1)
+            .await?;
+
+        let staker = stakers.get(&address).ok_or(JsError::new(&format!(
+            "Missing trie proof node for {}",
+            address
+        )))?;
+        let plain_staker = PlainStakerWithProof::from_native(staker.as_ref(), &proof);
+        Ok(serde_wasm_bindgen::to_value(&plain_staker)?.into())
+    }
+
+    /// Fetches the validator for the provided address from the network, together with the trie
+    /// proof it was verified with and the block whose state root the proof was verified against.
+    ///
+    /// Throws if the address cannot be parsed and on network errors.
+    #[wasm_bindgen(js_name = getValidatorWithProof)]
+    pub async fn get_validator_with_proof(
+        &self,
+        address: &AddressAnyType,
+    ) -> Result<PlainValidatorWithProofType, JsError> {
+        let address = Address::from_any(address)?.take_native();
+        let (validators, proof) = self
+            .inner
+            .consensus_proxy()
+            .request_validators_with_proof(vec![address.clone()], 1)
+            .await?;
+
+        let validator = validators.get(&address).ok_or(JsError::new(&format!(
+            "Missing trie proof node for {}",
+            address
+        )))?;
+        let plain_validator = PlainValidatorWithProof::from_native(validator.as_ref(), &proof);
+        Ok(serde_wasm_bindgen::to_value(&plain_validator)?.into())
+    }
+
+    /// Verifies a hex-encoded account proof, as returned by {@link getAccountWithProof}, against
+    /// the hex-encoded state root and returns the proven account. Does not need a network
+    /// connection.
+    ///
+    /// Throws if the proof cannot be parsed or does not prove the account.
+    #[wasm_bindgen(js_name = verifyAccountProof)]
+    pub fn verify_account_proof(
+        proof: &str,
+        state_root: &str,
+        address: &AddressAnyType,
+    ) -> Result<PlainAccountType, JsError> {
+        let address = Address::from_any(address)?.take_native();
+        let mut accounts = VerifiedTrieProof::verify_hex::<nimiq_account::Account>(
+            proof,
+            state_root,
+            TrieItemKind::Account,
+            &[address.clone()],
+        )?;
+        let account = accounts.remove(&address).flatten().unwrap_or_default();
+        Ok(serde_wasm_bindgen::to_value(&PlainAccount::from_native(&account))?.into())
+    }
+
+    /// Verifies a hex-encoded staker proof, as returned by {@link getStakerWithProof}, against
+    /// the hex-encoded state root and returns the proven staker, `undefined` if the proof shows
+    /// that it does not exist. Does not need a network connection.
+    ///
+    /// Throws if the proof cannot be parsed or does not prove the staker.
+    #[wasm_bindgen(js_name = verifyStakerProof)]
+    pub fn verify_staker_proof(
+        proof: &str,
+        state_root: &str,
+        address: &AddressAnyType,
+    ) -> Result<PlainStakerType, JsError> {
+        let address = Address::from_any(address)?.take_native();
+        let mut stakers = VerifiedTrieProof::verify_hex::<nimiq_account::Staker>(
+            proof,
+            state_root,
+            TrieItemKind::Staker,
+            &[address.clone()],
+        )?;
+        let staker = stakers
+            .remove(&address)
+            .flatten()
+            .map(|staker| PlainStaker::from_native(&staker));
+        Ok(serde_wasm_bindgen::to_value(&staker)?.into())
+    }
+
+    /// Verifies a hex-encoded validator proof, as returned by {@link getValidatorWithProof},
+    /// against the hex-encoded state root and returns the proven validator, `undefined` if the
+    /// proof shows that it does not exist. Does not need a network connection.
+    ///
+    /// Throws if the proof cannot be parsed or does not prove the validator.
+    #[wasm_bindgen(js_name = verifyValidatorProof)]
+    pub fn verify_validator_proof(
+        proof: &str,
+        state_root: &str,
+        address: &AddressAnyType,
+    ) -> Result<PlainValidatorType, JsError> {
+        let address = Address::from_any(address)?.take_native();
+        let mut validators = VerifiedTrieProof::verify_hex::<nimiq_account::Validator>(
+            proof,
+            state_root,
+            TrieItemKind::Validator,
+            &[address.clone()],
+        )?;
+        let validator = validators
+            .remove(&address)
+            .flatten()
+            .map(|validator| PlainValidator::from_native(&validator));
+        Ok(serde_wasm_bindgen::to_value(&validator)?.into())
+    }
+
     /// Fetches the accounts for the provided addresses from the network.
     ///
     /// Throws if an address cannot be parsed and on network errors.
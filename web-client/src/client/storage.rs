@@ -0,0 +1,265 @@
+use std::sync::Mutex;
+
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use futures_util::StreamExt;
+use js_sys::{global, Array, Promise, Reflect, Uint8Array};
+use nimiq_block::{MacroBlock, MacroHeader};
+use nimiq_blockchain_interface::ChainInfo;
+use nimiq_light_blockchain::{
+    LightChainStorage, LightChainUpdate, StoredChainInfo, StoredLightChain, StoredZKP,
+};
+use nimiq_primitives::policy::Policy;
+use nimiq_serde::{Deserialize, Serialize};
+use wasm_bindgen::{
+    prelude::{Closure, JsValue},
+    JsCast,
+};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{
+    Event, IdbDatabase, IdbFactory, IdbObjectStore, IdbRequest, IdbTransaction, IdbTransactionMode,
+};
+
+const DB_VERSION: u32 = 1;
+
+const CHAIN_INFO_STORE: &str = "chainInfos";
+const ELECTION_STORE: &str = "elections";
+const HEAD_STORE: &str = "heads";
+
+const ELECTION_HEAD_KEY: &str = "electionHead";
+const ZKP_KEY: &str = "zkp";
+
+/// IndexedDB implementation of a LightChainStorage, used to persist the light blockchain in the
+/// browser.
+///
+/// IndexedDB is asynchronous, so the persisted state is read when opening the storage and updates
+/// are written in the background, in the order in which they are made. Every update is written in
+/// a single transaction, which has to complete before the next update is written.
+pub struct IndexedDbLightChainStorage {
+    // The state read when opening the storage. It is handed out once.
+    loaded: Mutex<Option<StoredLightChain>>,
+    // The channel to the task that writes the updates to the database.
+    updates: UnboundedSender<LightChainUpdate>,
+}
+
+impl IndexedDbLightChainStorage {
+    /// Opens (or creates) the database with the given name and reads the persisted state.
+    pub async fn open(name: &str) -> Result<Self, JsValue> {
+        let factory: IdbFactory = Reflect::get(&global(), &JsValue::from_str("indexedDB"))?
+            .dyn_into()
+            .map_err(|_| JsValue::from_str("IndexedDB is not available"))?;
+
+        let request = factory.open_with_u32(name, DB_VERSION)?;
+        let on_upgrade_needed = Closure::once_into_js(move |event: Event| {
+            let db: IdbDatabase = event
+                .target()
+                .and_then(|target| target.dyn_into::<IdbRequest>().ok())
+                .and_then(|request| request.result().ok())
+                .expect("The open request must hold the database")
+                .unchecked_into();
+            for store in [CHAIN_INFO_STORE, ELECTION_STORE, HEAD_STORE] {
+                if let Err(e) = db.create_object_store(store) {
+                    log::error!(store, error = ?e, "Failed to create object store");
+                }
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade_needed.unchecked_ref()));
+        let db: IdbDatabase = request_result(&request).await?.unchecked_into();
+
+        let loaded = Self::read(&db).await?;
+
+        let (updates, mut rx) = unbounded();
+        spawn_local(async move {
+            while let Some(update) = rx.next().await {
+                if let Err(e) = Self::apply(&db, &update).await {
+                    log::error!(error = ?e, "Failed to write to the light chain storage");
+                }
+            }
+        });
+
+        Ok(Self {
+            loaded: Mutex::new(loaded),
+            updates,
+        })
+    }
+
+    /// Reads the persisted state from the database.
+    async fn read(db: &IdbDatabase) -> Result<Option<StoredLightChain>, JsValue> {
+        // Both requests are made before waiting, so that they belong to the same transaction.
+        let heads = object_store(db, HEAD_STORE, IdbTransactionMode::Readonly)?;
+        let election_head_result =
+            request_result(&heads.get(&JsValue::from_str(ELECTION_HEAD_KEY))?);
+        let zkp_result = request_result(&heads.get(&JsValue::from_str(ZKP_KEY))?);
+
+        let election_head: Option<MacroBlock> = decode(&election_head_result.await?);
+        let election_head = match election_head {
+            Some(election_head) => election_head,
+            None => return Ok(None),
+        };
+        let zkp: Option<StoredZKP> = decode(&zkp_result.await?);
+
+        let chain_infos = read_all::<StoredChainInfo>(db, CHAIN_INFO_STORE)
+            .await?
+            .into_iter()
+            .map(ChainInfo::from)
+            .collect();
+        let elections = read_all::<MacroHeader>(db, ELECTION_STORE).await?;
+
+        Ok(Some(StoredLightChain {
+            election_head,
+            chain_infos,
+            elections,
+            zkp,
+        }))
+    }
+
+    /// Writes an update to the database in a single transaction and waits for it to complete.
+    async fn apply(db: &IdbDatabase, update: &LightChainUpdate) -> Result<(), JsValue> {
+        let stores = Array::of3(
+            &JsValue::from_str(CHAIN_INFO_STORE),
+            &JsValue::from_str(ELECTION_STORE),
+            &JsValue::from_str(HEAD_STORE),
+        );
+        let transaction =
+            db.transaction_with_str_sequence_and_mode(&stores, IdbTransactionMode::Readwrite)?;
+        let completed = transaction_result(&transaction);
+
+        // The results are awaited only once all requests were made, so that none is missed.
+        let mut results = vec![];
+        if update.clear_chain_infos {
+            results.push(request_result(
+                &transaction.object_store(CHAIN_INFO_STORE)?.clear()?,
+            ));
+        }
+        for chain_info in &update.chain_infos {
+            results.push(put(
+                &transaction,
+                CHAIN_INFO_STORE,
+                &chain_info.head.hash().to_hex(),
+                &StoredChainInfo::from(chain_info),
+            )?);
+        }
+        for header in &update.elections {
+            let epoch_number = Policy::epoch_at(header.block_number);
+            results.push(put(
+                &transaction,
+                ELECTION_STORE,
+                &epoch_number.to_string(),
+                header,
+            )?);
+        }
+        if let Some(election_head) = &update.election_head {
+            results.push(put(
+                &transaction,
+                HEAD_STORE,
+                ELECTION_HEAD_KEY,
+                election_head,
+            )?);
+        }
+        if let Some(zkp) = &update.zkp {
+            results.push(put(&transaction, HEAD_STORE, ZKP_KEY, zkp)?);
+        }
+
+        for result in results {
+            result.await?;
+        }
+        completed.await?;
+        Ok(())
+    }
+}
+
+impl LightChainStorage for IndexedDbLightChainStorage {
+    fn load(&self) -> Option<StoredLightChain> {
+        self.loaded.lock().unwrap().take()
+    }
+
+    fn write(&self, update: &LightChainUpdate) {
+        if self.updates.unbounded_send(update.clone()).is_err() {
+            log::error!("The light chain storage was closed");
+        }
+    }
+}
+
+/// Stores a value under the given key in one of the object stores of a transaction.
+fn put(
+    transaction: &IdbTransaction,
+    store: &str,
+    key: &str,
+    value: &impl Serialize,
+) -> Result<JsFuture, JsValue> {
+    let request = transaction.object_store(store)?.put_with_key(
+        &Uint8Array::from(&value.serialize_to_vec()[..]),
+        &JsValue::from_str(key),
+    )?;
+    Ok(request_result(&request))
+}
+
+/// Opens an object store in a new transaction.
+fn object_store(
+    db: &IdbDatabase,
+    store: &str,
+    mode: IdbTransactionMode,
+) -> Result<IdbObjectStore, JsValue> {
+    db.transaction_with_str_and_mode(store, mode)?
+        .object_store(store)
+}
+
+/// Reads and decodes all values of an object store. Values that fail to decode are skipped.
+async fn read_all<T: Deserialize>(db: &IdbDatabase, store: &str) -> Result<Vec<T>, JsValue> {
+    let values: Array =
+        request_result(&object_store(db, store, IdbTransactionMode::Readonly)?.get_all()?)
+            .await?
+            .unchecked_into();
+    Ok(values.iter().filter_map(|value| decode(&value)).collect())
+}
+
+/// Decodes a value stored as a byte array. Returns None if there is no such value or it fails to
+/// decode.
+fn decode<T: Deserialize>(value: &JsValue) -> Option<T> {
+    if value.is_undefined() {
+        return None;
+    }
+    match T::deserialize_from_vec(&Uint8Array::new(value).to_vec()) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            log::error!(error = %e, "Failed to decode stored light chain data");
+            None
+        }
+    }
+}
+
+/// Returns a future waiting for an IndexedDB request to complete with its result. It must be
+/// created right after making the request, before yielding to the event loop.
+fn request_result(request: &IdbRequest) -> JsFuture {
+    let promise = Promise::new(&mut |resolve, reject| {
+        let on_success = Closure::once_into_js(move |event: Event| {
+            let result = event
+                .target()
+                .and_then(|target| target.dyn_into::<IdbRequest>().ok())
+                .and_then(|request| request.result().ok())
+                .unwrap_or(JsValue::UNDEFINED);
+            let _ = resolve.call1(&JsValue::NULL, &result);
+        });
+        let on_error = Closure::once_into_js(move |event: Event| {
+            let _ = reject.call1(&JsValue::NULL, &event);
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    JsFuture::from(promise)
+}
+
+/// Returns a future waiting for an IndexedDB transaction to complete. It fails if the transaction
+/// is aborted, e.g. because one of its requests failed.
+fn transaction_result(transaction: &IdbTransaction) -> JsFuture {
+    let promise = Promise::new(&mut |resolve, reject| {
+        let on_complete = Closure::once_into_js(move |_: Event| {
+            let _ = resolve.call0(&JsValue::NULL);
+        });
+        let on_abort = Closure::once_into_js(move |event: Event| {
+            let _ = reject.call1(&JsValue::NULL, &event);
+        });
+        transaction.set_oncomplete(Some(on_complete.unchecked_ref()));
+        transaction.set_onabort(Some(on_abort.unchecked_ref()));
+    });
+    JsFuture::from(promise)
+}
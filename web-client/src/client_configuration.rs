@@ -21,6 +21,8 @@ pub struct ClientConfiguration {
     pub seed_nodes: Vec<String>,
     #[wasm_bindgen(skip)]
     pub log_level: String,
+    #[wasm_bindgen(skip)]
+    pub persistent_storage: bool,
 }
 
 #[cfg(any(feature = "client", feature = "primitives"))]
@@ -38,6 +40,8 @@ pub struct PlainClientConfiguration {
     pub seed_nodes: Option<Vec<String>>,
     #[cfg_attr(feature = "client", serde(skip_serializing_if = "Option::is_none"))]
     pub log_level: Option<String>,
+    #[cfg_attr(feature = "client", serde(skip_serializing_if = "Option::is_none"))]
+    pub persistent_storage: Option<bool>,
 }
 
 impl Default for ClientConfiguration {
@@ -46,6 +50,7 @@ impl Default for ClientConfiguration {
             network_id: NetworkId::TestAlbatross,
             seed_nodes: vec!["/dns4/seed1.pos.nimiq-testnet.com/tcp/8443/wss".to_string()],
             log_level: "info".to_string(),
+            persistent_storage: false,
         }
     }
 }
@@ -91,6 +96,15 @@ impl ClientConfiguration {
         self.log_level = log_level.to_lowercase();
     }
 
+    /// Sets whether the client persists the blockchain in IndexedDB, so that it resumes from its
+    /// last known election block instead of syncing from scratch after a reload.
+    ///
+    /// Default is `false`.
+    #[wasm_bindgen(js_name = persistentStorage)]
+    pub fn persistent_storage(&mut self, persistent_storage: bool) {
+        self.persistent_storage = persistent_storage;
+    }
+
     // TODO: Find a way to make this method work, maybe by using the synthetic Client from the main thread as an import?
     // /// Instantiates a client from this configuration builder.
     // #[wasm_bindgen(js_name = instantiateClient)]
@@ -107,6 +121,7 @@ impl ClientConfiguration {
             network_id: Some(self.network_id.to_string()),
             seed_nodes: Some(self.seed_nodes.clone()),
             log_level: Some(self.log_level.clone()),
+            persistent_storage: Some(self.persistent_storage),
         })
         .unwrap()
         .into()
@@ -133,6 +148,10 @@ impl TryFrom<PlainClientConfiguration> for ClientConfiguration {
             client_config.log_level = log_level;
         }
 
+        if let Some(persistent_storage) = config.persistent_storage {
+            client_config.persistent_storage = persistent_storage;
+        }
+
         Ok(client_config)
     }
 }
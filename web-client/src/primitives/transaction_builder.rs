@@ -182,8 +182,80 @@ impl TransactionBuilder {
         Ok(Transaction::from_native(tx))
     }
 
-    /// Unstakes stake from the staking contract and transfers `value` amount of luna (NIM's smallest unit)
-    /// from the staker to the recipient.
+    /// Sets the inactive balance of a staker in the staking contract to `new_inactive_balance`
+    /// luna (NIM's smallest unit). Inactive stake doesn't count towards the stake of the validator
+    /// and can be unstaked once it has been released. This is a signaling transaction and as such
+    /// does not transfer any value.
+    ///
+    /// The returned transaction is not yet signed. You can sign it e.g. with `tx.sign(keyPair)`.
+    ///
+    /// Throws when the numbers given for new_inactive_balance and fee do not fit within a u64 or the
+    /// networkId is unknown.
+    #[wasm_bindgen(js_name = newSetInactiveStake)]
+    pub fn new_set_inactive_stake(
+        sender: &Address,
+        new_inactive_balance: u64,
+        fee: Option<u64>,
+        validity_start_height: u32,
+        network_id: u8,
+    ) -> Result<Transaction, JsError> {
+        let mut recipient = Recipient::new_staking_builder();
+        recipient.set_inactive_stake(Coin::try_from(new_inactive_balance)?);
+
+        let mut builder = nimiq_transaction_builder::TransactionBuilder::new();
+        builder
+            .with_sender(sender.native_ref().clone())
+            .with_recipient(recipient.generate().unwrap())
+            .with_value(Coin::ZERO)
+            .with_fee(Coin::try_from(fee.unwrap_or(0))?)
+            .with_validity_start_height(validity_start_height)
+            .with_network_id(to_network_id(network_id)?);
+
+        let proof_builder = builder.generate()?;
+        let tx = proof_builder.preliminary_transaction().to_owned();
+        Ok(Transaction::from_native(tx))
+    }
+
+    /// Sets the stake a staker delegates to an additional validator to `stake` luna (NIM's
+    /// smallest unit). The difference is moved from or to the staker's main delegation, a stake of
+    /// zero removes the delegation to the validator. This is a signaling transaction and as such
+    /// does not transfer any value.
+    ///
+    /// The returned transaction is not yet signed. You can sign it e.g. with `tx.sign(keyPair)`.
+    ///
+    /// Throws when the numbers given for stake and fee do not fit within a u64 or the networkId is
+    /// unknown.
+    #[wasm_bindgen(js_name = newSetDelegationStake)]
+    pub fn new_set_delegation_stake(
+        sender: &Address,
+        validator_address: &Address,
+        stake: u64,
+        fee: Option<u64>,
+        validity_start_height: u32,
+        network_id: u8,
+    ) -> Result<Transaction, JsError> {
+        let mut recipient = Recipient::new_staking_builder();
+        recipient.set_delegation_stake(
+            validator_address.native_ref().clone(),
+            Coin::try_from(stake)?,
+        );
+
+        let mut builder = nimiq_transaction_builder::TransactionBuilder::new();
+        builder
+            .with_sender(sender.native_ref().clone())
+            .with_recipient(recipient.generate().unwrap())
+            .with_value(Coin::ZERO)
+            .with_fee(Coin::try_from(fee.unwrap_or(0))?)
+            .with_validity_start_height(validity_start_height)
+            .with_network_id(to_network_id(network_id)?);
+
+        let proof_builder = builder.generate()?;
+        let tx = proof_builder.preliminary_transaction().to_owned();
+        Ok(Transaction::from_native(tx))
+    }
+
+    /// Unstakes released inactive stake from the staking contract and transfers `value` amount of
+    /// luna (NIM's smallest unit) from the staker to the recipient.
     ///
     /// The returned transaction is not yet signed. You can sign it e.g. with `tx.sign(keyPair)`.
     ///
@@ -441,13 +441,23 @@ impl Transaction {
                                 staker: staker_address.to_user_friendly_address(),
                             })
                         }
-                        IncomingStakingTransactionData::UpdateStaker { new_delegation, .. } => {
-                            PlainTransactionData::UpdateStaker(PlainUpdateStakerData {
-                                raw: hex::encode(self.data()),
-                                new_delegation: new_delegation
-                                    .map(|address| address.to_user_friendly_address()),
-                            })
-                        }
+                        IncomingStakingTransactionData::UpdateStaker {
+                            new_delegation,
+                            delegation_stake,
+                            ..
+                        } => PlainTransactionData::UpdateStaker(PlainUpdateStakerData {
+                            raw: hex::encode(self.data()),
+                            new_delegation: new_delegation
+                                .map(|address| address.to_user_friendly_address()),
+                            delegation_stake: delegation_stake.map(|stake| stake.into()),
+                        }),
+                        IncomingStakingTransactionData::SetInactiveStake {
+                            new_inactive_balance,
+                            ..
+                        } => PlainTransactionData::SetInactiveStake(PlainSetInactiveStakeData {
+                            raw: hex::encode(self.data()),
+                            new_inactive_balance: new_inactive_balance.into(),
+                        }),
                         IncomingStakingTransactionData::CreateValidator {
                             signing_key,
                             voting_key,
@@ -493,6 +503,15 @@ impl Transaction {
                             raw: hex::encode(self.data()),
                             validator: validator_address.to_user_friendly_address(),
                         }),
+                        IncomingStakingTransactionData::UpdateValidatorCommission {
+                            new_commission,
+                            ..
+                        } => PlainTransactionData::UpdateValidatorCommission(
+                            PlainUpdateValidatorCommissionData {
+                                raw: hex::encode(self.data()),
+                                new_commission,
+                            },
+                        ),
                         IncomingStakingTransactionData::RetireValidator { .. } => {
                             PlainTransactionData::Raw(PlainRawData {
                                 raw: hex::encode(self.data()),
@@ -572,6 +591,7 @@ impl Transaction {
                 PlainTransactionData::CreateStaker(ref data) => &data.raw,
                 PlainTransactionData::AddStake(ref data) => &data.raw,
                 PlainTransactionData::UpdateStaker(ref data) => &data.raw,
+                PlainTransactionData::SetInactiveStake(ref data) => &data.raw,
             })?),
             Some(plain.flags),
             plain.validity_start_height,
@@ -592,10 +612,12 @@ pub enum PlainTransactionData {
     Htlc(PlainHtlcData),
     CreateValidator(PlainCreateValidatorData),
     UpdateValidator(PlainUpdateValidatorData),
+    UpdateValidatorCommission(PlainUpdateValidatorCommissionData),
     GenericValidator(PlainValidatorData),
     CreateStaker(PlainCreateStakerData),
     AddStake(PlainAddStakeData),
     UpdateStaker(PlainUpdateStakerData),
+    SetInactiveStake(PlainSetInactiveStakeData),
 }
 
 #[derive(Clone, serde::Serialize, serde::Deserialize, Tsify)]
@@ -647,6 +669,13 @@ pub struct PlainUpdateValidatorData {
     new_proof_of_knowledge: Option<String>,
 }
 
+#[derive(Clone, serde::Serialize, serde::Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct PlainUpdateValidatorCommissionData {
+    raw: String,
+    new_commission: Option<u16>,
+}
+
 // Used for DeactivateValidator & ReactivateValidator, as they have the same fields.
 #[derive(Clone, serde::Serialize, serde::Deserialize, Tsify)]
 #[serde(rename_all = "camelCase")]
@@ -674,6 +703,14 @@ pub struct PlainAddStakeData {
 pub struct PlainUpdateStakerData {
     pub raw: String,
     pub new_delegation: Option<String>,
+    pub delegation_stake: Option<u64>,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct PlainSetInactiveStakeData {
+    pub raw: String,
+    pub new_inactive_balance: u64,
 }
 
 /// Placeholder struct to serialize proofs of transactions as hex strings in the style of the Nimiq 1.0 library.
@@ -1,18 +1,30 @@
 #[cfg(feature = "database-storage")]
 use nimiq_database::{
-    traits::{Database, ReadTransaction, WriteTransaction},
+    traits::{Database, ReadCursor, ReadTransaction, WriteTransaction},
     DatabaseProxy, TableProxy,
 };
 
 use crate::types::*;
 
 /// Defines an interface for storing and retrieving ZK proofs.
-pub trait ProofStore: Send {
-    /// Gets a ZK proof.
+///
+/// A store keeps the proofs of several election blocks, so that peers syncing to an older election
+/// block can still be served.
+pub trait ProofStore: Send + Sync {
+    /// Gets the most recent ZK proof.
     fn get_zkp(&self) -> Option<ZKProof>;
 
-    /// Sets or stores a ZK proof.
+    /// Gets the ZK proof for the election block with the given block number.
+    fn get_zkp_at(&self, block_number: u32) -> Option<ZKProof>;
+
+    /// Returns the block numbers of all stored ZK proofs in ascending order.
+    fn list_zkps(&self) -> Vec<u32>;
+
+    /// Sets or stores a ZK proof. If the store is full, the oldest proofs are removed.
     fn set_zkp(&self, zk_proof: &ZKProof);
+
+    /// Removes the ZK proof for the election block with the given block number.
+    fn remove_zkp(&self, block_number: u32);
 }
 
 #[cfg(feature = "database-storage")]
@@ -21,19 +33,52 @@ pub trait ProofStore: Send {
 pub struct DBProofStore {
     /// Environment for the DB creation and transaction handling.
     env: DatabaseProxy,
-    // A database of the current zkp state.
+    // A database of the stored proofs, indexed by the big endian block number of the election block.
     zkp_db: TableProxy,
+    // The maximum number of proofs kept in the store.
+    max_proofs: usize,
 }
 
 #[cfg(feature = "database-storage")]
 impl DBProofStore {
     const PROOF_DB_NAME: &'static str = "ZKPState";
-    const PROOF_KEY: &'static str = "proof";
+    /// The key under which previous versions stored their only proof.
+    const LEGACY_PROOF_KEY: &'static str = "proof";
+    /// The default number of proofs kept in the store.
+    pub const DEFAULT_MAX_PROOFS: usize = 64;
 
     pub fn new(env: DatabaseProxy) -> Self {
+        Self::with_max_proofs(env, Self::DEFAULT_MAX_PROOFS)
+    }
+
+    pub fn with_max_proofs(env: DatabaseProxy, max_proofs: usize) -> Self {
+        assert!(
+            max_proofs > 0,
+            "The proof store must keep at least one proof"
+        );
         let zkp_db = env.open_table(Self::PROOF_DB_NAME.to_string());
 
-        Self { env, zkp_db }
+        let store = Self {
+            env,
+            zkp_db,
+            max_proofs,
+        };
+        store.migrate_legacy_proof();
+        store
+    }
+
+    fn key(block_number: u32) -> Vec<u8> {
+        block_number.to_be_bytes().to_vec()
+    }
+
+    /// Moves the proof stored by previous versions into the proof history.
+    fn migrate_legacy_proof(&self) {
+        let mut tx = self.env.write_transaction();
+        if let Some(zk_proof) = tx.get::<_, ZKProof>(&self.zkp_db, Self::LEGACY_PROOF_KEY) {
+            tx.remove(&self.zkp_db, Self::LEGACY_PROOF_KEY);
+            tx.put(&self.zkp_db, &Self::key(zk_proof.block_number), &zk_proof);
+            tx.commit();
+        }
     }
 }
 
@@ -42,12 +87,44 @@ impl ProofStore for DBProofStore {
     fn get_zkp(&self) -> Option<ZKProof> {
         self.env
             .read_transaction()
-            .get(&self.zkp_db, Self::PROOF_KEY)
+            .cursor(&self.zkp_db)
+            .last::<Vec<u8>, ZKProof>()
+            .map(|(_, zk_proof)| zk_proof)
+    }
+
+    fn get_zkp_at(&self, block_number: u32) -> Option<ZKProof> {
+        self.env
+            .read_transaction()
+            .get(&self.zkp_db, &Self::key(block_number))
+    }
+
+    fn list_zkps(&self) -> Vec<u32> {
+        self.env
+            .read_transaction()
+            .cursor(&self.zkp_db)
+            .into_iter_start::<Vec<u8>, ZKProof>()
+            .map(|(_, zk_proof)| zk_proof.block_number)
+            .collect()
     }
 
     fn set_zkp(&self, zk_proof: &ZKProof) {
         let mut tx = self.env.write_transaction();
-        tx.put(&self.zkp_db, Self::PROOF_KEY, zk_proof);
+        tx.put(&self.zkp_db, &Self::key(zk_proof.block_number), zk_proof);
+
+        // Evicts the oldest proofs beyond the capacity of the store.
+        let keys: Vec<Vec<u8>> = WriteTransaction::cursor(&tx, &self.zkp_db)
+            .into_iter_start::<Vec<u8>, ZKProof>()
+            .map(|(key, _)| key)
+            .collect();
+        for key in keys.iter().take(keys.len().saturating_sub(self.max_proofs)) {
+            tx.remove(&self.zkp_db, key);
+        }
+        tx.commit();
+    }
+
+    fn remove_zkp(&self, block_number: u32) {
+        let mut tx = self.env.write_transaction();
+        tx.remove(&self.zkp_db, &Self::key(block_number));
         tx.commit();
     }
 }
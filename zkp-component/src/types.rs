@@ -11,12 +11,13 @@ use nimiq_network_interface::{
     network::{Network, Topic},
     request::{Handle, RequestCommon, RequestError, RequestMarker},
 };
+use nimiq_primitives::policy::Policy;
 use nimiq_serde::{Deserialize, DeserializeError, Serialize};
 use nimiq_zkp_primitives::NanoZKPError;
 use parking_lot::RwLock;
 use thiserror::Error;
 
-use crate::ZKPComponent;
+use crate::{proof_store::ProofStore, ZKPComponent};
 
 pub const PROOF_GENERATION_OUTPUT_DELIMITER: [u8; 2] = [242, 208];
 
@@ -245,10 +246,36 @@ pub enum RequestZKPResponse {
     Outdated(u32),
 }
 
+/// The request of the zkp for the election block of a specific epoch, which peers syncing to an
+/// older election block need.
+/// The response either has the proof for that election block or none if it isn't available.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RequestZKPForEpoch {
+    pub(crate) epoch_number: u32,
+    pub(crate) request_election_block: bool,
+}
+
+impl RequestCommon for RequestZKPForEpoch {
+    type Kind = RequestMarker;
+    const TYPE_ID: u16 = 219;
+    type Response = RequestZKPForEpochResponse;
+
+    const MAX_REQUESTS: u32 = MAX_REQUEST_RESPONSE_ZKP;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum RequestZKPForEpochResponse {
+    Proof(ZKProof, Option<MacroBlock>),
+    /// The proof for the election block with this block number is not available.
+    Unavailable(u32),
+}
+
 #[derive(Clone)]
 pub(crate) struct ZKPStateEnvironment {
     pub(crate) zkp_state: Arc<RwLock<ZKPState>>,
     pub(crate) blockchain: BlockchainProxy,
+    pub(crate) proof_storage: Option<Arc<dyn ProofStore>>,
 }
 
 impl<N: Network> From<&ZKPComponent<N>> for ZKPStateEnvironment {
@@ -256,6 +283,7 @@ impl<N: Network> From<&ZKPComponent<N>> for ZKPStateEnvironment {
         ZKPStateEnvironment {
             zkp_state: Arc::clone(&component.zkp_state),
             blockchain: component.blockchain.clone(),
+            proof_storage: component.proof_storage.clone(),
         }
     }
 }
@@ -285,6 +313,49 @@ impl<N: Network> Handle<N, RequestZKPResponse, Arc<ZKPStateEnvironment>> for Req
     }
 }
 
+impl<N: Network> Handle<N, RequestZKPForEpochResponse, Arc<ZKPStateEnvironment>>
+    for RequestZKPForEpoch
+{
+    fn handle(
+        &self,
+        _peer_id: N::PeerId,
+        env: &Arc<ZKPStateEnvironment>,
+    ) -> RequestZKPForEpochResponse {
+        let block_number = match Policy::election_block_of(self.epoch_number) {
+            Some(block_number) => block_number,
+            None => return RequestZKPForEpochResponse::Unavailable(0),
+        };
+
+        // The current proof may not have been stored, e.g. if there is no proof storage.
+        let zkp_state = env.zkp_state.read();
+        let zkp_proof = if zkp_state.latest_block.block_number() == block_number {
+            Some((*zkp_state).clone().into())
+        } else {
+            env.proof_storage
+                .as_ref()
+                .and_then(|proof_storage| proof_storage.get_zkp_at(block_number))
+        };
+        drop(zkp_state);
+
+        let zkp_proof = match zkp_proof {
+            Some(zkp_proof) => zkp_proof,
+            None => return RequestZKPForEpochResponse::Unavailable(block_number),
+        };
+
+        // Then get the corresponding block if necessary.
+        let block = if self.request_election_block {
+            env.blockchain
+                .read()
+                .get_block_at(block_number, true)
+                .ok()
+                .map(|block| block.unwrap_macro())
+        } else {
+            None
+        };
+        RequestZKPForEpochResponse::Proof(zkp_proof, block)
+    }
+}
+
 mod serde_derive {
 
     use std::fmt;
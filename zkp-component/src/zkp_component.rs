@@ -35,6 +35,7 @@ pub struct ZKPComponentProxy<N: Network> {
     network: Arc<N>,
     zkp_state: Arc<RwLock<ZKPState>>,
     zkp_requests: Arc<Mutex<ZKPRequests<N>>>,
+    proof_storage: Option<Arc<dyn ProofStore>>,
     pub(crate) zkp_events_notifier: BroadcastSender<ZKPEvent<N>>,
 }
 
@@ -44,6 +45,7 @@ impl<N: Network> Clone for ZKPComponentProxy<N> {
             network: Arc::clone(&self.network),
             zkp_state: Arc::clone(&self.zkp_state),
             zkp_requests: Arc::clone(&self.zkp_requests),
+            proof_storage: self.proof_storage.clone(),
             zkp_events_notifier: self.zkp_events_notifier.clone(),
         }
     }
@@ -87,6 +89,21 @@ impl<N: Network> ZKPComponentProxy<N> {
         (request.await, peer_id)
     }
 
+    /// Returns the block numbers of the election blocks for which we store a proof.
+    pub fn list_stored_zkps(&self) -> Vec<u32> {
+        self.proof_storage
+            .as_ref()
+            .map(|proof_storage| proof_storage.list_zkps())
+            .unwrap_or_default()
+    }
+
+    /// Returns the stored proof for the election block with the given block number.
+    pub fn get_stored_zkp(&self, block_number: u32) -> Option<ZKProof> {
+        self.proof_storage
+            .as_ref()
+            .and_then(|proof_storage| proof_storage.get_zkp_at(block_number))
+    }
+
     pub fn subscribe_zkps(&self) -> BroadcastStream<ZKPEvent<N>> {
         BroadcastStream::new(self.zkp_events_notifier.subscribe())
     }
@@ -102,7 +119,7 @@ impl<N: Network> ZKPComponentProxy<N> {
 /// - The current zkp state
 /// - The proof generating component that can be activated by a client configuration
 /// - The zkp gossip stream
-/// - The db storage for the current proof
+/// - The db storage for the proofs of the most recent election blocks
 /// - The zkp requests component to fetch an up to date proof from our peers
 /// - The zkp events notifies newly stored proofs.
 ///
@@ -114,7 +131,7 @@ pub struct ZKPComponent<N: Network> {
     #[cfg(feature = "zkp-prover")]
     zk_prover: Option<ZKProver<N>>,
     zk_proofs_stream: ZKProofsStream<N>,
-    proof_storage: Option<Box<dyn ProofStore>>,
+    pub(crate) proof_storage: Option<Arc<dyn ProofStore>>,
     zkp_requests: Arc<Mutex<ZKPRequests<N>>>,
     zkp_events_notifier: BroadcastSender<ZKPEvent<N>>,
 }
@@ -146,7 +163,7 @@ impl<N: Network> ZKPComponent<N> {
             #[cfg(feature = "zkp-prover")]
             zk_prover: None,
             zk_proofs_stream,
-            proof_storage,
+            proof_storage: proof_storage.map(Arc::from),
             zkp_requests: Arc::new(Mutex::new(ZKPRequests::new(network))),
             zkp_events_notifier,
         };
@@ -154,6 +171,9 @@ impl<N: Network> ZKPComponent<N> {
         // Loads the proof from the db if any.
         zkp_component.load_proof_from_db();
 
+        // Light nodes may have resumed from a proof they synced with before.
+        zkp_component.load_proof_from_light_blockchain();
+
         // The handler for zkp request is launched.
         zkp_component.launch_request_handler(executor);
         zkp_component
@@ -195,8 +215,10 @@ impl<N: Network> ZKPComponent<N> {
 
     /// Launches thread that processes the zkp requests and replies to them.
     fn launch_request_handler(&self, executor: impl TaskExecutor + Send + 'static) {
-        let stream = self.network.receive_requests::<RequestZKP>();
         let env = Arc::new(ZKPStateEnvironment::from(self));
+        let stream = self.network.receive_requests::<RequestZKP>();
+        executor.exec(Box::pin(request_handler(&self.network, stream, &env)));
+        let stream = self.network.receive_requests::<RequestZKPForEpoch>();
         executor.exec(Box::pin(request_handler(&self.network, stream, &env)));
     }
 
@@ -206,6 +228,7 @@ impl<N: Network> ZKPComponent<N> {
             network: Arc::clone(&self.network),
             zkp_state: Arc::clone(&self.zkp_state),
             zkp_requests: Arc::clone(&self.zkp_requests),
+            proof_storage: self.proof_storage.clone(),
             zkp_events_notifier: self.zkp_events_notifier.clone(),
         }
     }
@@ -218,24 +241,26 @@ impl<N: Network> ZKPComponent<N> {
         false
     }
 
-    /// Loads the proof from the database into the current state. It does all verification steps before loading it into
-    /// our state. In case of failure, it replaces the db proof with the current state.
+    /// Loads the most recent proof from the database into the current state. It does all verification steps before
+    /// loading it into our state. In case of failure, the proof is kept in the db, so that it can still be served to
+    /// our peers.
     fn load_proof_from_db(&mut self) {
         if let Some(proof_storage) = &self.proof_storage {
             if let Some(loaded_proof) = proof_storage.get_zkp() {
-                let mut this = Pin::new(self);
+                let block_number = loaded_proof.block_number;
+                let this = Pin::new(self);
 
-                if let Err(e) = this.as_mut().push_proof_from_peers(
+                if let Err(e) = this.push_proof_from_peers(
                     loaded_proof,
                     None,
                     false,
                     ProofSource::SelfGenerated,
                 ) {
-                    log::error!("Error pushing the zk proof load from disk {}", e);
-                    this.proof_storage
-                        .as_ref()
-                        .unwrap()
-                        .set_zkp(&this.zkp_state.read().clone().into());
+                    log::error!(
+                        block_number,
+                        "Error pushing the zk proof load from disk {}",
+                        e
+                    );
                 } else {
                     log::info!("The zk proof was successfully load from disk");
                 }
@@ -245,6 +270,41 @@ impl<N: Network> ZKPComponent<N> {
         }
     }
 
+    /// Loads the proof a light blockchain persisted into the current state, if it is more recent. Like the proof
+    /// loaded from the database, it is verified again before loading it into our state.
+    fn load_proof_from_light_blockchain(&mut self) {
+        let latest_zkp = match &self.blockchain {
+            BlockchainProxy::Light(blockchain) => blockchain.read().latest_zkp.clone(),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        };
+
+        if let Some(latest_zkp) = latest_zkp {
+            let block_number = latest_zkp.block.block_number();
+            let zk_proof = ZKProof::new(block_number, Some(latest_zkp.proof));
+
+            match Pin::new(self).push_proof_from_peers(
+                zk_proof,
+                Some(latest_zkp.block),
+                false,
+                ProofSource::SelfGenerated,
+            ) {
+                Ok(_) => {
+                    log::info!(
+                        block_number,
+                        "The zk proof was loaded from the light blockchain"
+                    )
+                }
+                Err(Error::OutdatedProof) => {}
+                Err(e) => log::error!(
+                    block_number,
+                    "Error pushing the zk proof of the light blockchain {}",
+                    e
+                ),
+            }
+        }
+    }
+
     /// Pushes the proof sent from an peer into our own state. If the proof is invalid or it's older than the
     /// current state it fails.
     fn push_proof_from_peers(
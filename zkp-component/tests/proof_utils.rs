@@ -179,3 +179,34 @@ async fn can_store_and_load_zkp_state_from_db() {
         "Load from db was not successful"
     );
 }
+
+#[test(tokio::test)]
+async fn can_keep_a_bounded_history_of_zkps() {
+    let env = VolatileDatabase::new(1).unwrap();
+
+    let proof_store = DBProofStore::with_max_proofs(env, 2);
+    let proofs: Vec<ZKProof> = (1..=3)
+        .map(|epoch| ZKProof {
+            block_number: Policy::election_block_of(epoch).unwrap(),
+            proof: Some(Proof::default()),
+        })
+        .collect();
+
+    for proof in &proofs {
+        proof_store.set_zkp(proof);
+    }
+    assert_eq!(
+        proof_store.list_zkps(),
+        vec![proofs[1].block_number, proofs[2].block_number],
+        "The oldest proof was not evicted"
+    );
+    assert_eq!(proof_store.get_zkp().unwrap(), proofs[2]);
+    assert_eq!(
+        proof_store.get_zkp_at(proofs[1].block_number).unwrap(),
+        proofs[1]
+    );
+    assert!(proof_store.get_zkp_at(proofs[0].block_number).is_none());
+
+    proof_store.remove_zkp(proofs[2].block_number);
+    assert_eq!(proof_store.get_zkp().unwrap(), proofs[1]);
+}